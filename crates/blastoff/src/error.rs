@@ -56,6 +56,28 @@ impl ToResult for cublas_sys::cublasStatus_t {
     }
 }
 
+// cuBLASLt has its own bindings of the same statuses.
+#[cfg(feature = "cublaslt")]
+impl ToResult for cust_raw::cublaslt_sys::cublasStatus_t {
+    fn to_result(self) -> Result<(), CublasError> {
+        use cust_raw::cublaslt_sys::cublasStatus_t::*;
+        use CublasError::*;
+
+        Err(match self {
+            CUBLAS_STATUS_SUCCESS => return Ok(()),
+            CUBLAS_STATUS_NOT_INITIALIZED => NotInitialized,
+            CUBLAS_STATUS_ALLOC_FAILED => AllocFailed,
+            CUBLAS_STATUS_INVALID_VALUE => InvalidValue,
+            CUBLAS_STATUS_ARCH_MISMATCH => ArchMismatch,
+            CUBLAS_STATUS_MAPPING_ERROR => MappingError,
+            CUBLAS_STATUS_EXECUTION_FAILED => ExecutionFailed,
+            CUBLAS_STATUS_INTERNAL_ERROR => InternalError,
+            CUBLAS_STATUS_NOT_SUPPORTED => NotSupported,
+            CUBLAS_STATUS_LICENSE_ERROR => LicenseError,
+        })
+    }
+}
+
 impl CublasError {
     pub fn into_raw(self) -> cublas_sys::cublasStatus_t {
         use cust_raw::cublas_sys::cublasStatus_t::*;
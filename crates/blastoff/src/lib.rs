@@ -11,6 +11,9 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
 pub use cust_raw::cublas_sys;
+#[cfg_attr(docsrs, doc(cfg(feature = "cublaslt")))]
+#[cfg(feature = "cublaslt")]
+pub use cust_raw::cublaslt_sys;
 use num_complex::{Complex32, Complex64};
 
 pub use context::*;
@@ -19,6 +22,9 @@ mod context;
 pub mod error;
 mod level1;
 mod level3;
+#[cfg_attr(docsrs, doc(cfg(feature = "cublaslt")))]
+#[cfg(feature = "cublaslt")]
+pub mod lt;
 pub mod raw;
 
 /// A possible datatype for a generic matrix mul operation. This is just [`BlasDatatype`] except optionally
@@ -0,0 +1,455 @@
+//! Matrix multiplication with cuBLASLt, the lightweight cuBLAS API which can fuse an epilogue,
+//! such as adding a bias or applying an activation, into the multiplication.
+//!
+//! This requires the `cublaslt` feature. See [`Matmul`].
+
+use std::marker::PhantomData;
+use std::mem::{self, MaybeUninit};
+use std::os::raw::c_void;
+use std::ptr;
+
+use cust::memory::{DevicePointer, GpuBuffer};
+use cust::stream::Stream;
+use cust_raw::cublaslt_sys;
+
+use crate::error::{CublasError, Error, ToResult as _};
+use crate::{CublasContext, GemmDatatype, MatrixOp};
+
+type Result<T = (), E = Error> = std::result::Result<T, E>;
+
+/// A datatype which can be multiplied with [`Matmul`]. This is `f32` and `f64`, and `f16` with
+/// the `half` feature.
+pub trait LtDatatype: GemmDatatype {
+    /// The type of `alpha` and `beta`, which is the type of the computation.
+    type Scale: Copy;
+    #[doc(hidden)]
+    const DATA_TYPE: cublaslt_sys::cudaDataType;
+    #[doc(hidden)]
+    const SCALE_TYPE: cublaslt_sys::cudaDataType;
+    #[doc(hidden)]
+    const COMPUTE_TYPE: cublaslt_sys::cublasComputeType_t;
+}
+
+impl LtDatatype for f32 {
+    type Scale = f32;
+    const DATA_TYPE: cublaslt_sys::cudaDataType = cublaslt_sys::cudaDataType::CUDA_R_32F;
+    const SCALE_TYPE: cublaslt_sys::cudaDataType = cublaslt_sys::cudaDataType::CUDA_R_32F;
+    const COMPUTE_TYPE: cublaslt_sys::cublasComputeType_t =
+        cublaslt_sys::cublasComputeType_t::CUBLAS_COMPUTE_32F;
+}
+
+impl LtDatatype for f64 {
+    type Scale = f64;
+    const DATA_TYPE: cublaslt_sys::cudaDataType = cublaslt_sys::cudaDataType::CUDA_R_64F;
+    const SCALE_TYPE: cublaslt_sys::cudaDataType = cublaslt_sys::cudaDataType::CUDA_R_64F;
+    const COMPUTE_TYPE: cublaslt_sys::cublasComputeType_t =
+        cublaslt_sys::cublasComputeType_t::CUBLAS_COMPUTE_64F;
+}
+
+/// Half precision matrices are multiplied and accumulated in single precision.
+#[cfg_attr(docsrs, doc(cfg(feature = "half")))]
+#[cfg(feature = "half")]
+impl LtDatatype for half::f16 {
+    type Scale = f32;
+    const DATA_TYPE: cublaslt_sys::cudaDataType = cublaslt_sys::cudaDataType::CUDA_R_16F;
+    const SCALE_TYPE: cublaslt_sys::cudaDataType = cublaslt_sys::cudaDataType::CUDA_R_32F;
+    const COMPUTE_TYPE: cublaslt_sys::cublasComputeType_t =
+        cublaslt_sys::cublasComputeType_t::CUBLAS_COMPUTE_32F;
+}
+
+/// An operation fused into a [`Matmul`], applied to every element of the result before it is
+/// written to D.
+///
+/// The bias is a vector with an element for every row of D, added to every column.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Epilogue {
+    /// No operation, the result is written as is. This is the default.
+    #[default]
+    None,
+    /// Clamps negative elements to zero.
+    Relu,
+    /// Applies the tanh approximation of GELU.
+    Gelu,
+    /// Adds the bias.
+    Bias,
+    /// Adds the bias, then clamps negative elements to zero.
+    ReluBias,
+    /// Adds the bias, then applies the tanh approximation of GELU.
+    GeluBias,
+}
+
+impl Epilogue {
+    /// Returns the corresponding `cublasLtEpilogue_t` for this epilogue.
+    pub fn to_raw(self) -> cublaslt_sys::cublasLtEpilogue_t {
+        use cublaslt_sys::cublasLtEpilogue_t::*;
+
+        match self {
+            Epilogue::None => CUBLASLT_EPILOGUE_DEFAULT,
+            Epilogue::Relu => CUBLASLT_EPILOGUE_RELU,
+            Epilogue::Gelu => CUBLASLT_EPILOGUE_GELU,
+            Epilogue::Bias => CUBLASLT_EPILOGUE_BIAS,
+            Epilogue::ReluBias => CUBLASLT_EPILOGUE_RELU_BIAS,
+            Epilogue::GeluBias => CUBLASLT_EPILOGUE_GELU_BIAS,
+        }
+    }
+
+    /// Whether this epilogue adds a bias, which must then be given with [`Matmul::bias`].
+    pub fn has_bias(self) -> bool {
+        matches!(
+            self,
+            Epilogue::Bias | Epilogue::ReluBias | Epilogue::GeluBias
+        )
+    }
+}
+
+/// A matrix multiplication $D = \text{epilogue}(\alpha \text{op}(A) \text{op}(B) + \beta C)$
+/// executed with cuBLASLt, where $\text{op}(A)$ is $m \times k$, $\text{op}(B)$ is $k \times n$
+/// and C and D are $m \times n$.
+///
+/// Matrices are column major and packed, the leading dimension of every matrix is its number of
+/// rows, before its operation is applied. `Matmul` only holds the configuration, the cuBLASLt
+/// descriptors are created by [`Matmul::execute`], which also selects the fastest algorithm
+/// which fits in the workspace with the heuristics of cuBLASLt every time it is called.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # let _a = cust::quick_init()?;
+/// # use blastoff::CublasContext;
+/// # use cust::prelude::*;
+/// # use cust::util::SliceExt;
+/// use blastoff::lt::{Epilogue, Matmul};
+///
+/// # let stream = Stream::new(StreamFlags::DEFAULT, None)?;
+/// let ctx = CublasContext::new()?;
+/// // column major 2x2 matrices.
+/// let a = [1.0f32, 0.0, 0.0, 1.0].as_dbuf()?;
+/// let b = [1.0f32, -2.0, 3.0, -4.0].as_dbuf()?;
+/// let c = [0.0f32; 4].as_dbuf()?;
+/// let mut d = [0.0f32; 4].as_dbuf()?;
+/// let bias = [1.0f32, 1.0].as_dbuf()?;
+/// let mut workspace = DeviceBuffer::<u8>::zeroed(1 << 20)?;
+///
+/// Matmul::<f32>::new(2, 2, 2)
+///     .epilogue(Epilogue::ReluBias)
+///     .bias(&bias)
+///     .execute(&ctx, 1.0, &a, &b, 0.0, &c, &mut d, &mut workspace, &stream)?;
+///
+/// stream.synchronize()?;
+///
+/// assert_eq!(d.as_host_vec()?, [2.0, 0.0, 4.0, 0.0]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Matmul<'a, T: LtDatatype> {
+    m: usize,
+    n: usize,
+    k: usize,
+    op_a: MatrixOp,
+    op_b: MatrixOp,
+    epilogue: Epilogue,
+    bias: Option<(DevicePointer<T>, usize)>,
+    _bias: PhantomData<&'a T>,
+}
+
+impl<'a, T: LtDatatype> Matmul<'a, T> {
+    /// Creates the configuration of a multiplication of an $m \times k$ matrix with a
+    /// $k \times n$ matrix, without operations on the matrices and without an epilogue.
+    pub fn new(m: usize, n: usize, k: usize) -> Self {
+        Self {
+            m,
+            n,
+            k,
+            op_a: MatrixOp::None,
+            op_b: MatrixOp::None,
+            epilogue: Epilogue::None,
+            bias: None,
+            _bias: PhantomData,
+        }
+    }
+
+    /// Sets the operation applied to A, A is $k \times m$ if it is transposed.
+    pub fn op_a(mut self, op: MatrixOp) -> Self {
+        self.op_a = op;
+        self
+    }
+
+    /// Sets the operation applied to B, B is $n \times k$ if it is transposed.
+    pub fn op_b(mut self, op: MatrixOp) -> Self {
+        self.op_b = op;
+        self
+    }
+
+    /// Sets the epilogue fused into the multiplication.
+    pub fn epilogue(mut self, epilogue: Epilogue) -> Self {
+        self.epilogue = epilogue;
+        self
+    }
+
+    /// Sets the bias added by the epilogue, which must have at least $m$ elements. The bias is
+    /// ignored if the epilogue does not add one.
+    pub fn bias(mut self, bias: &'a impl GpuBuffer<T>) -> Self {
+        self.bias = Some((bias.as_device_ptr(), bias.len()));
+        self
+    }
+
+    /// Queues the multiplication on `stream`, using `workspace` as the scratch memory of
+    /// cuBLASLt.
+    ///
+    /// The algorithm is the fastest one of the heuristics of cuBLASLt which needs at most
+    /// `workspace.len()` bytes of workspace. A bigger workspace allows faster algorithms, cuBLAS
+    /// recommends 32 MiB on Hopper and 4 MiB on older architectures. C is not read if `beta` is
+    /// zero.
+    ///
+    /// The context is only used for its cuBLASLt handle, any `cublasHandle_t` is one, so this
+    /// does not set the stream of the context.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the following conditions are not met:
+    /// - `m > 0 && n > 0 && k > 0`
+    /// - `a.len() >= m * k`
+    /// - `b.len() >= k * n`
+    /// - `c.len() >= m * n` and `d.len() >= m * n`
+    /// - a bias of at least `m` elements was given if the epilogue adds one
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CublasError::NotSupported`] if no algorithm supports the configuration with
+    /// the workspace given, such as the epilogue with `f64` on some versions of cuBLASLt, and
+    /// other errors if the multiplication failed.
+    #[track_caller]
+    pub fn execute(
+        &self,
+        ctx: &CublasContext,
+        alpha: T::Scale,
+        a: &impl GpuBuffer<T>,
+        b: &impl GpuBuffer<T>,
+        beta: T::Scale,
+        c: &impl GpuBuffer<T>,
+        d: &mut impl GpuBuffer<T>,
+        workspace: &mut impl GpuBuffer<u8>,
+        stream: &Stream,
+    ) -> Result {
+        let (m, n, k) = (self.m, self.n, self.k);
+        assert!(m > 0 && n > 0 && k > 0, "m, n, and k must be at least 1");
+        assert!(a.len() >= m * k, "matrix A's length must be at least m * k");
+        assert!(b.len() >= k * n, "matrix B's length must be at least k * n");
+        assert!(c.len() >= m * n, "matrix C's length must be at least m * n");
+        assert!(d.len() >= m * n, "matrix D's length must be at least m * n");
+        let bias = if self.epilogue.has_bias() {
+            let (bias, len) = self
+                .bias
+                .expect("the epilogue adds a bias but none was given");
+            assert!(len >= m, "the bias' length must be at least m");
+            Some(bias)
+        } else {
+            None
+        };
+
+        let desc = MatmulDesc::new::<T>()?;
+        desc.set(
+            cublaslt_sys::cublasLtMatmulDescAttributes_t::CUBLASLT_MATMUL_DESC_TRANSA,
+            &lt_op(self.op_a),
+        )?;
+        desc.set(
+            cublaslt_sys::cublasLtMatmulDescAttributes_t::CUBLASLT_MATMUL_DESC_TRANSB,
+            &lt_op(self.op_b),
+        )?;
+        desc.set(
+            cublaslt_sys::cublasLtMatmulDescAttributes_t::CUBLASLT_MATMUL_DESC_EPILOGUE,
+            &self.epilogue.to_raw(),
+        )?;
+        if let Some(bias) = bias {
+            desc.set(
+                cublaslt_sys::cublasLtMatmulDescAttributes_t::CUBLASLT_MATMUL_DESC_BIAS_POINTER,
+                &(bias.as_ptr() as *const c_void),
+            )?;
+        }
+
+        let (a_rows, a_cols) = rows_cols(self.op_a, m, k);
+        let (b_rows, b_cols) = rows_cols(self.op_b, k, n);
+        let a_layout = Layout::new::<T>(a_rows, a_cols)?;
+        let b_layout = Layout::new::<T>(b_rows, b_cols)?;
+        let c_layout = Layout::new::<T>(m, n)?;
+
+        // a cuBLAS handle is a cuBLASLt handle.
+        let handle: cublaslt_sys::cublasLtHandle_t = ctx.raw.cast();
+        let heuristic = heuristic(
+            handle,
+            &desc,
+            &a_layout,
+            &b_layout,
+            &c_layout,
+            workspace.len(),
+        )?;
+
+        unsafe {
+            Ok(cublaslt_sys::cublasLtMatmul(
+                handle,
+                desc.0,
+                &alpha as *const T::Scale as *const c_void,
+                a.as_device_ptr().as_ptr() as *const c_void,
+                a_layout.0,
+                b.as_device_ptr().as_ptr() as *const c_void,
+                b_layout.0,
+                &beta as *const T::Scale as *const c_void,
+                c.as_device_ptr().as_ptr() as *const c_void,
+                c_layout.0,
+                d.as_device_ptr().as_mut_ptr() as *mut c_void,
+                c_layout.0,
+                &heuristic.algo,
+                workspace.as_device_ptr().as_mut_ptr() as *mut c_void,
+                workspace.len(),
+                // cudaStream_t is the same as CUstream
+                stream.as_inner().cast(),
+            )
+            .to_result()?)
+        }
+    }
+}
+
+/// Returns the rows and columns a matrix is stored with, before `op` is applied to it.
+fn rows_cols(op: MatrixOp, rows: usize, cols: usize) -> (usize, usize) {
+    if op == MatrixOp::None {
+        (rows, cols)
+    } else {
+        (cols, rows)
+    }
+}
+
+fn lt_op(op: MatrixOp) -> cublaslt_sys::cublasOperation_t {
+    match op {
+        MatrixOp::None => cublaslt_sys::cublasOperation_t::CUBLAS_OP_N,
+        MatrixOp::Transpose => cublaslt_sys::cublasOperation_t::CUBLAS_OP_T,
+        MatrixOp::ConjugateTranspose => cublaslt_sys::cublasOperation_t::CUBLAS_OP_C,
+    }
+}
+
+/// Returns the fastest algorithm which needs at most `max_workspace` bytes of workspace.
+fn heuristic(
+    handle: cublaslt_sys::cublasLtHandle_t,
+    desc: &MatmulDesc,
+    a: &Layout,
+    b: &Layout,
+    c: &Layout,
+    max_workspace: usize,
+) -> Result<cublaslt_sys::cublasLtMatmulHeuristicResult_t> {
+    let preference = Preference::new()?;
+    let max_workspace = max_workspace as u64;
+    unsafe {
+        cublaslt_sys::cublasLtMatmulPreferenceSetAttribute(
+            preference.0,
+            cublaslt_sys::cublasLtMatmulPreferenceAttributes_t::CUBLASLT_MATMUL_PREF_MAX_WORKSPACE_BYTES,
+            &max_workspace as *const u64 as *const c_void,
+            mem::size_of::<u64>(),
+        )
+        .to_result()?;
+
+        // the results are sorted by their estimated time, the first one is the fastest.
+        let mut result = MaybeUninit::<cublaslt_sys::cublasLtMatmulHeuristicResult_t>::zeroed();
+        let mut count = 0;
+        cublaslt_sys::cublasLtMatmulAlgoGetHeuristic(
+            handle,
+            desc.0,
+            a.0,
+            b.0,
+            c.0,
+            c.0,
+            preference.0,
+            1,
+            result.as_mut_ptr(),
+            &mut count,
+        )
+        .to_result()?;
+        if count == 0 {
+            return Err(CublasError::NotSupported.into());
+        }
+        Ok(result.assume_init())
+    }
+}
+
+// the descriptors are destroyed when dropped, so that they are not leaked on errors. cuBLASLt
+// does not keep them once a multiplication is queued.
+
+struct MatmulDesc(cublaslt_sys::cublasLtMatmulDesc_t);
+
+impl MatmulDesc {
+    fn new<T: LtDatatype>() -> Result<Self> {
+        let mut raw = ptr::null_mut();
+        unsafe {
+            cublaslt_sys::cublasLtMatmulDescCreate(&mut raw, T::COMPUTE_TYPE, T::SCALE_TYPE)
+                .to_result()?;
+        }
+        Ok(Self(raw))
+    }
+
+    fn set<V>(&self, attribute: cublaslt_sys::cublasLtMatmulDescAttributes_t, value: &V) -> Result {
+        unsafe {
+            Ok(cublaslt_sys::cublasLtMatmulDescSetAttribute(
+                self.0,
+                attribute,
+                value as *const V as *const c_void,
+                mem::size_of::<V>(),
+            )
+            .to_result()?)
+        }
+    }
+}
+
+impl Drop for MatmulDesc {
+    fn drop(&mut self) {
+        unsafe {
+            cublaslt_sys::cublasLtMatmulDescDestroy(self.0);
+        }
+    }
+}
+
+struct Layout(cublaslt_sys::cublasLtMatrixLayout_t);
+
+impl Layout {
+    fn new<T: LtDatatype>(rows: usize, cols: usize) -> Result<Self> {
+        let mut raw = ptr::null_mut();
+        unsafe {
+            cublaslt_sys::cublasLtMatrixLayoutCreate(
+                &mut raw,
+                T::DATA_TYPE,
+                rows as u64,
+                cols as u64,
+                rows as i64,
+            )
+            .to_result()?;
+        }
+        Ok(Self(raw))
+    }
+}
+
+impl Drop for Layout {
+    fn drop(&mut self) {
+        unsafe {
+            cublaslt_sys::cublasLtMatrixLayoutDestroy(self.0);
+        }
+    }
+}
+
+struct Preference(cublaslt_sys::cublasLtMatmulPreference_t);
+
+impl Preference {
+    fn new() -> Result<Self> {
+        let mut raw = ptr::null_mut();
+        unsafe {
+            cublaslt_sys::cublasLtMatmulPreferenceCreate(&mut raw).to_result()?;
+        }
+        Ok(Self(raw))
+    }
+}
+
+impl Drop for Preference {
+    fn drop(&mut self) {
+        unsafe {
+            cublaslt_sys::cublasLtMatmulPreferenceDestroy(self.0);
+        }
+    }
+}
@@ -15,6 +15,7 @@ pub enum CudaBuilderError {
     CratePathDoesntExist(PathBuf),
     FailedToCopyPtxFile(std::io::Error),
     BuildFailed,
+    InvalidPath(PathBuf),
 }
 
 impl fmt::Display for CudaBuilderError {
@@ -24,6 +25,9 @@ impl fmt::Display for CudaBuilderError {
                 write!(f, "Crate path {} does not exist", path.display())
             }
             CudaBuilderError::BuildFailed => f.write_str("Build failed"),
+            CudaBuilderError::InvalidPath(path) => {
+                write!(f, "Path {} is empty or not valid UTF-8", path.display())
+            }
             CudaBuilderError::FailedToCopyPtxFile(err) => {
                 f.write_str(&format!("Failed to copy PTX file: {:?}", err))
             }
@@ -44,7 +48,10 @@ impl DebugInfo {
     fn into_nvvm_and_rustc_options(self) -> (String, String) {
         match self {
             DebugInfo::None => unreachable!(),
-            DebugInfo::LineTables => ("-generate-line-info".into(), "-Cdebuginfo=1".into()),
+            DebugInfo::LineTables => (
+                "-generate-line-info".into(),
+                "-Cdebuginfo=line-tables-only".into(),
+            ),
             // DebugInfo::Full => ("-g".into(), "-Cdebuginfo=2".into()),
         }
     }
@@ -137,6 +144,24 @@ pub struct CudaBuilder {
     /// An optional path where to dump LLVM IR of the final output the codegen will feed to libnvvm. Usually
     /// used for debugging.
     pub final_module_path: Option<PathBuf>,
+    /// Whether to count how many threads execute every basic block of every kernel, see
+    /// [`instrument_kernels`](Self::instrument_kernels).
+    ///
+    /// `false` by default.
+    pub instrument_kernels: bool,
+    /// Whether to emit a table of the parameters of every kernel, see
+    /// [`emit_kernel_params`](Self::emit_kernel_params).
+    ///
+    /// `false` by default.
+    pub emit_kernel_params: bool,
+    /// Paths of additional LLVM bitcode libraries to link into the kernels, see
+    /// [`link_bitcode`](Self::link_bitcode).
+    pub link_bitcode: Vec<PathBuf>,
+    /// Whether panics go through the panic handler of `cuda_std`, see
+    /// [`panic_messages`](Self::panic_messages).
+    ///
+    /// `false` by default.
+    pub panic_messages: bool,
 }
 
 impl CudaBuilder {
@@ -158,6 +183,10 @@ impl CudaBuilder {
             debug: DebugInfo::None,
             build_args: vec![],
             final_module_path: None,
+            instrument_kernels: false,
+            emit_kernel_params: false,
+            link_bitcode: vec![],
+            panic_messages: false,
         }
     }
 
@@ -286,11 +315,73 @@ impl CudaBuilder {
 
     /// An optional path where to dump LLVM IR of the final output the codegen will feed to libnvvm. Usually
     /// used for debugging.
+    ///
+    /// A relative path is relative to the current directory, which for a build script is the
+    /// directory of the package being built, not the gpu crate.
     pub fn final_module_path(mut self, path: impl AsRef<Path>) -> Self {
         self.final_module_path = Some(path.as_ref().to_path_buf());
         self
     }
 
+    /// Whether to count how many threads execute every basic block of every kernel, for coverage
+    /// and coarse profiling.
+    ///
+    /// For every kernel `foo` with `N` basic blocks the codegen emits a `[u64; N]` global named
+    /// `__rust_cuda_kernel_calls_foo`, and atomically increments the counter of a block at its
+    /// start. The first counter belongs to the entry block, so it counts the threads entering the
+    /// kernel. The blocks are those of the kernel body itself, functions it calls are only
+    /// covered once inlined into it. A `[u32; N]` global named `__rust_cuda_kernel_lines_foo`
+    /// holds the source line of every block, which is only known with [`debug`](Self::debug)
+    /// info and 0 otherwise. The host can read the counts along with their lines, or reset them
+    /// between launches, through `Module::get_kernel_block_counts` and
+    /// `Module::reset_kernel_block_counts`. This adds an atomic operation per thread to every
+    /// block, so it should not be enabled for release builds.
+    pub fn instrument_kernels(mut self, instrument_kernels: bool) -> Self {
+        self.instrument_kernels = instrument_kernels;
+        self
+    }
+
+    /// Whether to emit a table of the parameter layout of every kernel, so the host can check
+    /// launch arguments against it.
+    ///
+    /// For every kernel `foo` the codegen emits a `u32` array named `__rust_cuda_kernel_params_foo`
+    /// holding the number of parameters followed by the offset and size of each one. The host
+    /// can read it through `Module::get_kernel_params` and check a launch's arguments with
+    /// `KernelArgs::check`.
+    pub fn emit_kernel_params(mut self, emit_kernel_params: bool) -> Self {
+        self.emit_kernel_params = emit_kernel_params;
+        self
+    }
+
+    /// Links an LLVM bitcode library into the kernels, alongside libdevice.
+    ///
+    /// The library is given to libnvvm as a lazy module like libdevice is, so only the functions
+    /// the kernels call are linked in. Those functions can be declared in the gpu crate with an
+    /// `extern "C"` block. The bitcode must target NVVM, that is, be compatible with the version of
+    /// libnvvm used. Can be called multiple times to link multiple libraries.
+    ///
+    /// A relative path is relative to the current directory, as with
+    /// [`final_module_path`](Self::final_module_path). Changing the library does not by itself
+    /// rebuild the gpu crate, since cargo does not know the kernels depend on it.
+    pub fn link_bitcode(mut self, path: impl AsRef<Path>) -> Self {
+        self.link_bitcode.push(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Whether panics go through the panic handler of `cuda_std`, so that with its
+    /// `panic_messages` feature the formatted message of a panic is reported and the launch fails
+    /// with `CUDA_ERROR_ASSERT`.
+    ///
+    /// By default `core` is built with `panic_immediate_abort`, which traps right away without
+    /// calling the panic handler and keeps the formatting machinery out of the kernels. Enabling
+    /// this builds `core` without it. The `panic_messages` feature of `cuda_std` must still be
+    /// enabled by the gpu crate. This has no effect with [`optix`](Self::optix), which always
+    /// aborts immediately.
+    pub fn panic_messages(mut self, panic_messages: bool) -> Self {
+        self.panic_messages = panic_messages;
+        self
+    }
+
     /// Runs rustc to build the codegen and codegens the gpu crate, returning the path of the final
     /// ptx file. If [`ptx_file_copy_path`](Self::ptx_file_copy_path) is set, this returns the copied path.
     pub fn build(self) -> Result<PathBuf, CudaBuilderError> {
@@ -352,6 +443,28 @@ fn join_checking_for_separators(strings: Vec<impl Borrow<str>>, sep: &str) -> St
     strings.join(sep)
 }
 
+/// Turns a path into an llvm-arg for the codegen.
+///
+/// The path is made absolute, since the codegen runs in another directory. rustc splits the
+/// llvm-args on whitespace, so whitespace and `%` are percent-encoded, the codegen decodes them.
+fn path_arg(path: &Path) -> Result<String, CudaBuilderError> {
+    let invalid = || CudaBuilderError::InvalidPath(path.to_path_buf());
+    let path = std::path::absolute(path).map_err(|_| invalid())?;
+    let path = path.to_str().ok_or_else(invalid)?;
+
+    let mut arg = String::with_capacity(path.len());
+    for c in path.chars() {
+        if c == '%' || c.is_whitespace() {
+            for byte in c.encode_utf8(&mut [0; 4]).bytes() {
+                arg.push_str(&format!("%{:02X}", byte));
+            }
+        } else {
+            arg.push(c);
+        }
+    }
+    Ok(arg)
+}
+
 fn invoke_rustc(builder: &CudaBuilder) -> Result<PathBuf, CudaBuilderError> {
     // see https://github.com/EmbarkStudios/rust-gpu/blob/main/crates/spirv-builder/src/lib.rs#L385-L392
     // on what this does
@@ -401,7 +514,20 @@ fn invoke_rustc(builder: &CudaBuilder) -> Result<PathBuf, CudaBuilderError> {
 
     if let Some(path) = &builder.final_module_path {
         llvm_args.push("--final-module-path".to_string());
-        llvm_args.push(path.to_str().unwrap().to_string());
+        llvm_args.push(path_arg(path)?);
+    }
+
+    if builder.instrument_kernels {
+        llvm_args.push("--instrument-kernels".to_string());
+    }
+
+    if builder.emit_kernel_params {
+        llvm_args.push("--emit-kernel-params".to_string());
+    }
+
+    for path in &builder.link_bitcode {
+        llvm_args.push("--link-bitcode".to_string());
+        llvm_args.push(path_arg(path)?);
     }
 
     if builder.debug != DebugInfo::None {
@@ -431,11 +557,13 @@ fn invoke_rustc(builder: &CudaBuilder) -> Result<PathBuf, CudaBuilderError> {
     }
 
     // TODO(RDambrosio016): Remove this once we can get meaningful error messages in panic to work.
-    // for now we enable it to remove some useless indirect calls in the ptx.
-    cargo.arg("-Zbuild-std-features=panic_immediate_abort");
+    // for now we enable it to remove some useless indirect calls in the ptx, unless panic messages
+    // were asked for, which are reported by the panic handler.
+    if !builder.panic_messages || builder.optix {
+        cargo.arg("-Zbuild-std-features=panic_immediate_abort");
+    }
 
     if builder.optix {
-        cargo.arg("-Zbuild-std-features=panic_immediate_abort");
         cargo.arg("-Zunstable-options");
         cargo.arg("--config");
         cargo.arg("optix=\"1\"");
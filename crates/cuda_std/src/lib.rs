@@ -24,7 +24,12 @@
 #![allow(internal_features)]
 #![cfg_attr(
     target_os = "cuda",
-    feature(alloc_error_handler, asm_experimental_arch, link_llvm_intrinsics)
+    feature(
+        alloc_error_handler,
+        asm_experimental_arch,
+        core_intrinsics,
+        link_llvm_intrinsics
+    )
 )]
 
 extern crate alloc;
@@ -45,6 +50,8 @@ pub mod thread;
 pub mod warp;
 
 mod float_ext;
+#[cfg(target_arch = "nvptx64")]
+mod panic;
 
 pub use cuda_std_macros::*;
 pub use float::GpuFloat;
@@ -74,54 +81,3 @@ pub mod prelude {
 fn alloc_handler(layout: core::alloc::Layout) -> ! {
     core::panic!("Memory allocation of {} bytes failed", layout.size());
 }
-
-// FIXME(RDambrosio016): For some very odd reason, this function causes an InvalidAddress error when called,
-// despite it having no reason for doing that. It needs more debugging to see what is causing it exactly. For now we just trap.
-#[cfg(target_arch = "nvptx64")]
-#[panic_handler]
-fn panic(_info: &core::panic::PanicInfo) -> ! {
-    // use crate::prelude::*;
-    // let block = thread::block_idx();
-    // let thread = thread::thread_idx();
-
-    // let thread_str = if thread.z == 0 && thread.y == 0 {
-    //     format!("{}", thread.x)
-    // } else if thread.z == 0 {
-    //     format!("({}, {})", thread.x, thread.y)
-    // } else {
-    //     format!("({}, {}, {})", thread.x, thread.y, thread.z)
-    // };
-
-    // let block_str = if block.z == 0 && block.y == 0 {
-    //     format!("{}", block.x)
-    // } else if block.z == 0 {
-    //     format!("({}, {})", block.x, block.y)
-    // } else {
-    //     format!("({}, {}, {})", block.x, block.y, block.z)
-    // };
-
-    // let locstr = if let Some(loc) = info.location() {
-    //     let file = loc.file().to_string();
-    //     let line = loc.line().to_string();
-    //     format!("(in file `{}` at line `{}`\n) ", file, line)
-    // } else {
-    //     String::new()
-    // };
-
-    // // let msg = if let Some(s) = info.payload().downcast_ref::<&str>() {
-    // //     format!(
-    // //         "thread {} in block {} {}panicked: {}",
-    // //         thread, block, locstr, s
-    // //     )
-    // // } else {
-    // //     format!("thread {} in block {} {}panicked", thread, block, locstr)
-    // // };
-
-    // // crate::println!("{}", msg);
-
-    extern "C" {
-        fn __nvvm_trap() -> !;
-    }
-
-    unsafe { __nvvm_trap() };
-}
@@ -47,3 +47,36 @@ pub fn clock() -> u64 {
     }
     clock
 }
+
+/// Returns `a` if `cond` is true and `b` otherwise, without branching.
+///
+/// This always lowers to a predicated `selp` instruction instead of a conditional branch, so
+/// threads of a warp taking different sides never diverge. Both `a` and `b` are always evaluated,
+/// so this is meant for cheap, side-effect-free values such as in a clamp:
+///
+/// ```
+/// # use cuda_std::misc::select;
+/// fn clamp(x: f32, lo: f32, hi: f32) -> f32 {
+///     let x = select(x < lo, lo, x);
+///     select(x > hi, hi, x)
+/// }
+/// # assert_eq!(clamp(5.0, 0.0, 1.0), 1.0);
+/// ```
+///
+/// Plain `if` expressions over scalars are usually turned into selects by the optimizer as well,
+/// but that is not guaranteed, especially at lower optimization levels.
+#[inline(always)]
+pub fn select<T>(cond: bool, a: T, b: T) -> T {
+    #[cfg(target_os = "cuda")]
+    {
+        core::intrinsics::select_unpredictable(cond, a, b)
+    }
+    #[cfg(not(target_os = "cuda"))]
+    {
+        if cond {
+            a
+        } else {
+            b
+        }
+    }
+}
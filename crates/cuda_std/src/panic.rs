@@ -0,0 +1,107 @@
+//! The panic handler for GPU code.
+//!
+//! By default a panic only traps, which aborts the kernel with a `CUDA_ERROR_LAUNCH_FAILED` error
+//! but reports nothing about the panic.
+//!
+//! With the `panic_messages` feature, panics are instead reported through `__assertfail`, so the
+//! formatted panic message and its location are printed along with the block and thread which
+//! panicked, and the kernel launch fails with a `CUDA_ERROR_ASSERT` error which the host sees when
+//! it next synchronizes. This pulls `core::fmt` into every kernel which can panic and has not been
+//! verified on hardware yet, so it is opt-in. The gpu crate must also be built with
+//! `CudaBuilder::panic_messages`, since by default `core` aborts on panic without calling the
+//! handler.
+//!
+//! The message is formatted into a fixed size buffer on the stack rather than with `format!`,
+//! so that panicking does not depend on the device heap, which may itself be the reason for the
+//! panic. Messages longer than the buffer are truncated.
+
+use core::panic::PanicInfo;
+
+extern "C" {
+    fn __nvvm_trap() -> !;
+}
+
+// FIXME(RDambrosio016): For some very odd reason, the previous handler, which formatted the message
+// with `format!`, caused an InvalidAddress error when called, despite it having no reason for doing
+// that. It needs more debugging to see what is causing it exactly. The `panic_messages` handler
+// below formats into a stack buffer instead but has not been checked against it, so by default we
+// just trap.
+#[cfg(not(feature = "panic_messages"))]
+#[panic_handler]
+fn panic(_info: &PanicInfo) -> ! {
+    unsafe { __nvvm_trap() }
+}
+
+#[cfg(feature = "panic_messages")]
+#[panic_handler]
+fn panic(info: &PanicInfo) -> ! {
+    message::report(info);
+    unsafe { __nvvm_trap() }
+}
+
+#[cfg(feature = "panic_messages")]
+mod message {
+    use core::fmt::{self, Write};
+    use core::panic::PanicInfo;
+
+    use crate::io::__assertfail;
+
+    /// The size in bytes of the buffer holding a reported panic message, including the nul
+    /// terminator.
+    const MESSAGE_SIZE: usize = 256;
+    /// The size in bytes of the buffer holding a reported file name, including the nul terminator.
+    const FILE_SIZE: usize = 128;
+
+    /// A nul terminated string in a buffer of `N` bytes which silently truncates anything written
+    /// past its capacity.
+    struct CStrBuf<const N: usize> {
+        bytes: [u8; N],
+        len: usize,
+    }
+
+    impl<const N: usize> CStrBuf<N> {
+        fn new() -> Self {
+            Self {
+                bytes: [0; N],
+                len: 0,
+            }
+        }
+
+        fn as_ptr(&self) -> *const u8 {
+            self.bytes.as_ptr()
+        }
+    }
+
+    impl<const N: usize> Write for CStrBuf<N> {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            // a nul byte would end the string early, leave those out. the last byte of the buffer
+            // is never written to so that the string stays nul terminated.
+            for &byte in s.as_bytes() {
+                if self.len == N - 1 {
+                    break;
+                }
+                if byte != 0 {
+                    self.bytes[self.len] = byte;
+                    self.len += 1;
+                }
+            }
+            Ok(())
+        }
+    }
+
+    /// Reports the message and location of a panic through `__assertfail`.
+    pub(super) fn report(info: &PanicInfo) {
+        let mut message = CStrBuf::<MESSAGE_SIZE>::new();
+        let _ = write!(message, "{}", info.message());
+
+        let mut file = CStrBuf::<FILE_SIZE>::new();
+        let line = match info.location() {
+            Some(location) => {
+                let _ = file.write_str(location.file());
+                location.line()
+            }
+            None => 0,
+        };
+        unsafe { __assertfail(message.as_ptr(), file.as_ptr(), line, b"\0".as_ptr(), 1) }
+    }
+}
@@ -124,3 +124,31 @@ pub unsafe fn convert_specific_address_space_to_generic<T>(
 
     ret
 }
+
+/// The cache level a [`prefetch_global`] should bring a line of memory into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PrefetchLevel {
+    /// Prefetch into the L1 cache (and therefore also L2).
+    L1,
+    /// Prefetch into the L2 cache only.
+    L2,
+}
+
+/// Prefetches the cache line containing `ptr` from global memory into the specified cache level.
+/// This maps directly to the [`prefetch`](https://docs.nvidia.com/cuda/parallel-thread-execution/index.html#data-movement-and-conversion-instructions-prefetch-prefetchu) PTX instruction.
+///
+/// `core::intrinsics::prefetch_read_data` and `prefetch_write_data` are also lowered to `prefetch`,
+/// with a locality of `2` or `3` selecting L1 and anything lower selecting L2.
+///
+/// # Safety
+///
+/// The pointer must be a generic pointer which falls in the global address space.
+#[gpu_only]
+pub unsafe fn prefetch_global<T>(ptr: *const T, level: PrefetchLevel) {
+    let ptr = convert_generic_to_specific_address_space(ptr, AddressSpace::Global);
+
+    match level {
+        PrefetchLevel::L1 => asm!("prefetch.global.L1 [{}];", in(reg64) ptr),
+        PrefetchLevel::L2 => asm!("prefetch.global.L2 [{}];", in(reg64) ptr),
+    }
+}
@@ -38,6 +38,18 @@ use crate::gpu_only;
 /// It is suggested to run your executable in `cuda-memcheck` to make sure usages of
 /// shared memory are right.
 ///
+/// # Alignment
+///
+/// The buffer is aligned to the alignment of `array_type`. A larger alignment can be requested
+/// with `shared_array![array_type; len, align = N]`, where `N` is a power of two. This is
+/// useful for vectorized accesses, such as reading four `f32`s at a time as a 16 byte value.
+///
+/// # Generic Element Types
+///
+/// The element type may depend on generic parameters, in which case every instantiation of the
+/// generic function gets its own buffer, sized for the element type it was instantiated with.
+/// `len` itself must not depend on generic parameters, use a constant such as a tile size instead.
+///
 /// # Examples
 ///
 /// ```no_run
@@ -53,40 +65,122 @@ use crate::gpu_only;
 ///    thread::sync_threads();
 ///    *d.add(t) = *s.add(tr);
 /// }
+///
+/// // statics are aligned to their type as well, they are declared as `.const .align 16` and
+/// // `.global .align 8` in the PTX.
+/// ##[repr(C, align(16))]
+/// struct Vec4([f32; 4]);
+/// static SCALE: Vec4 = Vec4([1.0, 2.0, 3.0, 4.0]);
+/// static mut TOTAL: f64 = 0.0;
+///
+/// ##[kernel]
+/// pub unsafe fn aligned(d: *mut f32) {
+///    // 8 byte aligned because of the element type, and 16 byte aligned on request. These are
+///    // `.shared .align 8 .b8 a[256]` and `.shared .align 16 .b8 b[256]` in the PTX, with the
+///    // names shortened.
+///    let a = shared_array![f64; 32];
+///    let b = shared_array![f32; 64, align = 16];
+///    assert_eq!(a as usize % 8, 0);
+///    assert_eq!(b as usize % 16, 0);
+///    assert_eq!(&SCALE as *const Vec4 as usize % 16, 0);
+///    assert_eq!(&raw const TOTAL as usize % 8, 0);
+/// }
+///
+/// const TILE: usize = 64;
+///
+/// // every instantiation gets its own buffer of `TILE` elements, such as 256 bytes for `f32` and
+/// // 512 bytes for `f64`.
+/// unsafe fn reverse_tile<T: Copy>(d: *mut T) {
+///    let s = shared_array![T; TILE];
+///    let t = thread::thread_idx_x() as usize;
+///    *s.add(t) = *d.add(t);
+///    thread::sync_threads();
+///    *d.add(t) = *s.add(TILE - t - 1);
+/// }
+///
+/// ##[kernel]
+/// pub unsafe fn reverse_tiles(a: *mut f32, b: *mut f64) {
+///    reverse_tile(a);
+///    reverse_tile(b);
+/// }
+/// # // the names the macro uses internally do not clash with the element type.
+/// # struct Site(f32);
+/// # unsafe fn site() -> *mut Site {
+/// #    shared_array![Site; 4]
+/// # }
 /// ```
 #[macro_export]
 macro_rules! shared_array {
+    ($array_type:ty; $len:expr, align = $align:literal) => {{
+        // a type unique to this invocation, so it gets its own buffer.
+        struct __SharedArraySite;
+        $crate::shared::__shared_array::<$array_type, { $len }, $align, __SharedArraySite>()
+            .cast::<$array_type>()
+    }};
     ($array_type:ty; $len:expr) => {{
-        #[$crate::gpu_only]
-        #[inline(always)]
-        fn shared_array() -> *mut $array_type {
-            use ::core::{cell::UnsafeCell, mem::MaybeUninit};
-            struct SyncWrapper(UnsafeCell<MaybeUninit<[$array_type; $len]>>);
-            // SAFETY: it is up to the user to verify sound shared memory usage, we cannot
-            // fundamentally check it for soundness.
-            unsafe impl Send for SyncWrapper {}
-            // SAFETY: see above
-            unsafe impl Sync for SyncWrapper {}
-
-            // the initializer is discarded when declaring shared globals, so it is unimportant.
-            #[$crate::address_space(shared)]
-            static SHARED: SyncWrapper = SyncWrapper(UnsafeCell::new(MaybeUninit::uninit()));
-
-            SHARED.0.get() as *mut $array_type
-        }
-        shared_array()
+        // a type unique to this invocation, so it gets its own buffer.
+        struct __SharedArraySite;
+        $crate::shared::__shared_array::<$array_type, { $len }, 1, __SharedArraySite>()
+            .cast::<$array_type>()
     }};
 }
 
-/// Gets a pointer to the dynamic shared memory that was allocated by the caller of the kernel. The
-/// data is left uninitialized.
+/// Implementation detail of [`shared_array!`].
 ///
-/// **Calling this function multiple times will yield the same pointer**.
+/// The codegen defines every instance of this function as returning a pointer to its own shared
+/// memory global of type `[T; N]`, aligned to at least `ALIGN`. Statics cannot be generic, so
+/// this is what lets `shared_array!` be used with generic element types. `Site` is a type unique
+/// to each invocation of the macro.
+#[doc(hidden)]
 #[gpu_only]
-pub fn dynamic_shared_mem<T>() -> *mut T {
-    // it is unclear whether an alignment of 16 is actually required for correctness, however,
-    // it seems like nvcc always generates the global with .align 16 no matter the type, so we just copy
-    // nvcc's behavior for now.
+#[inline(never)]
+#[cfg_attr(target_os = "cuda", nvvm_internal::shared_array)]
+pub fn __shared_array<T, const N: usize, const ALIGN: usize, Site>() -> *mut [T; N] {
+    const {
+        assert!(
+            ALIGN.is_power_of_two(),
+            "shared array alignment must be a power of two"
+        )
+    };
+    unreachable!("the body of shared arrays is defined by the codegen")
+}
+
+/// Gets a pointer to the dynamic shared memory of the block, the equivalent of an
+/// `extern __shared__` array in CUDA C++. The data is left uninitialized.
+///
+/// Its size is the number of bytes of dynamic shared memory the kernel was launched with, such as
+/// the `shared_mem_bytes` of cust's `LaunchConfig` or the third parameter of `launch!`, which lets
+/// the size of tiles be chosen at runtime. The memory is aligned to 16 bytes.
+///
+/// **Calling this function multiple times will yield the same pointer**, so separate buffers
+/// must be carved out of the memory by offsetting the pointer.
+///
+/// The safety requirements of accessing the memory are those of [`shared_array!`], and no
+/// access may be past the size given at launch.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use cuda_std::kernel;
+/// # use cuda_std::shared::dynamic_shared_memory;
+/// # use cuda_std::thread;
+/// // launched with `n * size_of::<f32>()` bytes of dynamic shared memory and `n` threads.
+/// ##[kernel]
+/// pub unsafe fn reverse(d: *mut f32, n: usize) {
+///    let s = dynamic_shared_memory::<f32>();
+///    let t = thread::thread_idx_x() as usize;
+///    *s.add(t) = *d.add(t);
+///    thread::sync_threads();
+///    *d.add(t) = *s.add(n - t - 1);
+/// }
+/// ```
+#[gpu_only]
+pub fn dynamic_shared_memory<T>() -> *mut T {
+    // nvcc always declares the dynamic shared memory with .align 16 no matter the type, so we just
+    // copy its behavior. Declarations of statics carry the alignment of their type.
+    #[repr(C, align(16))]
+    struct DynamicShared(::core::cell::UnsafeCell<u128>);
+
     extern "C" {
         // need to use nvvm_internal and not address_space because address_space only parses
         // static definitions, not extern static definitions.
@@ -94,10 +188,54 @@ pub fn dynamic_shared_mem<T>() -> *mut T {
         #[allow(improper_ctypes)]
         // mangle it a bit to make sure nobody makes the same thing
         #[link_name = "_Zcuda_std_dyn_shared"]
-        static DYN_SHARED: ::core::cell::UnsafeCell<u128>;
+        static DYN_SHARED: DynamicShared;
     }
 
     // SAFETY: extern statics is how dynamic shared mem is done in CUDA. This will turn into
     // an extern variable decl in ptx, which is the same thing nvcc does if you dump the ptx from a cuda file.
-    unsafe { DYN_SHARED.get() as *mut T }
+    unsafe { DYN_SHARED.0.get() as *mut T }
+}
+
+/// Gets the first `len` elements of the dynamic shared memory of the block as a slice, see
+/// [`dynamic_shared_memory`].
+///
+/// # Safety
+///
+/// Behavior is undefined if:
+///  - `len` elements of `T` are larger than the dynamic shared memory the kernel was launched
+///    with, or `T` needs an alignment larger than 16 bytes.
+///  - Elements are read before they were written to, the memory is uninitialized.
+///  - An element is accessed by a thread while another thread of the block writes to it. Every
+///    thread of the block gets the same slice, so writes from other threads only become visible
+///    after [`thread::sync_threads`](crate::thread::sync_threads), and the slice must not be
+///    used in the meantime for the elements other threads write.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use cuda_std::kernel;
+/// # use cuda_std::shared::dynamic_shared_slice;
+/// # use cuda_std::thread;
+/// ##[kernel]
+/// pub unsafe fn block_sums(d: *const u32, sums: *mut u32) {
+///    let n = thread::block_dim_x() as usize;
+///    let t = thread::thread_idx_x() as usize;
+///    let s = dynamic_shared_slice::<u32>(n);
+///    s[t] = *d.add(thread::index_1d() as usize);
+///    thread::sync_threads();
+///    if t == 0 {
+///        *sums.add(thread::block_idx_x() as usize) = s.iter().sum();
+///    }
+/// }
+/// ```
+#[gpu_only]
+pub unsafe fn dynamic_shared_slice<'a, T>(len: usize) -> &'a mut [T] {
+    unsafe { core::slice::from_raw_parts_mut(dynamic_shared_memory::<T>(), len) }
+}
+
+/// Gets a pointer to the dynamic shared memory that was allocated by the caller of the kernel.
+#[deprecated(note = "renamed to `dynamic_shared_memory`")]
+#[gpu_only]
+pub fn dynamic_shared_mem<T>() -> *mut T {
+    dynamic_shared_memory()
 }
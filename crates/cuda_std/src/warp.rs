@@ -622,6 +622,85 @@ pub unsafe fn warp_shuffle_xor<T: WarpShuffleValue>(
     T::shuffle(WarpShuffleMode::Xor, mask, value, lane_mask, width)
 }
 
+/// Returns `value` of lane `src_lane` of the warp, like CUDA's `__shfl_sync`.
+///
+/// This is [`warp_shuffle_idx`] over the whole warp, without the target lane's
+/// activity. Any [`WarpShuffleValue`] can be shuffled, values wider than 32 bits are
+/// shuffled as multiple 32-bit words.
+///
+/// # Safety
+///
+/// Behavior is undefined if any thread in `mask` has exited or the executing thread is
+/// not in `mask`. See [`warp_shuffle_idx`] for more details.
+#[inline(always)]
+pub unsafe fn shfl_sync<T: WarpShuffleValue>(mask: u32, value: T, src_lane: u32) -> T {
+    warp_shuffle_idx(mask, value, src_lane, 32).0
+}
+
+/// Returns `value` of the lane `delta` lanes below this one, like CUDA's
+/// `__shfl_up_sync`. The lowest `delta` lanes get their own `value` back.
+///
+/// This is [`warp_shuffle_up`] over the whole warp, see [`shfl_sync`] for the types
+/// which can be shuffled.
+///
+/// # Safety
+///
+/// Behavior is undefined if any thread in `mask` has exited or the executing thread is
+/// not in `mask`. See [`warp_shuffle_up`] for more details.
+#[inline(always)]
+pub unsafe fn shfl_up_sync<T: WarpShuffleValue>(mask: u32, value: T, delta: u32) -> T {
+    warp_shuffle_up(mask, value, delta, 32).0
+}
+
+/// Returns `value` of the lane `delta` lanes above this one, like CUDA's
+/// `__shfl_down_sync`. The highest `delta` lanes get their own `value` back.
+///
+/// This is [`warp_shuffle_down`] over the whole warp, see [`shfl_sync`] for the types
+/// which can be shuffled.
+///
+/// # Safety
+///
+/// Behavior is undefined if any thread in `mask` has exited or the executing thread is
+/// not in `mask`. See [`warp_shuffle_down`] for more details.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use cuda_std::kernel;
+/// # use cuda_std::thread;
+/// # use cuda_std::warp::{lane_id, shfl_down_sync};
+/// // sums every warp's values, the first lane of the warp ends up with the sum.
+/// ##[kernel]
+/// pub unsafe fn warp_sums(values: *const f32, sums: *mut f32) {
+///    let idx = thread::index_1d() as usize;
+///    let mut value = *values.add(idx);
+///    for delta in [16, 8, 4, 2, 1] {
+///        value += shfl_down_sync(u32::MAX, value, delta);
+///    }
+///    if lane_id() == 0 {
+///        *sums.add(idx / 32) = value;
+///    }
+/// }
+/// ```
+#[inline(always)]
+pub unsafe fn shfl_down_sync<T: WarpShuffleValue>(mask: u32, value: T, delta: u32) -> T {
+    warp_shuffle_down(mask, value, delta, 32).0
+}
+
+/// Returns `value` of lane `lane_id() ^ lane_mask`, like CUDA's `__shfl_xor_sync`.
+///
+/// This is [`warp_shuffle_xor`] over the whole warp, see [`shfl_sync`] for the types
+/// which can be shuffled. A butterfly reduction with it leaves the result in every lane.
+///
+/// # Safety
+///
+/// Behavior is undefined if any thread in `mask` has exited or the executing thread is
+/// not in `mask`. See [`warp_shuffle_xor`] for more details.
+#[inline(always)]
+pub unsafe fn shfl_xor_sync<T: WarpShuffleValue>(mask: u32, value: T, lane_mask: u32) -> T {
+    warp_shuffle_xor(mask, value, lane_mask, 32).0
+}
+
 /// A value that can be used in a warp shuffle
 pub trait WarpShuffleValue: Sized {
     /// Executes the shuffle, note that `mode` must be a constant value.
@@ -748,14 +827,17 @@ unsafe fn warp_shuffle_32(
     }
 
     assert!(
-        !(width & (width - 1)) != 0 && width <= 32,
+        width.is_power_of_two() && width <= 32,
         "width must be a power of 2 and less than or equal to 32"
     );
 
-    // mimicking nvcc's behavior
-    let mut c = 0;
-    c |= 0b11111;
-    c |= (32 - width) << 8;
+    // mimicking nvcc's behavior, the segment mask goes in the upper byte and the clamp
+    // lane in the lower one. Up shuffles clamp to the first lane of the segment and the
+    // others to the last one.
+    let mut c = (32 - width) << 8;
+    if !matches!(mode, WarpShuffleMode::Up) {
+        c |= 0b11111;
+    }
 
     __nvvm_warp_shuffle(mask, mode as u32, value, b, c)
 }
@@ -1,9 +1,9 @@
 use proc_macro::TokenStream;
-use proc_macro2::Span;
+use proc_macro2::{Literal, Span};
 use quote::{quote_spanned, ToTokens};
 use syn::{
-    parse::Parse, parse_macro_input, parse_quote, punctuated::Punctuated, spanned::Spanned, Error,
-    FnArg, Ident, ItemFn, ReturnType, Stmt, Token,
+    parenthesized, parse::Parse, parse_macro_input, parse_quote, punctuated::Punctuated,
+    spanned::Spanned, Error, FnArg, Ident, ItemFn, LitInt, ReturnType, Stmt, Token,
 };
 
 /// Registers a function as a gpu kernel.
@@ -19,10 +19,23 @@ use syn::{
 ///
 /// Note that this does not cfg the function for nvptx(64), that is explicit so that rust analyzer is able to
 /// offer intellisense by default.
+///
+/// # Launch bounds
+///
+/// `#[kernel(launch_bounds(max_threads = 256, min_blocks = 2))]` is the equivalent of CUDA C++'s
+/// `__launch_bounds__(256, 2)`: it promises that the kernel is never launched with more than
+/// `max_threads` threads per block, and optionally asks for at least `min_blocks` blocks to fit on
+/// a multiprocessor at once. The compiler then limits the registers used by the kernel so that
+/// this many threads and blocks fit, spilling the rest to local memory if needed.
+///
+/// Launching the kernel with more than `max_threads` threads per block fails. The occupancy API of
+/// cust (`Function::max_active_blocks_per_multiprocessor` and
+/// `Function::suggested_launch_configuration`) works on the compiled kernel, so it accounts for the
+/// capped register usage, and the suggested block size never exceeds `max_threads`.
 #[proc_macro_attribute]
 pub fn kernel(input: proc_macro::TokenStream, item: proc_macro::TokenStream) -> TokenStream {
     let cloned = input.clone();
-    let _ = parse_macro_input!(input as KernelHints);
+    let hints = parse_macro_input!(input as KernelHints);
     let input = parse_macro_input!(cloned as proc_macro2::TokenStream);
     let mut item = parse_macro_input!(item as ItemFn);
     let no_mangle = parse_quote!(#[no_mangle]);
@@ -30,6 +43,15 @@ pub fn kernel(input: proc_macro::TokenStream, item: proc_macro::TokenStream) ->
     let internal = parse_quote!(#[cfg_attr(target_arch="nvptx64", nvvm_internal::kernel(#input))]);
     item.attrs.push(internal);
 
+    if let Some(bounds) = hints.launch_bounds {
+        let max_threads = Literal::u32_unsuffixed(bounds.max_threads);
+        let min_blocks = bounds.min_blocks.map(Literal::u32_unsuffixed).into_iter();
+        let launch_bounds = parse_quote! {
+            #[cfg_attr(target_arch="nvptx64", nvvm_internal::launch_bounds(#max_threads #(, #min_blocks)*))]
+        };
+        item.attrs.push(launch_bounds);
+    }
+
     // used to guarantee some things about how params are passed in the codegen.
     item.sig.abi = Some(parse_quote!(extern "C"));
 
@@ -38,6 +60,7 @@ pub fn kernel(input: proc_macro::TokenStream, item: proc_macro::TokenStream) ->
     };
     item.block.stmts.insert(0, check_fn);
 
+    let mut calls: Vec<Stmt> = vec![];
     for param in &item.sig.inputs {
         let ty = match param {
             FnArg::Receiver(_) => quote_spanned! {
@@ -45,10 +68,26 @@ pub fn kernel(input: proc_macro::TokenStream, item: proc_macro::TokenStream) ->
             },
             FnArg::Typed(ty) => ty.ty.to_token_stream(),
         };
-        let call = parse_quote! {
+        calls.push(parse_quote! {
             assert_kernel_parameter_is_copy::<#ty>();
+        });
+    }
+
+    // the body of naked functions may only contain their `naked_asm!` block and items, so the
+    // checks go in a function that is never called.
+    let is_naked = item.attrs.iter().any(|attr| attr.path().is_ident("naked"));
+    if is_naked {
+        let checks = parse_quote! {
+            #[allow(dead_code)]
+            fn assert_kernel_parameters_are_copy() {
+                #(#calls)*
+            }
         };
-        item.block.stmts.insert(0, call);
+        item.block.stmts.insert(0, checks);
+    } else {
+        for call in calls {
+            item.block.stmts.insert(0, call);
+        }
     }
 
     let ret = item.sig.output.clone();
@@ -91,15 +130,64 @@ impl Parse for Dimension {
     }
 }
 
+/// `launch_bounds(max_threads = 256, min_blocks = 2)`, `min_blocks` is optional.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct LaunchBounds {
+    max_threads: u32,
+    min_blocks: Option<u32>,
+}
+
+impl Parse for LaunchBounds {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let span = input.span();
+        let mut max_threads = None;
+        let mut min_blocks = None;
+
+        let iter =
+            Punctuated::<(Ident, LitInt), Token![,]>::parse_terminated_with(input, |input| {
+                let name = Ident::parse(input)?;
+                <Token![=]>::parse(input)?;
+                Ok((name, input.parse()?))
+            })?;
+        for (name, value) in iter {
+            let value = value.base10_parse::<u32>()?;
+            if value == 0 {
+                return Err(Error::new(
+                    name.span(),
+                    "Launch bounds must be greater than zero",
+                ));
+            }
+            match name.to_string().as_str() {
+                "max_threads" => max_threads = Some(value),
+                "min_blocks" => min_blocks = Some(value),
+                _ => return Err(Error::new(name.span(), "Unrecognized launch bound")),
+            }
+        }
+
+        let max_threads =
+            max_threads.ok_or_else(|| Error::new(span, "Launch bounds require `max_threads`"))?;
+        Ok(Self {
+            max_threads,
+            min_blocks,
+        })
+    }
+}
+
 enum KernelHint {
     GridDim(Dimension),
     BlockDim(Dimension),
+    LaunchBounds(LaunchBounds),
 }
 
 impl Parse for KernelHint {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
         let name = Ident::parse(input)?;
         let key = name.to_string();
+        if key == "launch_bounds" {
+            let content;
+            parenthesized!(content in input);
+            return Ok(Self::LaunchBounds(content.parse()?));
+        }
         <Token![=]>::parse(input)?;
         match key.as_str() {
             "grid_dim" => {
@@ -119,6 +207,7 @@ impl Parse for KernelHint {
 struct KernelHints {
     grid_dim: Option<Dimension>,
     block_dim: Option<Dimension>,
+    launch_bounds: Option<LaunchBounds>,
 }
 
 impl Parse for KernelHints {
@@ -135,6 +224,7 @@ impl Parse for KernelHints {
             match hint {
                 KernelHint::GridDim(dim) => out.grid_dim = Some(dim),
                 KernelHint::BlockDim(dim) => out.block_dim = Some(dim),
+                KernelHint::LaunchBounds(bounds) => out.launch_bounds = Some(bounds),
             }
         }
 
@@ -231,3 +321,62 @@ pub fn address_space(attr: proc_macro::TokenStream, item: proc_macro::TokenStrea
 
     global.into_token_stream().into()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn launch_bounds(hints: &str) -> syn::Result<Option<LaunchBounds>> {
+        syn::parse_str::<KernelHints>(hints).map(|hints| hints.launch_bounds)
+    }
+
+    #[test]
+    fn parses_launch_bounds() {
+        assert_eq!(
+            launch_bounds("launch_bounds(max_threads = 256, min_blocks = 2)").unwrap(),
+            Some(LaunchBounds {
+                max_threads: 256,
+                min_blocks: Some(2),
+            })
+        );
+        assert_eq!(
+            launch_bounds("launch_bounds(min_blocks = 2, max_threads = 256,)").unwrap(),
+            Some(LaunchBounds {
+                max_threads: 256,
+                min_blocks: Some(2),
+            })
+        );
+        assert_eq!(
+            launch_bounds("launch_bounds(max_threads = 128)").unwrap(),
+            Some(LaunchBounds {
+                max_threads: 128,
+                min_blocks: None,
+            })
+        );
+        assert_eq!(launch_bounds("").unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_zero_launch_bounds() {
+        let err = launch_bounds("launch_bounds(max_threads = 0)").unwrap_err();
+        assert_eq!(err.to_string(), "Launch bounds must be greater than zero");
+
+        let err = launch_bounds("launch_bounds(max_threads = 256, min_blocks = 0)").unwrap_err();
+        assert_eq!(err.to_string(), "Launch bounds must be greater than zero");
+    }
+
+    #[test]
+    fn rejects_unknown_launch_bounds() {
+        let err = launch_bounds("launch_bounds(max_threads = 256, max_blocks = 2)").unwrap_err();
+        assert_eq!(err.to_string(), "Unrecognized launch bound");
+    }
+
+    #[test]
+    fn requires_max_threads() {
+        let err = launch_bounds("launch_bounds(min_blocks = 2)").unwrap_err();
+        assert_eq!(err.to_string(), "Launch bounds require `max_threads`");
+
+        let err = launch_bounds("launch_bounds()").unwrap_err();
+        assert_eq!(err.to_string(), "Launch bounds require `max_threads`");
+    }
+}
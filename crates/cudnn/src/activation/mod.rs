@@ -1,7 +1,8 @@
 use cust::memory::GpuBuffer;
 
 use crate::{
-    private, CudnnContext, CudnnError, DataType, IntoResult, ScalingDataType, TensorDescriptor,
+    private, BinaryOp, BinaryOpTensorDescriptor, CudnnContext, CudnnError, DataType, IntoResult,
+    NanPropagation, ScalingDataType, SupportedOp, TensorDescriptor,
 };
 
 mod activation_descriptor;
@@ -102,6 +103,160 @@ impl CudnnContext {
         }
     }
 
+    /// Applies a per-channel scale and bias followed by a neuron activation function, computing
+    /// `y = act(alpha * scale * x + beta * bias)`.
+    ///
+    /// This is the affine transformation and activation following the convolutions of modern
+    /// CNNs, for instance with a batch normalization folded into `scale` and `bias` for inference.
+    ///
+    /// **Do note** that this is not fused, it queues three cuDNN calls: `cudnnAddTensor`
+    /// broadcasts the bias into `y`, `cudnnOpTensor` multiplies `x` by `scale` and accumulates the
+    /// product onto it, and `cudnnActivationForward` runs the activation in place. It saves
+    /// writing them by hand and allocates no intermediate buffer, but `y` is written three times.
+    ///
+    /// # Arguments
+    ///
+    ///   * `activation_desc` - descriptor of the activation.
+    ///   * `alpha` - scaling factor for the product of the input and the scale.
+    ///   * `x_desc` - tensor descriptor for the input.
+    ///   * `x` - data for the input.
+    ///   * `scale_bias_desc` - tensor descriptor shared by the scale and bias. Each of its
+    ///     dimensions must either match the corresponding dimension of `x_desc` or be 1, so it is
+    ///     1xCx1x1 for per-channel parameters.
+    ///   * `scale` - data for the scale.
+    ///   * `beta` - scaling factor for the bias.
+    ///   * `bias` - data for the bias.
+    ///   * `y_desc` - tensor descriptor for the output, must have the shape of `x_desc`.
+    ///   * `y` - data for the output.
+    ///
+    /// The scaling factors are `f32` for `f16` tensors, see [`SupportedOp`].
+    ///
+    /// cuDNN [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnOpTensor)
+    /// may offer additional information about the APi behavior.
+    ///
+    /// # Errors
+    ///
+    /// Returns errors if the shapes of the tensors do not match or an unsupported configuration
+    /// of arguments is detected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use cudnn::{ActivationDescriptor, ActivationMode, CudnnContext, NanPropagation, ScalarC, TensorDescriptor};
+    /// use cust::memory::DeviceBuffer;
+    ///
+    /// let ctx = CudnnContext::new()?;
+    ///
+    /// let relu = ActivationDescriptor::new(ActivationMode::Relu, NanPropagation::PropagateNaN, None)?;
+    ///
+    /// let x_desc = TensorDescriptor::<f32>::new_format(&[1, 2, 1, 2], ScalarC::Nchw)?;
+    /// let x = DeviceBuffer::<f32>::from_slice(&[1.0, 2.0, 3.0, 4.0])?;
+    ///
+    /// let scale_bias_desc = TensorDescriptor::<f32>::new_format(&[1, 2, 1, 1], ScalarC::Nchw)?;
+    /// let scale = DeviceBuffer::<f32>::from_slice(&[2.0, -1.0])?;
+    /// let bias = DeviceBuffer::<f32>::from_slice(&[1.0, 0.0])?;
+    ///
+    /// let y_desc = TensorDescriptor::<f32>::new_format(&[1, 2, 1, 2], ScalarC::Nchw)?;
+    /// let mut y = DeviceBuffer::<f32>::zeroed(4)?;
+    ///
+    /// ctx.scale_bias_activation_forward(
+    ///     &relu,
+    ///     1.0,
+    ///     &x_desc,
+    ///     &x,
+    ///     &scale_bias_desc,
+    ///     &scale,
+    ///     1.0,
+    ///     &bias,
+    ///     &y_desc,
+    ///     &mut y,
+    /// )?;
+    ///
+    /// assert_eq!(y.as_host_vec()?, [3.0, 5.0, 0.0, 0.0]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub fn scale_bias_activation_forward<CompT, T>(
+        &self,
+        activation_desc: &ActivationDescriptor,
+        alpha: CompT,
+        x_desc: &TensorDescriptor<T>,
+        x: &impl GpuBuffer<T>,
+        scale_bias_desc: &TensorDescriptor<T>,
+        scale: &impl GpuBuffer<T>,
+        beta: CompT,
+        bias: &impl GpuBuffer<T>,
+        y_desc: &TensorDescriptor<T>,
+        y: &mut impl GpuBuffer<T>,
+    ) -> Result<(), CudnnError>
+    where
+        CompT: SupportedOp<T, T, T> + ScalingDataType<T> + From<f32>,
+        T: DataType,
+    {
+        let mul_desc =
+            BinaryOpTensorDescriptor::<CompT>::new(BinaryOp::Mul, NanPropagation::PropagateNaN)?;
+
+        let one = CompT::from(1.0);
+        let zero = CompT::from(0.0);
+
+        let alpha = &alpha as *const CompT as *const std::ffi::c_void;
+        let beta = &beta as *const CompT as *const std::ffi::c_void;
+        let one = &one as *const CompT as *const std::ffi::c_void;
+        let zero = &zero as *const CompT as *const std::ffi::c_void;
+
+        let x_ptr = x.as_device_ptr().as_ptr() as *const _;
+        let scale_ptr = scale.as_device_ptr().as_ptr() as *const _;
+        let bias_ptr = bias.as_device_ptr().as_ptr() as *const _;
+        let y_ptr = y.as_device_ptr().as_mut_ptr() as *mut std::ffi::c_void;
+
+        unsafe {
+            // broadcasts the bias into y, discarding its previous contents.
+            cudnn_sys::cudnnAddTensor(
+                self.raw,
+                beta,
+                scale_bias_desc.raw,
+                bias_ptr,
+                zero,
+                y_desc.raw,
+                y_ptr,
+            )
+            .into_result()?;
+
+            // y = alpha * x * scale + y, so the product and the bias are summed by the same op.
+            cudnn_sys::cudnnOpTensor(
+                self.raw,
+                mul_desc.raw,
+                alpha,
+                x_desc.raw,
+                x_ptr,
+                one,
+                scale_bias_desc.raw,
+                scale_ptr,
+                one,
+                y_desc.raw,
+                y_ptr,
+            )
+            .into_result()?;
+
+            // cudnnActivationForward supports running in place.
+            cudnn_sys::cudnnActivationForward(
+                self.raw,
+                activation_desc.raw,
+                one,
+                y_desc.raw,
+                y_ptr,
+                zero,
+                y_desc.raw,
+                y_ptr,
+            )
+            .into_result()
+        }
+    }
+
     /// Computes the gradient of a neuron activation function.
     ///
     /// # Arguments
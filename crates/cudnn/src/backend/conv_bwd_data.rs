@@ -50,11 +50,11 @@ impl ConvBwdDataBuilder {
     }
 
     pub fn build(self) -> Result<Operation, CudnnError> {
-        let cfg = self.cfg.expect("convolution configuration is required.");
+        let cfg = self.cfg.ok_or(CudnnError::BadParam)?;
 
-        let w = self.w.expect("w tensor is required");
-        let dx = self.dx.expect("dx tensor is required.");
-        let dy = self.dy.expect("dy tensor is required.");
+        let w = self.w.ok_or(CudnnError::BadParam)?;
+        let dx = self.dx.ok_or(CudnnError::BadParam)?;
+        let dy = self.dy.ok_or(CudnnError::BadParam)?;
 
         let alpha = self.alpha.unwrap_or(Real::Float(1.0));
         let beta = self.beta.unwrap_or(Real::Float(0.0));
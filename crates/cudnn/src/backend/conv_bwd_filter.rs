@@ -50,10 +50,10 @@ impl ConvBwdFilterBuilder {
     }
 
     pub fn build(self) -> Result<Operation, CudnnError> {
-        let cfg = self.cfg.expect("convolution configuration is required.");
-        let dw = self.dw.expect("dw tensor is required");
-        let x = self.x.expect("x tensor is required.");
-        let dy = self.dy.expect("dy tensor is required.");
+        let cfg = self.cfg.ok_or(CudnnError::BadParam)?;
+        let dw = self.dw.ok_or(CudnnError::BadParam)?;
+        let x = self.x.ok_or(CudnnError::BadParam)?;
+        let dy = self.dy.ok_or(CudnnError::BadParam)?;
 
         let alpha = self.alpha.unwrap_or(Real::Float(1.0));
         let beta = self.beta.unwrap_or(Real::Float(0.0));
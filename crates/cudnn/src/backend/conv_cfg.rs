@@ -45,12 +45,12 @@ impl<'a> ConvCfgBuilder<'a> {
     }
 
     pub fn build(self) -> Result<ConvCfg, CudnnError> {
-        let comp_type = self.comp_type.expect("computation type is required");
-        let mode = self.mode.expect("convolution mode is required");
-        let dilations = self.dilations.expect("dilations are required");
-        let strides = self.strides.expect("strides are required");
-        let pre_paddings = self.pre_paddings.expect("pre-paddings are required.");
-        let post_paddings = self.post_paddings.expect("post-paddings are required.");
+        let comp_type = self.comp_type.ok_or(CudnnError::BadParam)?;
+        let mode = self.mode.ok_or(CudnnError::BadParam)?;
+        let dilations = self.dilations.ok_or(CudnnError::BadParam)?;
+        let strides = self.strides.ok_or(CudnnError::BadParam)?;
+        let pre_paddings = self.pre_paddings.ok_or(CudnnError::BadParam)?;
+        let post_paddings = self.post_paddings.ok_or(CudnnError::BadParam)?;
 
         unsafe {
             let mut raw = Descriptor::new(
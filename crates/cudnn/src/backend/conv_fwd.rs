@@ -50,11 +50,11 @@ impl ConvFwdBuilder {
     }
 
     pub fn build(self) -> Result<Operation, CudnnError> {
-        let cfg = self.cfg.expect("convolution configuration is required.");
+        let cfg = self.cfg.ok_or(CudnnError::BadParam)?;
 
-        let w = self.w.expect("w tensor is required");
-        let x = self.x.expect("x tensor is required.");
-        let y = self.y.expect("y tensor is required.");
+        let w = self.w.ok_or(CudnnError::BadParam)?;
+        let x = self.x.ok_or(CudnnError::BadParam)?;
+        let y = self.y.ok_or(CudnnError::BadParam)?;
 
         let alpha = self.alpha.unwrap_or(Real::Float(1.0));
         let beta = self.beta.unwrap_or(Real::Float(0.0));
@@ -21,8 +21,8 @@ impl EngineBuilder {
     }
 
     pub fn build(self) -> Result<Engine, CudnnError> {
-        let graph = self.graph.expect("operation graph is required");
-        let global_index = self.global_index.expect("global index is required.");
+        let graph = self.graph.ok_or(CudnnError::BadParam)?;
+        let global_index = self.global_index.ok_or(CudnnError::BadParam)?;
 
         unsafe {
             let mut descriptor = Descriptor::new(
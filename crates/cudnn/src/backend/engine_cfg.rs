@@ -21,7 +21,7 @@ impl EngineCfgBuilder {
     }
 
     pub fn build(self) -> Result<EngineCfg, CudnnError> {
-        let engine = self.engine.expect("engine is required.");
+        let engine = self.engine.ok_or(CudnnError::BadParam)?;
 
         unsafe {
             let mut descriptor = match self.descriptor {
@@ -15,7 +15,7 @@ impl ExecutionPlanBuilder {
     }
 
     pub fn build(self) -> Result<ExecutionPlan, CudnnError> {
-        let engine_cfg = self.engine_cfg.expect("engine configuration is required.");
+        let engine_cfg = self.engine_cfg.ok_or(CudnnError::BadParam)?;
 
         unsafe {
             let mut descriptor = Descriptor::new(
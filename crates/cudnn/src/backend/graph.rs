@@ -21,8 +21,8 @@ impl GraphBuilder {
     }
 
     pub fn build(self) -> Result<Graph, CudnnError> {
-        let context = self.context.expect("cudnn context is required.");
-        let operations = self.operations.expect("operations are required");
+        let context = self.context.ok_or(CudnnError::BadParam)?;
+        let operations = self.operations.ok_or(CudnnError::BadParam)?;
 
         unsafe {
             let mut descriptor = Descriptor::new(
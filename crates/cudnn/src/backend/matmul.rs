@@ -35,10 +35,10 @@ impl MatMulBuilder {
     }
 
     pub fn build(self) -> Result<Operation, CudnnError> {
-        let a = self.a.expect("a matrix is required.");
-        let b = self.b.expect("b matrix is required");
-        let c = self.c.expect("c matrix is required");
-        let cfg = self.cfg.expect("matmul configuration is required.");
+        let a = self.a.ok_or(CudnnError::BadParam)?;
+        let b = self.b.ok_or(CudnnError::BadParam)?;
+        let c = self.c.ok_or(CudnnError::BadParam)?;
+        let cfg = self.cfg.ok_or(CudnnError::BadParam)?;
 
         unsafe {
             let mut raw = Descriptor::new(
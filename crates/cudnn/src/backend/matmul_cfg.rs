@@ -15,7 +15,7 @@ impl MatMulCfgBuilder {
     }
 
     pub fn build(self) -> Result<MatMulCfg, CudnnError> {
-        let compt_type = self.compt_type.expect("computation type is rquired");
+        let compt_type = self.compt_type.ok_or(CudnnError::BadParam)?;
 
         unsafe {
             let mut raw = Descriptor::new(
@@ -51,9 +51,9 @@ impl PointwiseBuilder {
     }
 
     pub fn build(mut self) -> Result<Operation, CudnnError> {
-        let cfg = self.cfg.expect("pointwise configuration is required.");
-        let x = self.x.expect("primary input is required.");
-        let y = self.y.expect("output is required.");
+        let cfg = self.cfg.ok_or(CudnnError::BadParam)?;
+        let x = self.x.ok_or(CudnnError::BadParam)?;
+        let y = self.y.ok_or(CudnnError::BadParam)?;
 
         unsafe {
             let mut raw = Descriptor::new(
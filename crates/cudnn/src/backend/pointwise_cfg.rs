@@ -66,8 +66,7 @@ impl PointwiseCfgBuilder {
     }
 
     pub fn build(&mut self) -> Result<PointwiseCfg, CudnnError> {
-        let mode: cudnn_sys::cudnnPointwiseMode_t =
-            self.mode.expect("pointwise mode is required.").into();
+        let mode: cudnn_sys::cudnnPointwiseMode_t = self.mode.ok_or(CudnnError::BadParam)?.into();
 
         let math_precision = self
             .math_precision
@@ -27,9 +27,9 @@ impl ReductionBuilder {
     }
 
     pub fn build(self) -> Result<Operation, CudnnError> {
-        let cfg = self.cfg.expect("reduce configuration is required.");
-        let x = self.x.expect("x tensor is required.");
-        let y = self.y.expect("y tensor is required");
+        let cfg = self.cfg.ok_or(CudnnError::BadParam)?;
+        let x = self.x.ok_or(CudnnError::BadParam)?;
+        let y = self.y.ok_or(CudnnError::BadParam)?;
 
         unsafe {
             let mut raw = Descriptor::new(
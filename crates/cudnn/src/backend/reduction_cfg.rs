@@ -28,8 +28,7 @@ impl ReductionCfgBuilder {
             .math_precision
             .unwrap_or(cudnn_sys::cudnnDataType_t::CUDNN_DATA_FLOAT);
 
-        let mode: cudnn_sys::cudnnReduceTensorOp_t =
-            self.mode.expect("reduction mode is required.").into();
+        let mode: cudnn_sys::cudnnReduceTensorOp_t = self.mode.ok_or(CudnnError::BadParam)?.into();
 
         unsafe {
             let mut raw = Descriptor::new(
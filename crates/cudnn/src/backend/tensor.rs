@@ -53,11 +53,11 @@ impl<'a> TensorBuilder<'a> {
     }
 
     pub fn build(mut self) -> Result<Tensor, CudnnError> {
-        let id = self.id.expect("id is required.");
-        let data_type = self.data_type.expect("data type is required.");
-        let byte_alignment = self.byte_alignment.expect("byte alignment is required.");
-        let dimensions = self.dimensions.expect("dimensions are required.");
-        let strides = self.strides.expect("strides are required.");
+        let id = self.id.ok_or(CudnnError::BadParam)?;
+        let data_type = self.data_type.ok_or(CudnnError::BadParam)?;
+        let byte_alignment = self.byte_alignment.ok_or(CudnnError::BadParam)?;
+        let dimensions = self.dimensions.ok_or(CudnnError::BadParam)?;
+        let strides = self.strides.ok_or(CudnnError::BadParam)?;
 
         unsafe {
             let mut raw = Descriptor::new(
@@ -108,9 +108,8 @@ impl<'a> TensorBuilder<'a> {
                 )?;
 
                 if vector_count != 1 {
-                    let vectorized_dimension = self
-                        .vectorized_dimension
-                        .expect("vectorized_dimension is required when vector_count > 1");
+                    let vectorized_dimension =
+                        self.vectorized_dimension.ok_or(CudnnError::BadParam)?;
 
                     raw.set_attribute(
                         cudnn_sys::cudnnBackendAttributeName_t::CUDNN_ATTR_TENSOR_VECTORIZED_DIMENSION,
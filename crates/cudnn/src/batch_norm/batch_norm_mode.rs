@@ -0,0 +1,26 @@
+/// Specifies the mode of operation of a batch normalization.
+///
+/// cuDNN [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnBatchNormMode_t)
+/// may offer additional information about the APi behavior.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BatchNormMode {
+    /// Normalization is performed per-activation. This mode is intended to be used after
+    /// non-convolutional network layers.
+    PerActivation,
+    /// Normalization is performed over N+spatial dimensions. This mode is intended for use after
+    /// convolutional layers.
+    Spatial,
+    /// Like `Spatial`, but may be faster for some tasks by using a persistent kernel. The input
+    /// must be in NHWC layout with half precision data in order for the persistent kernel to be selected.
+    SpatialPersistent,
+}
+
+impl From<BatchNormMode> for cudnn_sys::cudnnBatchNormMode_t {
+    fn from(mode: BatchNormMode) -> Self {
+        match mode {
+            BatchNormMode::PerActivation => Self::CUDNN_BATCHNORM_PER_ACTIVATION,
+            BatchNormMode::Spatial => Self::CUDNN_BATCHNORM_SPATIAL,
+            BatchNormMode::SpatialPersistent => Self::CUDNN_BATCHNORM_SPATIAL_PERSISTENT,
+        }
+    }
+}
@@ -0,0 +1,24 @@
+/// Specifies which operations are fused with a batch normalization.
+///
+/// cuDNN [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnBatchNormOps_t)
+/// may offer additional information about the APi behavior.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BatchNormOps {
+    /// Only batch normalization is performed.
+    Bn,
+    /// The batch normalization is followed by an activation.
+    BnActivation,
+    /// The result of the batch normalization is added to a second tensor, then the activation is
+    /// applied. This is the typical residual block of a ResNet.
+    BnAddActivation,
+}
+
+impl From<BatchNormOps> for cudnn_sys::cudnnBatchNormOps_t {
+    fn from(ops: BatchNormOps) -> Self {
+        match ops {
+            BatchNormOps::Bn => Self::CUDNN_BATCHNORM_OPS_BN,
+            BatchNormOps::BnActivation => Self::CUDNN_BATCHNORM_OPS_BN_ACTIVATION,
+            BatchNormOps::BnAddActivation => Self::CUDNN_BATCHNORM_OPS_BN_ADD_ACTIVATION,
+        }
+    }
+}
@@ -0,0 +1,535 @@
+use std::mem::MaybeUninit;
+
+use cust::memory::GpuBuffer;
+
+use crate::{
+    private, ActivationDescriptor, CudnnContext, CudnnError, DataType, IntoResult, TensorDescriptor,
+};
+
+mod batch_norm_mode;
+mod batch_norm_ops;
+
+pub use batch_norm_mode::*;
+pub use batch_norm_ops::*;
+
+impl CudnnContext {
+    /// Returns the amount of GPU memory workspace the user should allocate to be able to call
+    /// [`batch_normalization_forward_training_ex()`](CudnnContext::batch_normalization_forward_training_ex)
+    /// with the specified configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - mode of operation.
+    /// * `ops` - operations fused with the batch normalization.
+    /// * `x_desc` - descriptor of the input tensor.
+    /// * `z_desc` - descriptor of the tensor added to the normalized input. Must only be `Some`
+    ///   when `ops` is `BatchNormOps::BnAddActivation`.
+    /// * `y_desc` - descriptor of the output tensor.
+    /// * `bn_scale_bias_mean_var_desc` - descriptor shared by the scale, bias, mean and variance
+    ///   tensors.
+    /// * `activation_desc` - descriptor of the activation. Must be `Some` when `ops` fuses an
+    ///   activation.
+    ///
+    /// cuDNN
+    /// [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnGetBatchNormalizationForwardTrainingExWorkspaceSize)
+    /// may offer additional information about the APi behavior.
+    ///
+    /// # Errors
+    ///
+    /// Returns errors if the combination of arguments is not supported.
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_batch_normalization_forward_training_ex_workspace_size<T, CompT>(
+        &self,
+        mode: BatchNormMode,
+        ops: BatchNormOps,
+        x_desc: &TensorDescriptor<T>,
+        z_desc: Option<&TensorDescriptor<T>>,
+        y_desc: &TensorDescriptor<T>,
+        bn_scale_bias_mean_var_desc: &TensorDescriptor<CompT>,
+        activation_desc: Option<&ActivationDescriptor>,
+    ) -> Result<usize, CudnnError>
+    where
+        T: DataType,
+        CompT: SupportedBatchNorm<T>,
+    {
+        check_fused_ops(ops, z_desc.is_some(), activation_desc.is_some())?;
+
+        let mut size = MaybeUninit::uninit();
+
+        unsafe {
+            cudnn_sys::cudnnGetBatchNormalizationForwardTrainingExWorkspaceSize(
+                self.raw,
+                mode.into(),
+                ops.into(),
+                x_desc.raw,
+                z_desc.map_or(std::ptr::null_mut(), |desc| desc.raw),
+                y_desc.raw,
+                bn_scale_bias_mean_var_desc.raw,
+                activation_desc.map_or(std::ptr::null_mut(), |desc| desc.raw),
+                size.as_mut_ptr(),
+            )
+            .into_result()?;
+
+            Ok(size.assume_init())
+        }
+    }
+
+    /// Returns the amount of reserve GPU memory workspace the user should allocate for the
+    /// batch normalization training operations with the specified configuration.
+    ///
+    /// The same reserve space must be passed unchanged to the backward pass.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - mode of operation.
+    /// * `ops` - operations fused with the batch normalization.
+    /// * `activation_desc` - descriptor of the activation. Must be `Some` when `ops` fuses an
+    ///   activation.
+    /// * `x_desc` - descriptor of the input tensor.
+    ///
+    /// cuDNN
+    /// [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnGetBatchNormalizationTrainingExReserveSpaceSize)
+    /// may offer additional information about the APi behavior.
+    ///
+    /// # Errors
+    ///
+    /// Returns errors if the combination of arguments is not supported.
+    pub fn get_batch_normalization_training_ex_reserve_space_size<T>(
+        &self,
+        mode: BatchNormMode,
+        ops: BatchNormOps,
+        activation_desc: Option<&ActivationDescriptor>,
+        x_desc: &TensorDescriptor<T>,
+    ) -> Result<usize, CudnnError>
+    where
+        T: DataType,
+    {
+        if ops != BatchNormOps::Bn && activation_desc.is_none() {
+            return Err(CudnnError::BadParam);
+        }
+
+        let mut size = MaybeUninit::uninit();
+
+        unsafe {
+            cudnn_sys::cudnnGetBatchNormalizationTrainingExReserveSpaceSize(
+                self.raw,
+                mode.into(),
+                ops.into(),
+                activation_desc.map_or(std::ptr::null_mut(), |desc| desc.raw),
+                x_desc.raw,
+                size.as_mut_ptr(),
+            )
+            .into_result()?;
+
+            Ok(size.assume_init())
+        }
+    }
+
+    /// Performs the forward batch normalization layer computation for training, optionally
+    /// fused with a residual addition and an activation.
+    ///
+    /// Depending on `ops` this computes `y = bn(x)`, `y = act(bn(x))` or `y = act(bn(x) + z)`,
+    /// where `bn` is the batch normalization using the statistics of the current mini-batch.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - mode of operation.
+    /// * `ops` - operations fused with the batch normalization.
+    /// * `alpha` - scaling factor for the result.
+    /// * `beta` - scaling factor for the prior value of `y`.
+    /// * `x_desc` - descriptor of the input tensor.
+    /// * `x` - input data in device memory.
+    /// * `z_desc` - descriptor of the tensor added to the normalized input. Must only be `Some`
+    ///   when `ops` is `BatchNormOps::BnAddActivation`.
+    /// * `z` - data of the tensor added to the normalized input in device memory.
+    /// * `y_desc` - descriptor of the output tensor.
+    /// * `y` - output data in device memory.
+    /// * `bn_scale_bias_mean_var_desc` - descriptor shared by the scale, bias, mean and variance
+    ///   tensors. Its dimensions are 1xCx1x1 for `BatchNormMode::Spatial` and
+    ///   `BatchNormMode::SpatialPersistent` and 1xCxHxW for `BatchNormMode::PerActivation`.
+    /// * `bn_scale` - scale parameters in device memory.
+    /// * `bn_bias` - bias parameters in device memory.
+    /// * `exponential_average_factor` - factor used in the moving average computation of the
+    ///   running mean and variance. Use `1.0 / (1.0 + n)` at the n-th call to obtain a cumulative
+    ///   moving average.
+    /// * `running_mean` - running mean, updated in place.
+    /// * `running_variance` - running variance, updated in place.
+    /// * `epsilon` - epsilon value used in the normalization formula. Must be greater than or
+    ///   equal to `CUDNN_BN_MIN_EPSILON`.
+    /// * `save_mean` - optional cache of the mini-batch mean, which speeds up the backward pass.
+    /// * `save_inv_variance` - optional cache of the mini-batch inverse variance, which speeds up
+    ///   the backward pass. Must be `Some` if and only if `save_mean` is `Some`.
+    /// * `activation_desc` - descriptor of the activation. Must be `Some` when `ops` fuses an
+    ///   activation.
+    /// * `work_space` - workspace buffer in device memory. Its size can be obtained with
+    ///   [`get_batch_normalization_forward_training_ex_workspace_size()`](CudnnContext::get_batch_normalization_forward_training_ex_workspace_size).
+    /// * `reserve_space` - reserve space buffer in device memory. Its size can be obtained with
+    ///   [`get_batch_normalization_training_ex_reserve_space_size()`](CudnnContext::get_batch_normalization_training_ex_reserve_space_size).
+    ///
+    /// cuDNN
+    /// [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnBatchNormalizationForwardTrainingEx)
+    /// may offer additional information about the APi behavior.
+    ///
+    /// # Errors
+    ///
+    /// Returns errors if the optional arguments do not match `ops`, if the tensor shapes are
+    /// inconsistent, or if the combination of arguments is not supported.
+    #[allow(clippy::too_many_arguments)]
+    pub fn batch_normalization_forward_training_ex<T, CompT>(
+        &self,
+        mode: BatchNormMode,
+        ops: BatchNormOps,
+        alpha: CompT,
+        beta: CompT,
+        x_desc: &TensorDescriptor<T>,
+        x: &impl GpuBuffer<T>,
+        z_desc: Option<&TensorDescriptor<T>>,
+        z: Option<&impl GpuBuffer<T>>,
+        y_desc: &TensorDescriptor<T>,
+        y: &mut impl GpuBuffer<T>,
+        bn_scale_bias_mean_var_desc: &TensorDescriptor<CompT>,
+        bn_scale: &impl GpuBuffer<CompT>,
+        bn_bias: &impl GpuBuffer<CompT>,
+        exponential_average_factor: f64,
+        running_mean: &mut impl GpuBuffer<CompT>,
+        running_variance: &mut impl GpuBuffer<CompT>,
+        epsilon: f64,
+        save_mean: Option<&mut impl GpuBuffer<CompT>>,
+        save_inv_variance: Option<&mut impl GpuBuffer<CompT>>,
+        activation_desc: Option<&ActivationDescriptor>,
+        work_space: &mut impl GpuBuffer<u8>,
+        reserve_space: &mut impl GpuBuffer<u8>,
+    ) -> Result<(), CudnnError>
+    where
+        T: DataType,
+        CompT: SupportedBatchNorm<T>,
+    {
+        check_fused_ops(ops, z_desc.is_some(), activation_desc.is_some())?;
+        check_epsilon(epsilon)?;
+
+        if z_desc.is_some() != z.is_some() || save_mean.is_some() != save_inv_variance.is_some() {
+            return Err(CudnnError::BadParam);
+        }
+
+        let alpha_ptr = &alpha as *const CompT as *const _;
+        let beta_ptr = &beta as *const CompT as *const _;
+
+        let x_ptr = x.as_device_ptr().as_ptr() as *const _;
+        let z_ptr = z.map_or(std::ptr::null(), |z| z.as_device_ptr().as_ptr() as *const _);
+        let y_ptr = y.as_device_ptr().as_mut_ptr() as *mut _;
+
+        let bn_scale_ptr = bn_scale.as_device_ptr().as_ptr() as *const _;
+        let bn_bias_ptr = bn_bias.as_device_ptr().as_ptr() as *const _;
+
+        let running_mean_ptr = running_mean.as_device_ptr().as_mut_ptr() as *mut _;
+        let running_variance_ptr = running_variance.as_device_ptr().as_mut_ptr() as *mut _;
+
+        let save_mean_ptr = save_mean.map_or(std::ptr::null_mut(), |buff| {
+            buff.as_device_ptr().as_mut_ptr() as *mut _
+        });
+        let save_inv_variance_ptr = save_inv_variance.map_or(std::ptr::null_mut(), |buff| {
+            buff.as_device_ptr().as_mut_ptr() as *mut _
+        });
+
+        let work_space_ptr = work_space.as_device_ptr().as_mut_ptr() as *mut _;
+        let reserve_space_ptr = reserve_space.as_device_ptr().as_mut_ptr() as *mut _;
+
+        unsafe {
+            cudnn_sys::cudnnBatchNormalizationForwardTrainingEx(
+                self.raw,
+                mode.into(),
+                ops.into(),
+                alpha_ptr,
+                beta_ptr,
+                x_desc.raw,
+                x_ptr,
+                z_desc.map_or(std::ptr::null_mut(), |desc| desc.raw),
+                z_ptr,
+                y_desc.raw,
+                y_ptr,
+                bn_scale_bias_mean_var_desc.raw,
+                bn_scale_ptr,
+                bn_bias_ptr,
+                exponential_average_factor,
+                running_mean_ptr,
+                running_variance_ptr,
+                epsilon,
+                save_mean_ptr,
+                save_inv_variance_ptr,
+                activation_desc.map_or(std::ptr::null_mut(), |desc| desc.raw),
+                work_space_ptr,
+                work_space.len(),
+                reserve_space_ptr,
+                reserve_space.len(),
+            )
+            .into_result()
+        }
+    }
+
+    /// Performs the forward batch normalization layer computation for training.
+    ///
+    /// This computes `y = bn(x)`, where `bn` is the batch normalization using the statistics of
+    /// the current mini-batch, and updates the running mean and variance used for inference.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - mode of operation.
+    /// * `alpha` - scaling factor for the result.
+    /// * `beta` - scaling factor for the prior value of `y`.
+    /// * `x_desc` - descriptor of the input tensor.
+    /// * `x` - input data in device memory.
+    /// * `y_desc` - descriptor of the output tensor.
+    /// * `y` - output data in device memory.
+    /// * `bn_scale_bias_mean_var_desc` - descriptor shared by the scale, bias, mean and variance
+    ///   tensors. Its dimensions are 1xCx1x1 for `BatchNormMode::Spatial` and
+    ///   `BatchNormMode::SpatialPersistent` and 1xCxHxW for `BatchNormMode::PerActivation`.
+    /// * `bn_scale` - scale parameters in device memory.
+    /// * `bn_bias` - bias parameters in device memory.
+    /// * `exponential_average_factor` - factor used in the moving average computation of the
+    ///   running mean and variance. Use `1.0 / (1.0 + n)` at the n-th call to obtain a cumulative
+    ///   moving average.
+    /// * `running_mean` - running mean, updated in place.
+    /// * `running_variance` - running variance, updated in place.
+    /// * `epsilon` - epsilon value used in the normalization formula. Must be greater than or
+    ///   equal to `CUDNN_BN_MIN_EPSILON`.
+    /// * `save_mean` - optional cache of the mini-batch mean, which speeds up the backward pass.
+    /// * `save_inv_variance` - optional cache of the mini-batch inverse variance, which speeds up
+    ///   the backward pass. Must be `Some` if and only if `save_mean` is `Some`.
+    ///
+    /// cuDNN
+    /// [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnBatchNormalizationForwardTraining)
+    /// may offer additional information about the APi behavior.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CudnnError::BadParam`] if `epsilon` is smaller than `CUDNN_BN_MIN_EPSILON` or
+    /// only one of the caches is given, and errors if the tensor shapes are inconsistent or the
+    /// combination of arguments is not supported.
+    #[allow(clippy::too_many_arguments)]
+    pub fn batch_normalization_forward_training<T, CompT>(
+        &self,
+        mode: BatchNormMode,
+        alpha: CompT,
+        beta: CompT,
+        x_desc: &TensorDescriptor<T>,
+        x: &impl GpuBuffer<T>,
+        y_desc: &TensorDescriptor<T>,
+        y: &mut impl GpuBuffer<T>,
+        bn_scale_bias_mean_var_desc: &TensorDescriptor<CompT>,
+        bn_scale: &impl GpuBuffer<CompT>,
+        bn_bias: &impl GpuBuffer<CompT>,
+        exponential_average_factor: f64,
+        running_mean: &mut impl GpuBuffer<CompT>,
+        running_variance: &mut impl GpuBuffer<CompT>,
+        epsilon: f64,
+        save_mean: Option<&mut impl GpuBuffer<CompT>>,
+        save_inv_variance: Option<&mut impl GpuBuffer<CompT>>,
+    ) -> Result<(), CudnnError>
+    where
+        T: DataType,
+        CompT: SupportedBatchNorm<T>,
+    {
+        check_epsilon(epsilon)?;
+
+        if save_mean.is_some() != save_inv_variance.is_some() {
+            return Err(CudnnError::BadParam);
+        }
+
+        let alpha_ptr = &alpha as *const CompT as *const _;
+        let beta_ptr = &beta as *const CompT as *const _;
+
+        let x_ptr = x.as_device_ptr().as_ptr() as *const _;
+        let y_ptr = y.as_device_ptr().as_mut_ptr() as *mut _;
+
+        let bn_scale_ptr = bn_scale.as_device_ptr().as_ptr() as *const _;
+        let bn_bias_ptr = bn_bias.as_device_ptr().as_ptr() as *const _;
+
+        let running_mean_ptr = running_mean.as_device_ptr().as_mut_ptr() as *mut _;
+        let running_variance_ptr = running_variance.as_device_ptr().as_mut_ptr() as *mut _;
+
+        let save_mean_ptr = save_mean.map_or(std::ptr::null_mut(), |buff| {
+            buff.as_device_ptr().as_mut_ptr() as *mut _
+        });
+        let save_inv_variance_ptr = save_inv_variance.map_or(std::ptr::null_mut(), |buff| {
+            buff.as_device_ptr().as_mut_ptr() as *mut _
+        });
+
+        unsafe {
+            cudnn_sys::cudnnBatchNormalizationForwardTraining(
+                self.raw,
+                mode.into(),
+                alpha_ptr,
+                beta_ptr,
+                x_desc.raw,
+                x_ptr,
+                y_desc.raw,
+                y_ptr,
+                bn_scale_bias_mean_var_desc.raw,
+                bn_scale_ptr,
+                bn_bias_ptr,
+                exponential_average_factor,
+                running_mean_ptr,
+                running_variance_ptr,
+                epsilon,
+                save_mean_ptr,
+                save_inv_variance_ptr,
+            )
+            .into_result()
+        }
+    }
+
+    /// Performs the backward batch normalization layer computation, computing the gradient of
+    /// the input as well as the gradients of the scale and bias parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - mode of operation, must be the same as for the forward pass.
+    /// * `alpha_data_diff` - scaling factor for the result of the input gradient.
+    /// * `beta_data_diff` - scaling factor for the prior value of `dx`.
+    /// * `alpha_param_diff` - scaling factor for the result of the parameter gradients.
+    /// * `beta_param_diff` - scaling factor for the prior values of `d_bn_scale` and `d_bn_bias`.
+    /// * `x_desc` - descriptor of the input tensor of the forward pass.
+    /// * `x` - input data of the forward pass in device memory.
+    /// * `dy_desc` - descriptor of the gradient of the output.
+    /// * `dy` - gradient of the output in device memory.
+    /// * `dx_desc` - descriptor of the gradient of the input.
+    /// * `dx` - gradient of the input in device memory.
+    /// * `d_bn_scale_bias_desc` - descriptor shared by the scale and bias tensors and their
+    ///   gradients.
+    /// * `bn_scale` - scale parameters in device memory.
+    /// * `d_bn_scale` - gradient of the scale parameters in device memory.
+    /// * `d_bn_bias` - gradient of the bias parameters in device memory.
+    /// * `epsilon` - epsilon value used in the normalization formula, must be the same as for the
+    ///   forward pass.
+    /// * `saved_mean` - optional mini-batch mean cached by the forward pass.
+    /// * `saved_inv_variance` - optional mini-batch inverse variance cached by the forward pass.
+    ///   Must be `Some` if and only if `saved_mean` is `Some`.
+    ///
+    /// cuDNN
+    /// [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnBatchNormalizationBackward)
+    /// may offer additional information about the APi behavior.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CudnnError::BadParam`] if `epsilon` is smaller than `CUDNN_BN_MIN_EPSILON` or
+    /// only one of the caches is given, and errors if the tensor shapes are inconsistent or the
+    /// combination of arguments is not supported.
+    #[allow(clippy::too_many_arguments)]
+    pub fn batch_normalization_backward<T, CompT>(
+        &self,
+        mode: BatchNormMode,
+        alpha_data_diff: CompT,
+        beta_data_diff: CompT,
+        alpha_param_diff: CompT,
+        beta_param_diff: CompT,
+        x_desc: &TensorDescriptor<T>,
+        x: &impl GpuBuffer<T>,
+        dy_desc: &TensorDescriptor<T>,
+        dy: &impl GpuBuffer<T>,
+        dx_desc: &TensorDescriptor<T>,
+        dx: &mut impl GpuBuffer<T>,
+        d_bn_scale_bias_desc: &TensorDescriptor<CompT>,
+        bn_scale: &impl GpuBuffer<CompT>,
+        d_bn_scale: &mut impl GpuBuffer<CompT>,
+        d_bn_bias: &mut impl GpuBuffer<CompT>,
+        epsilon: f64,
+        saved_mean: Option<&impl GpuBuffer<CompT>>,
+        saved_inv_variance: Option<&impl GpuBuffer<CompT>>,
+    ) -> Result<(), CudnnError>
+    where
+        T: DataType,
+        CompT: SupportedBatchNorm<T>,
+    {
+        check_epsilon(epsilon)?;
+
+        if saved_mean.is_some() != saved_inv_variance.is_some() {
+            return Err(CudnnError::BadParam);
+        }
+
+        let alpha_data_diff_ptr = &alpha_data_diff as *const CompT as *const _;
+        let beta_data_diff_ptr = &beta_data_diff as *const CompT as *const _;
+        let alpha_param_diff_ptr = &alpha_param_diff as *const CompT as *const _;
+        let beta_param_diff_ptr = &beta_param_diff as *const CompT as *const _;
+
+        let x_ptr = x.as_device_ptr().as_ptr() as *const _;
+        let dy_ptr = dy.as_device_ptr().as_ptr() as *const _;
+        let dx_ptr = dx.as_device_ptr().as_mut_ptr() as *mut _;
+
+        let bn_scale_ptr = bn_scale.as_device_ptr().as_ptr() as *const _;
+        let d_bn_scale_ptr = d_bn_scale.as_device_ptr().as_mut_ptr() as *mut _;
+        let d_bn_bias_ptr = d_bn_bias.as_device_ptr().as_mut_ptr() as *mut _;
+
+        let saved_mean_ptr = saved_mean.map_or(std::ptr::null(), |buff| {
+            buff.as_device_ptr().as_ptr() as *const _
+        });
+        let saved_inv_variance_ptr = saved_inv_variance.map_or(std::ptr::null(), |buff| {
+            buff.as_device_ptr().as_ptr() as *const _
+        });
+
+        unsafe {
+            cudnn_sys::cudnnBatchNormalizationBackward(
+                self.raw,
+                mode.into(),
+                alpha_data_diff_ptr,
+                beta_data_diff_ptr,
+                alpha_param_diff_ptr,
+                beta_param_diff_ptr,
+                x_desc.raw,
+                x_ptr,
+                dy_desc.raw,
+                dy_ptr,
+                dx_desc.raw,
+                dx_ptr,
+                d_bn_scale_bias_desc.raw,
+                bn_scale_ptr,
+                d_bn_scale_ptr,
+                d_bn_bias_ptr,
+                epsilon,
+                saved_mean_ptr,
+                saved_inv_variance_ptr,
+            )
+            .into_result()
+        }
+    }
+}
+
+/// Checks that `epsilon` is at least the smallest value supported by cuDNN.
+fn check_epsilon(epsilon: f64) -> Result<(), CudnnError> {
+    if !(epsilon >= cudnn_sys::CUDNN_BN_MIN_EPSILON) {
+        return Err(CudnnError::BadParam);
+    }
+
+    Ok(())
+}
+
+/// Checks that the optional tensor and activation descriptors are given exactly when `ops` needs
+/// them.
+fn check_fused_ops(ops: BatchNormOps, has_z: bool, has_activation: bool) -> Result<(), CudnnError> {
+    let (needs_z, needs_activation) = match ops {
+        BatchNormOps::Bn => (false, false),
+        BatchNormOps::BnActivation => (false, true),
+        BatchNormOps::BnAddActivation => (true, true),
+    };
+
+    if has_z != needs_z || (needs_activation && !has_activation) {
+        return Err(CudnnError::BadParam);
+    }
+
+    Ok(())
+}
+
+/// Supported data type configurations for batch normalization operations.
+///
+/// The implementing type is the data type of the scale, bias, mean and variance tensors, and of
+/// `alpha` and `beta`. Half precision tensors, which the fused [`BatchNormOps`] kernels require
+/// along with the NHWC layout, use `f32` for those and need the `half` feature.
+pub trait SupportedBatchNorm<T>: DataType + private::Sealed
+where
+    T: DataType,
+{
+}
+
+impl SupportedBatchNorm<f32> for f32 {}
+impl SupportedBatchNorm<f64> for f64 {}
+#[cfg(feature = "half")]
+impl SupportedBatchNorm<half::f16> for f32 {}
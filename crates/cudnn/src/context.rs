@@ -1,6 +1,10 @@
-use std::mem::MaybeUninit;
+use std::{
+    cell::RefCell,
+    hash::{Hash, Hasher},
+    mem::MaybeUninit,
+};
 
-use crate::{CudnnError, IntoResult};
+use crate::{CudnnError, IntoResult, Workspace};
 
 /// cuDNN library context. It's the central structure required to interact with cuDNN.
 /// It holds and manages internal memory allocations.
@@ -23,9 +27,29 @@ use crate::{CudnnError, IntoResult};
 /// resources on drop. They will also synchronize the entire device when dropping the context.
 /// Therefore, you should minimize both the amount of contexts, and the amount of context drops.
 /// You should generally create and drop context outside of performance critical code paths.
-#[derive(PartialEq, Eq, Hash, Debug)]
+///
+/// # Workspace
+///
+/// Every context owns a [`Workspace`], which the convolutions use when they are not given a
+/// workspace. It grows to the largest size an algorithm needed and is freed with the context.
+#[derive(Debug)]
 pub struct CudnnContext {
     pub(crate) raw: cudnn_sys::cudnnHandle_t,
+    pub(crate) work_space: RefCell<Workspace>,
+}
+
+impl PartialEq for CudnnContext {
+    fn eq(&self, other: &Self) -> bool {
+        self.raw == other.raw
+    }
+}
+
+impl Eq for CudnnContext {}
+
+impl Hash for CudnnContext {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.raw.hash(state);
+    }
 }
 
 impl CudnnContext {
@@ -53,7 +77,10 @@ impl CudnnContext {
             cudnn_sys::cudnnCreate(raw.as_mut_ptr()).into_result()?;
             let raw = raw.assume_init();
 
-            Ok(Self { raw })
+            Ok(Self {
+                raw,
+                work_space: RefCell::new(Workspace::new()),
+            })
         }
     }
 
@@ -73,6 +100,41 @@ impl CudnnContext {
         }
     }
 
+    /// Sets whether convolutions may use TF32 tensor core operations on devices which support
+    /// them, A100 and newer. TF32 is allowed by default.
+    ///
+    /// This is a process wide setting. While TF32 is disallowed, convolution descriptors are
+    /// created with [`MathType::Fma`](crate::MathType::Fma) instead of the default math type, and
+    /// [`MathType::Default`](crate::MathType::Default) passed to
+    /// [`ConvDescriptor::set_math_type`](crate::ConvDescriptor::set_math_type) is replaced by
+    /// `MathType::Fma` as well. Descriptors created before the call are not affected.
+    ///
+    /// Disallowing TF32 makes `f32` convolutions compute with full `f32` precision, which changes
+    /// their results and can make them considerably slower.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cudnn::CudnnContext;
+    ///
+    /// // full precision for a reproducibility test.
+    /// CudnnContext::set_allow_tf32(false);
+    /// assert!(!CudnnContext::allow_tf32());
+    ///
+    /// // ...
+    ///
+    /// CudnnContext::set_allow_tf32(true);
+    /// ```
+    pub fn set_allow_tf32(allow: bool) {
+        crate::math_type::set_allow_tf32(allow)
+    }
+
+    /// Returns whether convolutions may use TF32 tensor core operations, see
+    /// [`CudnnContext::set_allow_tf32`].
+    pub fn allow_tf32() -> bool {
+        crate::math_type::allow_tf32()
+    }
+
     /// Since The same version of a given cuDNN library can be compiled against different CUDA
     /// toolkit versions, this routine returns the CUDA toolkit version that the currently used
     /// cuDNN library has been compiled against.
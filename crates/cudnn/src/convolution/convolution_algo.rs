@@ -38,6 +38,101 @@ where
     }
 }
 
+/// The measured performance of one candidate algorithm, as returned by the `find_*` functions of
+/// [`CudnnContext`](crate::CudnnContext).
+///
+/// Unlike [`BestHeuristic`], this is returned for every algorithm cuDNN tried, including the ones
+/// which failed, so that callers can apply their own selection policy.
+///
+/// # Examples
+///
+/// Picking the fastest deterministic algorithm which needs at most 256MB of workspace.
+///
+/// ```
+/// use cudnn::{AlgoPerf, Determinism};
+///
+/// fn select<T: Copy>(results: &[AlgoPerf<T>]) -> Option<T> {
+///     const BUDGET: usize = 256 * 1024 * 1024;
+///
+///     // the results are sorted by time.
+///     results
+///         .iter()
+///         .filter(|perf| perf.status().is_ok())
+///         .filter(|perf| perf.determinism() == Determinism::Deterministic)
+///         .find(|perf| perf.memory() <= BUDGET)
+///         .map(|perf| perf.algo())
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AlgoPerf<T>
+where
+    T: Copy,
+{
+    algo: T,
+    status: Result<(), CudnnError>,
+    time: f32,
+    memory: usize,
+    determinism: Determinism,
+    math_type: MathType,
+}
+
+impl<T> AlgoPerf<T>
+where
+    T: Copy,
+{
+    /// Returns the algorithm.
+    pub fn algo(&self) -> T {
+        self.algo
+    }
+
+    /// Returns whether the algorithm ran successfully. The other measurements are meaningless if
+    /// it did not.
+    pub fn status(&self) -> Result<(), CudnnError> {
+        self.status
+    }
+
+    /// Returns the execution time of the algorithm in milliseconds.
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    /// Returns the workspace size in bytes needed by the algorithm.
+    pub fn memory(&self) -> usize {
+        self.memory
+    }
+
+    /// Returns the determinism of the algorithm.
+    pub fn determinism(&self) -> Determinism {
+        self.determinism
+    }
+
+    /// Returns the math type the algorithm was run with.
+    pub fn math_type(&self) -> MathType {
+        self.math_type
+    }
+}
+
+macro_rules! impl_algo_perf_from {
+    ($raw_type:ident, $algo:ident) => {
+        impl From<cudnn_sys::$raw_type> for AlgoPerf<$algo> {
+            fn from(raw: cudnn_sys::$raw_type) -> Self {
+                Self {
+                    algo: raw.algo.into(),
+                    status: raw.status.into_result(),
+                    time: raw.time,
+                    memory: raw.memory,
+                    determinism: Determinism::from(raw.determinism),
+                    math_type: raw.mathType.into(),
+                }
+            }
+        }
+    };
+}
+
+impl_algo_perf_from!(cudnnConvolutionFwdAlgoPerf_t, ConvFwdAlgo);
+impl_algo_perf_from!(cudnnConvolutionBwdDataAlgoPerf_t, ConvBwdDataAlgo);
+impl_algo_perf_from!(cudnnConvolutionBwdFilterAlgoPerf_t, ConvBwdFilterAlgo);
+
 /// Convolution forward algorithms as listed in the [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnConvolutionFwdAlgo_t).
 #[non_exhaustive]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
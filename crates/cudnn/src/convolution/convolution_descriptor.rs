@@ -1,6 +1,8 @@
 use std::{marker::PhantomData, mem::MaybeUninit};
 
-use crate::{ConvMode, CudnnError, DataType, IntoResult, MathType};
+use crate::{
+    ConvMode, CudnnError, DataType, FilterDescriptor, IntoResult, MathType, TensorDescriptor,
+};
 
 /// A generic description of an n-dimensional convolution.
 ///
@@ -114,6 +116,11 @@ impl<T: DataType> ConvDescriptor<T> {
             )
             .into_result()?;
 
+            if !crate::math_type::allow_tf32() {
+                cudnn_sys::cudnnSetConvolutionMathType(conv_desc.raw, MathType::Fma.into())
+                    .into_result()?;
+            }
+
             Ok(conv_desc)
         }
     }
@@ -124,7 +131,9 @@ impl<T: DataType> ConvDescriptor<T> {
     ///
     /// `math_type` - the provided math type.
     ///
-    /// **Do note** that tensor core operations may not be available on all device architectures.
+    /// **Do note** that tensor core operations may not be available on all device architectures,
+    /// and that [`MathType::Default`] is replaced by [`MathType::Fma`] if TF32 has been disallowed
+    /// with [`CudnnContext::set_allow_tf32`](crate::CudnnContext::set_allow_tf32).
     ///
     /// cuDNN [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnSetConvolutionMathType)
     /// may offer additional information about the APi behavior.
@@ -152,11 +161,21 @@ impl<T: DataType> ConvDescriptor<T> {
     /// # }
     /// ```
     pub fn set_math_type(&mut self, math_type: MathType) -> Result<(), CudnnError> {
-        unsafe { cudnn_sys::cudnnSetConvolutionMathType(self.raw, math_type.into()).into_result() }
+        unsafe {
+            cudnn_sys::cudnnSetConvolutionMathType(self.raw, math_type.respecting_tf32().into())
+                .into_result()
+        }
     }
 
     /// Sets the group count for this convolution descriptor instance.
     ///
+    /// In a grouped convolution the input channels and the output feature maps are split into
+    /// `groups` groups, and every group of output feature maps is only computed from the
+    /// corresponding group of input channels. The number of input channels and the number of
+    /// output feature maps must therefore both be divisible by `groups`, and the filter must have
+    /// `input channels / groups` channels. Setting `groups` to the number of input channels gives a
+    /// depthwise convolution.
+    ///
     /// # Arguments
     ///
     /// `groups` - group count.
@@ -189,6 +208,113 @@ impl<T: DataType> ConvDescriptor<T> {
     pub fn set_group_count(&mut self, groups: i32) -> Result<(), CudnnError> {
         unsafe { cudnn_sys::cudnnSetConvolutionGroupCount(self.raw, groups) }.into_result()
     }
+
+    /// Returns the group count of this convolution descriptor instance.
+    ///
+    /// cuDNN [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnGetConvolutionGroupCount)
+    /// may offer additional information about the APi behavior.
+    pub fn group_count(&self) -> Result<i32, CudnnError> {
+        let mut groups = 0;
+
+        unsafe { cudnn_sys::cudnnGetConvolutionGroupCount(self.raw, &mut groups).into_result()? };
+
+        Ok(groups)
+    }
+
+    /// Returns the number of spatial dimensions of this convolution, that is 2 for a 2-d
+    /// convolution and 3 for a 3-d one.
+    pub(crate) fn spatial_dims(&self) -> Result<usize, CudnnError> {
+        const MAX_DIMS: usize = cudnn_sys::CUDNN_DIM_MAX as usize;
+
+        let mut ndims = 0;
+        let mut padding = [0; MAX_DIMS];
+        let mut stride = [0; MAX_DIMS];
+        let mut dilation = [0; MAX_DIMS];
+        let mut mode = MaybeUninit::uninit();
+        let mut comp_type = MaybeUninit::uninit();
+
+        unsafe {
+            cudnn_sys::cudnnGetConvolutionNdDescriptor(
+                self.raw,
+                MAX_DIMS as i32,
+                &mut ndims,
+                padding.as_mut_ptr(),
+                stride.as_mut_ptr(),
+                dilation.as_mut_ptr(),
+                mode.as_mut_ptr(),
+                comp_type.as_mut_ptr(),
+            )
+            .into_result()?;
+        }
+
+        Ok(ndims as usize)
+    }
+}
+
+impl<T: DataType> ConvDescriptor<T> {
+    /// Returns the dimensions of the output tensor of the forward convolution of an input
+    /// described by `x_desc` with a filter described by `w_desc`.
+    ///
+    /// cuDNN [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnGetConvolutionNdForwardOutputDim)
+    /// may offer additional information about the APi behavior.
+    ///
+    /// # Errors
+    ///
+    /// Returns errors if the descriptors are not compatible with each other.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use cudnn::{
+    ///     ConvDescriptor, ConvMode, CudnnContext, FilterDescriptor, ScalarC, TensorDescriptor,
+    /// };
+    ///
+    /// let ctx = CudnnContext::new()?;
+    ///
+    /// let mode = ConvMode::CrossCorrelation;
+    /// let conv_desc = ConvDescriptor::<f32>::new([0, 0], [1, 1], [1, 1], mode)?;
+    /// let x_desc = TensorDescriptor::<f32>::new_format(&[3, 2, 5, 5], ScalarC::Nchw)?;
+    /// let w_desc = FilterDescriptor::<f32>::new(&[3, 2, 2, 2], ScalarC::Nchw)?;
+    ///
+    /// assert_eq!(conv_desc.forward_output_dim(&x_desc, &w_desc)?, [3, 3, 4, 4]);
+    ///
+    /// // 3-d convolution of NCDHW volumes.
+    /// let conv_desc = ConvDescriptor::<f32>::new([0, 0, 0], [1, 1, 1], [1, 1, 1], mode)?;
+    /// let x_desc = TensorDescriptor::<f32>::new_format(&[1, 1, 4, 4, 4], ScalarC::Nchw)?;
+    /// let w_desc = FilterDescriptor::<f32>::new(&[2, 1, 3, 3, 3], ScalarC::Nchw)?;
+    ///
+    /// assert_eq!(conv_desc.forward_output_dim(&x_desc, &w_desc)?, [1, 2, 2, 2, 2]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn forward_output_dim<T1, T2>(
+        &self,
+        x_desc: &TensorDescriptor<T1>,
+        w_desc: &FilterDescriptor<T2>,
+    ) -> Result<Vec<i32>, CudnnError>
+    where
+        T1: DataType,
+        T2: DataType,
+    {
+        let ndims = x_desc.shape()?.len();
+        let mut shape = vec![0; ndims];
+
+        unsafe {
+            cudnn_sys::cudnnGetConvolutionNdForwardOutputDim(
+                self.raw,
+                x_desc.raw,
+                w_desc.raw,
+                ndims as i32,
+                shape.as_mut_ptr(),
+            )
+            .into_result()?;
+        }
+
+        Ok(shape)
+    }
 }
 
 impl<T: DataType> Drop for ConvDescriptor<T> {
@@ -132,6 +132,30 @@ where
             })
         }
     }
+
+    /// Returns the dimensions of the filter described by this descriptor, in KCRS order.
+    pub(crate) fn shape(&self) -> Result<Vec<i32>, CudnnError> {
+        const MAX_DIMS: usize = cudnn_sys::CUDNN_DIM_MAX as usize;
+
+        let mut data_type = MaybeUninit::uninit();
+        let mut format = MaybeUninit::uninit();
+        let mut ndims = 0;
+        let mut shape = [0; MAX_DIMS];
+
+        unsafe {
+            cudnn_sys::cudnnGetFilterNdDescriptor(
+                self.raw,
+                MAX_DIMS as i32,
+                data_type.as_mut_ptr(),
+                format.as_mut_ptr(),
+                &mut ndims,
+                shape.as_mut_ptr(),
+            )
+            .into_result()?;
+        }
+
+        Ok(shape[..ndims as usize].to_vec())
+    }
 }
 
 impl<T> Drop for FilterDescriptor<T>
@@ -3,7 +3,8 @@ use std::mem::MaybeUninit;
 use cust::memory::GpuBuffer;
 
 use crate::{
-    ActivationDescriptor, CudnnContext, CudnnError, DataType, IntoResult, TensorDescriptor,
+    ActivationDescriptor, CudnnContext, CudnnError, DataType, Determinism, IntoResult, Severity,
+    TensorDescriptor,
 };
 
 mod convolution_algo;
@@ -90,54 +91,703 @@ impl CudnnContext {
         CompT: SupportedConv<T1, T2, T3>,
         T3: DataType,
     {
-        let mut returned_algo_count = MaybeUninit::uninit();
-        let mut perf_results = MaybeUninit::uninit();
+        let perf_results = unsafe {
+            query_algorithms(1, |count, returned_count, results| {
+                cudnn_sys::cudnnGetConvolutionForwardAlgorithm_v7(
+                    self.raw,
+                    x_desc.raw,
+                    w_desc.raw,
+                    conv_desc.raw,
+                    y_desc.raw,
+                    count,
+                    returned_count,
+                    results,
+                )
+            })?
+        };
+
+        perf_results
+            .into_iter()
+            .map(BestHeuristic::try_from)
+            .find_map(Result::ok)
+            .ok_or(CudnnError::BadParam)
+    }
+
+    /// Like [`get_convolution_forward_algorithm`](Self::get_convolution_forward_algorithm), but
+    /// only considers algorithms which need at most `max_workspace` bytes of workspace.
+    ///
+    /// This picks the best algorithm, according to the cuDNN heuristic, which fits in the budget.
+    /// When none does, such as when the device is nearly out of memory, it falls back to
+    /// [`ImplicitGemm`](ConvFwdAlgo::ImplicitGemm), which needs little to no workspace but is
+    /// usually slower, even if it does not fit either, and reports a
+    /// [`Warning`](crate::Severity::Warning) to the callback installed with
+    /// [`set_log_callback`](crate::set_log_callback).
+    ///
+    /// cuDNN
+    /// [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnGetConvolutionForwardAlgorithm_v7)
+    /// may offer additional information about the APi behavior.
+    ///
+    /// # Errors
+    ///
+    /// Returns errors if an invalid combination of arguments is passed, and
+    /// [`CudnnError::NotSupported`] if neither an algorithm within the budget nor the fallback
+    /// algorithm supports the layer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use cudnn::{
+    ///     ConvDescriptor, ConvMode, CudnnContext, FilterDescriptor, ScalarC,
+    ///     TensorDescriptor
+    /// };
+    ///
+    /// use cust::memory::DeviceBuffer;
+    ///
+    /// let ctx = CudnnContext::new()?;
+    ///
+    /// let mode = ConvMode::CrossCorrelation;
+    /// let conv_desc = ConvDescriptor::<f32>::new([0, 0], [1, 1], [1, 1], mode)?;
+    ///
+    /// let x_desc = TensorDescriptor::<f32>::new_format(&[3, 2, 5, 5,], ScalarC::Nchw)?;
+    /// let w_desc = FilterDescriptor::<f32>::new(&[3, 2, 2, 2], ScalarC::Nchw)?;
+    /// let y_desc = TensorDescriptor::<f32>::new_format(&[3, 3, 4, 4], ScalarC::Nchw)?;
+    ///
+    /// // no workspace can be allocated at all.
+    /// let res = ctx.get_convolution_forward_algorithm_within(
+    ///     &x_desc, &w_desc, &y_desc, &conv_desc, 0,
+    /// )?;
+    ///
+    /// assert_eq!(res.workspace_size(), 0);
+    ///
+    /// // the chosen algorithm still computes the convolution without any workspace.
+    /// let x = DeviceBuffer::from_slice(&[1.0_f32; 150])?;
+    /// let w = DeviceBuffer::from_slice(&[1.0_f32; 24])?;
+    /// let mut y = DeviceBuffer::from_slice(&[0.0_f32; 144])?;
+    ///
+    /// ctx.convolution_forward(
+    ///     1.0,
+    ///     &x_desc,
+    ///     &x,
+    ///     &w_desc,
+    ///     &w,
+    ///     &conv_desc,
+    ///     res.algo(),
+    ///     None::<&mut DeviceBuffer<u8>>,
+    ///     0.0,
+    ///     &y_desc,
+    ///     &mut y,
+    /// )?;
+    ///
+    /// // every output sums a 2x2 window over 2 channels of ones.
+    /// assert!(y.as_host_vec()?.iter().all(|&value| value == 8.0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_convolution_forward_algorithm_within<T1, T2, CompT, T3>(
+        &self,
+        x_desc: &TensorDescriptor<T1>,
+        w_desc: &FilterDescriptor<T2>,
+        y_desc: &TensorDescriptor<T3>,
+        conv_desc: &ConvDescriptor<CompT>,
+        max_workspace: usize,
+    ) -> Result<BestHeuristic<ConvFwdAlgo>, CudnnError>
+    where
+        T1: DataType,
+        T2: DataType,
+        CompT: SupportedConv<T1, T2, T3>,
+        T3: DataType,
+    {
+        let perf_results = unsafe {
+            query_algorithms(FWD_ALGO_COUNT, |count, returned_count, results| {
+                cudnn_sys::cudnnGetConvolutionForwardAlgorithm_v7(
+                    self.raw,
+                    x_desc.raw,
+                    w_desc.raw,
+                    conv_desc.raw,
+                    y_desc.raw,
+                    count,
+                    returned_count,
+                    results,
+                )
+            })?
+        };
+
+        let results = perf_results
+            .into_iter()
+            .map(BestHeuristic::<ConvFwdAlgo>::try_from)
+            .filter_map(Result::ok)
+            .collect();
+
+        best_within_workspace(results, max_workspace, ConvFwdAlgo::ImplicitGemm)
+    }
+
+    /// This function serves as a heuristic for obtaining the best suited algorithm for
+    /// `convolution_backward_data()` for the given layer specifications.
+    ///
+    /// It will return the best algorithm according to an internal heuristic.
+    ///
+    /// # Arguments
+    ///
+    ///   * `w_desc` - previously initialized filter descriptor.
+    ///   * `dy_desc` - previously initialized differential tensor descriptor for the
+    ///     output map.
+    ///   * `dx_desc` - previously initialized differential tensor descriptor for the
+    ///     input map.
+    ///   * `conv_desc` - previously initialized convolution descriptor.
+    ///
+    /// **Do note** that the best found algorithm `MathType` must be set manually on the
+    /// convolution descriptor.
+    ///
+    /// cuDNN
+    /// [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnGetConvolutionBackwardDataAlgorithm_v7)
+    /// may offer additional information about the APi behavior.
+    ///
+    /// # Errors
+    ///
+    /// Returns errors if an invalid combination of arguments is passed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use cudnn::{
+    ///     ConvDescriptor, ConvMode, CudnnContext, FilterDescriptor, ScalarC,
+    ///     TensorDescriptor
+    /// };
+    ///
+    /// let ctx = CudnnContext::new()?;
+    ///
+    /// let padding = [0, 0];
+    /// let stride = [1, 1];
+    /// let dilation = [1, 1];
+    /// let mode = ConvMode::CrossCorrelation;
+    ///
+    /// let mut conv_desc = ConvDescriptor::<f32>::new(padding, stride, dilation, mode)?;
+    ///
+    /// let w_desc = FilterDescriptor::<f32>::new(&[3, 2, 2, 2], ScalarC::Nchw)?;
+    /// let dy_desc = TensorDescriptor::<f32>::new_format(&[3, 3, 4, 4], ScalarC::Nchw)?;
+    /// let dx_desc = TensorDescriptor::<f32>::new_format(&[3, 2, 5, 5,], ScalarC::Nchw)?;
+    ///
+    /// let res = ctx.get_convolution_backward_data_algorithm(&w_desc, &dy_desc, &dx_desc, &conv_desc)?;
+    ///
+    /// let algo = res.algo();
+    /// let math_type = res.math_type();
+    ///
+    /// conv_desc.set_math_type(math_type)?; // Sets math type.
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_convolution_backward_data_algorithm<T1, T2, CompT, T3>(
+        &self,
+        w_desc: &FilterDescriptor<T1>,
+        dy_desc: &TensorDescriptor<T2>,
+        dx_desc: &TensorDescriptor<T3>,
+        conv_desc: &ConvDescriptor<CompT>,
+    ) -> Result<BestHeuristic<ConvBwdDataAlgo>, CudnnError>
+    where
+        T1: DataType,
+        T2: DataType,
+        CompT: SupportedConv<T1, T2, T3>,
+        T3: DataType,
+    {
+        let perf_results = unsafe {
+            query_algorithms(1, |count, returned_count, results| {
+                cudnn_sys::cudnnGetConvolutionBackwardDataAlgorithm_v7(
+                    self.raw,
+                    w_desc.raw,
+                    dy_desc.raw,
+                    conv_desc.raw,
+                    dx_desc.raw,
+                    count,
+                    returned_count,
+                    results,
+                )
+            })?
+        };
+
+        perf_results
+            .into_iter()
+            .map(BestHeuristic::try_from)
+            .find_map(Result::ok)
+            .ok_or(CudnnError::BadParam)
+    }
+
+    /// Like [`get_convolution_backward_data_algorithm`](Self::get_convolution_backward_data_algorithm),
+    /// but only considers algorithms whose results are reproducible.
+    ///
+    /// Some backward data algorithms accumulate with atomics, so gradients computed with them can
+    /// differ between runs. This picks the best algorithm, according to the cuDNN heuristic, among
+    /// the [`Deterministic`](Determinism::Deterministic) ones.
+    ///
+    /// cuDNN
+    /// [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnGetConvolutionBackwardDataAlgorithm_v7)
+    /// may offer additional information about the APi behavior.
+    ///
+    /// # Errors
+    ///
+    /// Returns errors if an invalid combination of arguments is passed, and
+    /// [`CudnnError::NotSupported`] if no deterministic algorithm supports the layer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use cudnn::{
+    ///     ConvDescriptor, ConvMode, CudnnContext, Determinism, FilterDescriptor, ScalarC,
+    ///     TensorDescriptor
+    /// };
+    ///
+    /// let ctx = CudnnContext::new()?;
+    ///
+    /// let mode = ConvMode::CrossCorrelation;
+    /// let conv_desc = ConvDescriptor::<f32>::new([0, 0], [1, 1], [1, 1], mode)?;
+    ///
+    /// let w_desc = FilterDescriptor::<f32>::new(&[3, 2, 2, 2], ScalarC::Nchw)?;
+    /// let dy_desc = TensorDescriptor::<f32>::new_format(&[3, 3, 4, 4], ScalarC::Nchw)?;
+    /// let dx_desc = TensorDescriptor::<f32>::new_format(&[3, 2, 5, 5,], ScalarC::Nchw)?;
+    ///
+    /// let res = ctx.get_convolution_backward_data_algorithm_deterministic(
+    ///     &w_desc, &dy_desc, &dx_desc, &conv_desc,
+    /// )?;
+    ///
+    /// assert_eq!(res.determinism(), Determinism::Deterministic);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_convolution_backward_data_algorithm_deterministic<T1, T2, CompT, T3>(
+        &self,
+        w_desc: &FilterDescriptor<T1>,
+        dy_desc: &TensorDescriptor<T2>,
+        dx_desc: &TensorDescriptor<T3>,
+        conv_desc: &ConvDescriptor<CompT>,
+    ) -> Result<BestHeuristic<ConvBwdDataAlgo>, CudnnError>
+    where
+        T1: DataType,
+        T2: DataType,
+        CompT: SupportedConv<T1, T2, T3>,
+        T3: DataType,
+    {
+        let perf_results = unsafe {
+            query_algorithms(BWD_DATA_ALGO_COUNT, |count, returned_count, results| {
+                cudnn_sys::cudnnGetConvolutionBackwardDataAlgorithm_v7(
+                    self.raw,
+                    w_desc.raw,
+                    dy_desc.raw,
+                    conv_desc.raw,
+                    dx_desc.raw,
+                    count,
+                    returned_count,
+                    results,
+                )
+            })?
+        };
+
+        // the results are sorted by expected performance.
+        perf_results
+            .into_iter()
+            .map(BestHeuristic::<ConvBwdDataAlgo>::try_from)
+            .filter_map(Result::ok)
+            .find(|algo| algo.determinism() == Determinism::Deterministic)
+            .ok_or(CudnnError::NotSupported)
+    }
+
+    /// Like [`get_convolution_backward_data_algorithm`](Self::get_convolution_backward_data_algorithm),
+    /// but only considers algorithms which need at most `max_workspace` bytes of workspace.
+    ///
+    /// When no algorithm fits in the budget this falls back to
+    /// [`Algo0`](ConvBwdDataAlgo::Algo0), which needs little to no workspace, with a warning. See
+    /// [`get_convolution_forward_algorithm_within`](Self::get_convolution_forward_algorithm_within).
+    ///
+    /// cuDNN
+    /// [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnGetConvolutionBackwardDataAlgorithm_v7)
+    /// may offer additional information about the APi behavior.
+    ///
+    /// # Errors
+    ///
+    /// Returns errors if an invalid combination of arguments is passed, and
+    /// [`CudnnError::NotSupported`] if neither an algorithm within the budget nor the fallback
+    /// algorithm supports the layer.
+    pub fn get_convolution_backward_data_algorithm_within<T1, T2, CompT, T3>(
+        &self,
+        w_desc: &FilterDescriptor<T1>,
+        dy_desc: &TensorDescriptor<T2>,
+        dx_desc: &TensorDescriptor<T3>,
+        conv_desc: &ConvDescriptor<CompT>,
+        max_workspace: usize,
+    ) -> Result<BestHeuristic<ConvBwdDataAlgo>, CudnnError>
+    where
+        T1: DataType,
+        T2: DataType,
+        CompT: SupportedConv<T1, T2, T3>,
+        T3: DataType,
+    {
+        let perf_results = unsafe {
+            query_algorithms(BWD_DATA_ALGO_COUNT, |count, returned_count, results| {
+                cudnn_sys::cudnnGetConvolutionBackwardDataAlgorithm_v7(
+                    self.raw,
+                    w_desc.raw,
+                    dy_desc.raw,
+                    conv_desc.raw,
+                    dx_desc.raw,
+                    count,
+                    returned_count,
+                    results,
+                )
+            })?
+        };
+
+        let results = perf_results
+            .into_iter()
+            .map(BestHeuristic::<ConvBwdDataAlgo>::try_from)
+            .filter_map(Result::ok)
+            .collect();
+
+        best_within_workspace(results, max_workspace, ConvBwdDataAlgo::Algo0)
+    }
+
+    /// This function serves as a heuristic for obtaining the best suited algorithm for
+    /// `convolution_backward_filter()` for the given layer specifications.
+    ///
+    /// It will return the best algorithm according to an internal heuristic.
+    ///
+    /// # Arguments
+    ///
+    ///   * `x_desc` -  previously initialized tensor descriptor for the input map.
+    ///   * `dy_desc` - previously initialized differential tensor descriptor for the
+    ///     output map.
+    ///   * `dw_desc` - previously initialized differential tensor descriptor for the
+    ///     filter.
+    ///   * `conv_desc` - previously initialized convolution descriptor.
+    ///
+    /// **Do note** that the best found algorithm `MathType` must be set manually on the
+    /// convolution descriptor.
+    ///
+    /// cuDNN
+    /// [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnGetConvolutionBackwardFilterAlgorithm_v7)
+    /// may offer additional information about the APi behavior.
+    ///
+    /// # Errors
+    ///
+    /// Returns errors if an invalid combination of arguments is passed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use cudnn::{
+    ///     ConvDescriptor, ConvMode, CudnnContext, FilterDescriptor, ScalarC,
+    ///     TensorDescriptor
+    /// };
+    ///
+    /// let ctx = CudnnContext::new()?;
+    ///
+    /// let padding = [0, 0];
+    /// let stride = [1, 1];
+    /// let dilation = [1, 1];
+    /// let mode = ConvMode::CrossCorrelation;
+    ///
+    /// let mut conv_desc = ConvDescriptor::<f32>::new(padding, stride, dilation, mode)?;
+    ///
+    /// let x_desc = TensorDescriptor::<f32>::new_format(&[3, 2, 5, 5,], ScalarC::Nchw)?;
+    /// let dy_desc = TensorDescriptor::<f32>::new_format(&[3, 3, 4, 4], ScalarC::Nchw)?;
+    /// let dw_desc = FilterDescriptor::<f32>::new(&[3, 2, 2, 2], ScalarC::Nchw)?;
+    ///
+    /// let res = ctx.get_convolution_backward_filter_algorithm(&x_desc, &dy_desc, &dw_desc, &conv_desc)?;
+    ///
+    /// let algo = res.algo();
+    /// let math_type = res.math_type();
+    ///
+    /// conv_desc.set_math_type(math_type)?; // Sets math type.
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_convolution_backward_filter_algorithm<T1, T2, CompT, T3>(
+        &self,
+        x_desc: &TensorDescriptor<T1>,
+        dy_desc: &TensorDescriptor<T2>,
+        dw_desc: &FilterDescriptor<T3>,
+        conv_desc: &ConvDescriptor<CompT>,
+    ) -> Result<BestHeuristic<ConvBwdFilterAlgo>, CudnnError>
+    where
+        T1: DataType,
+        T2: DataType,
+        CompT: SupportedConv<T1, T2, T3>,
+        T3: DataType,
+    {
+        let perf_results = unsafe {
+            query_algorithms(1, |count, returned_count, results| {
+                cudnn_sys::cudnnGetConvolutionBackwardFilterAlgorithm_v7(
+                    self.raw,
+                    x_desc.raw,
+                    dy_desc.raw,
+                    conv_desc.raw,
+                    dw_desc.raw,
+                    count,
+                    returned_count,
+                    results,
+                )
+            })?
+        };
+
+        perf_results
+            .into_iter()
+            .map(BestHeuristic::try_from)
+            .find_map(Result::ok)
+            .ok_or(CudnnError::BadParam)
+    }
+
+    /// Like
+    /// [`get_convolution_backward_filter_algorithm`](Self::get_convolution_backward_filter_algorithm),
+    /// but only considers algorithms whose results are reproducible.
+    ///
+    /// Some backward filter algorithms accumulate with atomics, so gradients computed with them
+    /// can differ between runs. This picks the best algorithm, according to the cuDNN heuristic,
+    /// among the [`Deterministic`](Determinism::Deterministic) ones.
+    ///
+    /// cuDNN
+    /// [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnGetConvolutionBackwardFilterAlgorithm_v7)
+    /// may offer additional information about the APi behavior.
+    ///
+    /// # Errors
+    ///
+    /// Returns errors if an invalid combination of arguments is passed, and
+    /// [`CudnnError::NotSupported`] if no deterministic algorithm supports the layer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use cudnn::{
+    ///     ConvDescriptor, ConvMode, CudnnContext, Determinism, FilterDescriptor, ScalarC,
+    ///     TensorDescriptor
+    /// };
+    ///
+    /// let ctx = CudnnContext::new()?;
+    ///
+    /// let mode = ConvMode::CrossCorrelation;
+    /// let conv_desc = ConvDescriptor::<f32>::new([0, 0], [1, 1], [1, 1], mode)?;
+    ///
+    /// let x_desc = TensorDescriptor::<f32>::new_format(&[3, 2, 5, 5,], ScalarC::Nchw)?;
+    /// let dy_desc = TensorDescriptor::<f32>::new_format(&[3, 3, 4, 4], ScalarC::Nchw)?;
+    /// let dw_desc = FilterDescriptor::<f32>::new(&[3, 2, 2, 2], ScalarC::Nchw)?;
+    ///
+    /// let res = ctx.get_convolution_backward_filter_algorithm_deterministic(
+    ///     &x_desc, &dy_desc, &dw_desc, &conv_desc,
+    /// )?;
+    ///
+    /// assert_eq!(res.determinism(), Determinism::Deterministic);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_convolution_backward_filter_algorithm_deterministic<T1, T2, CompT, T3>(
+        &self,
+        x_desc: &TensorDescriptor<T1>,
+        dy_desc: &TensorDescriptor<T2>,
+        dw_desc: &FilterDescriptor<T3>,
+        conv_desc: &ConvDescriptor<CompT>,
+    ) -> Result<BestHeuristic<ConvBwdFilterAlgo>, CudnnError>
+    where
+        T1: DataType,
+        T2: DataType,
+        CompT: SupportedConv<T1, T2, T3>,
+        T3: DataType,
+    {
+        let perf_results = unsafe {
+            query_algorithms(BWD_FILTER_ALGO_COUNT, |count, returned_count, results| {
+                cudnn_sys::cudnnGetConvolutionBackwardFilterAlgorithm_v7(
+                    self.raw,
+                    x_desc.raw,
+                    dy_desc.raw,
+                    conv_desc.raw,
+                    dw_desc.raw,
+                    count,
+                    returned_count,
+                    results,
+                )
+            })?
+        };
+
+        // the results are sorted by expected performance.
+        perf_results
+            .into_iter()
+            .map(BestHeuristic::<ConvBwdFilterAlgo>::try_from)
+            .filter_map(Result::ok)
+            .find(|algo| algo.determinism() == Determinism::Deterministic)
+            .ok_or(CudnnError::NotSupported)
+    }
+
+    /// Like [`get_convolution_backward_filter_algorithm`](Self::get_convolution_backward_filter_algorithm),
+    /// but only considers algorithms which need at most `max_workspace` bytes of workspace.
+    ///
+    /// When no algorithm fits in the budget this falls back to
+    /// [`Algo0`](ConvBwdFilterAlgo::Algo0), which needs little to no workspace, with a warning.
+    /// See
+    /// [`get_convolution_forward_algorithm_within`](Self::get_convolution_forward_algorithm_within).
+    ///
+    /// cuDNN
+    /// [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnGetConvolutionBackwardFilterAlgorithm_v7)
+    /// may offer additional information about the APi behavior.
+    ///
+    /// # Errors
+    ///
+    /// Returns errors if an invalid combination of arguments is passed, and
+    /// [`CudnnError::NotSupported`] if neither an algorithm within the budget nor the fallback
+    /// algorithm supports the layer.
+    pub fn get_convolution_backward_filter_algorithm_within<T1, T2, CompT, T3>(
+        &self,
+        x_desc: &TensorDescriptor<T1>,
+        dy_desc: &TensorDescriptor<T2>,
+        dw_desc: &FilterDescriptor<T3>,
+        conv_desc: &ConvDescriptor<CompT>,
+        max_workspace: usize,
+    ) -> Result<BestHeuristic<ConvBwdFilterAlgo>, CudnnError>
+    where
+        T1: DataType,
+        T2: DataType,
+        CompT: SupportedConv<T1, T2, T3>,
+        T3: DataType,
+    {
+        let perf_results = unsafe {
+            query_algorithms(BWD_FILTER_ALGO_COUNT, |count, returned_count, results| {
+                cudnn_sys::cudnnGetConvolutionBackwardFilterAlgorithm_v7(
+                    self.raw,
+                    x_desc.raw,
+                    dy_desc.raw,
+                    conv_desc.raw,
+                    dw_desc.raw,
+                    count,
+                    returned_count,
+                    results,
+                )
+            })?
+        };
 
-        unsafe {
-            cudnn_sys::cudnnGetConvolutionForwardAlgorithm_v7(
-                self.raw,
-                x_desc.raw,
-                w_desc.raw,
-                conv_desc.raw,
-                y_desc.raw,
-                1,
-                returned_algo_count.as_mut_ptr(),
-                perf_results.as_mut_ptr(),
-            )
-            .into_result()?;
+        let results = perf_results
+            .into_iter()
+            .map(BestHeuristic::<ConvBwdFilterAlgo>::try_from)
+            .filter_map(Result::ok)
+            .collect();
+
+        best_within_workspace(results, max_workspace, ConvBwdFilterAlgo::Algo0)
+    }
+
+    /// Benchmarks the algorithms for `convolution_forward()` on the given layer specifications
+    /// and returns the measured performance of every candidate.
+    ///
+    /// The results are sorted by execution time, fastest first. Algorithms which failed to run
+    /// are included with an error [`status`](AlgoPerf::status), so that callers can apply their
+    /// own selection policy, such as picking the fastest algorithm whose workspace fits in a
+    /// memory budget.
+    ///
+    /// # Arguments
+    ///
+    ///   * `x_desc` - previously initialized tensor descriptor for the input map.
+    ///   * `w_desc` - previously initialized tensor descriptor for the filter map.
+    ///   * `y_desc` - previously initialized tensor descriptor for the output map.
+    ///   * `conv_desc` - previously initialized convolution descriptor.
+    ///
+    /// **Do note** that this actually runs every algorithm, and allocates their workspaces, so
+    /// it is considerably slower than
+    /// [`get_convolution_forward_algorithm`](Self::get_convolution_forward_algorithm). As with
+    /// that function, the math type of the chosen algorithm should be set on the convolution
+    /// descriptor manually.
+    ///
+    /// cuDNN
+    /// [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnFindConvolutionForwardAlgorithm)
+    /// may offer additional information about the APi behavior.
+    ///
+    /// # Errors
+    ///
+    /// Returns errors if an invalid combination of arguments is passed, and
+    /// [`CudnnError::NotSupported`] if none of the algorithms ran successfully.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use cudnn::{
+    ///     ConvDescriptor, ConvMode, CudnnContext, FilterDescriptor, ScalarC,
+    ///     TensorDescriptor
+    /// };
+    ///
+    /// let ctx = CudnnContext::new()?;
+    ///
+    /// let mode = ConvMode::CrossCorrelation;
+    /// let mut conv_desc = ConvDescriptor::<f32>::new([0, 0], [1, 1], [1, 1], mode)?;
+    ///
+    /// let x_desc = TensorDescriptor::<f32>::new_format(&[3, 2, 5, 5,], ScalarC::Nchw)?;
+    /// let w_desc = FilterDescriptor::<f32>::new(&[3, 2, 2, 2], ScalarC::Nchw)?;
+    /// let y_desc = TensorDescriptor::<f32>::new_format(&[3, 3, 4, 4], ScalarC::Nchw)?;
+    ///
+    /// let results = ctx.find_convolution_forward_algorithms(&x_desc, &w_desc, &y_desc, &conv_desc)?;
+    ///
+    /// // the fastest algorithm which needs at most 64MB of workspace.
+    /// let best = results
+    ///     .iter()
+    ///     .filter(|perf| perf.status().is_ok())
+    ///     .find(|perf| perf.memory() <= 64 * 1024 * 1024)
+    ///     .unwrap();
+    ///
+    /// conv_desc.set_math_type(best.math_type())?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn find_convolution_forward_algorithms<T1, T2, CompT, T3>(
+        &self,
+        x_desc: &TensorDescriptor<T1>,
+        w_desc: &FilterDescriptor<T2>,
+        y_desc: &TensorDescriptor<T3>,
+        conv_desc: &ConvDescriptor<CompT>,
+    ) -> Result<Vec<AlgoPerf<ConvFwdAlgo>>, CudnnError>
+    where
+        T1: DataType,
+        T2: DataType,
+        CompT: SupportedConv<T1, T2, T3>,
+        T3: DataType,
+    {
+        let perf_results = unsafe {
+            query_algorithms(FWD_ALGO_COUNT, |count, returned_count, results| {
+                cudnn_sys::cudnnFindConvolutionForwardAlgorithm(
+                    self.raw,
+                    x_desc.raw,
+                    w_desc.raw,
+                    conv_desc.raw,
+                    y_desc.raw,
+                    count,
+                    returned_count,
+                    results,
+                )
+            })?
+        };
+
+        let results: Vec<_> = perf_results.into_iter().map(AlgoPerf::from).collect();
 
-            let returned_algo_count = returned_algo_count.assume_init();
-
-            match returned_algo_count {
-                // This is general enough so that in the future it can be expanded to be more
-                // complex.
-                1 => {
-                    let results: Vec<BestHeuristic<ConvFwdAlgo>> = {
-                        let raw_results = std::slice::from_raw_parts(
-                            perf_results.as_ptr(),
-                            returned_algo_count as usize,
-                        );
-
-                        raw_results
-                            .iter()
-                            .copied()
-                            .map(BestHeuristic::<ConvFwdAlgo>::try_from)
-                            .filter_map(Result::ok)
-                            .collect()
-                    };
-
-                    let algo = results[0];
-                    Ok(algo)
-                }
-                _ => Err(CudnnError::BadParam),
-            }
+        if results.iter().all(|perf| perf.status().is_err()) {
+            return Err(CudnnError::NotSupported);
         }
+
+        Ok(results)
     }
 
-    /// This function serves as a heuristic for obtaining the best suited algorithm for
-    /// `convolution_backward_data()` for the given layer specifications.
+    /// Benchmarks the algorithms for `convolution_backward_data()` on the given layer
+    /// specifications and returns the measured performance of every candidate.
     ///
-    /// It will return the best algorithm according to an internal heuristic.
+    /// The results are sorted by execution time, fastest first. Algorithms which failed to run
+    /// are included with an error [`status`](AlgoPerf::status), so that callers can apply their
+    /// own selection policy.
     ///
     /// # Arguments
     ///
@@ -148,16 +798,18 @@ impl CudnnContext {
     ///     input map.
     ///   * `conv_desc` - previously initialized convolution descriptor.
     ///
-    /// **Do note** that the best found algorithm `MathType` must be set manually on the
-    /// convolution descriptor.
+    /// **Do note** that this actually runs every algorithm, and allocates their workspaces, so
+    /// it is considerably slower than
+    /// [`get_convolution_backward_data_algorithm`](Self::get_convolution_backward_data_algorithm).
     ///
     /// cuDNN
-    /// [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnGetConvolutionBackwardDataAlgorithm_v7)
+    /// [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnFindConvolutionBackwardDataAlgorithm)
     /// may offer additional information about the APi behavior.
     ///
     /// # Errors
     ///
-    /// Returns errors if an invalid combination of arguments is passed.
+    /// Returns errors if an invalid combination of arguments is passed, and
+    /// [`CudnnError::NotSupported`] if none of the algorithms ran successfully.
     ///
     /// # Examples
     ///
@@ -172,87 +824,66 @@ impl CudnnContext {
     ///
     /// let ctx = CudnnContext::new()?;
     ///
-    /// let padding = [0, 0];
-    /// let stride = [1, 1];
-    /// let dilation = [1, 1];
     /// let mode = ConvMode::CrossCorrelation;
-    ///
-    /// let mut conv_desc = ConvDescriptor::<f32>::new(padding, stride, dilation, mode)?;
+    /// let conv_desc = ConvDescriptor::<f32>::new([0, 0], [1, 1], [1, 1], mode)?;
     ///
     /// let w_desc = FilterDescriptor::<f32>::new(&[3, 2, 2, 2], ScalarC::Nchw)?;
     /// let dy_desc = TensorDescriptor::<f32>::new_format(&[3, 3, 4, 4], ScalarC::Nchw)?;
     /// let dx_desc = TensorDescriptor::<f32>::new_format(&[3, 2, 5, 5,], ScalarC::Nchw)?;
     ///
-    /// let res = ctx.get_convolution_backward_data_algorithm(&w_desc, &dy_desc, &dx_desc, &conv_desc)?;
-    ///
-    /// let algo = res.algo();
-    /// let math_type = res.math_type();
+    /// let results = ctx.find_convolution_backward_data_algorithms(
+    ///     &w_desc, &dy_desc, &dx_desc, &conv_desc,
+    /// )?;
     ///
-    /// conv_desc.set_math_type(math_type)?; // Sets math type.
+    /// for perf in results.iter().filter(|perf| perf.status().is_ok()) {
+    ///     println!("{:?}: {}ms, {} bytes", perf.algo(), perf.time(), perf.memory());
+    /// }
     /// # Ok(())
     /// # }
     /// ```
-    pub fn get_convolution_backward_data_algorithm<T1, T2, CompT, T3>(
+    pub fn find_convolution_backward_data_algorithms<T1, T2, CompT, T3>(
         &self,
         w_desc: &FilterDescriptor<T1>,
         dy_desc: &TensorDescriptor<T2>,
         dx_desc: &TensorDescriptor<T3>,
         conv_desc: &ConvDescriptor<CompT>,
-    ) -> Result<BestHeuristic<ConvBwdDataAlgo>, CudnnError>
+    ) -> Result<Vec<AlgoPerf<ConvBwdDataAlgo>>, CudnnError>
     where
         T1: DataType,
         T2: DataType,
         CompT: SupportedConv<T1, T2, T3>,
         T3: DataType,
     {
-        let mut returned_algo_count = MaybeUninit::uninit();
-        let mut perf_results = MaybeUninit::uninit();
+        let perf_results = unsafe {
+            query_algorithms(BWD_DATA_ALGO_COUNT, |count, returned_count, results| {
+                cudnn_sys::cudnnFindConvolutionBackwardDataAlgorithm(
+                    self.raw,
+                    w_desc.raw,
+                    dy_desc.raw,
+                    conv_desc.raw,
+                    dx_desc.raw,
+                    count,
+                    returned_count,
+                    results,
+                )
+            })?
+        };
 
-        unsafe {
-            cudnn_sys::cudnnGetConvolutionBackwardDataAlgorithm_v7(
-                self.raw,
-                w_desc.raw,
-                dy_desc.raw,
-                conv_desc.raw,
-                dx_desc.raw,
-                1,
-                returned_algo_count.as_mut_ptr(),
-                perf_results.as_mut_ptr(),
-            )
-            .into_result()?;
+        let results: Vec<_> = perf_results.into_iter().map(AlgoPerf::from).collect();
 
-            let returned_algo_count = returned_algo_count.assume_init();
-
-            match returned_algo_count {
-                // This is general enough so that in the future it can be expanded to be more
-                // complex.
-                1 => {
-                    let results: Vec<BestHeuristic<ConvBwdDataAlgo>> = {
-                        let raw_results = std::slice::from_raw_parts(
-                            perf_results.as_ptr(),
-                            returned_algo_count as usize,
-                        );
-
-                        raw_results
-                            .iter()
-                            .copied()
-                            .map(BestHeuristic::<ConvBwdDataAlgo>::try_from)
-                            .filter_map(Result::ok)
-                            .collect()
-                    };
-
-                    let algo = results[0];
-                    Ok(algo)
-                }
-                _ => Err(CudnnError::BadParam),
-            }
+        if results.iter().all(|perf| perf.status().is_err()) {
+            return Err(CudnnError::NotSupported);
         }
+
+        Ok(results)
     }
 
-    /// This function serves as a heuristic for obtaining the best suited algorithm for
-    /// `convolution_backward_filter()` for the given layer specifications.
+    /// Benchmarks the algorithms for `convolution_backward_filter()` on the given layer
+    /// specifications and returns the measured performance of every candidate.
     ///
-    /// It will return the best algorithm according to an internal heuristic.
+    /// The results are sorted by execution time, fastest first. Algorithms which failed to run
+    /// are included with an error [`status`](AlgoPerf::status), so that callers can apply their
+    /// own selection policy.
     ///
     /// # Arguments
     ///
@@ -263,16 +894,18 @@ impl CudnnContext {
     ///     filter.
     ///   * `conv_desc` - previously initialized convolution descriptor.
     ///
-    /// **Do note** that the best found algorithm `MathType` must be set manually on the
-    /// convolution descriptor.
+    /// **Do note** that this actually runs every algorithm, and allocates their workspaces, so
+    /// it is considerably slower than
+    /// [`get_convolution_backward_filter_algorithm`](Self::get_convolution_backward_filter_algorithm).
     ///
     /// cuDNN
-    /// [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnGetConvolutionBackwardFilterAlgorithm_v7)
+    /// [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnFindConvolutionBackwardFilterAlgorithm)
     /// may offer additional information about the APi behavior.
     ///
     /// # Errors
     ///
-    /// Returns errors if an invalid combination of arguments is passed.
+    /// Returns errors if an invalid combination of arguments is passed, and
+    /// [`CudnnError::NotSupported`] if none of the algorithms ran successfully.
     ///
     /// # Examples
     ///
@@ -281,87 +914,68 @@ impl CudnnContext {
     /// #
     /// # fn main() -> Result<(), Box<dyn Error>> {
     /// use cudnn::{
-    ///     ConvDescriptor, ConvMode, CudnnContext, FilterDescriptor, ScalarC,
+    ///     ConvDescriptor, ConvMode, CudnnContext, Determinism, FilterDescriptor, ScalarC,
     ///     TensorDescriptor
     /// };
     ///
     /// let ctx = CudnnContext::new()?;
     ///
-    /// let padding = [0, 0];
-    /// let stride = [1, 1];
-    /// let dilation = [1, 1];
     /// let mode = ConvMode::CrossCorrelation;
-    ///
-    /// let mut conv_desc = ConvDescriptor::<f32>::new(padding, stride, dilation, mode)?;
+    /// let conv_desc = ConvDescriptor::<f32>::new([0, 0], [1, 1], [1, 1], mode)?;
     ///
     /// let x_desc = TensorDescriptor::<f32>::new_format(&[3, 2, 5, 5,], ScalarC::Nchw)?;
     /// let dy_desc = TensorDescriptor::<f32>::new_format(&[3, 3, 4, 4], ScalarC::Nchw)?;
     /// let dw_desc = FilterDescriptor::<f32>::new(&[3, 2, 2, 2], ScalarC::Nchw)?;
     ///
-    /// let res = ctx.get_convolution_backward_filter_algorithm(&x_desc, &dy_desc, &dw_desc, &conv_desc)?;
-    ///
-    /// let algo = res.algo();
-    /// let math_type = res.math_type();
+    /// let results = ctx.find_convolution_backward_filter_algorithms(
+    ///     &x_desc, &dy_desc, &dw_desc, &conv_desc,
+    /// )?;
     ///
-    /// conv_desc.set_math_type(math_type)?; // Sets math type.
+    /// // the fastest deterministic algorithm within a 256MB workspace budget.
+    /// let algo = results
+    ///     .iter()
+    ///     .filter(|perf| perf.status().is_ok())
+    ///     .filter(|perf| perf.determinism() == Determinism::Deterministic)
+    ///     .find(|perf| perf.memory() <= 256 * 1024 * 1024)
+    ///     .map(|perf| perf.algo());
     /// # Ok(())
     /// # }
     /// ```
-    pub fn get_convolution_backward_filter_algorithm<T1, T2, CompT, T3>(
+    pub fn find_convolution_backward_filter_algorithms<T1, T2, CompT, T3>(
         &self,
         x_desc: &TensorDescriptor<T1>,
         dy_desc: &TensorDescriptor<T2>,
         dw_desc: &FilterDescriptor<T3>,
         conv_desc: &ConvDescriptor<CompT>,
-    ) -> Result<BestHeuristic<ConvBwdFilterAlgo>, CudnnError>
+    ) -> Result<Vec<AlgoPerf<ConvBwdFilterAlgo>>, CudnnError>
     where
         T1: DataType,
         T2: DataType,
         CompT: SupportedConv<T1, T2, T3>,
         T3: DataType,
     {
-        let mut returned_algo_count = MaybeUninit::uninit();
-        let mut perf_results = MaybeUninit::uninit();
+        let perf_results = unsafe {
+            query_algorithms(BWD_FILTER_ALGO_COUNT, |count, returned_count, results| {
+                cudnn_sys::cudnnFindConvolutionBackwardFilterAlgorithm(
+                    self.raw,
+                    x_desc.raw,
+                    dy_desc.raw,
+                    conv_desc.raw,
+                    dw_desc.raw,
+                    count,
+                    returned_count,
+                    results,
+                )
+            })?
+        };
 
-        unsafe {
-            cudnn_sys::cudnnGetConvolutionBackwardFilterAlgorithm_v7(
-                self.raw,
-                x_desc.raw,
-                dy_desc.raw,
-                conv_desc.raw,
-                dw_desc.raw,
-                1,
-                returned_algo_count.as_mut_ptr(),
-                perf_results.as_mut_ptr(),
-            )
-            .into_result()?;
+        let results: Vec<_> = perf_results.into_iter().map(AlgoPerf::from).collect();
 
-            let returned_algo_count = returned_algo_count.assume_init();
-
-            match returned_algo_count {
-                // This is general enough so that in the future it can be expanded to be more
-                // complex.
-                1 => {
-                    let results: Vec<BestHeuristic<ConvBwdFilterAlgo>> = {
-                        let raw_results = std::slice::from_raw_parts(
-                            perf_results.as_ptr(),
-                            returned_algo_count as usize,
-                        );
-
-                        raw_results
-                            .iter()
-                            .copied()
-                            .map(BestHeuristic::<ConvBwdFilterAlgo>::try_from)
-                            .filter_map(Result::ok)
-                            .collect()
-                    };
-
-                    let algo = results[0];
-                    Ok(algo)
-                }
-                _ => Err(CudnnError::BadParam),
-            }
+        if results.iter().all(|perf| perf.status().is_err()) {
+            return Err(CudnnError::NotSupported);
         }
+
+        Ok(results)
     }
 
     /// This function returns the amount of GPU memory workspace the user needs to
@@ -708,9 +1322,10 @@ impl CudnnContext {
     ///   * `conv_desc` - convolution descriptor.
     ///   * `algo` - convolution algorithm that should be used to compute the result.
     ///   * `work_space` -  a buffer to GPU memory to a workspace needed to be able to
-    ///     execute the specified algorithm. Must be left to `None` if the algorithm
-    ///     works in-place. The workspace dimension can be obtained with
-    ///     `get_convolution_forward_workspace_size`.
+    ///     execute the specified algorithm, whose size can be obtained with
+    ///     [`get_convolution_forward_workspace_size()`](crate::CudnnContext::get_convolution_forward_workspace_size).
+    ///     If `None`, the workspace owned by the context is used instead, grown to the
+    ///     size the algorithm needs.
     ///   * `beta` - scaling parameter.
     ///   * `y_desc` - output map descriptor.
     ///   * `y` - output map data. It carries the result of the convolution. Scaling
@@ -811,6 +1426,9 @@ impl CudnnContext {
         T3: DataType,
         W: GpuBuffer<u8>,
     {
+        check_dims(x_desc, w_desc, y_desc, conv_desc)?;
+        check_group_channels(x_desc, w_desc, conv_desc)?;
+
         let x_data = x.as_device_ptr().as_ptr() as *const std::ffi::c_void;
         let w_data = w.as_device_ptr().as_ptr() as *const std::ffi::c_void;
         let y_data = y.as_device_ptr().as_mut_ptr() as *mut std::ffi::c_void;
@@ -818,16 +1436,10 @@ impl CudnnContext {
         let alpha = &alpha as *const CompT as *const std::ffi::c_void;
         let beta = &beta as *const CompT as *const std::ffi::c_void;
 
-        // If the size is 0 then the algorithm can work in-place and cuDNN expects a null
-        // pointer.
-        let (work_space_ptr, work_space_size) = {
-            work_space.map_or((std::ptr::null_mut(), 0), |work_space| {
-                (
-                    work_space.as_device_ptr().as_mut_ptr() as *mut std::ffi::c_void,
-                    work_space.len(),
-                )
-            })
-        };
+        let mut context_work_space = self.work_space.borrow_mut();
+        let (work_space_ptr, work_space_size) = context_work_space.raw_parts(work_space, || {
+            self.get_convolution_forward_workspace_size(x_desc, w_desc, y_desc, conv_desc, algo)
+        })?;
 
         unsafe {
             cudnn_sys::cudnnConvolutionForward(
@@ -866,9 +1478,10 @@ impl CudnnContext {
     ///   * `conv_desc` - convolution descriptor.
     ///   * `algo` - convolution algorithm that should be used to compute the result.
     ///   * `work_space` -  a buffer to GPU memory to a workspace needed to be able to
-    ///     execute the specified algorithm. Must be left to `None` if the algorithm
-    ///     works in-place. The workspace dimension can be obtained with
-    ///     `get_convolution_forward_workspace_size`.
+    ///     execute the specified algorithm, whose size can be obtained with
+    ///     [`get_convolution_forward_workspace_size()`](crate::CudnnContext::get_convolution_forward_workspace_size).
+    ///     If `None`, the workspace owned by the context is used instead, grown to the
+    ///     size the algorithm needs.
     ///   * `beta` - scaling parameter.
     ///   * `z_desc` - descriptor for the z tensor.
     ///   * `z` - data for the z tensor.
@@ -991,6 +1604,9 @@ impl CudnnContext {
         T3: DataType,
         W: GpuBuffer<u8>,
     {
+        check_dims(x_desc, w_desc, y_desc, conv_desc)?;
+        check_group_channels(x_desc, w_desc, conv_desc)?;
+
         let x_data = x.as_device_ptr().as_ptr() as *const std::ffi::c_void;
         let w_data = w.as_device_ptr().as_ptr() as *const std::ffi::c_void;
         let z_data = z.as_device_ptr().as_ptr() as *const std::ffi::c_void;
@@ -1000,14 +1616,10 @@ impl CudnnContext {
         let alpha = &alpha as *const CompT as *const std::ffi::c_void;
         let beta = &beta as *const CompT as *const std::ffi::c_void;
 
-        let (work_space_ptr, work_space_size) = {
-            work_space.map_or((std::ptr::null_mut(), 0), |work_space| {
-                (
-                    work_space.as_device_ptr().as_mut_ptr() as *mut std::ffi::c_void,
-                    work_space.len(),
-                )
-            })
-        };
+        let mut context_work_space = self.work_space.borrow_mut();
+        let (work_space_ptr, work_space_size) = context_work_space.raw_parts(work_space, || {
+            self.get_convolution_forward_workspace_size(x_desc, w_desc, y_desc, conv_desc, algo)
+        })?;
 
         unsafe {
             cudnn_sys::cudnnConvolutionBiasActivationForward(
@@ -1053,9 +1665,10 @@ impl CudnnContext {
     ///   the same layer.
     /// * `algo` - convolution algorithm that should be used to compute the result.
     /// * `work_space` -  a buffer to GPU memory to a workspace needed to be able to
-    ///   execute the specified algorithm. Must be left to `None` if the algorithm works
-    ///   in-place. The workspace dimension can be obtained with
+    ///   execute the specified algorithm, whose size can be obtained with
     ///   [`get_convolution_backward_data_workspace_size()`](crate::CudnnContext::get_convolution_backward_data_workspace_size).
+    ///   If `None`, the workspace owned by the context is used instead, grown to the
+    ///   size the algorithm needs.
     /// * `beta` - scaling parameter.
     /// * `dx_desc` - input map gradient descriptor.
     /// * `dx` - input map gradient data.
@@ -1147,6 +1760,9 @@ impl CudnnContext {
         T3: DataType,
         W: GpuBuffer<u8>,
     {
+        check_dims(dx_desc, w_desc, dy_desc, conv_desc)?;
+        check_group_channels(dx_desc, w_desc, conv_desc)?;
+
         let w_data = w.as_device_ptr().as_ptr() as *const std::ffi::c_void;
         let dy_data = dy.as_device_ptr().as_ptr() as *const std::ffi::c_void;
         let dx_data = dx.as_device_ptr().as_mut_ptr() as *mut std::ffi::c_void;
@@ -1154,14 +1770,12 @@ impl CudnnContext {
         let alpha = &alpha as *const CompT as *const std::ffi::c_void;
         let beta = &beta as *const CompT as *const std::ffi::c_void;
 
-        let (work_space_ptr, work_space_size) = {
-            work_space.map_or((std::ptr::null_mut(), 0), |work_space| {
-                (
-                    work_space.as_device_ptr().as_mut_ptr() as *mut std::ffi::c_void,
-                    work_space.len(),
-                )
-            })
-        };
+        let mut context_work_space = self.work_space.borrow_mut();
+        let (work_space_ptr, work_space_size) = context_work_space.raw_parts(work_space, || {
+            self.get_convolution_backward_data_workspace_size(
+                w_desc, dy_desc, dx_desc, conv_desc, algo,
+            )
+        })?;
 
         unsafe {
             cudnn_sys::cudnnConvolutionBackwardData(
@@ -1203,9 +1817,10 @@ impl CudnnContext {
     ///     refers to the same layer.
     ///   * `algo` - convolution algorithm that should be used to compute the result.
     ///   * `work_space` -  a buffer to GPU memory to a workspace needed to be able to
-    ///     execute the specified algorithm. Must be left to `None` if the algorithm
-    ///     works in-place. The workspace dimension can be obtained with
-    ///     [`get_convolution_backward_data_workspace_size()`](crate::CudnnContext::get_convolution_backward_data_workspace_size).
+    ///     execute the specified algorithm, whose size can be obtained with
+    ///     [`get_convolution_backward_filter_workspace_size()`](crate::CudnnContext::get_convolution_backward_filter_workspace_size).
+    ///     If `None`, the workspace owned by the context is used instead, grown to the
+    ///     size the algorithm needs.
     ///   * `beta` - scaling parameter.
     ///   * `dw_desc` - filter gradient descriptor.
     ///   * `dw` - filter gradient data.
@@ -1297,6 +1912,9 @@ impl CudnnContext {
         T3: DataType,
         W: GpuBuffer<u8>,
     {
+        check_dims(x_desc, dw_desc, dy_desc, conv_desc)?;
+        check_group_channels(x_desc, dw_desc, conv_desc)?;
+
         let x_data = x.as_device_ptr().as_ptr() as *const std::ffi::c_void;
         let dy_data = y.as_device_ptr().as_ptr() as *const std::ffi::c_void;
         let dw_data = dw.as_device_ptr().as_mut_ptr() as *mut std::ffi::c_void;
@@ -1304,14 +1922,12 @@ impl CudnnContext {
         let alpha = &alpha as *const CompT as *const std::ffi::c_void;
         let beta = &beta as *const CompT as *const std::ffi::c_void;
 
-        let (work_space_ptr, work_space_size) = {
-            work_space.map_or((std::ptr::null_mut(), 0), |work_space| {
-                (
-                    work_space.as_device_ptr().as_mut_ptr() as *mut std::ffi::c_void,
-                    work_space.len(),
-                )
-            })
-        };
+        let mut context_work_space = self.work_space.borrow_mut();
+        let (work_space_ptr, work_space_size) = context_work_space.raw_parts(work_space, || {
+            self.get_convolution_backward_filter_workspace_size(
+                x_desc, dy_desc, dw_desc, conv_desc, algo,
+            )
+        })?;
 
         unsafe {
             cudnn_sys::cudnnConvolutionBackwardFilter(
@@ -1332,4 +1948,265 @@ impl CudnnContext {
             .into_result()
         }
     }
+
+    /// Returns the number of elements of the column buffer written by
+    /// [`im2col()`](CudnnContext::im2col) for the given convolution.
+    ///
+    /// The column buffer is a matrix with one row for every output element of every
+    /// image, so N x H<sub>out</sub> x W<sub>out</sub> rows, and one column for every input
+    /// element a filter window covers, so C x R x S columns.
+    ///
+    /// # Arguments
+    ///
+    ///   * `x_desc` - input map descriptor.
+    ///   * `w_desc` - filter descriptor.
+    ///   * `conv_desc` - convolution descriptor.
+    ///
+    /// # Errors
+    ///
+    /// Returns errors if the descriptors are not compatible with each other.
+    pub fn get_im2col_size<T1, T2, CompT>(
+        &self,
+        x_desc: &TensorDescriptor<T1>,
+        w_desc: &FilterDescriptor<T2>,
+        conv_desc: &ConvDescriptor<CompT>,
+    ) -> Result<usize, CudnnError>
+    where
+        T1: DataType,
+        T2: DataType,
+        CompT: DataType,
+    {
+        let x_shape = x_desc.shape()?;
+        let w_shape = w_desc.shape()?;
+        let y_shape = conv_desc.forward_output_dim(x_desc, w_desc)?;
+
+        let (Some(&batch_size), Some(&in_channels)) = (y_shape.first(), x_shape.get(1)) else {
+            return Err(CudnnError::BadParam);
+        };
+
+        let rows = batch_size as usize * y_shape[2..].iter().product::<i32>() as usize;
+        let columns = in_channels as usize * w_shape[2..].iter().product::<i32>() as usize;
+
+        Ok(rows * columns)
+    }
+
+    /// Constructs the column matrix of a GEMM based forward convolution, also known as im2col.
+    ///
+    /// Multiplying the column matrix by the filter, reshaped to a C x R x S by K matrix, yields
+    /// the output of the convolution described by `conv_desc`. The layout of the column matrix is
+    /// described in [`get_im2col_size()`](CudnnContext::get_im2col_size).
+    ///
+    /// # Arguments
+    ///
+    ///   * `x_desc` - input map descriptor.
+    ///   * `x` - input map data.
+    ///   * `w_desc` - filter descriptor.
+    ///   * `conv_desc` - convolution descriptor.
+    ///   * `col_buffer` - output column matrix. Must hold at least the number of elements
+    ///     returned by [`get_im2col_size()`](CudnnContext::get_im2col_size).
+    ///
+    /// cuDNN [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnIm2Col)
+    /// may offer additional information about the APi behavior.
+    ///
+    /// # Errors
+    ///
+    /// Returns errors if `col_buffer` is too small or if an invalid or unsupported combination of
+    /// arguments is passed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use cudnn::{
+    ///     ConvDescriptor, ConvMode, CudnnContext, FilterDescriptor, ScalarC, TensorDescriptor,
+    /// };
+    /// use cust::memory::DeviceBuffer;
+    ///
+    /// let ctx = CudnnContext::new()?;
+    ///
+    /// let mode = ConvMode::CrossCorrelation;
+    /// let conv_desc = ConvDescriptor::<f32>::new([0, 0], [1, 1], [1, 1], mode)?;
+    /// let x_desc = TensorDescriptor::<f32>::new_format(&[3, 2, 5, 5], ScalarC::Nchw)?;
+    /// let w_desc = FilterDescriptor::<f32>::new(&[3, 2, 2, 2], ScalarC::Nchw)?;
+    ///
+    /// # let x = DeviceBuffer::from_slice(&[1.0_f32; 150])?;
+    /// let size = ctx.get_im2col_size(&x_desc, &w_desc, &conv_desc)?;
+    /// // 3 images with 4x4 outputs each, and 2 channels covered by a 2x2 filter.
+    /// assert_eq!(size, (3 * 4 * 4) * (2 * 2 * 2));
+    ///
+    /// let mut col = DeviceBuffer::from_slice(&vec![0.0_f32; size])?;
+    /// ctx.im2col(&x_desc, &x, &w_desc, &conv_desc, &mut col)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn im2col<T, T2, CompT>(
+        &self,
+        x_desc: &TensorDescriptor<T>,
+        x: &impl GpuBuffer<T>,
+        w_desc: &FilterDescriptor<T2>,
+        conv_desc: &ConvDescriptor<CompT>,
+        col_buffer: &mut impl GpuBuffer<T>,
+    ) -> Result<(), CudnnError>
+    where
+        T: DataType,
+        T2: DataType,
+        CompT: DataType,
+    {
+        if col_buffer.len() < self.get_im2col_size(x_desc, w_desc, conv_desc)? {
+            return Err(CudnnError::BadParam);
+        }
+
+        let x_data = x.as_device_ptr().as_ptr() as *const std::ffi::c_void;
+        let col_data = col_buffer.as_device_ptr().as_mut_ptr() as *mut std::ffi::c_void;
+
+        unsafe {
+            cudnn_sys::cudnnIm2Col(
+                self.raw,
+                x_desc.raw,
+                x_data,
+                w_desc.raw,
+                conv_desc.raw,
+                col_data,
+            )
+            .into_result()
+        }
+    }
+}
+
+/// Checks that the input, described by `x_desc`, the filter, described by `w_desc`, and the
+/// output, described by `y_desc`, all have two more dimensions than the spatial dimensions of
+/// `conv_desc`, i.e. that they are all NCHW for a 2-d convolution or all NCDHW for a 3-d one.
+fn check_dims<T1, T2, T3, CompT>(
+    x_desc: &TensorDescriptor<T1>,
+    w_desc: &FilterDescriptor<T2>,
+    y_desc: &TensorDescriptor<T3>,
+    conv_desc: &ConvDescriptor<CompT>,
+) -> Result<(), CudnnError>
+where
+    T1: DataType,
+    T2: DataType,
+    T3: DataType,
+    CompT: DataType,
+{
+    let ndims = conv_desc.spatial_dims()? + 2;
+
+    if x_desc.shape()?.len() != ndims
+        || w_desc.shape()?.len() != ndims
+        || y_desc.shape()?.len() != ndims
+    {
+        return Err(CudnnError::BadParam);
+    }
+
+    Ok(())
+}
+
+/// Checks that the channels of the input map `x_desc` and of the filter `w_desc` are compatible
+/// with the group count of `conv_desc`.
+///
+/// For a grouped convolution the number of input channels C and output feature maps K must both be
+/// divisible by the group count, and the filter must have C / groups channels. Ungrouped
+/// convolutions are left to cuDNN to validate.
+fn check_group_channels<T1, T2, CompT>(
+    x_desc: &TensorDescriptor<T1>,
+    w_desc: &FilterDescriptor<T2>,
+    conv_desc: &ConvDescriptor<CompT>,
+) -> Result<(), CudnnError>
+where
+    T1: DataType,
+    T2: DataType,
+    CompT: DataType,
+{
+    let groups = conv_desc.group_count()?;
+    if groups <= 1 {
+        return Ok(());
+    }
+
+    let x_shape = x_desc.shape()?;
+    let w_shape = w_desc.shape()?;
+    let (Some(&in_channels), Some(&out_channels), Some(&filter_channels)) =
+        (x_shape.get(1), w_shape.first(), w_shape.get(1))
+    else {
+        return Err(CudnnError::BadParam);
+    };
+
+    if in_channels % groups != 0
+        || out_channels % groups != 0
+        || filter_channels != in_channels / groups
+    {
+        return Err(CudnnError::BadParam);
+    }
+
+    Ok(())
+}
+
+const FWD_ALGO_COUNT: usize =
+    cudnn_sys::cudnnConvolutionFwdAlgo_t::CUDNN_CONVOLUTION_FWD_ALGO_COUNT as usize;
+const BWD_DATA_ALGO_COUNT: usize =
+    cudnn_sys::cudnnConvolutionBwdDataAlgo_t::CUDNN_CONVOLUTION_BWD_DATA_ALGO_COUNT as usize;
+const BWD_FILTER_ALGO_COUNT: usize =
+    cudnn_sys::cudnnConvolutionBwdFilterAlgo_t::CUDNN_CONVOLUTION_BWD_FILTER_ALGO_COUNT as usize;
+
+/// Runs one of the `cudnnGet*Algorithm_v7` or `cudnnFind*Algorithm` functions, which `query`
+/// forwards the requested count, the returned count and the results array to, and collects the
+/// performance results it wrote.
+///
+/// # Safety
+///
+/// `query` must write as many results as it reports through the returned count, and at most the
+/// requested count.
+unsafe fn query_algorithms<P>(
+    max_algos: usize,
+    query: impl FnOnce(i32, &mut i32, *mut P) -> cudnn_sys::cudnnStatus_t,
+) -> Result<Vec<P>, CudnnError> {
+    let mut returned_algo_count = 0;
+    let mut perf_results = Vec::with_capacity(max_algos);
+
+    query(
+        max_algos as i32,
+        &mut returned_algo_count,
+        perf_results.as_mut_ptr(),
+    )
+    .into_result()?;
+
+    perf_results.set_len(returned_algo_count as usize);
+
+    Ok(perf_results)
+}
+
+/// Returns the first of `results`, which are sorted by expected performance, needing at most
+/// `max_workspace` bytes of workspace, or else the result of `fallback` with a warning.
+fn best_within_workspace<T>(
+    results: Vec<BestHeuristic<T>>,
+    max_workspace: usize,
+    fallback: T,
+) -> Result<BestHeuristic<T>, CudnnError>
+where
+    T: Copy + PartialEq + std::fmt::Debug,
+{
+    if let Some(best) = results
+        .iter()
+        .find(|algo| algo.workspace_size() <= max_workspace)
+    {
+        return Ok(*best);
+    }
+
+    let fallback = results
+        .into_iter()
+        .find(|algo| algo.algo() == fallback)
+        .ok_or(CudnnError::NotSupported)?;
+
+    crate::logging::log(
+        Severity::Warning,
+        &format!(
+            "no convolution algorithm needs at most {} bytes of workspace, falling back to {:?} \
+             which needs {} bytes",
+            max_workspace,
+            fallback.algo(),
+            fallback.workspace_size()
+        ),
+    );
+
+    Ok(fallback)
 }
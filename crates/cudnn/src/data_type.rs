@@ -24,6 +24,12 @@ impl_cudnn_data_type!(u8, CUDNN_DATA_UINT8);
 impl_cudnn_data_type!(i32, CUDNN_DATA_INT32);
 impl_cudnn_data_type!(i64, CUDNN_DATA_INT64);
 
+#[cfg(feature = "half")]
+use half::f16;
+
+#[cfg(feature = "half")]
+impl_cudnn_data_type!(f16, CUDNN_DATA_HALF);
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct Vec4;
 
@@ -69,5 +75,7 @@ impl ScalingDataType<u8> for f32 {}
 impl ScalingDataType<i32> for f32 {}
 impl ScalingDataType<i64> for f32 {}
 impl ScalingDataType<f32> for f32 {}
+#[cfg(feature = "half")]
+impl ScalingDataType<f16> for f32 {}
 
 impl ScalingDataType<f64> for f64 {}
@@ -1,5 +1,9 @@
+use std::mem::MaybeUninit;
+
 use cust::memory::GpuBuffer;
 
+use crate::{CudnnContext, CudnnError, IntoResult};
+
 /// The descriptor of a dropout operation.
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub struct DropoutDescriptor<T>
@@ -10,6 +14,129 @@ where
     pub(crate) states: T,
 }
 
+impl<T> DropoutDescriptor<T>
+where
+    T: GpuBuffer<u8>,
+{
+    /// Returns the dropout probability this descriptor was configured with.
+    ///
+    /// # Arguments
+    ///
+    ///   * `ctx` - the cuDNN context the descriptor was created with.
+    ///
+    /// cuDNN
+    /// [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnGetDropoutDescriptor)
+    /// may offer additional information about the APi behavior.
+    pub fn dropout(&self, ctx: &CudnnContext) -> Result<f32, CudnnError> {
+        let mut dropout = MaybeUninit::uninit();
+        let mut states = MaybeUninit::uninit();
+        let mut seed = MaybeUninit::uninit();
+
+        unsafe {
+            cudnn_sys::cudnnGetDropoutDescriptor(
+                self.raw,
+                ctx.raw,
+                dropout.as_mut_ptr(),
+                states.as_mut_ptr(),
+                seed.as_mut_ptr(),
+            )
+            .into_result()?;
+
+            Ok(dropout.assume_init())
+        }
+    }
+
+    /// Re-initializes the random number generator states of this descriptor with a new seed.
+    ///
+    /// The existing states buffer is reused, so this is considerably cheaper than creating a new
+    /// descriptor, but the states themselves are still regenerated on the device. Resetting the
+    /// seed makes subsequent dropout masks reproducible.
+    ///
+    /// # Arguments
+    ///
+    ///   * `ctx` - the cuDNN context the descriptor was created with.
+    ///   * `seed` - seed used to initialize random number generator states.
+    ///
+    /// cuDNN
+    /// [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnSetDropoutDescriptor)
+    /// may offer additional information about the APi behavior.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use cudnn::CudnnContext;
+    /// use cust::memory::DeviceBuffer;
+    ///
+    /// let ctx = CudnnContext::new()?;
+    ///
+    /// let size = ctx.get_dropout_states_size()?;
+    /// let states = unsafe { DeviceBuffer::uninitialized(size)? };
+    ///
+    /// let mut dropout_desc = ctx.create_dropout_descriptor(0.5, states, 123)?;
+    ///
+    /// for epoch in 0..3 {
+    ///     dropout_desc.set_seed(&ctx, 123 + epoch)?;
+    ///     // ... run the epoch
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_seed(&mut self, ctx: &CudnnContext, seed: u64) -> Result<(), CudnnError> {
+        let dropout = self.dropout(ctx)?;
+        let states_ptr = self.states.as_device_ptr().as_mut_ptr() as *mut std::ffi::c_void;
+        let states_size = self.states.len();
+
+        unsafe {
+            cudnn_sys::cudnnSetDropoutDescriptor(
+                self.raw,
+                ctx.raw,
+                dropout,
+                states_ptr,
+                states_size,
+                seed,
+            )
+            .into_result()
+        }
+    }
+
+    /// Restores this descriptor to use the random number generator states currently held in its
+    /// states buffer, without re-initializing them.
+    ///
+    /// This is useful after the contents of the states buffer have been replaced with states that
+    /// were saved off earlier, for instance from a checkpoint, as it avoids both reallocating the
+    /// buffer and regenerating the states. Use [`set_seed()`](DropoutDescriptor::set_seed) to
+    /// initialize fresh states instead.
+    ///
+    /// # Arguments
+    ///
+    ///   * `ctx` - the cuDNN context the descriptor was created with.
+    ///   * `seed` - seed the saved states were originally initialized with.
+    ///
+    /// cuDNN
+    /// [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnRestoreDropoutDescriptor)
+    /// may offer additional information about the APi behavior.
+    pub fn restore(&mut self, ctx: &CudnnContext, seed: u64) -> Result<(), CudnnError> {
+        let dropout = self.dropout(ctx)?;
+        let states_ptr = self.states.as_device_ptr().as_mut_ptr() as *mut std::ffi::c_void;
+        let states_size = self.states.len();
+
+        unsafe {
+            cudnn_sys::cudnnRestoreDropoutDescriptor(
+                self.raw,
+                ctx.raw,
+                dropout,
+                states_ptr,
+                states_size,
+                seed,
+            )
+            .into_result()
+        }
+    }
+}
+
 impl<T> Drop for DropoutDescriptor<T>
 where
     T: GpuBuffer<u8>,
@@ -87,14 +87,7 @@ impl CudnnContext {
     where
         T: DataType,
     {
-        let mut size = MaybeUninit::uninit();
-
-        unsafe {
-            cudnn_sys::cudnnDropoutGetReserveSpaceSize(desc.raw, size.as_mut_ptr())
-                .into_result()?;
-
-            Ok(size.assume_init())
-        }
+        desc.dropout_reserve_space_size()
     }
 
     /// Creates and initializes a generic dropout descriptor.
@@ -116,7 +109,7 @@ impl CudnnContext {
     ///
     /// # Errors
     ///
-    /// Return errors if `states` size is less than that returned by
+    /// Returns [`CudnnError::BadParam`] if `states` is smaller than the size returned by
     /// `get_dropout_states_size`.
     ///
     /// # Examples
@@ -146,6 +139,10 @@ impl CudnnContext {
         states: T,
         seed: u64,
     ) -> Result<DropoutDescriptor<T>, CudnnError> {
+        if states.len() < self.get_dropout_states_size()? {
+            return Err(CudnnError::BadParam);
+        }
+
         let mut raw = MaybeUninit::uninit();
         let states_ptr = states.as_device_ptr().as_mut_ptr() as *mut std::ffi::c_void;
         let states_size = states.len();
@@ -349,8 +346,8 @@ impl CudnnContext {
     where
         T: DataType,
     {
-        let dy_data = dx.as_device_ptr().as_ptr() as *const std::ffi::c_void;
-        let dx_data = dy.as_device_ptr().as_mut_ptr() as *mut std::ffi::c_void;
+        let dy_data = dy.as_device_ptr().as_ptr() as *const std::ffi::c_void;
+        let dx_data = dx.as_device_ptr().as_mut_ptr() as *mut std::ffi::c_void;
         let reserve_space_ptr = reserve_space.as_device_ptr().as_mut_ptr() as *mut std::ffi::c_void;
         let reserve_space_size = reserve_space.len();
 
@@ -369,3 +366,55 @@ impl CudnnContext {
         }
     }
 }
+
+impl<T> TensorDescriptor<T>
+where
+    T: DataType,
+{
+    /// Returns the size in bytes of the reserve space needed to run dropout on tensors described
+    /// by this descriptor, see
+    /// [`get_dropout_reserve_space_size()`](CudnnContext::get_dropout_reserve_space_size).
+    ///
+    /// cuDNN [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnDropoutGetReserveSpaceSize)
+    /// may offer additional information about the APi behavior.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query was not successful.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use cudnn::{CudnnContext, ScalarC, TensorDescriptor};
+    /// use cust::memory::DeviceBuffer;
+    ///
+    /// let ctx = CudnnContext::new()?;
+    ///
+    /// let desc = TensorDescriptor::<f32>::new_format(&[1, 1, 1, 5], ScalarC::Nchw)?;
+    /// let x = DeviceBuffer::from_slice(&[3.0f32; 5])?;
+    /// let mut y = DeviceBuffer::from_slice(&[0.0f32; 5])?;
+    ///
+    /// // buffers of exactly the reported sizes are enough.
+    /// let states = unsafe { DeviceBuffer::uninitialized(ctx.get_dropout_states_size()?)? };
+    /// let mut reserve_space =
+    ///     unsafe { DeviceBuffer::uninitialized(desc.dropout_reserve_space_size()?)? };
+    ///
+    /// let dropout_desc = ctx.create_dropout_descriptor(0.5, states, 123)?;
+    /// ctx.dropout_forward(&dropout_desc, &desc, &x, &desc, &mut y, &mut reserve_space)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn dropout_reserve_space_size(&self) -> Result<usize, CudnnError> {
+        let mut size = MaybeUninit::uninit();
+
+        unsafe {
+            cudnn_sys::cudnnDropoutGetReserveSpaceSize(self.raw, size.as_mut_ptr())
+                .into_result()?;
+
+            Ok(size.assume_init())
+        }
+    }
+}
@@ -124,8 +124,15 @@ impl IntoResult for cudnn_sys::cudnnStatus_t {
             CUDNN_STATUS_RUNTIME_FP_OVERFLOW => CudnnError::RuntimeFpOverflow,
             #[cfg(not(cudnn9))]
             CUDNN_STATUS_VERSION_MISMATCH => CudnnError::VersionMismatch,
-            // TODO(adamcavendish): implement cuDNN 9 error codes.
-            _ => todo!(),
+            // cuDNN 9 refines the statuses into sub-codes whose thousands digit is the category
+            // of the error, so fall back to the category rather than failing on new codes.
+            status => match status as u32 / 1000 {
+                1 => CudnnError::NotInitialized,
+                2 => CudnnError::BadParam,
+                3 => CudnnError::NotSupported,
+                5 => CudnnError::ExecutionFailed,
+                _ => CudnnError::InternalError,
+            },
         })
     }
 }
@@ -3,39 +3,49 @@
 mod activation;
 mod attention;
 mod backend;
+mod batch_norm;
 mod context;
 mod convolution;
 mod data_type;
 mod determinism;
 mod dropout;
 mod error;
+mod library_property;
+mod logging;
 mod math_type;
 mod nan_propagation;
 mod op;
 mod pooling;
+mod quantization;
 mod reduction;
 mod rnn;
 mod softmax;
 mod tensor;
 mod w_grad_mode;
+mod workspace;
 
 pub use activation::*;
 pub use attention::*;
+pub use batch_norm::*;
 pub use context::*;
 pub use convolution::*;
 pub use data_type::*;
 pub use determinism::*;
 pub use dropout::*;
 pub use error::*;
+pub use library_property::*;
+pub use logging::*;
 pub use math_type::*;
 pub use nan_propagation::*;
 pub use op::*;
 pub use pooling::*;
+pub use quantization::*;
 pub use reduction::*;
 pub use rnn::*;
 pub use softmax::*;
 pub use tensor::*;
 pub use w_grad_mode::*;
+pub use workspace::*;
 
 pub(crate) mod private {
     pub trait Sealed {}
@@ -0,0 +1,65 @@
+use crate::{CudnnError, IntoResult};
+use std::mem::MaybeUninit;
+
+/// A property of the cuDNN library that can be queried with [`get_property`].
+///
+/// cuDNN [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnGetProperty)
+/// may offer additional information about the APi behavior.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LibraryPropertyType {
+    /// The major version number.
+    MajorVersion,
+    /// The minor version number.
+    MinorVersion,
+    /// The patch level.
+    PatchLevel,
+}
+
+impl From<LibraryPropertyType> for cudnn_sys::libraryPropertyType {
+    fn from(property: LibraryPropertyType) -> Self {
+        use cudnn_sys::libraryPropertyType::*;
+        match property {
+            LibraryPropertyType::MajorVersion => MAJOR_VERSION,
+            LibraryPropertyType::MinorVersion => MINOR_VERSION,
+            LibraryPropertyType::PatchLevel => PATCH_LEVEL,
+        }
+    }
+}
+
+/// Returns the value of the requested property of the cuDNN library that is loaded at runtime.
+///
+/// Unlike [`CudnnContext::version`](crate::CudnnContext::version), this does not need a cuDNN
+/// handle, so it can be used to pick a code path before any context has been created.
+///
+/// cuDNN [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnGetProperty)
+/// may offer additional information about the APi behavior.
+///
+/// # Errors
+///
+/// Returns errors if the property could not be queried.
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// #
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use cudnn::{get_property, LibraryPropertyType};
+///
+/// let major = get_property(LibraryPropertyType::MajorVersion)?;
+///
+/// if major >= 8 {
+///     // ... use an operation that was introduced in cuDNN 8
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn get_property(property: LibraryPropertyType) -> Result<i32, CudnnError> {
+    let mut value = MaybeUninit::uninit();
+
+    unsafe {
+        cudnn_sys::cudnnGetProperty(property.into(), value.as_mut_ptr()).into_result()?;
+
+        Ok(value.assume_init())
+    }
+}
@@ -0,0 +1,168 @@
+use std::{
+    ffi::{c_char, c_void, CStr},
+    panic::{self, AssertUnwindSafe},
+    sync::RwLock,
+};
+
+use crate::{CudnnError, IntoResult};
+
+/// The severity of a cuDNN log message.
+///
+/// Severities are ordered from the most to the least severe, so that `Severity::Error <
+/// Severity::Info`.
+///
+/// cuDNN [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnSeverity_t)
+/// may offer additional information about the APi behavior.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// An unrecoverable error, such messages are always reported.
+    Fatal,
+    /// A recoverable error, such as an invalid argument.
+    Error,
+    /// A warning, such as the use of a deprecated feature.
+    Warning,
+    /// Informational messages, such as the parameters of API calls.
+    Info,
+}
+
+impl From<cudnn_sys::cudnnSeverity_t> for Severity {
+    fn from(raw: cudnn_sys::cudnnSeverity_t) -> Self {
+        use cudnn_sys::cudnnSeverity_t::*;
+        match raw {
+            CUDNN_SEV_FATAL => Self::Fatal,
+            CUDNN_SEV_ERROR => Self::Error,
+            CUDNN_SEV_WARNING => Self::Warning,
+            CUDNN_SEV_INFO => Self::Info,
+        }
+    }
+}
+
+impl From<Severity> for cudnn_sys::cudnnSeverity_t {
+    fn from(severity: Severity) -> Self {
+        use cudnn_sys::cudnnSeverity_t::*;
+        match severity {
+            Severity::Fatal => CUDNN_SEV_FATAL,
+            Severity::Error => CUDNN_SEV_ERROR,
+            Severity::Warning => CUDNN_SEV_WARNING,
+            Severity::Info => CUDNN_SEV_INFO,
+        }
+    }
+}
+
+type LogCallback = Box<dyn Fn(Severity, &str) + Send + Sync>;
+
+/// The callback installed by [`set_log_callback`] and the least severe severity it receives.
+///
+/// cuDNN may log from any thread, so the callback is kept behind a lock rather than handed to
+/// cuDNN as user data, which makes replacing it while a message is being logged sound.
+static LOG_CALLBACK: RwLock<Option<(Severity, LogCallback)>> = RwLock::new(None);
+
+/// Reports a message of this crate to the callback installed by [`set_log_callback`], if its
+/// level includes `severity`.
+pub(crate) fn log(severity: Severity, message: &str) {
+    if let Ok(callback) = LOG_CALLBACK.read() {
+        if let Some((level, callback)) = callback.as_ref() {
+            if severity <= *level {
+                let _ = panic::catch_unwind(AssertUnwindSafe(|| callback(severity, message)));
+            }
+        }
+    }
+}
+
+unsafe extern "C" fn log_callback_wrapper(
+    severity: cudnn_sys::cudnnSeverity_t,
+    _udata: *mut c_void,
+    _dbg: *const cudnn_sys::cudnnDebug_t,
+    message: *const c_char,
+) {
+    if message.is_null() {
+        return;
+    }
+
+    let message = CStr::from_ptr(message).to_string_lossy();
+
+    // Stop panics from unwinding across the FFI
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+        if let Ok(callback) = LOG_CALLBACK.read() {
+            if let Some((_, callback)) = callback.as_ref() {
+                callback(severity.into(), message.trim_end());
+            }
+        }
+    }));
+}
+
+/// Routes the log messages of cuDNN whose severity is `level` or more severe to `callback`,
+/// replacing any previously set callback.
+///
+/// The callback receives the severity and the text of each message, and may be called from any
+/// thread which calls into cuDNN. Panics in the callback are caught and ignored. The callback also
+/// receives the warnings of this crate, such as an algorithm picker falling back to a slower
+/// algorithm.
+///
+/// **Do note** that depending on the version, cuDNN may only produce warning and informational
+/// messages when logging is also enabled through its environment variables, see the
+/// cuDNN documentation on API logging.
+///
+/// cuDNN [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnSetCallback)
+/// may offer additional information about the APi behavior.
+///
+/// # Errors
+///
+/// Returns errors if the callback could not be installed.
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// #
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use cudnn::{clear_log_callback, set_log_callback, Severity};
+///
+/// set_log_callback(Severity::Warning, |severity, message| {
+///     eprintln!("cuDNN {:?}: {}", severity, message);
+/// })?;
+///
+/// // ... autotune the convolutions
+///
+/// clear_log_callback()?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn set_log_callback<F>(level: Severity, callback: F) -> Result<(), CudnnError>
+where
+    F: Fn(Severity, &str) + Send + Sync + 'static,
+{
+    // fatal messages are always reported, the mask only enables the other severities.
+    let mask = [Severity::Error, Severity::Warning, Severity::Info]
+        .into_iter()
+        .filter(|severity| *severity <= level)
+        .fold(0, |mask, severity| {
+            mask | 1 << cudnn_sys::cudnnSeverity_t::from(severity) as u32
+        });
+
+    *LOG_CALLBACK.write().unwrap_or_else(|e| e.into_inner()) = Some((level, Box::new(callback)));
+
+    unsafe {
+        cudnn_sys::cudnnSetCallback(mask, std::ptr::null_mut(), Some(log_callback_wrapper))
+            .into_result()
+    }
+}
+
+/// Removes the callback set with [`set_log_callback`], restoring the default logging behavior of
+/// cuDNN.
+///
+/// cuDNN [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnSetCallback)
+/// may offer additional information about the APi behavior.
+///
+/// # Errors
+///
+/// Returns errors if the callback could not be removed.
+pub fn clear_log_callback() -> Result<(), CudnnError> {
+    unsafe {
+        cudnn_sys::cudnnSetCallback(0, std::ptr::null_mut(), None).into_result()?;
+    }
+
+    *LOG_CALLBACK.write().unwrap_or_else(|e| e.into_inner()) = None;
+
+    Ok(())
+}
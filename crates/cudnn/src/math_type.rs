@@ -1,3 +1,17 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether convolutions may use TF32 tensor core operations, see
+/// [`CudnnContext::set_allow_tf32`](crate::CudnnContext::set_allow_tf32).
+static ALLOW_TF32: AtomicBool = AtomicBool::new(true);
+
+pub(crate) fn allow_tf32() -> bool {
+    ALLOW_TF32.load(Ordering::Relaxed)
+}
+
+pub(crate) fn set_allow_tf32(allow: bool) {
+    ALLOW_TF32.store(allow, Ordering::Relaxed)
+}
+
 /// Enum stating whether the use of tensor core operations is permitted in a given library routine.
 ///
 /// cuDNN [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnMathType_t)
@@ -40,3 +54,15 @@ impl From<MathType> for cudnn_sys::cudnnMathType_t {
         }
     }
 }
+
+impl MathType {
+    /// Returns the math type to actually use for a convolution, which is [`MathType::Fma`]
+    /// instead of [`MathType::Default`] when TF32 has been disallowed, as the default math type
+    /// permits TF32 on A100 and newer devices.
+    pub(crate) fn respecting_tf32(self) -> Self {
+        match self {
+            MathType::Default if !allow_tf32() => MathType::Fma,
+            math_type => math_type,
+        }
+    }
+}
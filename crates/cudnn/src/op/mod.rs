@@ -87,7 +87,7 @@ impl CudnnContext {
         a: &impl GpuBuffer<T1>,
         beta: CompT,
         b_desc: &TensorDescriptor<T2>,
-        b: &impl GpuBuffer<T2>,
+        b: &(impl GpuBuffer<T2> + ?Sized),
         gamma: CompT,
         c_desc: &TensorDescriptor<T3>,
         c: &mut impl GpuBuffer<T3>,
@@ -160,6 +160,9 @@ impl<T: DataType> Drop for BinaryOpTensorDescriptor<T> {
 /// | f64      | f64      | f64      | f64      |
 /// | f32      | i8       | i8       | i8       |
 /// | f32      | f32      | f32      | i8       |
+/// | f32      | f16      | f16      | f16      |
+///
+/// The last configuration requires the `half` feature.
 ///
 /// cuDNN [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnOpTensor).
 pub trait SupportedOp<AType, BType, CType>
@@ -182,3 +185,5 @@ impl_supported_types!(f32, i8, i8, f32);
 impl_supported_types!(f64, f64, f64, f64);
 impl_supported_types!(f32, i8, i8, i8);
 impl_supported_types!(f32, f32, f32, i8);
+#[cfg(feature = "half")]
+impl_supported_types!(f32, half::f16, half::f16, half::f16);
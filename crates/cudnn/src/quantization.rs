@@ -0,0 +1,124 @@
+use cust::memory::GpuBuffer;
+
+use crate::{
+    BinaryOp, BinaryOpTensorDescriptor, CudnnContext, CudnnError, DataType, NanPropagation,
+    SupportedOp, TensorDescriptor,
+};
+
+/// Scale factors mapping quantized values back to real values.
+///
+/// A convolution of INT8 data accumulates products of the quantized inputs and filters, so its
+/// output has to be multiplied by the input scale times the filter scale to recover the real
+/// valued result.
+pub enum QuantizationScale<'a> {
+    /// A single scale factor shared by all elements of the tensor.
+    PerTensor(f32),
+    /// One scale factor per channel, typically used when every output channel of a
+    /// convolution has its own filter scale.
+    PerChannel {
+        /// Descriptor of the scales, of shape `[1, C, 1, 1]` where `C` is the number of
+        /// channels of the scaled tensor. Tensors with more spatial dimensions need a matching
+        /// number of trailing ones.
+        desc: &'a TensorDescriptor<f32>,
+        /// The scale factors, one per channel.
+        scales: &'a dyn GpuBuffer<f32>,
+    },
+}
+
+impl CudnnContext {
+    /// Applies quantization scale factors to a tensor.
+    ///
+    /// This computes `y = x * scale`, either with a single scale factor or with one scale factor
+    /// per channel. With `x` being the `f32` output of an INT8 convolution and `y` an `f32`
+    /// tensor, this dequantizes the convolution result. With `y` being an `i8` tensor, it
+    /// requantizes the result for the next quantized layer instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `scale` - the scale factors to apply.
+    /// * `x_desc` - descriptor of the input tensor.
+    /// * `x` - data of the input tensor.
+    /// * `y_desc` - descriptor of the output tensor. It must have the same shape as `x_desc`.
+    /// * `y` - data of the output tensor.
+    ///
+    /// cuDNN [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnOpTensor)
+    /// may offer additional information about the APi behavior.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CudnnError::BadParam`] if the per channel scales do not have the shape
+    /// `[1, C, 1, ..]`, where `C` is the number of channels of `x`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use cudnn::{CudnnContext, QuantizationScale, ScalarC, TensorDescriptor};
+    /// use cust::memory::DeviceBuffer;
+    ///
+    /// let ctx = CudnnContext::new()?;
+    ///
+    /// // the accumulated output of an INT8 convolution with 2 output channels.
+    /// let x_desc = TensorDescriptor::<f32>::new_format(&[1, 2, 1, 2], ScalarC::Nchw)?;
+    /// let x = DeviceBuffer::from_slice(&[10.0f32, 20.0, 10.0, 20.0])?;
+    ///
+    /// let scale_desc = TensorDescriptor::<f32>::new_format(&[1, 2, 1, 1], ScalarC::Nchw)?;
+    /// let scales = DeviceBuffer::from_slice(&[0.5f32, 0.25])?;
+    ///
+    /// let y_desc = TensorDescriptor::<f32>::new_format(&[1, 2, 1, 2], ScalarC::Nchw)?;
+    /// let mut y = DeviceBuffer::from_slice(&[0.0f32; 4])?;
+    ///
+    /// let scale = QuantizationScale::PerChannel {
+    ///     desc: &scale_desc,
+    ///     scales: &scales,
+    /// };
+    /// ctx.dequantize(scale, &x_desc, &x, &y_desc, &mut y)?;
+    ///
+    /// assert_eq!(y.as_host_vec()?, [5.0, 10.0, 2.5, 5.0]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn dequantize<T>(
+        &self,
+        scale: QuantizationScale<'_>,
+        x_desc: &TensorDescriptor<f32>,
+        x: &impl GpuBuffer<f32>,
+        y_desc: &TensorDescriptor<T>,
+        y: &mut impl GpuBuffer<T>,
+    ) -> Result<(), CudnnError>
+    where
+        T: DataType,
+        f32: SupportedOp<f32, f32, T>,
+    {
+        let nan_opt = NanPropagation::PropagateNaN;
+
+        match scale {
+            QuantizationScale::PerTensor(scale) => {
+                // y = scale * x + 0 * x
+                let op_desc = BinaryOpTensorDescriptor::<f32>::new(BinaryOp::Add, nan_opt)?;
+                self.binary_tensor_op(&op_desc, scale, x_desc, x, 0.0, x_desc, x, 0.0, y_desc, y)
+            }
+            QuantizationScale::PerChannel { desc, scales } => {
+                let x_shape = x_desc.shape()?;
+                let scale_shape = desc.shape()?;
+
+                let valid = x_shape.len() == scale_shape.len()
+                    && x_shape.len() >= 2
+                    && scale_shape
+                        .iter()
+                        .enumerate()
+                        .all(|(i, dim)| *dim == if i == 1 { x_shape[1] } else { 1 });
+
+                if !valid {
+                    return Err(CudnnError::BadParam);
+                }
+
+                // y = x * scales, broadcasting the scales over all but the channel dimension.
+                let op_desc = BinaryOpTensorDescriptor::<f32>::new(BinaryOp::Mul, nan_opt)?;
+                self.binary_tensor_op(&op_desc, 1.0, x_desc, x, 1.0, desc, scales, 0.0, y_desc, y)
+            }
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use std::mem::MaybeUninit;
 
-use cust::memory::GpuBuffer;
+use cust::memory::{DeviceBuffer, GpuBuffer};
 
 use crate::{CudnnContext, CudnnError, IntoResult, TensorDescriptor, WGradMode};
 
@@ -14,6 +14,7 @@ mod rnn_descriptor;
 mod rnn_direction_mode;
 mod rnn_input_mode;
 mod rnn_mode;
+mod rnn_weight_params;
 
 pub use forward_mode::*;
 pub use rnn_algo::*;
@@ -25,6 +26,7 @@ pub use rnn_descriptor::*;
 pub use rnn_direction_mode::*;
 pub use rnn_input_mode::*;
 pub use rnn_mode::*;
+pub use rnn_weight_params::*;
 
 impl CudnnContext {
     /// Computes the work and reserve space buffer sizes based on the RNN network
@@ -113,6 +115,116 @@ impl CudnnContext {
         }
     }
 
+    /// Returns the location of the weight matrix and bias vector of a single linear layer
+    /// inside of the packed weight space buffer, so that they can be initialized individually.
+    ///
+    /// # Arguments
+    ///
+    /// * `rnn_desc` - an RNN descriptor.
+    /// * `pseudo_layer` - the pseudo-layer to query. In unidirectional RNNs, a pseudo-layer is
+    ///   the same as a physical layer. In bidirectional RNNs, pseudo-layer `0` is the forward
+    ///   direction of the first physical layer, pseudo-layer `1` its backward direction, and so
+    ///   on.
+    /// * `weight_space` - the weight space buffer, its size must be the one returned by
+    ///   [`get_rnn_weight_space_size()`](Self::get_rnn_weight_space_size).
+    /// * `lin_layer_id` - the linear layer to query. For `RnnMode::RnnReLu` and
+    ///   `RnnMode::RnnTanh`, `0` refers to the input weights and `1` to the recurrent weights.
+    ///   For `RnnMode::Lstm`, `0..=3` refer to the input weights of the input, forget, cell and
+    ///   output gates, `4..=7` to the recurrent weights of the same gates, and `8` to the
+    ///   projection matrix. For `RnnMode::Gru`, `0..=2` refer to the input weights of the reset,
+    ///   update and new gates, and `3..=5` to the recurrent weights of the same gates.
+    ///
+    /// cuDNN [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnGetRNNWeightParams)
+    /// may offer additional information about the APi behavior.
+    ///
+    /// # Errors
+    ///
+    /// Returns errors if `pseudo_layer` or `lin_layer_id` are out of range, or if
+    /// `weight_space` is too small.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use cudnn::{
+    ///     CudnnContext, DropoutDescriptor, MathType, RnnAlgo, RnnAuxFlags, RnnBiasMode,
+    ///     RnnDescriptor, RnnDirectionMode, RnnInputMode, RnnMode,
+    /// };
+    /// use cust::memory::{CopyDestination, DeviceBuffer};
+    ///
+    /// let _cust_ctx = cust::quick_init()?;
+    /// let ctx = CudnnContext::new()?;
+    ///
+    /// let dropout_desc: Option<&DropoutDescriptor<DeviceBuffer<u8>>> = None;
+    /// let rnn_desc = RnnDescriptor::<f32, f32>::new(
+    ///     RnnAlgo::Standard,
+    ///     RnnMode::Lstm,
+    ///     RnnBiasMode::SingleInpBias,
+    ///     RnnDirectionMode::Unidirectional,
+    ///     RnnInputMode::LinearInput,
+    ///     MathType::Default,
+    ///     16,
+    ///     32,
+    ///     None,
+    ///     1,
+    ///     dropout_desc,
+    ///     RnnAuxFlags::PADDED_IO_DISABLED,
+    /// )?;
+    ///
+    /// let size = ctx.get_rnn_weight_space_size(&rnn_desc)?;
+    /// let mut weight_space = DeviceBuffer::<u8>::zeroed(size)?;
+    ///
+    /// // Set the bias of the forget gate to 1.
+    /// let params = ctx.get_rnn_weight_params(&rnn_desc, 0, &weight_space, 1)?;
+    /// let bias = params.bias.unwrap();
+    /// bias.slice_mut(&mut weight_space)
+    ///     .copy_from(&vec![1.0f32; bias.len()])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_rnn_weight_params<T1, T2>(
+        &self,
+        rnn_desc: &RnnDescriptor<T1, T2>,
+        pseudo_layer: i32,
+        weight_space: &impl GpuBuffer<u8>,
+        lin_layer_id: i32,
+    ) -> Result<RnnWeightParams<T1>, CudnnError>
+    where
+        T1: RnnDataType,
+        T2: SupportedRnn<T1>,
+    {
+        let m_desc = TensorDescriptor::<T1>::create()?;
+        let b_desc = TensorDescriptor::<T1>::create()?;
+
+        let mut m_addr = std::ptr::null_mut();
+        let mut b_addr = std::ptr::null_mut();
+
+        let weight_space_ptr = weight_space.as_device_ptr().as_ptr() as *const std::ffi::c_void;
+
+        unsafe {
+            cudnn_sys::cudnnGetRNNWeightParams(
+                self.raw,
+                rnn_desc.raw,
+                pseudo_layer,
+                weight_space.len(),
+                weight_space_ptr,
+                lin_layer_id,
+                m_desc.raw,
+                &mut m_addr,
+                b_desc.raw,
+                &mut b_addr,
+            )
+            .into_result()?;
+        }
+
+        Ok(RnnWeightParams {
+            matrix: RnnWeightRegion::from_raw(weight_space, m_addr, || m_desc.shape())?,
+            bias: RnnWeightRegion::from_raw(weight_space, b_addr, || b_desc.shape())?,
+        })
+    }
+
     /// This routine computes the forward response of the recurrent neural network
     /// described by `rnn_desc` with inputs in `x`, `hx`, `cx`, and weights / biases in
     /// the `weight_space` buffer. RNN outputs are written to `y`, `hy`, and `cy`
@@ -136,6 +248,8 @@ impl CudnnContext {
     /// Sequence length arrays from `x_desc` and `y_desc` descriptors are checked for
     /// consistency, however.
     ///
+    /// For inference, `rnn_forward_inference()` does not take a reserve space buffer.
+    ///
     /// # Arguments
     ///
     /// * `rnn_desc` - a RNN descriptor.
@@ -284,6 +398,69 @@ impl CudnnContext {
         }
     }
 
+    /// Computes the forward response of the recurrent neural network described by
+    /// `rnn_desc` in inference mode.
+    ///
+    /// This is `rnn_forward()` with `ForwardMode::Inference`. No intermediate data is
+    /// kept for a backward pass, so no reserve space buffer is needed. The workspace
+    /// size should be computed by `get_rnn_temp_space_sizes` with
+    /// `ForwardMode::Inference`, which is usually smaller than the training one. Use
+    /// `rnn_forward()` with `ForwardMode::Training` when gradients are needed.
+    ///
+    /// # Arguments
+    ///
+    /// See `rnn_forward()` for a description of the arguments.
+    ///
+    /// cuDNN
+    /// [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnRNNForward)
+    /// may offer additional information about the APi behavior.
+    ///
+    /// # Errors
+    ///
+    /// Returns errors is an unsupported arguments combination is detected or if the
+    /// supplied buffers are too small.
+    #[allow(clippy::too_many_arguments)]
+    pub fn rnn_forward_inference<T1, T2>(
+        &self,
+        rnn_desc: &RnnDescriptor<T1, T2>,
+        device_seq_lengths: &impl GpuBuffer<i32>,
+        x_desc: &RnnDataDescriptor<T1>,
+        x: &impl GpuBuffer<T1>,
+        y_desc: &RnnDataDescriptor<T1>,
+        y: &mut impl GpuBuffer<T1>,
+        h_desc: &TensorDescriptor<T1>,
+        hx: Option<&impl GpuBuffer<T1>>,
+        hy: Option<&mut impl GpuBuffer<T1>>,
+        c_desc: Option<&TensorDescriptor<T1>>,
+        cx: Option<&impl GpuBuffer<T1>>,
+        cy: Option<&mut impl GpuBuffer<T1>>,
+        weight_space: &mut impl GpuBuffer<u8>,
+        work_space: &mut impl GpuBuffer<u8>,
+    ) -> Result<(), CudnnError>
+    where
+        T1: RnnDataType,
+        T2: SupportedRnn<T1>,
+    {
+        self.rnn_forward(
+            rnn_desc,
+            ForwardMode::Inference,
+            device_seq_lengths,
+            x_desc,
+            x,
+            y_desc,
+            y,
+            h_desc,
+            hx,
+            hy,
+            c_desc,
+            cx,
+            cy,
+            weight_space,
+            work_space,
+            None::<&mut DeviceBuffer<u8>>,
+        )
+    }
+
     /// This function computes exact, first-order derivatives of the RNN model with
     /// respect to its inputs: `x`, `hx` and for the LSTM cell type also `cx`.
     ///
@@ -56,13 +56,10 @@ where
     /// [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnSetRNNDataDescriptor)
     /// may offer additional information about the APi behavior.
     ///
-    /// # Panics
-    ///
-    /// If the length of `seq_lengths` doesn't match `batch_size`.
-    ///
     /// # Errors
     ///
-    /// Returns errors if an element of `seq_lengths` is less than zero or greater than
+    /// Returns [`CudnnError::BadParam`] if the length of `seq_lengths` doesn't match
+    /// `batch_size`, and errors if an element of `seq_lengths` is less than zero or greater than
     /// `max_seq_length` or if the allocation of internal array storage has failed.
     ///
     /// # Examples
@@ -74,7 +71,7 @@ where
     /// # use std::error::Error;
     /// #
     /// # fn main() -> Result<(), Box<dyn Error>> {
-    /// use cudnn::{CudnnContext, RnnDataDescriptor, RnnDataLayout};
+    /// use cudnn::{CudnnContext, CudnnError, RnnDataDescriptor, RnnDataLayout};
     ///
     /// let ctx = CudnnContext::new()?;
     ///
@@ -93,6 +90,17 @@ where
     ///     &seq_lengths,
     ///     padding_fill,
     /// )?;
+    ///
+    /// // every sequence of the batch needs a length.
+    /// let result = RnnDataDescriptor::<f32>::new(
+    ///     layout,
+    ///     max_seq_length,
+    ///     batch_size,
+    ///     vector_size,
+    ///     &seq_lengths[1..],
+    ///     padding_fill,
+    /// );
+    /// assert_eq!(result.unwrap_err(), CudnnError::BadParam);
     /// # Ok(())
     /// # }
     /// ```
@@ -104,13 +112,9 @@ where
         seq_lengths: &[i32],
         padding_fill: impl Into<Option<T>>,
     ) -> Result<Self, CudnnError> {
-        assert_eq!(
-            seq_lengths.len(),
-            batch_size as usize,
-            "Found {} sequence lengths, but batch size is {}",
-            seq_lengths.len(),
-            batch_size
-        );
+        if seq_lengths.len() != batch_size as usize {
+            return Err(CudnnError::BadParam);
+        }
 
         let mut raw = MaybeUninit::uninit();
 
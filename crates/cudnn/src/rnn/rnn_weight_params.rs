@@ -0,0 +1,105 @@
+use std::{marker::PhantomData, mem::size_of};
+
+use cust::memory::{DeviceSlice, GpuBuffer};
+
+use crate::{CudnnError, DataType};
+
+/// Location of a single weight matrix or bias vector inside of a packed RNN weight space buffer.
+///
+/// Returned as part of [`RnnWeightParams`] by
+/// [`CudnnContext::get_rnn_weight_params()`](crate::CudnnContext::get_rnn_weight_params).
+#[derive(Debug, Clone, PartialEq)]
+pub struct RnnWeightRegion<T>
+where
+    T: DataType,
+{
+    offset: usize,
+    shape: Vec<i32>,
+    data_type: PhantomData<T>,
+}
+
+impl<T> RnnWeightRegion<T>
+where
+    T: DataType,
+{
+    pub(crate) fn new(offset: usize, shape: Vec<i32>) -> Self {
+        Self {
+            offset,
+            shape,
+            data_type: PhantomData,
+        }
+    }
+
+    /// Offset in bytes of the region from the start of the weight space buffer.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Dimensions of the weight matrix or bias vector, as reported by cuDNN.
+    pub fn shape(&self) -> &[i32] {
+        &self.shape
+    }
+
+    /// Number of elements in the region.
+    pub fn len(&self) -> usize {
+        self.shape.iter().map(|&dim| dim as usize).product()
+    }
+
+    /// Returns `true` if the region holds no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the portion of `weight_space` described by this region.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the region does not fit in `weight_space`.
+    pub fn slice<'a>(&self, weight_space: &'a DeviceSlice<u8>) -> &'a DeviceSlice<T> {
+        let bytes = &weight_space[self.offset..self.offset + self.len() * size_of::<T>()];
+        // SAFETY: the range is checked to be in bounds by the indexing above, and every bit
+        // pattern is valid for the plain numeric types implementing `DataType`.
+        unsafe { DeviceSlice::from_raw_parts(bytes.as_device_ptr().cast(), self.len()) }
+    }
+
+    /// Returns the portion of `weight_space` described by this region, mutably.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the region does not fit in `weight_space`.
+    pub fn slice_mut<'a>(&self, weight_space: &'a mut DeviceSlice<u8>) -> &'a mut DeviceSlice<T> {
+        let bytes = &mut weight_space[self.offset..self.offset + self.len() * size_of::<T>()];
+        // SAFETY: the range is checked to be in bounds by the indexing above, and every bit
+        // pattern is valid for the plain numeric types implementing `DataType`.
+        unsafe { DeviceSlice::from_raw_parts_mut(bytes.as_device_ptr().cast(), self.len()) }
+    }
+
+    /// Returns the region at `addr`, or `None` if it is null. `shape` is only queried for
+    /// regions which exist, since the descriptor of a missing one is left unset by cuDNN.
+    pub(crate) fn from_raw(
+        weight_space: &impl GpuBuffer<u8>,
+        addr: *mut std::ffi::c_void,
+        shape: impl FnOnce() -> Result<Vec<i32>, CudnnError>,
+    ) -> Result<Option<Self>, CudnnError> {
+        if addr.is_null() {
+            return Ok(None);
+        }
+        let base = weight_space.as_device_ptr().as_raw() as usize;
+        Ok(Some(Self::new(addr as usize - base, shape()?)))
+    }
+}
+
+/// The weight matrix and bias vector of a single linear layer of an RNN pseudo-layer.
+///
+/// Either of them may be missing depending on the RNN configuration, for instance when the
+/// descriptor was created with `RnnBiasMode::NoBias`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RnnWeightParams<T>
+where
+    T: DataType,
+{
+    /// Location of the weight matrix in the weight space buffer.
+    pub matrix: Option<RnnWeightRegion<T>>,
+    /// Location of the bias vector in the weight space buffer.
+    pub bias: Option<RnnWeightRegion<T>>,
+}
@@ -1,5 +1,7 @@
 mod tensor_descriptor;
+mod tensor_descriptor_cache;
 mod tensor_format;
 
 pub use tensor_descriptor::*;
+pub use tensor_descriptor_cache::*;
 pub use tensor_format::*;
@@ -27,18 +27,26 @@ where
     /// cuDNN [docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnSetTensorNdDescriptor)
     /// may offer additional information about the APi behavior.
     ///
+    /// # Errors
+    ///
+    /// Returns [`CudnnError::BadParam`] if `shape` and `strides` have different lengths.
+    ///
     /// # Examples
     ///
     /// ```
     /// # use std::error::Error;
     /// #
     /// # fn main() -> Result<(), Box<dyn Error>> {
-    /// use cudnn::TensorDescriptor;
+    /// use cudnn::{CudnnError, TensorDescriptor};
     ///
     /// let shape = &[5, 5, 10, 25];
     /// let strides = &[1250, 250, 25, 1];
     ///
     /// let desc = TensorDescriptor::<f32>::new_strides(shape, strides)?;
+    ///
+    /// // every dimension needs a stride.
+    /// let result = TensorDescriptor::<f32>::new_strides(shape, &strides[1..]);
+    /// assert_eq!(result.unwrap_err(), CudnnError::BadParam);
     /// # Ok(())
     /// # }
     /// ```
@@ -47,11 +55,9 @@ where
 
         let ndims = shape.len();
 
-        assert_eq!(
-            ndims,
-            strides.len(),
-            "shape and strides length do not match."
-        );
+        if ndims != strides.len() {
+            return Err(CudnnError::BadParam);
+        }
 
         unsafe {
             cudnn_sys::cudnnCreateTensorDescriptor(raw.as_mut_ptr()).into_result()?;
@@ -174,6 +180,44 @@ where
             })
         }
     }
+
+    /// Creates a tensor descriptor without setting its shape, to be filled in by cuDNN.
+    pub(crate) fn create() -> Result<Self, CudnnError> {
+        let mut raw = MaybeUninit::uninit();
+
+        unsafe {
+            cudnn_sys::cudnnCreateTensorDescriptor(raw.as_mut_ptr()).into_result()?;
+
+            Ok(TensorDescriptor {
+                raw: raw.assume_init(),
+                data_type: PhantomData,
+            })
+        }
+    }
+
+    /// Returns the dimensions of the tensor described by this descriptor.
+    pub(crate) fn shape(&self) -> Result<Vec<i32>, CudnnError> {
+        const MAX_DIMS: usize = cudnn_sys::CUDNN_DIM_MAX as usize;
+
+        let mut data_type = MaybeUninit::uninit();
+        let mut ndims = 0;
+        let mut shape = [0; MAX_DIMS];
+        let mut strides = [0; MAX_DIMS];
+
+        unsafe {
+            cudnn_sys::cudnnGetTensorNdDescriptor(
+                self.raw,
+                MAX_DIMS as i32,
+                data_type.as_mut_ptr(),
+                &mut ndims,
+                shape.as_mut_ptr(),
+                strides.as_mut_ptr(),
+            )
+            .into_result()?;
+        }
+
+        Ok(shape[..ndims as usize].to_vec())
+    }
 }
 
 impl<T> Drop for TensorDescriptor<T>
@@ -0,0 +1,139 @@
+use std::{collections::HashMap, rc::Rc};
+
+use crate::{CudnnError, DataType, ScalarC, TensorDescriptor};
+
+/// How the layout of a cached tensor descriptor was specified.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum TensorLayout {
+    Format(ScalarC),
+    Strides(Vec<i32>),
+}
+
+/// A cache of tensor descriptors keyed by shape and layout.
+///
+/// Creating a tensor descriptor requires a round trip through cuDNN, so building the same
+/// descriptors on every iteration of a training loop adds measurable CPU overhead. This cache
+/// creates each distinct descriptor once and hands out shared references to it afterwards. The
+/// data type of the descriptors is fixed by the cache's generic parameter, so use one cache per
+/// data type.
+///
+/// Descriptors are reference counted, so several can be borrowed at once to be passed to a single
+/// operation. They are destroyed once the cache has been cleared or dropped and all outstanding
+/// references have gone away.
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// #
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use cudnn::{ScalarC, TensorDescriptorCache};
+/// use std::rc::Rc;
+///
+/// let mut cache = TensorDescriptorCache::<f32>::new();
+///
+/// for _ in 0..10 {
+///     let x_desc = cache.get_format(&[3, 2, 5, 5], ScalarC::Nchw)?;
+///     let y_desc = cache.get_format(&[3, 3, 4, 4], ScalarC::Nchw)?;
+///
+///     // ... use the descriptors
+/// }
+///
+/// assert_eq!(cache.len(), 2);
+///
+/// let a = cache.get_format(&[3, 2, 5, 5], ScalarC::Nchw)?;
+/// let b = cache.get_format(&[3, 2, 5, 5], ScalarC::Nchw)?;
+/// assert!(Rc::ptr_eq(&a, &b));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct TensorDescriptorCache<T>
+where
+    T: DataType,
+{
+    descriptors: HashMap<(Vec<i32>, TensorLayout), Rc<TensorDescriptor<T>>>,
+}
+
+impl<T> TensorDescriptorCache<T>
+where
+    T: DataType,
+{
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Self {
+            descriptors: HashMap::new(),
+        }
+    }
+
+    /// Returns a tensor descriptor with the given shape and format, creating it on first use.
+    ///
+    /// See [`TensorDescriptor::new_format`] for the meaning of the arguments.
+    pub fn get_format(
+        &mut self,
+        shape: &[i32],
+        format: ScalarC,
+    ) -> Result<Rc<TensorDescriptor<T>>, CudnnError> {
+        self.get_or_create(shape, TensorLayout::Format(format), || {
+            TensorDescriptor::new_format(shape, format)
+        })
+    }
+
+    /// Returns a tensor descriptor with the given shape and strides, creating it on first use.
+    ///
+    /// See [`TensorDescriptor::new_strides`] for the meaning of the arguments.
+    pub fn get_strides(
+        &mut self,
+        shape: &[i32],
+        strides: &[i32],
+    ) -> Result<Rc<TensorDescriptor<T>>, CudnnError> {
+        self.get_or_create(shape, TensorLayout::Strides(strides.to_vec()), || {
+            TensorDescriptor::new_strides(shape, strides)
+        })
+    }
+
+    fn get_or_create(
+        &mut self,
+        shape: &[i32],
+        layout: TensorLayout,
+        create: impl FnOnce() -> Result<TensorDescriptor<T>, CudnnError>,
+    ) -> Result<Rc<TensorDescriptor<T>>, CudnnError> {
+        let key = (shape.to_vec(), layout);
+
+        if let Some(desc) = self.descriptors.get(&key) {
+            return Ok(Rc::clone(desc));
+        }
+
+        let desc = Rc::new(create()?);
+        self.descriptors.insert(key, Rc::clone(&desc));
+
+        Ok(desc)
+    }
+
+    /// Returns the number of distinct descriptors held by the cache.
+    pub fn len(&self) -> usize {
+        self.descriptors.len()
+    }
+
+    /// Returns `true` if the cache holds no descriptors.
+    pub fn is_empty(&self) -> bool {
+        self.descriptors.is_empty()
+    }
+
+    /// Removes all descriptors from the cache.
+    ///
+    /// Descriptors that are still referenced elsewhere are destroyed once the last reference is
+    /// dropped.
+    pub fn clear(&mut self) {
+        self.descriptors.clear();
+    }
+}
+
+impl<T> Default for TensorDescriptorCache<T>
+where
+    T: DataType,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,160 @@
+use cust::{
+    error::{CudaError, CudaResult},
+    memory::{DeviceBuffer, GpuBuffer},
+};
+
+use crate::CudnnError;
+
+/// A growable device buffer that can be reused as the workspace of several operations.
+///
+/// Operations such as [`convolution_forward()`](crate::CudnnContext::convolution_forward) need a
+/// workspace whose size depends on the algorithm and on the shapes of their tensors. Allocating a
+/// new buffer for every call is wasteful, so a `Workspace` keeps a single buffer around and only
+/// reallocates it when an operation requires more memory than it currently holds. It never
+/// shrinks.
+///
+/// Every [`CudnnContext`](crate::CudnnContext) owns a `Workspace`, which the convolutions use when
+/// they are not given a workspace, growing it to the size they query for their algorithm. A
+/// separate `Workspace` is only needed to manage the memory explicitly, for instance to allocate
+/// it up front.
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// #
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use cudnn::{
+///     ConvDescriptor, ConvFwdAlgo, ConvMode, CudnnContext, FilterDescriptor, ScalarC,
+///     TensorDescriptor, Workspace,
+/// };
+/// use cust::memory::DeviceBuffer;
+///
+/// let ctx = CudnnContext::new()?;
+///
+/// let conv_desc = ConvDescriptor::<f32>::new([0, 0], [1, 1], [1, 1], ConvMode::CrossCorrelation)?;
+/// let w_desc = FilterDescriptor::<f32>::new(&[3, 2, 2, 2], ScalarC::Nchw)?;
+/// let w = DeviceBuffer::from_slice(&[1.0_f32; 24])?;
+///
+/// let algo = ConvFwdAlgo::ImplicitPrecompGemm;
+/// let mut workspace = Workspace::new();
+///
+/// for size in [5, 9, 7] {
+///     let x_desc = TensorDescriptor::<f32>::new_format(&[3, 2, size, size], ScalarC::Nchw)?;
+///     let y_desc =
+///         TensorDescriptor::<f32>::new_format(&[3, 3, size - 1, size - 1], ScalarC::Nchw)?;
+///
+///     let x_len = (3 * 2 * size * size) as usize;
+///     let y_len = (3 * 3 * (size - 1) * (size - 1)) as usize;
+///     let x = DeviceBuffer::from_slice(&vec![1.0_f32; x_len])?;
+///     let mut y = DeviceBuffer::from_slice(&vec![0.0_f32; y_len])?;
+///
+///     let workspace_size =
+///         ctx.get_convolution_forward_workspace_size(&x_desc, &w_desc, &y_desc, &conv_desc, algo)?;
+///
+///     ctx.convolution_forward(
+///         1.0,
+///         &x_desc,
+///         &x,
+///         &w_desc,
+///         &w,
+///         &conv_desc,
+///         algo,
+///         workspace.reserve(workspace_size)?,
+///         0.0,
+///         &y_desc,
+///         &mut y,
+///     )?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct Workspace {
+    buffer: Option<DeviceBuffer<u8>>,
+}
+
+impl Workspace {
+    /// Creates an empty workspace. No memory is allocated until the first call to
+    /// [`reserve()`](Workspace::reserve).
+    pub fn new() -> Self {
+        Self { buffer: None }
+    }
+
+    /// Creates a workspace holding at least `size` bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error from CUDA if the allocation fails.
+    pub fn with_capacity(size: usize) -> CudaResult<Self> {
+        let mut workspace = Self::new();
+        workspace.reserve(Some(size))?;
+        Ok(workspace)
+    }
+
+    /// Returns the number of bytes the workspace currently holds.
+    pub fn capacity(&self) -> usize {
+        self.buffer.as_ref().map_or(0, |buffer| buffer.len())
+    }
+
+    /// Makes sure the workspace holds at least `size` bytes and returns its buffer.
+    ///
+    /// `size` is the workspace size returned by functions such as
+    /// [`get_convolution_forward_workspace_size()`](crate::CudnnContext::get_convolution_forward_workspace_size),
+    /// where `None` means that the operation does not need a workspace, and the result can be
+    /// passed as the `work_space` argument of the matching operation.
+    ///
+    /// The buffer is only reallocated if it is smaller than `size`. Its previous contents are not
+    /// preserved when that happens.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error from CUDA if the allocation fails, in which case the workspace is left
+    /// empty.
+    pub fn reserve(&mut self, size: Option<usize>) -> CudaResult<Option<&mut DeviceBuffer<u8>>> {
+        let Some(size) = size.filter(|&size| size > 0) else {
+            return Ok(None);
+        };
+
+        if self.capacity() < size {
+            // Free the old buffer first to keep the peak memory usage down.
+            self.buffer = None;
+            self.buffer = Some(unsafe { DeviceBuffer::uninitialized(size)? });
+        }
+
+        Ok(self.buffer.as_mut())
+    }
+
+    /// Returns the pointer and size of `work_space` in the form cuDNN takes them, or those of
+    /// this workspace grown to the size returned by `size` if it is `None`.
+    pub(crate) fn raw_parts<W: GpuBuffer<u8>>(
+        &mut self,
+        work_space: Option<&mut W>,
+        size: impl FnOnce() -> Result<Option<usize>, CudnnError>,
+    ) -> Result<(*mut std::ffi::c_void, usize), CudnnError> {
+        fn parts(work_space: &mut impl GpuBuffer<u8>) -> (*mut std::ffi::c_void, usize) {
+            (
+                work_space.as_device_ptr().as_mut_ptr() as *mut std::ffi::c_void,
+                work_space.len(),
+            )
+        }
+
+        // If the size is 0 then the algorithm can work in-place and cuDNN expects a null
+        // pointer.
+        Ok(match work_space {
+            Some(work_space) => parts(work_space),
+            None => self
+                .reserve(size()?)
+                .map_err(alloc_error)?
+                .map_or((std::ptr::null_mut(), 0), parts),
+        })
+    }
+}
+
+fn alloc_error(_: CudaError) -> CudnnError {
+    // cuDNN 9 reports failed allocations as internal errors.
+    #[cfg(not(cudnn9))]
+    return CudnnError::AllocFailed;
+    #[cfg(cudnn9)]
+    return CudnnError::InternalError;
+}
@@ -0,0 +1,162 @@
+use std::mem::size_of;
+
+use super::{load_module, scan_exclusive, BLOCK_SIZE};
+use crate::error::{CudaError, CudaResult};
+use crate::launch;
+use crate::memory::{CopyDestination, DeviceBuffer, DeviceCopy, DeviceSlice};
+use crate::stream::Stream;
+
+// see the comments in the template on how the kernels work.
+const COMPACT_PTX: &str = include_str!("compact.ptx");
+
+/// The maximum number of blocks of the kernels, which loop over the elements.
+const MAX_BLOCKS: usize = 4096;
+
+fn compact_ptx(size: usize) -> String {
+    // the widest word which divides the size, so that the words of every element are aligned.
+    let word_bytes = 1usize << size.trailing_zeros().min(3);
+    let word_bits = word_bytes * 8;
+    COMPACT_PTX
+        .replace("$WORD_REG_BITS", &word_bits.max(16).to_string())
+        .replace("$WORD_BITS", &word_bits.to_string())
+        .replace("$WORD_BYTES", &word_bytes.to_string())
+        .replace("$WORDS", &(size / word_bytes).to_string())
+        .replace("$SIZE", &size.to_string())
+}
+
+/// Copies the elements of `input` for which `predicate` is `true` to a new buffer, keeping their
+/// order, and returns it along with the number of elements kept.
+///
+/// The predicate is scanned with [`scan_exclusive`] into the position of every kept element,
+/// then a kernel copies them to their positions. The number of kept elements is needed to
+/// allocate the output, so this **synchronizes `stream`** once the predicate has been scanned. The
+/// copy itself is only queued, the contents of the returned buffer are ready once the stream has
+/// reached it.
+///
+/// # Errors
+///
+/// Returns [`CudaError::InvalidValue`] if `input` and `predicate` have different lengths or if
+/// there are more than `u32::MAX` elements. Returns other errors if the kernels cannot be loaded
+/// or launched, or if allocating the output or the scratch memory fails.
+///
+/// # Examples
+///
+/// ```
+/// # let _context = cust::quick_init().unwrap();
+/// use cust::algorithms::compact;
+/// use cust::memory::*;
+/// use cust::stream::{Stream, StreamFlags};
+///
+/// let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+/// let values = DeviceBuffer::from_slice(&[1.0f32, 2.0, 3.0, 4.0]).unwrap();
+/// let keep = DeviceBuffer::from_slice(&[true, false, false, true]).unwrap();
+///
+/// let (kept, count) = compact(&values, &keep, &stream).unwrap();
+/// stream.synchronize().unwrap();
+/// assert_eq!(count, 2);
+/// assert_eq!(kept.as_host_vec().unwrap(), [1.0, 4.0]);
+/// ```
+pub fn compact<T: DeviceCopy>(
+    input: &DeviceSlice<T>,
+    predicate: &DeviceSlice<bool>,
+    stream: &Stream,
+) -> CudaResult<(DeviceBuffer<T>, usize)> {
+    let len = input.len();
+    // the positions of the elements are scanned as u32.
+    if predicate.len() != len || len > u32::MAX as usize {
+        return Err(CudaError::InvalidValue);
+    }
+    if len == 0 {
+        return Ok((unsafe { DeviceBuffer::uninitialized(0)? }, 0));
+    }
+
+    let size = size_of::<T>();
+    let module = load_module("compact", &size.to_string(), || compact_ptx(size))?;
+    let compact_flags = module.get_function("compact_flags")?;
+    let compact_scatter = module.get_function("compact_scatter")?;
+    let blocks = len.div_ceil(BLOCK_SIZE as usize).min(MAX_BLOCKS) as u32;
+
+    unsafe {
+        let mut positions = DeviceBuffer::<u32>::uninitialized_async(len, stream)?;
+        launch!(compact_flags<<<blocks, BLOCK_SIZE, 0, stream>>>(
+            predicate.as_device_ptr(),
+            positions.as_device_ptr(),
+            len as u64
+        ))?;
+        scan_exclusive(&mut positions, stream)?;
+        stream.synchronize()?;
+
+        // the position of the last element is the number of kept elements before it.
+        let mut last_position = 0u32;
+        let mut last_kept = false;
+        positions[len - 1..].copy_to(std::slice::from_mut(&mut last_position))?;
+        predicate[len - 1..].copy_to(std::slice::from_mut(&mut last_kept))?;
+        let count = last_position as usize + last_kept as usize;
+
+        let output = DeviceBuffer::<T>::uninitialized_async(count, stream)?;
+        if count > 0 && size > 0 {
+            launch!(compact_scatter<<<blocks, BLOCK_SIZE, 0, stream>>>(
+                input.as_device_ptr(),
+                predicate.as_device_ptr(),
+                positions.as_device_ptr(),
+                output.as_device_ptr(),
+                len as u64
+            ))?;
+        }
+
+        positions.drop_async(stream)?;
+        Ok((output, count))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::stream::StreamFlags;
+
+    // 12 bytes, so that it is copied as three 32-bit words.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    #[repr(C)]
+    struct Particle {
+        position: [f32; 2],
+        id: u32,
+    }
+    unsafe impl DeviceCopy for Particle {}
+
+    #[test]
+    fn test_compact() {
+        let _context = crate::quick_init().unwrap();
+        let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+
+        let particles: Vec<Particle> = (0..100_000u32)
+            .map(|i| Particle {
+                position: [i as f32, -(i as f32)],
+                id: i,
+            })
+            .collect();
+        let alive: Vec<bool> = (0..particles.len()).map(|i| i % 3 != 1).collect();
+        let expected: Vec<Particle> = particles
+            .iter()
+            .zip(&alive)
+            .filter(|(_, &alive)| alive)
+            .map(|(particle, _)| *particle)
+            .collect();
+
+        let device_particles = DeviceBuffer::from_slice(&particles).unwrap();
+        let device_alive = DeviceBuffer::from_slice(&alive).unwrap();
+        let (kept, count) = compact(&device_particles, &device_alive, &stream).unwrap();
+        stream.synchronize().unwrap();
+        assert_eq!(count, expected.len());
+        assert_eq!(kept.as_host_vec().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_compact_none_kept() {
+        let _context = crate::quick_init().unwrap();
+        let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+        let values = DeviceBuffer::from_slice(&[1u8, 2, 3]).unwrap();
+        let keep = DeviceBuffer::from_slice(&[false; 3]).unwrap();
+        let (kept, count) = compact(&values, &keep, &stream).unwrap();
+        assert_eq!((kept.len(), count), (0, 0));
+    }
+}
@@ -0,0 +1,140 @@
+//! Common parallel algorithms over device memory, implemented with kernels shipped with cust.
+//!
+//! The kernels are written in PTX and JIT compiled by the driver the first time they are used on
+//! a device, so they need no CUDA compiler at build time. Every algorithm queues its work on a
+//! stream and returns without waiting for it.
+//!
+//! The algorithms run in the current context, which must be the primary context of its device,
+//! as the contexts of cust are. Once an algorithm ran on a device, its kernels stay loaded and
+//! the primary context of the device stays retained for the rest of the process.
+
+mod compact;
+mod reduce;
+mod scan;
+mod sort;
+
+pub use self::compact::*;
+pub use self::reduce::*;
+pub use self::scan::*;
+pub use self::sort::*;
+
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+use std::ptr;
+use std::sync::Mutex;
+
+use cust_raw::driver_sys;
+
+use crate::error::{CudaError, CudaResult, ToResult};
+use crate::module::Module;
+
+/// The number of threads of every block of the kernels, must be a power of two.
+const BLOCK_SIZE: u32 = 256;
+
+/// The modules loaded so far in the primary context of a device.
+struct DeviceModules {
+    /// The primary context of the device, retained for as long as the process runs so that the
+    /// modules are never loaded in a context which was destroyed.
+    context: driver_sys::CUcontext,
+    /// The modules by kernel and variant of the kernel, usually the element type.
+    modules: HashMap<(&'static str, String), &'static Module>,
+}
+
+// the context is only used to compare it to the current one.
+unsafe impl Send for DeviceModules {}
+
+/// The modules loaded so far, by device.
+///
+/// Modules are leaked so that a kernel is only JIT compiled once per device, and so that it is
+/// never unloaded while a launch of it is still pending.
+static MODULES: Mutex<BTreeMap<driver_sys::CUdevice, DeviceModules>> = Mutex::new(BTreeMap::new());
+
+/// Returns the module of `variant` of `kernel` in the current context, loading it from the PTX
+/// returned by `ptx` if it is the first use.
+///
+/// Returns [`CudaError::InvalidContext`] if the current context is not the primary context of its
+/// device.
+fn load_module(
+    kernel: &'static str,
+    variant: &str,
+    ptx: impl FnOnce() -> String,
+) -> CudaResult<&'static Module> {
+    let mut current = ptr::null_mut();
+    let mut device = 0;
+    unsafe {
+        driver_sys::cuCtxGetCurrent(&mut current).to_result()?;
+        if current.is_null() {
+            return Err(CudaError::InvalidContext);
+        }
+        driver_sys::cuCtxGetDevice(&mut device).to_result()?;
+    }
+
+    let mut modules = MODULES.lock().unwrap_or_else(|e| e.into_inner());
+    let device_modules = match modules.entry(device) {
+        Entry::Occupied(entry) => entry.into_mut(),
+        Entry::Vacant(entry) => {
+            let mut context = ptr::null_mut();
+            // retaining does not make the context current, unlike `Context::new`.
+            unsafe { driver_sys::cuDevicePrimaryCtxRetain(&mut context, device).to_result()? };
+            entry.insert(DeviceModules {
+                context,
+                modules: HashMap::new(),
+            })
+        }
+    };
+    if device_modules.context != current {
+        return Err(CudaError::InvalidContext);
+    }
+
+    let key = (kernel, variant.to_string());
+    if let Some(module) = device_modules.modules.get(&key) {
+        return Ok(module);
+    }
+    let module = Box::leak(Box::new(Module::from_ptx(ptx(), &[])?));
+    device_modules.modules.insert(key, module);
+    Ok(module)
+}
+
+/// Replaces the placeholders of a kernel template which depend on the element type, `$TYPE`,
+/// `$BITS`, `$SIZE`, `$SIZE_LOG2` and `$SHARED_BYTES`, the size of an element per thread.
+fn specialize<T: Reducible>(template: &str, block_size: u32) -> String {
+    let size = std::mem::size_of::<T>();
+    template
+        .replace("$TYPE", T::PTX_TYPE)
+        .replace("$BITS", &(size * 8).to_string())
+        .replace("$SHARED_BYTES", &(size * block_size as usize).to_string())
+        // before `$SIZE`, which it starts with.
+        .replace("$SIZE_LOG2", &size.trailing_zeros().to_string())
+        .replace("$SIZE", &size.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const NOOP_PTX: &str = "
+.version 3.2
+.target sm_20
+.address_size 64
+
+.visible .entry noop()
+{
+        ret;
+}
+";
+
+    #[test]
+    fn test_modules_are_cached() {
+        let context = crate::quick_init().unwrap();
+        let first = load_module("noop", "", || NOOP_PTX.to_string()).unwrap();
+        let second = load_module("noop", "", || unreachable!()).unwrap();
+        assert!(ptr::eq(first, second));
+
+        // the primary context stays retained, so the module outlives the context of cust.
+        drop(context);
+        let _context = crate::quick_init().unwrap();
+        let third = load_module("noop", "", || unreachable!()).unwrap();
+        assert!(ptr::eq(first, third));
+        third.get_function("noop").unwrap();
+    }
+}
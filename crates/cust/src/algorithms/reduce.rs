@@ -0,0 +1,259 @@
+use super::{load_module, specialize, BLOCK_SIZE};
+use crate::error::CudaResult;
+use crate::launch;
+use crate::memory::{DeviceBox, DeviceBuffer, DeviceCopy, DeviceSlice};
+use crate::stream::Stream;
+
+// see the comments in the template on how the kernel works.
+const REDUCE_PTX: &str = include_str!("reduce.ptx");
+
+/// The maximum number of blocks of the first pass, and so of partial results of the second.
+const MAX_BLOCKS: usize = 1024;
+
+/// An element type which can be reduced by [`reduce_sum`], [`reduce_max`] and [`reduce_min`],
+/// scanned by [`scan_inclusive`](super::scan_inclusive) and
+/// [`scan_exclusive`](super::scan_exclusive), and sorted along with keys by
+/// [`sort_pairs`](super::sort_pairs).
+///
+/// This is implemented for `f32`, `f64`, `i32`, `u32`, `i64` and `u64`.
+pub trait Reducible: DeviceCopy + crate::private::Sealed {
+    #[doc(hidden)]
+    const PTX_TYPE: &'static str;
+    /// The bits of the smallest value of the type, the identity of `max`.
+    #[doc(hidden)]
+    const MIN_BITS: u64;
+    /// The bits of the largest value of the type, the identity of `min`.
+    #[doc(hidden)]
+    const MAX_BITS: u64;
+}
+
+macro_rules! impl_reducible {
+    ($($ty:ty => $ptx:literal, $min:expr, $max:expr;)*) => {
+        $(
+            impl Reducible for $ty {
+                const PTX_TYPE: &'static str = $ptx;
+                const MIN_BITS: u64 = $min;
+                const MAX_BITS: u64 = $max;
+            }
+        )*
+    };
+}
+
+// the other types are already sealed for `ArrayPrimitive`.
+impl crate::private::Sealed for i64 {}
+impl crate::private::Sealed for u64 {}
+
+// floats use infinities rather than their finite extremes, so that they are identities.
+impl_reducible! {
+    f32 => "f32", 0xff80_0000, 0x7f80_0000;
+    f64 => "f64", 0xfff0_0000_0000_0000, 0x7ff0_0000_0000_0000;
+    i32 => "s32", i32::MIN as u32 as u64, i32::MAX as u64;
+    u32 => "u32", u32::MIN as u64, u32::MAX as u64;
+    i64 => "s64", i64::MIN as u64, i64::MAX as u64;
+    u64 => "u64", u64::MIN, u64::MAX;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReduceOp {
+    Sum,
+    Max,
+    Min,
+}
+
+impl ReduceOp {
+    fn name(self) -> &'static str {
+        match self {
+            ReduceOp::Sum => "reduce_sum",
+            ReduceOp::Max => "reduce_max",
+            ReduceOp::Min => "reduce_min",
+        }
+    }
+}
+
+fn reduce_ptx<T: Reducible>(op: ReduceOp) -> String {
+    let ty = T::PTX_TYPE;
+    let is_float = ty.starts_with('f');
+
+    let (combine, identity) = match op {
+        // .rn keeps ptxas from contracting the compensated sum into fused multiply-adds.
+        ReduceOp::Sum if is_float => (format!("add.rn.{ty}"), 0),
+        ReduceOp::Sum => (format!("add.{ty}"), 0),
+        ReduceOp::Max => (format!("max.{ty}"), T::MIN_BITS),
+        ReduceOp::Min => (format!("min.{ty}"), T::MAX_BITS),
+    };
+
+    let accumulate = if op == ReduceOp::Sum && is_float {
+        // Kahan summation, with %v2 holding the low order bits lost so far.
+        format!(
+            "        sub.rn.{ty}      %v6, %v3, %v2;
+        add.rn.{ty}      %v7, %v1, %v6;
+        sub.rn.{ty}      %v2, %v7, %v1;
+        sub.rn.{ty}      %v2, %v2, %v6;
+        mov.{ty}         %v1, %v7;"
+        )
+    } else {
+        format!("        {combine}        %v1, %v1, %v3;")
+    };
+
+    let ptx = REDUCE_PTX
+        .replace("$ACCUMULATE", &accumulate)
+        .replace("$COMBINE", &combine)
+        .replace("$IDENTITY", &format!("{identity:#x}"));
+    specialize::<T>(&ptx, BLOCK_SIZE)
+}
+
+fn reduce<T: Reducible>(
+    op: ReduceOp,
+    input: &DeviceSlice<T>,
+    stream: &Stream,
+) -> CudaResult<DeviceBox<T>> {
+    let len = input.len();
+    let blocks = len.div_ceil(BLOCK_SIZE as usize).clamp(1, MAX_BLOCKS);
+
+    let module = load_module(op.name(), T::PTX_TYPE, || reduce_ptx::<T>(op))?;
+    let function = module.get_function("reduce")?;
+
+    unsafe {
+        let partials = DeviceBuffer::<T>::uninitialized_async(blocks, stream)?;
+        let result = DeviceBox::<T>::uninitialized()?;
+
+        launch!(function<<<blocks as u32, BLOCK_SIZE, 0, stream>>>(
+            input.as_device_ptr(),
+            partials.as_device_ptr(),
+            len as u64
+        ))?;
+        launch!(function<<<1, BLOCK_SIZE, 0, stream>>>(
+            partials.as_device_ptr(),
+            result.as_device_ptr(),
+            blocks as u64
+        ))?;
+
+        partials.drop_async(stream)?;
+        Ok(result)
+    }
+}
+
+/// Queues the sum of the elements of `input` on `stream`, returning a box which holds it once
+/// the stream has reached it.
+///
+/// The sum is computed in two kernel launches, the first reduces the input to at most a thousand
+/// partial sums and the second sums those. Integers wrap around on overflow. Floats are summed
+/// with compensated (Kahan) summation within every thread and pairwise across threads, so the
+/// rounding error stays small even for billions of elements. The sum of an empty slice is zero.
+///
+/// # Errors
+///
+/// Returns errors if the kernel cannot be loaded or launched, or if allocating the result fails.
+///
+/// # Examples
+///
+/// ```
+/// # let _context = cust::quick_init().unwrap();
+/// use cust::algorithms::reduce_sum;
+/// use cust::memory::*;
+/// use cust::stream::{Stream, StreamFlags};
+///
+/// let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+/// let values = DeviceBuffer::from_slice(&[1.0f32; 1 << 20]).unwrap();
+///
+/// let sum = reduce_sum(&values, &stream).unwrap();
+/// // the result is only ready once the stream has reached it.
+/// stream.synchronize().unwrap();
+/// assert_eq!(sum.as_host_value().unwrap(), (1 << 20) as f32);
+/// ```
+pub fn reduce_sum<T: Reducible>(
+    input: &DeviceSlice<T>,
+    stream: &Stream,
+) -> CudaResult<DeviceBox<T>> {
+    reduce(ReduceOp::Sum, input, stream)
+}
+
+/// Queues the maximum of the elements of `input` on `stream`, returning a box which holds it
+/// once the stream has reached it.
+///
+/// See [`reduce_sum`] for how the reduction is computed. The maximum of an empty slice is the
+/// smallest value of `T`, or negative infinity for floats. NaNs are ignored, like with
+/// [`f32::max`].
+///
+/// # Errors
+///
+/// Returns errors if the kernel cannot be loaded or launched, or if allocating the result fails.
+pub fn reduce_max<T: Reducible>(
+    input: &DeviceSlice<T>,
+    stream: &Stream,
+) -> CudaResult<DeviceBox<T>> {
+    reduce(ReduceOp::Max, input, stream)
+}
+
+/// Queues the minimum of the elements of `input` on `stream`, returning a box which holds it
+/// once the stream has reached it.
+///
+/// See [`reduce_sum`] for how the reduction is computed. The minimum of an empty slice is the
+/// largest value of `T`, or infinity for floats. NaNs are ignored, like with
+/// [`f32::min`].
+///
+/// # Errors
+///
+/// Returns errors if the kernel cannot be loaded or launched, or if allocating the result fails.
+pub fn reduce_min<T: Reducible>(
+    input: &DeviceSlice<T>,
+    stream: &Stream,
+) -> CudaResult<DeviceBox<T>> {
+    reduce(ReduceOp::Min, input, stream)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::stream::StreamFlags;
+
+    fn reduce_all<T: Reducible>(values: &[T]) -> [T; 3] {
+        let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+        let buffer = DeviceBuffer::from_slice(values).unwrap();
+        let sum = reduce_sum(&buffer, &stream).unwrap();
+        let max = reduce_max(&buffer, &stream).unwrap();
+        let min = reduce_min(&buffer, &stream).unwrap();
+        stream.synchronize().unwrap();
+        [
+            sum.as_host_value().unwrap(),
+            max.as_host_value().unwrap(),
+            min.as_host_value().unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_reduce_integers() {
+        let _context = crate::quick_init().unwrap();
+        // more elements than a single pass can handle, with the extremes in the middle.
+        let mut values: Vec<i64> = (0..1_000_003).map(|i| i % 7 - 3).collect();
+        values[500_000] = -100;
+        values[700_001] = 100;
+        let sum: i64 = values.iter().sum();
+        assert_eq!(reduce_all(&values), [sum, 100, -100]);
+
+        let values: Vec<u32> = (1..=1000).collect();
+        assert_eq!(reduce_all(&values), [500_500, 1000, 1]);
+    }
+
+    #[test]
+    fn test_reduce_floats() {
+        let _context = crate::quick_init().unwrap();
+        let values = vec![0.1f32; 1 << 22];
+        let [sum, max, min] = reduce_all(&values);
+        assert!((sum as f64 - 0.1 * (1 << 22) as f64).abs() < 1.0);
+        assert_eq!((max, min), (0.1, 0.1));
+
+        let [sum, max, min] = reduce_all(&[1.5f64, -2.0, 4.0]);
+        assert_eq!([sum, max, min], [3.5, 4.0, -2.0]);
+    }
+
+    #[test]
+    fn test_reduce_empty() {
+        let _context = crate::quick_init().unwrap();
+        assert_eq!(reduce_all::<i32>(&[]), [0, i32::MIN, i32::MAX]);
+        assert_eq!(
+            reduce_all::<f32>(&[]),
+            [0.0, f32::NEG_INFINITY, f32::INFINITY]
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use cust_raw::driver_sys;
+
+use super::{load_module, specialize, Reducible, BLOCK_SIZE};
+use crate::error::{CudaResult, ToResult};
+use crate::launch;
+use crate::memory::{DeviceBuffer, DeviceSlice};
+use crate::stream::Stream;
+
+// see the comments in the template on how the kernel works.
+const SCAN_PTX: &str = include_str!("scan.ptx");
+
+fn scan_ptx<T: Reducible>() -> String {
+    let ty = T::PTX_TYPE;
+    let add = if ty.starts_with('f') {
+        format!("add.rn.{ty}")
+    } else {
+        format!("add.{ty}")
+    };
+    specialize::<T>(&SCAN_PTX.replace("$ADD", &add), BLOCK_SIZE)
+}
+
+/// The scratch memory of the scans of up to `len` elements, which scans queued on the same stream
+/// can share.
+pub(super) struct ScanScratch<T: Reducible> {
+    /// The tile counter followed by the status of every tile.
+    status: DeviceBuffer<u32>,
+    aggregates: DeviceBuffer<T>,
+    inclusives: DeviceBuffer<T>,
+}
+
+impl<T: Reducible> ScanScratch<T> {
+    pub(super) unsafe fn new(len: usize, stream: &Stream) -> CudaResult<Self> {
+        let tiles = len.div_ceil(BLOCK_SIZE as usize);
+        Ok(Self {
+            status: DeviceBuffer::uninitialized_async(tiles + 1, stream)?,
+            aggregates: DeviceBuffer::uninitialized_async(tiles, stream)?,
+            inclusives: DeviceBuffer::uninitialized_async(tiles, stream)?,
+        })
+    }
+
+    pub(super) fn drop_async(self, stream: &Stream) -> CudaResult<()> {
+        self.status.drop_async(stream)?;
+        self.aggregates.drop_async(stream)?;
+        self.inclusives.drop_async(stream)
+    }
+}
+
+fn scan<T: Reducible>(
+    data: &mut DeviceSlice<T>,
+    exclusive: bool,
+    stream: &Stream,
+) -> CudaResult<()> {
+    if data.is_empty() {
+        return Ok(());
+    }
+    unsafe {
+        let scratch = ScanScratch::new(data.len(), stream)?;
+        scan_with(data, exclusive, &scratch, stream)?;
+        scratch.drop_async(stream)
+    }
+}
+
+/// Queues a scan of `data` using `scratch`, which must be for at least as many elements.
+pub(super) unsafe fn scan_with<T: Reducible>(
+    data: &mut DeviceSlice<T>,
+    exclusive: bool,
+    scratch: &ScanScratch<T>,
+    stream: &Stream,
+) -> CudaResult<()> {
+    let len = data.len();
+    if len == 0 {
+        return Ok(());
+    }
+    let tiles = len.div_ceil(BLOCK_SIZE as usize);
+    debug_assert!(tiles <= scratch.aggregates.len());
+
+    let module = load_module("scan", T::PTX_TYPE, scan_ptx::<T>)?;
+    let function = module.get_function("scan")?;
+
+    // the status is zeroed before every launch, since the previous one left it published.
+    driver_sys::cuMemsetD32Async(
+        scratch.status.as_device_ptr().as_raw(),
+        0,
+        tiles + 1,
+        stream.as_inner(),
+    )
+    .to_result()?;
+    launch!(function<<<tiles as u32, BLOCK_SIZE, 0, stream>>>(
+        data.as_device_ptr(),
+        scratch.status.as_device_ptr(),
+        scratch.aggregates.as_device_ptr(),
+        scratch.inclusives.as_device_ptr(),
+        len as u64,
+        exclusive as u32
+    ))
+}
+
+/// Queues an inclusive prefix sum of `data` on `stream`, replacing every element with the sum of
+/// itself and the elements before it.
+///
+/// The scan is done in place in a single kernel launch, using decoupled lookback: every block of
+/// the kernel scans a tile of the slice and adds the sums of the preceding tiles as they are
+/// published by the blocks scanning them. Integers wrap around on overflow. Floats are added in a
+/// different order than a sequential scan, so the results may differ from one by rounding.
+///
+/// # Errors
+///
+/// Returns errors if the kernel cannot be loaded or launched, or if allocating its scratch memory
+/// fails.
+///
+/// # Examples
+///
+/// ```
+/// # let _context = cust::quick_init().unwrap();
+/// use cust::algorithms::scan_inclusive;
+/// use cust::memory::*;
+/// use cust::stream::{Stream, StreamFlags};
+///
+/// let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+/// let mut values = DeviceBuffer::from_slice(&[1u32, 2, 3, 4]).unwrap();
+///
+/// scan_inclusive(&mut values, &stream).unwrap();
+/// stream.synchronize().unwrap();
+/// assert_eq!(values.as_host_vec().unwrap(), [1, 3, 6, 10]);
+/// ```
+pub fn scan_inclusive<T: Reducible>(data: &mut DeviceSlice<T>, stream: &Stream) -> CudaResult<()> {
+    scan(data, false, stream)
+}
+
+/// Queues an exclusive prefix sum of `data` on `stream`, replacing every element with the sum of
+/// the elements before it, so the first element becomes zero.
+///
+/// This turns the number of items produced for every element into the offset of the first of
+/// them, for instance to compute where every element goes in a stream compaction. See
+/// [`scan_inclusive`] for how the scan is computed.
+///
+/// # Errors
+///
+/// Returns errors if the kernel cannot be loaded or launched, or if allocating its scratch memory
+/// fails.
+///
+/// # Examples
+///
+/// ```
+/// # let _context = cust::quick_init().unwrap();
+/// use cust::algorithms::scan_exclusive;
+/// use cust::memory::*;
+/// use cust::stream::{Stream, StreamFlags};
+///
+/// let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+/// // whether every element is kept.
+/// let mut offsets = DeviceBuffer::from_slice(&[1u32, 0, 1, 1, 0]).unwrap();
+///
+/// scan_exclusive(&mut offsets, &stream).unwrap();
+/// stream.synchronize().unwrap();
+/// assert_eq!(offsets.as_host_vec().unwrap(), [0, 1, 1, 2, 3]);
+/// ```
+pub fn scan_exclusive<T: Reducible>(data: &mut DeviceSlice<T>, stream: &Stream) -> CudaResult<()> {
+    scan(data, true, stream)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::stream::StreamFlags;
+
+    fn scan_on_device<T: Reducible>(values: &[T], exclusive: bool) -> Vec<T> {
+        let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+        let mut buffer = DeviceBuffer::from_slice(values).unwrap();
+        if exclusive {
+            scan_exclusive(&mut buffer, &stream).unwrap();
+        } else {
+            scan_inclusive(&mut buffer, &stream).unwrap();
+        }
+        stream.synchronize().unwrap();
+        buffer.as_host_vec().unwrap()
+    }
+
+    #[test]
+    fn test_scan_integers() {
+        let _context = crate::quick_init().unwrap();
+        // many tiles, the last of them partial.
+        let values: Vec<i64> = (0..1_000_003).map(|i| i % 7 - 3).collect();
+        let inclusive: Vec<i64> = values
+            .iter()
+            .scan(0, |sum, &value| {
+                *sum += value;
+                Some(*sum)
+            })
+            .collect();
+        let mut exclusive = vec![0];
+        exclusive.extend_from_slice(&inclusive[..inclusive.len() - 1]);
+
+        assert_eq!(scan_on_device(&values, false), inclusive);
+        assert_eq!(scan_on_device(&values, true), exclusive);
+    }
+
+    #[test]
+    fn test_scan_floats() {
+        let _context = crate::quick_init().unwrap();
+        // small integers are exact, so the order of the additions does not matter.
+        let values: Vec<f32> = (0..5000).map(|i| (i % 3) as f32).collect();
+        let mut sum = 0.0;
+        for (i, scanned) in scan_on_device(&values, true).into_iter().enumerate() {
+            assert_eq!(scanned, sum);
+            sum += values[i];
+        }
+    }
+
+    #[test]
+    fn test_scan_empty() {
+        let _context = crate::quick_init().unwrap();
+        assert!(scan_on_device::<u32>(&[], false).is_empty());
+    }
+}
@@ -0,0 +1,290 @@
+use std::mem::size_of;
+
+use super::scan::{scan_with, ScanScratch};
+use super::{load_module, specialize, Reducible, BLOCK_SIZE};
+use crate::error::{CudaError, CudaResult};
+use crate::launch;
+use crate::memory::{DeviceBuffer, DevicePointer, DeviceSlice};
+use crate::stream::Stream;
+
+// see the comments in the template on how the kernels work.
+const SORT_PTX: &str = include_str!("sort.ptx");
+
+/// The number of bits of the digit every pass sorts by, so that keys have an even number of digits.
+const RADIX_BITS: usize = 4;
+
+/// The number of values of a digit.
+const DIGITS: usize = 1 << RADIX_BITS;
+
+/// A key type which can be sorted by [`sort_keys`] and [`sort_pairs`].
+///
+/// This is implemented for `i32`, `u32`, `i64` and `u64`.
+pub trait RadixKey: Reducible + private::Sealed {}
+
+impl RadixKey for i32 {}
+impl RadixKey for u32 {}
+impl RadixKey for i64 {}
+impl RadixKey for u64 {}
+
+mod private {
+    pub trait Sealed {}
+    impl Sealed for i32 {}
+    impl Sealed for u32 {}
+    impl Sealed for i64 {}
+    impl Sealed for u64 {}
+}
+
+fn sort_ptx<K: RadixKey, V: Reducible>() -> String {
+    let key_to_u32 = if size_of::<K>() == 8 {
+        "cvt.u32.u64"
+    } else {
+        "mov.b32"
+    };
+    let ptx = SORT_PTX
+        .replace("$KEY_TO_U32", key_to_u32)
+        .replace("$COUNTER_BYTES", &(32 * BLOCK_SIZE).to_string())
+        .replace("$VALUE_BITS", &(size_of::<V>() * 8).to_string())
+        .replace(
+            "$VALUE_SIZE_LOG2",
+            &size_of::<V>().trailing_zeros().to_string(),
+        );
+    specialize::<K>(&ptx, BLOCK_SIZE)
+}
+
+fn sort<K: RadixKey, V: Reducible>(
+    keys: &mut DeviceSlice<K>,
+    mut values: Option<&mut DeviceSlice<V>>,
+    stream: &Stream,
+) -> CudaResult<()> {
+    let len = keys.len();
+    // the positions of the keys are scanned as u32.
+    if values.as_ref().is_some_and(|values| values.len() != len) || len > u32::MAX as usize {
+        return Err(CudaError::InvalidValue);
+    }
+    if len < 2 {
+        return Ok(());
+    }
+
+    // the kernel only depends on the size of the values.
+    let kernel = if size_of::<V>() == 8 {
+        "sort_b64"
+    } else {
+        "sort_b32"
+    };
+    let module = load_module(kernel, K::PTX_TYPE, sort_ptx::<K, V>)?;
+    let radix_pass = module.get_function("radix_pass")?;
+
+    let passes = size_of::<K>() * 8 / RADIX_BITS;
+    let signed = K::PTX_TYPE.starts_with('s');
+    let tiles = len.div_ceil(BLOCK_SIZE as usize);
+
+    unsafe {
+        // the scratch memory is shared by all the passes.
+        let mut histograms = DeviceBuffer::<u32>::uninitialized_async(DIGITS * tiles, stream)?;
+        let scan_scratch = ScanScratch::new(histograms.len(), stream)?;
+        let keys_tmp = DeviceBuffer::<K>::uninitialized_async(len, stream)?;
+        let values_tmp = match values {
+            Some(_) => Some(DeviceBuffer::<V>::uninitialized_async(len, stream)?),
+            None => None,
+        };
+
+        // every pass moves the keys and values to the other buffer.
+        let key_ptrs = [keys.as_device_ptr(), keys_tmp.as_device_ptr()];
+        let value_ptrs = match (values.as_deref_mut(), &values_tmp) {
+            (Some(values), Some(values_tmp)) => {
+                [values.as_device_ptr(), values_tmp.as_device_ptr()]
+            }
+            _ => [DevicePointer::null(); 2],
+        };
+
+        for pass in 0..passes {
+            let (src, dst) = (pass % 2, 1 - pass % 2);
+            let shift = (pass * RADIX_BITS) as u32;
+            // the sign bit of signed keys, the top bit of the last digit, is set for the smaller
+            // keys.
+            let flip = if signed && pass == passes - 1 {
+                DIGITS as u32 / 2
+            } else {
+                0
+            };
+
+            for scatter in [false, true] {
+                if scatter {
+                    scan_with(&mut histograms, true, &scan_scratch, stream)?;
+                }
+                launch!(radix_pass<<<tiles as u32, BLOCK_SIZE, 0, stream>>>(
+                    key_ptrs[src],
+                    value_ptrs[src],
+                    key_ptrs[dst],
+                    value_ptrs[dst],
+                    histograms.as_device_ptr(),
+                    len as u64,
+                    shift,
+                    flip,
+                    values_tmp.is_some() as u32,
+                    scatter as u32
+                ))?;
+            }
+        }
+        // keys have an even number of digits, so the last pass moved them back.
+
+        histograms.drop_async(stream)?;
+        scan_scratch.drop_async(stream)?;
+        keys_tmp.drop_async(stream)?;
+        if let Some(values_tmp) = values_tmp {
+            values_tmp.drop_async(stream)?;
+        }
+        Ok(())
+    }
+}
+
+/// Queues a sort of `keys` in ascending order on `stream`.
+///
+/// The keys are sorted with a least significant digit first radix sort of 4 bit digits, one pass
+/// over the keys per digit which counts the digits of every block of keys, scans the counts and
+/// moves the keys, so the time it takes only depends on the number of keys and not on their
+/// values. It needs scratch memory for a copy of the keys and a `u32` for every 16 keys.
+///
+/// # Errors
+///
+/// Returns [`CudaError::InvalidValue`] if there are more than `u32::MAX` keys. Returns other
+/// errors if the kernels cannot be loaded or launched, or if allocating the scratch memory fails.
+///
+/// # Examples
+///
+/// ```
+/// # let _context = cust::quick_init().unwrap();
+/// use cust::algorithms::sort_keys;
+/// use cust::memory::*;
+/// use cust::stream::{Stream, StreamFlags};
+///
+/// let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+/// let mut keys = DeviceBuffer::from_slice(&[3i32, -1, 2, -5]).unwrap();
+///
+/// sort_keys(&mut keys, &stream).unwrap();
+/// stream.synchronize().unwrap();
+/// assert_eq!(keys.as_host_vec().unwrap(), [-5, -1, 2, 3]);
+/// ```
+pub fn sort_keys<K: RadixKey>(keys: &mut DeviceSlice<K>, stream: &Stream) -> CudaResult<()> {
+    sort::<K, K>(keys, None, stream)
+}
+
+/// Queues a sort of `keys` in ascending order on `stream`, moving every element of `values`
+/// along with its key.
+///
+/// The sort is stable, the values of equal keys keep their order. Sorting the indices of the
+/// keys as values gives the permutation which sorts them, to reorder other data with. See
+/// [`sort_keys`] for how the keys are sorted, the values take scratch memory for a copy of them
+/// too.
+///
+/// # Errors
+///
+/// Returns [`CudaError::InvalidValue`] if `keys` and `values` have different lengths or if there
+/// are more than `u32::MAX` keys. Returns other errors if the kernels cannot be loaded or
+/// launched, or if allocating the scratch memory fails.
+///
+/// # Examples
+///
+/// ```
+/// # let _context = cust::quick_init().unwrap();
+/// use cust::algorithms::sort_pairs;
+/// use cust::memory::*;
+/// use cust::stream::{Stream, StreamFlags};
+///
+/// let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+/// let mut keys = DeviceBuffer::from_slice(&[30u32, 10, 20]).unwrap();
+/// let mut values = DeviceBuffer::from_slice(&[0.3f32, 0.1, 0.2]).unwrap();
+///
+/// sort_pairs(&mut keys, &mut values, &stream).unwrap();
+/// stream.synchronize().unwrap();
+/// assert_eq!(keys.as_host_vec().unwrap(), [10, 20, 30]);
+/// assert_eq!(values.as_host_vec().unwrap(), [0.1, 0.2, 0.3]);
+/// ```
+pub fn sort_pairs<K: RadixKey, V: Reducible>(
+    keys: &mut DeviceSlice<K>,
+    values: &mut DeviceSlice<V>,
+    stream: &Stream,
+) -> CudaResult<()> {
+    sort(keys, Some(values), stream)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::stream::StreamFlags;
+
+    // a xorshift generator, to not depend on rand.
+    fn random_keys(len: usize) -> Vec<u64> {
+        let mut state = 0x9e37_79b9_7f4a_7c15u64;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sort_keys() {
+        let _context = crate::quick_init().unwrap();
+        let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+
+        let mut expected: Vec<u32> = random_keys(3_000_001)
+            .into_iter()
+            .map(|key| key as u32)
+            .collect();
+        let mut keys = DeviceBuffer::from_slice(&expected).unwrap();
+        sort_keys(&mut keys, &stream).unwrap();
+        stream.synchronize().unwrap();
+        expected.sort_unstable();
+        assert_eq!(keys.as_host_vec().unwrap(), expected);
+
+        let mut expected: Vec<i64> = random_keys(10_000)
+            .into_iter()
+            .map(|key| key as i64)
+            .collect();
+        let mut keys = DeviceBuffer::from_slice(&expected).unwrap();
+        sort_keys(&mut keys, &stream).unwrap();
+        stream.synchronize().unwrap();
+        expected.sort_unstable();
+        assert_eq!(keys.as_host_vec().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_sort_pairs_is_stable() {
+        let _context = crate::quick_init().unwrap();
+        let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+
+        // few distinct keys, so that most of them are equal to others.
+        let keys: Vec<i32> = random_keys(100_000)
+            .into_iter()
+            .map(|key| (key % 64) as i32 - 32)
+            .collect();
+        let indices: Vec<u64> = (0..keys.len() as u64).collect();
+        let mut expected: Vec<(i32, u64)> = keys.iter().copied().zip(indices.clone()).collect();
+        expected.sort_by_key(|&(key, _)| key);
+
+        let mut device_keys = DeviceBuffer::from_slice(&keys).unwrap();
+        let mut device_indices = DeviceBuffer::from_slice(&indices).unwrap();
+        sort_pairs(&mut device_keys, &mut device_indices, &stream).unwrap();
+        stream.synchronize().unwrap();
+
+        let (expected_keys, expected_indices): (Vec<_>, Vec<_>) = expected.into_iter().unzip();
+        assert_eq!(device_keys.as_host_vec().unwrap(), expected_keys);
+        assert_eq!(device_indices.as_host_vec().unwrap(), expected_indices);
+    }
+
+    #[test]
+    fn test_sort_pairs_length_mismatch() {
+        let _context = crate::quick_init().unwrap();
+        let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+        let mut keys = DeviceBuffer::from_slice(&[2u32, 1]).unwrap();
+        let mut values = DeviceBuffer::from_slice(&[1u32]).unwrap();
+        assert_eq!(
+            sort_pairs(&mut keys, &mut values, &stream),
+            Err(CudaError::InvalidValue)
+        );
+    }
+}
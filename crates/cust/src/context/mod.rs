@@ -46,8 +46,9 @@ pub mod legacy;
 use crate::context::legacy::StreamPriorityRange;
 use crate::{
     device::Device,
-    error::{CudaResult, DropResult, ToResult},
+    error::{CudaError, CudaResult, DropResult, ToResult},
     private::Sealed,
+    trace::trace_span,
     CudaApiVersion,
 };
 
@@ -163,6 +164,122 @@ bitflags::bitflags! {
     }
 }
 
+/// How the host thread waits for the device, selecting one of the `SCHED_*` [`ContextFlags`].
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub enum SyncPolicy {
+    /// Let CUDA choose between spinning and yielding, see [`ContextFlags::SCHED_AUTO`].
+    #[default]
+    Auto,
+    /// Actively spin, see [`ContextFlags::SCHED_SPIN`].
+    Spin,
+    /// Yield the thread, see [`ContextFlags::SCHED_YIELD`].
+    Yield,
+    /// Block on a synchronization primitive, see [`ContextFlags::SCHED_BLOCKING_SYNC`].
+    BlockingSync,
+}
+
+impl SyncPolicy {
+    fn flags(self) -> ContextFlags {
+        match self {
+            SyncPolicy::Auto => ContextFlags::SCHED_AUTO,
+            SyncPolicy::Spin => ContextFlags::SCHED_SPIN,
+            SyncPolicy::Yield => ContextFlags::SCHED_YIELD,
+            SyncPolicy::BlockingSync => ContextFlags::SCHED_BLOCKING_SYNC,
+        }
+    }
+}
+
+/// A builder for a [`Context`], selecting its device and flags in one expression.
+///
+/// The flags are set on the primary context of the device before it is retained, so they apply
+/// to every user of the primary context, just like [`Context::set_flags`]. The driver API must have
+/// been initialized with [`init`](crate::init) beforehand.
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use cust::context::{Context, ContextFlags, SyncPolicy};
+/// use cust::device::Device;
+///
+/// cust::init(cust::CudaFlags::empty())?;
+///
+/// let _ctx = Context::builder()
+///     .device(Device::get_device(0)?)
+///     .flags(ContextFlags::MAP_HOST)
+///     .sync_policy(SyncPolicy::BlockingSync)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+#[must_use = "a ContextBuilder does nothing until `build` is called"]
+pub struct ContextBuilder {
+    device: Option<Device>,
+    flags: ContextFlags,
+    sync_policy: Option<SyncPolicy>,
+}
+
+impl Default for ContextBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ContextBuilder {
+    /// Creates a builder for a context on the first device with no flags set.
+    pub fn new() -> Self {
+        Self {
+            device: None,
+            flags: ContextFlags::empty(),
+            sync_policy: None,
+        }
+    }
+
+    /// Sets the device to create the context on. Defaults to the first device.
+    pub fn device(mut self, device: Device) -> Self {
+        self.device = Some(device);
+        self
+    }
+
+    /// Sets the flags of the context.
+    pub fn flags(mut self, flags: ContextFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// Sets how the host waits for the device, replacing any `SCHED_*` flag set with
+    /// [`flags`](ContextBuilder::flags).
+    pub fn sync_policy(mut self, sync_policy: SyncPolicy) -> Self {
+        self.sync_policy = Some(sync_policy);
+        self
+    }
+
+    /// Sets the flags on the primary context of the device, then retains it and makes it current.
+    pub fn build(self) -> CudaResult<Context> {
+        let device = match self.device {
+            Some(device) => device,
+            None => Device::get_device(0)?,
+        };
+
+        let mut flags = self.flags;
+        if let Some(sync_policy) = self.sync_policy {
+            flags.remove(
+                ContextFlags::SCHED_SPIN
+                    | ContextFlags::SCHED_YIELD
+                    | ContextFlags::SCHED_BLOCKING_SYNC,
+            );
+            flags |= sync_policy.flags();
+        }
+
+        unsafe {
+            driver_sys::cuDevicePrimaryCtxSetFlags(device.as_raw(), flags.bits()).to_result()?;
+        }
+        Context::new(device)
+    }
+}
+
 #[derive(Debug)]
 pub struct Context {
     inner: driver_sys::CUcontext,
@@ -205,6 +322,11 @@ impl Context {
         }
     }
 
+    /// Returns a [`ContextBuilder`] to create a context with a specific device and flags.
+    pub fn builder() -> ContextBuilder {
+        ContextBuilder::new()
+    }
+
     /// Resets the primary context associated with the device, freeing all allocations created
     /// inside of the context. You must make sure that nothing else is using the context or using
     /// CUDA on the device in general. For this reason, it is usually highly advised to not use
@@ -224,6 +346,17 @@ impl Context {
         unsafe { driver_sys::cuDevicePrimaryCtxSetFlags(self.device, flags.bits()).to_result() }
     }
 
+    /// Names this context in profilers such as Nsight Systems, which label it with `name` instead
+    /// of a number.
+    ///
+    /// Nul bytes in `name` are removed. Since primary contexts are shared, this names the context
+    /// for every user of the primary context of the device.
+    #[cfg(feature = "nvtx")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "nvtx")))]
+    pub fn set_name(&self, name: &str) {
+        crate::nvtx::name_context(self.inner, name);
+    }
+
     /// Returns the raw handle to this context.
     pub fn as_raw(&self) -> driver_sys::CUcontext {
         self.inner
@@ -321,6 +454,15 @@ impl Drop for Context {
     }
 }
 
+/// The free and total memory of the device of the current context, in bytes.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub struct MemoryInfo {
+    /// The amount of memory which is currently free.
+    pub free: usize,
+    /// The total amount of memory of the device.
+    pub total: usize,
+}
+
 /// Type representing the context being currently used.
 #[derive(Debug)]
 pub struct CurrentContext;
@@ -502,6 +644,29 @@ impl CurrentContext {
         }
     }
 
+    /// Returns the free and total memory of the device of the current context.
+    ///
+    /// Other contexts and processes allocate from the same memory, so the amount of free memory can
+    /// change at any time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cust::context::CurrentContext;
+    /// # use std::error::Error;
+    /// #
+    /// # fn main () -> Result<(), Box<dyn Error>> {
+    /// # let _context = cust::quick_init()?;
+    /// let info = CurrentContext::get_memory_info()?;
+    /// println!("{} of {} bytes free", info.free, info.total);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_memory_info() -> CudaResult<MemoryInfo> {
+        let (free, total) = crate::memory::mem_get_info()?;
+        Ok(MemoryInfo { free, total })
+    }
+
     /// Sets the preferred cache configuration for the current context.
     ///
     /// On devices where L1 cache and shared memory use the same hardware resources, this sets the
@@ -644,11 +809,117 @@ impl CurrentContext {
         }
     }
 
+    /// Allows the current context to directly access memory allocated in the `peer` context.
+    ///
+    /// Once enabled, kernels running in the current context can dereference pointers to memory
+    /// of `peer`, and copies from `peer` with
+    /// [`DeviceSlice::copy_peer_async`](crate::memory::DeviceSlice::copy_peer_async) go directly
+    /// over the link between the devices. Access is only granted in one direction, `peer` must
+    /// enable access to the current context separately. It lasts until
+    /// [`disable_peer_access`](Self::disable_peer_access) is called or either context is destroyed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CudaError::PeerAccessUnsupported`] if the device of the current context cannot
+    /// access the device of `peer`, either because the topology does not allow it or because they
+    /// are the same device, see [`Device::can_access_peer`].
+    ///
+    /// Returns [`CudaError::PeerAccessAlreadyEnabled`] if access to `peer` is already enabled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cust::context::{Context, CurrentContext};
+    /// # use cust::device::Device;
+    /// # use std::error::Error;
+    /// #
+    /// # fn main () -> Result<(), Box<dyn Error>> {
+    /// # cust::init(cust::CudaFlags::empty())?;
+    /// if Device::num_devices()? >= 2 {
+    ///     let peer = Context::new(Device::get_device(1)?)?;
+    ///     let _context = Context::new(Device::get_device(0)?)?;
+    ///     if Device::get_device(0)?.can_access_peer(Device::get_device(1)?)? {
+    ///         CurrentContext::enable_peer_access(&peer)?;
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn enable_peer_access(peer: &Context) -> CudaResult<()> {
+        let device = Self::get_device()?;
+        let peer_device = Device {
+            device: peer.device,
+        };
+        if !device.can_access_peer(peer_device)? {
+            return Err(CudaError::PeerAccessUnsupported);
+        }
+
+        unsafe { driver_sys::cuCtxEnablePeerAccess(peer.inner, 0).to_result() }
+    }
+
+    /// Revokes the access of the current context to memory allocated in the `peer` context,
+    /// which was granted with [`enable_peer_access`](Self::enable_peer_access).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CudaError::PeerAccessNotEnabled`] if access to `peer` is not enabled.
+    pub fn disable_peer_access(peer: &Context) -> CudaResult<()> {
+        unsafe { driver_sys::cuCtxDisablePeerAccess(peer.inner).to_result() }
+    }
+
     /// Block to wait for a context's tasks to complete.
+    ///
+    /// This waits for all work queued on **every** stream of the current context, including the
+    /// default stream, and returns the first asynchronous error reported by any of them. Because
+    /// it also waits for unrelated work, calling it where only one stream's results are needed
+    /// keeps the host from running ahead of the device. It is best kept for coarse points such as
+    /// shutdown, benchmarking or error checking.
+    /// In a hot loop, wait for the work you actually depend on with
+    /// [`Stream::synchronize`](crate::stream::Stream::synchronize) or an
+    /// [`Event`](crate::event::Event) instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cust::context::CurrentContext;
+    /// # use std::error::Error;
+    /// #
+    /// # fn main () -> Result<(), Box<dyn Error>> {
+    /// # let _context = cust::quick_init()?;
+    /// // ... queue up work on one or more streams
+    ///
+    /// // Wait for everything queued on the current context to finish.
+    /// CurrentContext::synchronize()?;
+    /// # Ok(())
+    /// # }
+    /// ```
     pub fn synchronize() -> CudaResult<()> {
+        trace_span!("cuda.context.synchronize");
         unsafe {
-            driver_sys::cuCtxSynchronize().to_result()?;
+            driver_sys::cuCtxSynchronize().to_sync_result()?;
             Ok(())
         }
     }
 }
+
+#[cfg(test)]
+mod test_context {
+    use super::*;
+
+    #[test]
+    fn test_memory_info() {
+        let _context = crate::quick_init().unwrap();
+        let info = CurrentContext::get_memory_info().unwrap();
+        assert!(info.free > 0);
+        assert!(info.free <= info.total);
+    }
+
+    #[test]
+    fn test_enable_peer_access_to_self() {
+        let context = crate::quick_init().unwrap();
+        assert_eq!(
+            CurrentContext::enable_peer_access(&context),
+            Err(CudaError::PeerAccessUnsupported)
+        );
+    }
+}
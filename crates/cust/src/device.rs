@@ -1,6 +1,7 @@
 //! Functions and types for enumerating CUDA devices and retrieving information about them.
 
 use std::ffi::CStr;
+use std::fmt;
 use std::ops::Range;
 
 use cust_raw::driver_sys;
@@ -195,6 +196,35 @@ pub enum DeviceAttribute {
     ComputePreemptionSupported = 90,
     /// Device can access host registered memory at the same virtual address as the CPU
     CanUseHostPointerForRegisteredMem = 91,
+    /// Device supports stream memory operations such as `cuStreamWaitValue32`
+    CanUseStreamMemOps = 92,
+    /// Device supports 64-bit stream memory operations
+    CanUse64BitStreamMemOps = 93,
+    /// Device supports the `NOR` wait condition for stream wait value operations
+    CanUseStreamWaitValueNor = 94,
+    /// Device supports launching cooperative kernels
+    CooperativeLaunch = 95,
+    /// Device supports launching cooperative kernels across multiple devices
+    CooperativeMultiDeviceLaunch = 96,
+    /// Maximum opt-in shared memory per block in bytes
+    MaxSharedMemoryPerBlockOptin = 97,
+}
+
+/// Attributes of the link between two devices, used with
+/// [Device::p2p_attribute](struct.Device.html#method.p2p_attribute).
+#[repr(u32)]
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum P2PAttribute {
+    /// A relative value indicating the performance of the link between the two devices, lower
+    /// is better.
+    PerformanceRank = 1,
+    /// Peer access is supported between the two devices
+    AccessSupported = 2,
+    /// Native atomic operations over the link are supported
+    NativeAtomicSupported = 3,
+    /// Accessing CUDA arrays over the link is supported
+    CudaArrayAccessSupported = 4,
 }
 
 /// Opaque handle to a CUDA device.
@@ -385,6 +415,157 @@ impl Device {
         }
     }
 
+    /// Returns whether this device is capable of directly accessing memory allocated on `peer`.
+    ///
+    /// A device can never access itself as a peer, so this returns `false` if `peer` is this
+    /// device.
+    ///
+    /// # Example
+    /// ```
+    /// # use cust::*;
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # init(CudaFlags::empty())?;
+    /// use cust::device::Device;
+    /// let device = Device::get_device(0)?;
+    /// assert!(!device.can_access_peer(device)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn can_access_peer(self, peer: Device) -> CudaResult<bool> {
+        unsafe {
+            let mut can_access = 0i32;
+            driver_sys::cuDeviceCanAccessPeer(
+                &mut can_access as *mut i32,
+                self.device,
+                peer.device,
+            )
+            .to_result()?;
+            Ok(can_access != 0)
+        }
+    }
+
+    /// Returns information about the link between this device and `peer`.
+    ///
+    /// Returns an error if `peer` is this device.
+    pub fn p2p_attribute(self, peer: Device, attr: P2PAttribute) -> CudaResult<i32> {
+        unsafe {
+            let mut val = 0i32;
+            driver_sys::cuDeviceGetP2PAttribute(
+                &mut val as *mut i32,
+                // This should be safe, as the repr and values of P2PAttribute should match.
+                ::std::mem::transmute::<P2PAttribute, driver_sys::CUdevice_P2PAttribute_enum>(attr),
+                self.device,
+                peer.device,
+            )
+            .to_result()?;
+            Ok(val)
+        }
+    }
+
+    /// Queries the peer connectivity between every ordered pair of devices in the system.
+    ///
+    /// # Example
+    /// ```
+    /// # use cust::*;
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # init(CudaFlags::empty())?;
+    /// use cust::device::Device;
+    /// let matrix = Device::peer_access_matrix()?;
+    /// println!("{}", matrix);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn peer_access_matrix() -> CudaResult<PeerAccessMatrix> {
+        let devices = Device::devices()?.collect::<CudaResult<Vec<_>>>()?;
+        let mut links = Vec::with_capacity(devices.len() * devices.len());
+        for &from in &devices {
+            for &to in &devices {
+                if from == to {
+                    links.push(None);
+                    continue;
+                }
+                links.push(Some(PeerLink {
+                    access_supported: from.can_access_peer(to)?,
+                    performance_rank: from.p2p_attribute(to, P2PAttribute::PerformanceRank)?,
+                    native_atomic_supported: from
+                        .p2p_attribute(to, P2PAttribute::NativeAtomicSupported)?
+                        != 0,
+                }));
+            }
+        }
+        Ok(PeerAccessMatrix { devices, links })
+    }
+
+    /// Returns a human-readable summary of this device's properties, suitable for logs and bug
+    /// reports.
+    ///
+    /// The summary includes the name, compute capability, multiprocessor count, memory sizes,
+    /// clock rates, memory bus width, L2 cache size and whether managed memory, cooperative
+    /// launches and ECC are supported. Free memory is only reported if a context on this device is
+    /// current, since it can only be queried through one.
+    ///
+    /// # Example
+    /// ```
+    /// # use cust::*;
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let _ctx = quick_init()?;
+    /// use cust::device::Device;
+    /// let device = Device::get_device(0)?;
+    /// println!("{}", device.describe()?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn describe(self) -> CudaResult<String> {
+        const MIB: f64 = (1024 * 1024) as f64;
+
+        let attr = |attr| self.get_attribute(attr);
+        let yes_no = |val: i32| if val != 0 { "yes" } else { "no" };
+
+        let total_memory = self.total_memory()? as f64 / MIB;
+        let mut current = 0;
+        let is_current = unsafe { driver_sys::cuCtxGetDevice(&mut current).to_result().is_ok() }
+            && current == self.device;
+        let memory = if is_current {
+            let (free, _) = crate::memory::mem_get_info()?;
+            format!(
+                "{:.0} MiB free / {:.0} MiB total",
+                free as f64 / MIB,
+                total_memory
+            )
+        } else {
+            format!("{:.0} MiB total", total_memory)
+        };
+
+        Ok(format!(
+            "Device {}: {}\n\
+             \x20 Compute capability: {}.{}\n\
+             \x20 Multiprocessors: {}\n\
+             \x20 Memory: {}\n\
+             \x20 Clock rate: {} MHz (memory {} MHz)\n\
+             \x20 Memory bus width: {} bits\n\
+             \x20 L2 cache: {} KiB\n\
+             \x20 Managed memory: {}\n\
+             \x20 Cooperative launch: {}\n\
+             \x20 ECC: {}",
+            self.device,
+            self.name()?,
+            attr(DeviceAttribute::ComputeCapabilityMajor)?,
+            attr(DeviceAttribute::ComputeCapabilityMinor)?,
+            attr(DeviceAttribute::MultiprocessorCount)?,
+            memory,
+            attr(DeviceAttribute::ClockRate)? / 1000,
+            attr(DeviceAttribute::MemoryClockRate)? / 1000,
+            attr(DeviceAttribute::GlobalMemoryBusWidth)?,
+            attr(DeviceAttribute::L2CacheSize)? / 1024,
+            yes_no(attr(DeviceAttribute::ManagedMemory)?),
+            yes_no(attr(DeviceAttribute::CooperativeLaunch)?),
+            yes_no(attr(DeviceAttribute::EccEnabled)?),
+        ))
+    }
+
     /// Returns a raw handle to this device, not handing over ownership, meaning that dropping
     /// this device will try to drop the underlying device.
     pub fn as_raw(&self) -> driver_sys::CUdevice {
@@ -406,6 +587,84 @@ impl Iterator for Devices {
     }
 }
 
+/// Information about the link from one device to another, see
+/// [Device::peer_access_matrix](struct.Device.html#method.peer_access_matrix).
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub struct PeerLink {
+    /// Whether the source device can directly access memory on the destination device.
+    pub access_supported: bool,
+    /// A relative value indicating the performance of the link, lower is better. NVLink
+    /// connections typically rank better than links going over PCIe.
+    pub performance_rank: i32,
+    /// Whether native atomic operations over the link are supported.
+    pub native_atomic_supported: bool,
+}
+
+/// Peer connectivity between every ordered pair of devices in the system, indexed by device
+/// ordinal.
+#[derive(Debug, Clone)]
+pub struct PeerAccessMatrix {
+    devices: Vec<Device>,
+    links: Vec<Option<PeerLink>>,
+}
+
+impl PeerAccessMatrix {
+    /// Returns the devices that make up the rows and columns of the matrix.
+    pub fn devices(&self) -> &[Device] {
+        &self.devices
+    }
+
+    /// Returns the link from the `from`'th device to the `to`'th device, or `None` if
+    /// `from == to` or either ordinal is out of range.
+    pub fn link(&self, from: usize, to: usize) -> Option<PeerLink> {
+        let len = self.devices.len();
+        if from >= len || to >= len {
+            return None;
+        }
+        self.links[from * len + to]
+    }
+
+    /// Returns whether the `from`'th device can directly access memory on the `to`'th device.
+    pub fn can_access(&self, from: usize, to: usize) -> bool {
+        self.link(from, to)
+            .is_some_and(|link| link.access_supported)
+    }
+
+    /// Returns the matrix of [`can_access`](Self::can_access) results, indexed as
+    /// `matrix[from][to]`.
+    pub fn to_bool_matrix(&self) -> Vec<Vec<bool>> {
+        let len = self.devices.len();
+        (0..len)
+            .map(|from| (0..len).map(|to| self.can_access(from, to)).collect())
+            .collect()
+    }
+}
+
+impl fmt::Display for PeerAccessMatrix {
+    /// Prints the performance rank of every link which supports peer access, `-` for links which
+    /// do not support it, and `x` on the diagonal.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let len = self.devices.len();
+        write!(f, "    ")?;
+        for to in 0..len {
+            write!(f, "{:>4}", to)?;
+        }
+        for from in 0..len {
+            write!(f, "\n{:>4}", from)?;
+            for to in 0..len {
+                match self.link(from, to) {
+                    None => write!(f, "{:>4}", "x")?,
+                    Some(link) if link.access_supported => {
+                        write!(f, "{:>4}", link.performance_rank)?
+                    }
+                    Some(_) => write!(f, "{:>4}", "-")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -443,6 +702,20 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_peer_access_matrix() -> Result<(), Box<dyn Error>> {
+        test_init()?;
+        let num_devices = Device::num_devices()? as usize;
+        let matrix = Device::peer_access_matrix()?;
+        assert_eq!(matrix.devices().len(), num_devices);
+        for i in 0..num_devices {
+            assert!(matrix.link(i, i).is_none());
+            assert!(!matrix.can_access(i, i));
+        }
+        println!("{}", matrix);
+        Ok(())
+    }
+
     #[test]
     fn test_get_memory() -> Result<(), Box<dyn Error>> {
         test_init()?;
@@ -450,4 +723,15 @@ mod test {
         println!("{}", memory);
         Ok(())
     }
+
+    #[test]
+    fn test_describe() -> Result<(), Box<dyn Error>> {
+        let _ctx = crate::quick_init()?;
+        let device = Device::get_device(0)?;
+        let summary = device.describe()?;
+        println!("{}", summary);
+        assert!(summary.contains(&device.name()?));
+        assert!(summary.contains("free"));
+        Ok(())
+    }
 }
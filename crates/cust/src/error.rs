@@ -6,6 +6,28 @@
 //! the CUDA API. It is important to note that nearly every function in CUDA (and therefore
 //! cust) can fail. Even those functions which have no normal failure conditions can return
 //! errors related to previous asynchronous launches.
+//!
+//! # Error modes
+//!
+//! By default, every call returns its error as soon as the driver reports it. Kernel launches and
+//! asynchronous copies only report errors in enqueuing the work though, while faults that happen
+//! while the work runs surface at some later call. [`set_error_mode`] changes how the errors of
+//! these asynchronous operations are surfaced:
+//!
+//! - [`ErrorMode::Immediate`] (the default) returns them from the call that produced them.
+//! - [`ErrorMode::Deferred`] records the first error and lets the call succeed. The recorded
+//!   error is returned by the next synchronization ([`Stream::synchronize`],
+//!   [`CurrentContext::synchronize`] or [`Event::synchronize`]), which mirrors how the CUDA
+//!   runtime API reports asynchronous errors. This keeps tight launch loops free of error
+//!   handling.
+//! - [`ErrorMode::Strict`] panics as soon as an error is reported, and additionally waits for
+//!   every kernel launch to finish so that faults inside a kernel are attributed to the launch
+//!   that caused them. This is slow, but useful during development. Launches into a stream which
+//!   is being captured into a graph are not waited for, since that would invalidate the capture.
+//!
+//! [`Stream::synchronize`]: crate::stream::Stream::synchronize
+//! [`CurrentContext::synchronize`]: crate::context::CurrentContext::synchronize
+//! [`Event::synchronize`]: crate::event::Event::synchronize
 
 use std::error::Error;
 use std::ffi::CStr;
@@ -14,6 +36,8 @@ use std::mem;
 use std::os::raw::c_char;
 use std::ptr;
 use std::result::Result;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Mutex;
 
 use cust_raw::driver_sys;
 use cust_raw::driver_sys::cudaError_enum;
@@ -83,6 +107,15 @@ pub enum CudaError {
     LaunchFailed = 719,
     NotPermitted = 800,
     NotSupported = 801,
+    StreamCaptureUnsupported = 900,
+    StreamCaptureInvalidated = 901,
+    StreamCaptureMerge = 902,
+    StreamCaptureUnmatched = 903,
+    StreamCaptureUnjoined = 904,
+    StreamCaptureIsolation = 905,
+    StreamCaptureImplicit = 906,
+    CapturedEvent = 907,
+    StreamCaptureWrongThread = 908,
     UnknownError = 999,
 
     // cust errors
@@ -121,8 +154,155 @@ pub type CudaResult<T> = Result<T, CudaError>;
 /// Special result type for `drop` functions which includes the un-dropped value with the error.
 pub type DropResult<T> = Result<(), (CudaError, T)>;
 
+/// How errors from asynchronous operations such as kernel launches and asynchronous copies are
+/// surfaced. See the [module-level documentation](self#error-modes) for details.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub enum ErrorMode {
+    /// Errors are returned by the call that produced them.
+    #[default]
+    Immediate,
+    /// The first error is recorded and returned by the next synchronization.
+    Deferred,
+    /// Errors cause a panic, and kernel launches wait for the kernel to finish.
+    Strict,
+}
+
+/// The error mode and the deferred error. The process uses the one in [`ERROR_STATE`], tests use
+/// their own so that they don't change the mode of other tests running at the same time.
+struct ErrorState {
+    mode: AtomicU8,
+    deferred: Mutex<Option<CudaError>>,
+}
+
+static ERROR_STATE: ErrorState = ErrorState::new();
+
+impl ErrorState {
+    const fn new() -> Self {
+        Self {
+            mode: AtomicU8::new(ErrorMode::Immediate as u8),
+            deferred: Mutex::new(None),
+        }
+    }
+
+    fn set_mode(&self, mode: ErrorMode) {
+        self.mode.store(mode as u8, Ordering::Relaxed);
+    }
+
+    fn mode(&self) -> ErrorMode {
+        match self.mode.load(Ordering::Relaxed) {
+            1 => ErrorMode::Deferred,
+            2 => ErrorMode::Strict,
+            _ => ErrorMode::Immediate,
+        }
+    }
+
+    fn take_deferred(&self) -> Option<CudaError> {
+        self.deferred
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+    }
+
+    fn async_result(&self, result: CudaResult<()>) -> CudaResult<()> {
+        match (result, self.mode()) {
+            (Err(e), ErrorMode::Deferred) => {
+                self.deferred
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .get_or_insert(e);
+                Ok(())
+            }
+            (Err(e), ErrorMode::Strict) => panic!("CUDA error in strict error mode: {}", e),
+            (result, _) => result,
+        }
+    }
+
+    /// Waits for the work queued on `stream` after a launch in [`ErrorMode::Strict`].
+    ///
+    /// Streams which are being captured into a graph are not waited for, synchronizing them
+    /// would invalidate the capture. Neither is the legacy default stream while another stream
+    /// is being captured, in which case its capture status cannot be queried.
+    unsafe fn launch_sync(&self, stream: driver_sys::CUstream) -> CudaResult<()> {
+        if self.mode() != ErrorMode::Strict {
+            return Ok(());
+        }
+        let mut status = driver_sys::CUstreamCaptureStatus::CU_STREAM_CAPTURE_STATUS_NONE;
+        if driver_sys::cuStreamIsCapturing(stream, &mut status)
+            .to_result()
+            .is_err()
+            || status != driver_sys::CUstreamCaptureStatus::CU_STREAM_CAPTURE_STATUS_NONE
+        {
+            return Ok(());
+        }
+        self.async_result(driver_sys::cuStreamSynchronize(stream).to_result())
+    }
+
+    fn sync_result(&self, result: CudaResult<()>) -> CudaResult<()> {
+        let result = match self.take_deferred() {
+            Some(e) => Err(e),
+            None => result,
+        };
+        match (result, self.mode()) {
+            (Err(e), ErrorMode::Strict) => panic!("CUDA error in strict error mode: {}", e),
+            (result, _) => result,
+        }
+    }
+}
+
+/// Sets how errors from asynchronous operations are surfaced for the whole process.
+///
+/// Switching away from [`ErrorMode::Deferred`] does not discard an error that has already been
+/// recorded; it is still returned by the next synchronization.
+///
+/// # Example
+///
+/// ```
+/// use cust::error::ErrorMode;
+///
+/// cust::set_error_mode(ErrorMode::Strict);
+/// assert_eq!(cust::error::error_mode(), ErrorMode::Strict);
+/// # cust::set_error_mode(ErrorMode::Immediate);
+/// ```
+pub fn set_error_mode(mode: ErrorMode) {
+    ERROR_STATE.set_mode(mode);
+}
+
+/// Returns the current [`ErrorMode`].
+pub fn error_mode() -> ErrorMode {
+    ERROR_STATE.mode()
+}
+
+/// Removes and returns the error recorded in [`ErrorMode::Deferred`], if any, without
+/// synchronizing.
+pub fn take_deferred_error() -> Option<CudaError> {
+    ERROR_STATE.take_deferred()
+}
+
+/// Waits for the work queued on `stream` in [`ErrorMode::Strict`], so that faults of a kernel
+/// are reported by its launch. See [`ErrorState::launch_sync`].
+pub(crate) unsafe fn strict_launch_sync(stream: driver_sys::CUstream) -> CudaResult<()> {
+    ERROR_STATE.launch_sync(stream)
+}
+
 pub(crate) trait ToResult {
     fn to_result(self) -> CudaResult<()>;
+
+    /// Converts the result of an asynchronous operation, applying the current [`ErrorMode`].
+    fn to_async_result(self) -> CudaResult<()>
+    where
+        Self: Sized,
+    {
+        ERROR_STATE.async_result(self.to_result())
+    }
+
+    /// Converts the result of a synchronization, returning any error recorded in
+    /// [`ErrorMode::Deferred`] first.
+    fn to_sync_result(self) -> CudaResult<()>
+    where
+        Self: Sized,
+    {
+        ERROR_STATE.sync_result(self.to_result())
+    }
 }
 impl ToResult for cudaError_enum {
     fn to_result(self) -> CudaResult<()> {
@@ -220,7 +400,83 @@ impl ToResult for cudaError_enum {
             cudaError_enum::CUDA_ERROR_LAUNCH_FAILED => Err(CudaError::LaunchFailed),
             cudaError_enum::CUDA_ERROR_NOT_PERMITTED => Err(CudaError::NotPermitted),
             cudaError_enum::CUDA_ERROR_NOT_SUPPORTED => Err(CudaError::NotSupported),
+            cudaError_enum::CUDA_ERROR_STREAM_CAPTURE_UNSUPPORTED => {
+                Err(CudaError::StreamCaptureUnsupported)
+            }
+            cudaError_enum::CUDA_ERROR_STREAM_CAPTURE_INVALIDATED => {
+                Err(CudaError::StreamCaptureInvalidated)
+            }
+            cudaError_enum::CUDA_ERROR_STREAM_CAPTURE_MERGE => Err(CudaError::StreamCaptureMerge),
+            cudaError_enum::CUDA_ERROR_STREAM_CAPTURE_UNMATCHED => {
+                Err(CudaError::StreamCaptureUnmatched)
+            }
+            cudaError_enum::CUDA_ERROR_STREAM_CAPTURE_UNJOINED => {
+                Err(CudaError::StreamCaptureUnjoined)
+            }
+            cudaError_enum::CUDA_ERROR_STREAM_CAPTURE_ISOLATION => {
+                Err(CudaError::StreamCaptureIsolation)
+            }
+            cudaError_enum::CUDA_ERROR_STREAM_CAPTURE_IMPLICIT => {
+                Err(CudaError::StreamCaptureImplicit)
+            }
+            cudaError_enum::CUDA_ERROR_CAPTURED_EVENT => Err(CudaError::CapturedEvent),
+            cudaError_enum::CUDA_ERROR_STREAM_CAPTURE_WRONG_THREAD => {
+                Err(CudaError::StreamCaptureWrongThread)
+            }
             _ => Err(CudaError::UnknownError),
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_error_modes() {
+        // a local state, changing the mode of the process would affect tests running meanwhile.
+        let state = ErrorState::new();
+        let invalid = || cudaError_enum::CUDA_ERROR_INVALID_VALUE.to_result();
+        let success = || cudaError_enum::CUDA_SUCCESS.to_result();
+
+        state.set_mode(ErrorMode::Deferred);
+        assert_eq!(state.async_result(invalid()), Ok(()));
+        assert_eq!(
+            state.async_result(cudaError_enum::CUDA_ERROR_LAUNCH_FAILED.to_result()),
+            Ok(())
+        );
+        // only the first error is kept.
+        assert_eq!(state.sync_result(success()), Err(CudaError::InvalidValue));
+        assert_eq!(state.sync_result(success()), Ok(()));
+
+        state.set_mode(ErrorMode::Immediate);
+        assert_eq!(state.async_result(invalid()), Err(CudaError::InvalidValue));
+        assert_eq!(state.take_deferred(), None);
+        assert_eq!(error_mode(), ErrorMode::Immediate);
+    }
+
+    #[test]
+    #[should_panic(expected = "strict error mode")]
+    fn test_strict_error_mode_panics() {
+        let state = ErrorState::new();
+        state.set_mode(ErrorMode::Strict);
+        let _ = state.async_result(cudaError_enum::CUDA_ERROR_INVALID_VALUE.to_result());
+    }
+
+    #[test]
+    fn test_strict_launch_sync_keeps_capture() -> CudaResult<()> {
+        use crate::stream::{Stream, StreamCaptureMode, StreamFlags};
+
+        let _context = crate::quick_init()?;
+        let state = ErrorState::new();
+        state.set_mode(ErrorMode::Strict);
+        let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+
+        unsafe { state.launch_sync(stream.as_inner())? };
+        stream.begin_capture(StreamCaptureMode::ThreadLocal)?;
+        // synchronizing the stream would invalidate the capture, and ending it would fail.
+        unsafe { state.launch_sync(stream.as_inner())? };
+        stream.end_capture()?;
+        Ok(())
+    }
+}
@@ -24,6 +24,7 @@ use cust_raw::driver_sys::{
 
 use crate::error::{CudaError, CudaResult, DropResult, ToResult};
 use crate::stream::Stream;
+use crate::trace::trace_span;
 
 bitflags::bitflags! {
     /// Bit flags for configuring a CUDA Event.
@@ -177,6 +178,39 @@ impl Event {
         }
     }
 
+    /// Return `true` if all work submitted before the event was recorded has completed, without
+    /// blocking.
+    ///
+    /// This is a shorthand for checking whether [`query`](Event::query) returns
+    /// `EventStatus::Ready`, convenient for polling an event while doing other work on the host.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cust::quick_init;
+    /// # use cust::stream::{Stream, StreamFlags};
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let _context = quick_init()?;
+    /// use cust::event::{Event, EventFlags};
+    ///
+    /// let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+    /// let event = Event::new(EventFlags::DEFAULT)?;
+    ///
+    /// // do some work ...
+    ///
+    /// event.record(&stream)?;
+    ///
+    /// while !event.is_complete()? {
+    ///     // ... do some work on the host in the meantime
+    /// }
+    /// # Ok(())
+    /// }
+    /// ```
+    pub fn is_complete(&self) -> CudaResult<bool> {
+        Ok(self.query()? == EventStatus::Ready)
+    }
+
     /// Wait for an event to complete.
     ///
     /// Blocks thread execution until all work submitted before the event was
@@ -208,8 +242,9 @@ impl Event {
     /// }
     /// ```
     pub fn synchronize(&self) -> CudaResult<()> {
+        trace_span!("cuda.event.synchronize");
         unsafe {
-            cuEventSynchronize(self.0).to_result()?;
+            cuEventSynchronize(self.0).to_sync_result()?;
             Ok(())
         }
     }
@@ -286,11 +321,91 @@ impl Event {
         }
     }
 
-    /// Same as [`elapsed_time_f32`](Self::elapsed_time_f32) except returns the time as a [`Duration`].
-    pub fn elapsed(&self, start: &Self) -> CudaResult<Duration> {
+    /// Returns the throughput in gigabytes per second (10^9 bytes per second) of transferring
+    /// `bytes` bytes between the `start` event and this one.
+    ///
+    /// This is useful to compute the effective bandwidth of memory copies, see also
+    /// [`Stream::time_copy`](crate::stream::Stream::time_copy).
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`elapsed_time_f32`](Self::elapsed_time_f32).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cust::*;
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let _ctx = quick_init()?;
+    /// use cust::event::{Event, EventFlags};
+    /// use cust::memory::*;
+    /// use cust::stream::{Stream, StreamFlags};
+    ///
+    /// let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+    /// let start = Event::new(EventFlags::DEFAULT)?;
+    /// let stop = Event::new(EventFlags::DEFAULT)?;
+    ///
+    /// let host = LockedBuffer::new(&0u8, 1 << 20)?;
+    /// let mut device = unsafe { DeviceBuffer::uninitialized(1 << 20)? };
+    ///
+    /// start.record(&stream)?;
+    /// unsafe { device.async_copy_from(&host, &stream)? };
+    /// stop.record(&stream)?;
+    /// stop.synchronize()?;
+    ///
+    /// println!("{:.2} GB/s", stop.elapsed_throughput(&start, host.len())?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn elapsed_throughput(&self, start: &Self, bytes: usize) -> CudaResult<f64> {
+        let millis = self.elapsed_time_f32(start)?;
+        Ok(bytes as f64 / (millis as f64 * 1e6))
+    }
+
+    /// Returns the time elapsed between the `start` event and this one as a [`Duration`].
+    ///
+    /// This is [`elapsed_time_f32`](Self::elapsed_time_f32) converted from milliseconds, the time
+    /// is truncated to whole nanoseconds and is zero if `start` completed after this event. See
+    /// also [`Stream::time`](crate::stream::Stream::time), which records the events itself.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`elapsed_time_f32`](Self::elapsed_time_f32), in particular
+    /// `CudaError::InvalidHandle` if either event was created with the `DISABLE_TIMING` flag.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cust::*;
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let _ctx = quick_init()?;
+    /// use cust::event::{Event, EventFlags};
+    /// use cust::stream::{Stream, StreamFlags};
+    ///
+    /// let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+    /// let start = Event::new(EventFlags::DEFAULT)?;
+    /// let stop = Event::new(EventFlags::DEFAULT)?;
+    ///
+    /// start.record(&stream)?;
+    /// // queue some work on the stream ...
+    /// stop.record(&stream)?;
+    /// stop.synchronize()?;
+    ///
+    /// println!("took {:?}", stop.elapsed_time_since(&start)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn elapsed_time_since(&self, start: &Self) -> CudaResult<Duration> {
         let time_f32 = self.elapsed_time_f32(start)?;
         // multiply to nanos to preserve as much precision as possible
-        Ok(Duration::from_nanos((time_f32 * 1e6) as u64))
+        Ok(Duration::from_nanos((time_f32 as f64 * 1e6) as u64))
+    }
+
+    /// Same as [`elapsed_time_since`](Self::elapsed_time_since).
+    pub fn elapsed(&self, start: &Self) -> CudaResult<Duration> {
+        self.elapsed_time_since(start)
     }
 
     // Get the inner `CUevent` from the `Event`.
@@ -353,6 +468,92 @@ impl Drop for Event {
     }
 }
 
+/// Measures the GPU time of a block, see [`profile!`](crate::profile).
+#[doc(hidden)]
+pub struct __ProfileScope<'a> {
+    stream: &'a Stream,
+    start: Event,
+    stop: Event,
+    #[cfg(feature = "nvtx")]
+    range: crate::nvtx::Range,
+}
+
+impl<'a> __ProfileScope<'a> {
+    pub fn start(name: &'a str, stream: &'a Stream) -> CudaResult<Self> {
+        let start = Event::new(EventFlags::DEFAULT)?;
+        let stop = Event::new(EventFlags::DEFAULT)?;
+        #[cfg(feature = "nvtx")]
+        let range = crate::nvtx::Range::push(name);
+        start.record(stream)?;
+        Ok(Self {
+            stream,
+            start,
+            stop,
+            #[cfg(feature = "nvtx")]
+            range,
+        })
+    }
+
+    pub fn finish(self) -> CudaResult<f32> {
+        self.stop.record(self.stream)?;
+        #[cfg(feature = "nvtx")]
+        drop(self.range);
+        self.stop.synchronize()?;
+        self.stop.elapsed_time_f32(&self.start)
+    }
+}
+
+/// Profiles a block of code, showing it as a named range in profilers and measuring the GPU time
+/// of the work it submitted to a stream.
+///
+/// `profile!(name, stream, { ... })` records an event on `stream` before and after the block and
+/// returns the time between them in milliseconds once the work has completed, leaving it to the
+/// caller to log or aggregate. With the `nvtx` feature, the block is also wrapped in an NVTX
+/// [`Range`](crate::nvtx::Range) called `name`, so that it shows up on the timeline of Nsight
+/// Systems.
+///
+/// The stream is an argument because events are recorded on a specific stream, and only the work
+/// submitted to `stream` is timed, work submitted to other streams in the block is not.
+///
+/// This evaluates to a [`CudaResult`] holding the value of the block and the time. Timing requires
+/// waiting for the stream to reach the stop event, so this synchronizes every time the block is
+/// run.
+///
+/// # Examples
+///
+/// ```
+/// # use cust::*;
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// # let _ctx = quick_init()?;
+/// use cust::memory::*;
+/// use cust::stream::{Stream, StreamFlags};
+///
+/// let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+/// let host = LockedBuffer::new(&1u32, 1024)?;
+/// let mut buffer = DeviceBuffer::from_slice(&[0u32; 1024])?;
+///
+/// let (result, millis) = profile!("upload", stream, {
+///     unsafe { buffer.async_copy_from(&host, &stream) }
+/// })?;
+/// result?;
+/// println!("upload: {millis:.3}ms");
+/// # Ok(())
+/// # }
+/// ```
+#[macro_export]
+macro_rules! profile {
+    ($name:expr, $stream:expr, $body:block) => {
+        match $crate::event::__ProfileScope::start($name, &$stream) {
+            Ok(scope) => {
+                let value = $body;
+                scope.finish().map(|millis| (value, millis))
+            }
+            Err(error) => Err(error),
+        }
+    };
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -400,4 +601,31 @@ mod test {
         assert_eq!(result, Err(CudaError::InvalidHandle));
         Ok(())
     }
+
+    #[test]
+    fn test_elapsed_time_since_with_disable_timing() -> Result<(), Box<dyn Error>> {
+        let _context = quick_init()?;
+        let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+
+        let start_event = Event::new(EventFlags::DEFAULT)?;
+        start_event.record(&stream)?;
+
+        let stop_event = Event::new(EventFlags::DISABLE_TIMING)?;
+        stop_event.record(&stream)?;
+
+        stop_event.synchronize()?;
+        let result = stop_event.elapsed_time_since(&start_event);
+        assert_eq!(result, Err(CudaError::InvalidHandle));
+        Ok(())
+    }
+
+    #[test]
+    fn test_profile() -> Result<(), Box<dyn Error>> {
+        let _context = quick_init()?;
+        let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+        let (value, millis) = crate::profile!("test", stream, { 1 + 1 })?;
+        assert_eq!(value, 2);
+        assert!(millis >= 0.0);
+        Ok(())
+    }
 }
@@ -1,9 +1,25 @@
 //! External memory and synchronization resources
 
+use std::ffi::c_void;
+use std::mem;
+
 use cust_raw::driver_sys;
 
-use crate::error::{CudaResult, ToResult};
+use crate::error::{CudaError, CudaResult, ToResult};
 use crate::memory::{DeviceCopy, DevicePointer};
+use crate::stream::Stream;
+
+bitflags::bitflags! {
+    /// Bit flags for registering a [`GraphicsResource`].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct GraphicsRegisterFlags: u32 {
+        /// No flags set.
+        const NONE = 0x00;
+        /// The memory is a dedicated allocation, such as Vulkan memory allocated with
+        /// `VkMemoryDedicatedAllocateInfo`. This must match how the memory was allocated.
+        const DEDICATED = 0x01;
+    }
+}
 
 #[repr(transparent)]
 pub struct ExternalMemory(driver_sys::CUexternalMemory);
@@ -12,11 +28,15 @@ impl ExternalMemory {
     // Import an external memory referenced by `fd` with `size`
     #[allow(clippy::missing_safety_doc)]
     pub unsafe fn import(fd: i32, size: usize) -> CudaResult<ExternalMemory> {
+        Self::import_with_flags(fd, size, 0)
+    }
+
+    unsafe fn import_with_flags(fd: i32, size: usize, flags: u32) -> CudaResult<ExternalMemory> {
         let desc = driver_sys::CUDA_EXTERNAL_MEMORY_HANDLE_DESC {
             type_: driver_sys::CUexternalMemoryHandleType_enum::CU_EXTERNAL_MEMORY_HANDLE_TYPE_OPAQUE_FD,
             handle: driver_sys::CUDA_EXTERNAL_MEMORY_HANDLE_DESC_st__bindgen_ty_1 { fd },
             size: size as u64,
-            flags: 0,
+            flags,
             reserved: Default::default(),
         };
 
@@ -66,3 +86,369 @@ impl Drop for ExternalMemory {
         }
     }
 }
+
+/// The kind of handle an [`ExternalSemaphore`] is imported from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExternalSemaphoreHandleType {
+    /// A binary semaphore exported as an opaque file descriptor, such as a Vulkan semaphore
+    /// exported with `vkGetSemaphoreFdKHR`.
+    OpaqueFd,
+    /// A binary semaphore exported as an opaque NT handle.
+    OpaqueWin32,
+    /// A binary semaphore exported as an opaque global share handle.
+    OpaqueWin32Kmt,
+    /// A D3D12 fence exported as an NT handle.
+    D3D12Fence,
+    /// A Vulkan timeline semaphore exported as an opaque file descriptor.
+    TimelineSemaphoreFd,
+    /// A Vulkan timeline semaphore exported as an opaque NT handle.
+    TimelineSemaphoreWin32,
+}
+
+impl ExternalSemaphoreHandleType {
+    fn is_fd(self) -> bool {
+        matches!(self, Self::OpaqueFd | Self::TimelineSemaphoreFd)
+    }
+}
+
+impl From<ExternalSemaphoreHandleType> for driver_sys::CUexternalSemaphoreHandleType {
+    fn from(ty: ExternalSemaphoreHandleType) -> Self {
+        use driver_sys::CUexternalSemaphoreHandleType_enum::*;
+        match ty {
+            ExternalSemaphoreHandleType::OpaqueFd => CU_EXTERNAL_SEMAPHORE_HANDLE_TYPE_OPAQUE_FD,
+            ExternalSemaphoreHandleType::OpaqueWin32 => {
+                CU_EXTERNAL_SEMAPHORE_HANDLE_TYPE_OPAQUE_WIN32
+            }
+            ExternalSemaphoreHandleType::OpaqueWin32Kmt => {
+                CU_EXTERNAL_SEMAPHORE_HANDLE_TYPE_OPAQUE_WIN32_KMT
+            }
+            ExternalSemaphoreHandleType::D3D12Fence => {
+                CU_EXTERNAL_SEMAPHORE_HANDLE_TYPE_D3D12_FENCE
+            }
+            ExternalSemaphoreHandleType::TimelineSemaphoreFd => {
+                CU_EXTERNAL_SEMAPHORE_HANDLE_TYPE_TIMELINE_SEMAPHORE_FD
+            }
+            ExternalSemaphoreHandleType::TimelineSemaphoreWin32 => {
+                CU_EXTERNAL_SEMAPHORE_HANDLE_TYPE_TIMELINE_SEMAPHORE_WIN32
+            }
+        }
+    }
+}
+
+/// A handle to a semaphore owned by another API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExternalHandle {
+    /// A file descriptor, for the `*Fd` handle types.
+    Fd(i32),
+    /// A Windows handle, for the other handle types.
+    Win32(*mut c_void),
+}
+
+/// An external semaphore, such as a Vulkan semaphore or a D3D12 fence.
+///
+/// External semaphores order work queued on CUDA streams with work submitted to another API
+/// without synchronizing with the host, for example letting a CUDA post-processing pass wait for
+/// a Vulkan render pass and Vulkan wait for the CUDA pass in turn.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use cust::*;
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// # let _ctx = quick_init()?;
+/// # let (rendered_fd, processed_fd) = (0, 0);
+/// use cust::external::{ExternalHandle, ExternalSemaphore, ExternalSemaphoreHandleType};
+/// use cust::stream::{Stream, StreamFlags};
+///
+/// let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+///
+/// // the file descriptors are exported by Vulkan with `vkGetSemaphoreFdKHR`.
+/// let (rendered, processed) = unsafe {
+///     (
+///         ExternalSemaphore::import(
+///             ExternalHandle::Fd(rendered_fd),
+///             ExternalSemaphoreHandleType::OpaqueFd,
+///         )?,
+///         ExternalSemaphore::import(
+///             ExternalHandle::Fd(processed_fd),
+///             ExternalSemaphoreHandleType::OpaqueFd,
+///         )?,
+///     )
+/// };
+///
+/// unsafe {
+///     // wait for the render pass signaling `rendered`.
+///     rendered.wait(&stream)?;
+///     // ... launch the post-processing kernels on `stream`
+///     processed.signal(&stream)?;
+/// }
+/// // ... the next Vulkan submission waits on `processed`
+/// # Ok(())
+/// # }
+/// ```
+#[repr(transparent)]
+pub struct ExternalSemaphore(driver_sys::CUexternalSemaphore);
+
+impl ExternalSemaphore {
+    /// Import an external semaphore from `handle`, which is of the type `ty`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CudaError::InvalidValue`] if `handle` is a file descriptor and `ty` is not one
+    /// of the `*Fd` handle types, or the other way around.
+    ///
+    /// # Safety
+    ///
+    /// `handle` must be a valid handle of type `ty`. Ownership of file descriptors is
+    /// transferred to the driver on success and they must not be used afterward, Windows handles
+    /// are not closed by the driver.
+    pub unsafe fn import(
+        handle: ExternalHandle,
+        ty: ExternalSemaphoreHandleType,
+    ) -> CudaResult<ExternalSemaphore> {
+        let mut desc = mem::zeroed::<driver_sys::CUDA_EXTERNAL_SEMAPHORE_HANDLE_DESC>();
+        desc.type_ = ty.into();
+        match handle {
+            ExternalHandle::Fd(fd) if ty.is_fd() => desc.handle.fd = fd,
+            ExternalHandle::Win32(handle) if !ty.is_fd() => desc.handle.win32.handle = handle,
+            _ => return Err(CudaError::InvalidValue),
+        }
+
+        let mut semaphore: driver_sys::CUexternalSemaphore = std::ptr::null_mut();
+
+        driver_sys::cuImportExternalSemaphore(&mut semaphore, &desc)
+            .to_result()
+            .map(|_| ExternalSemaphore(semaphore))
+    }
+
+    /// Queue a signal of this semaphore on `stream`, which happens once all previously queued
+    /// work on `stream` has completed.
+    ///
+    /// Timeline semaphores and D3D12 fences are signaled with a value of 0, use
+    /// [`signal_value`](ExternalSemaphore::signal_value) for those instead.
+    ///
+    /// # Safety
+    ///
+    /// The semaphore must not be signaled again before the other API has waited on it.
+    pub unsafe fn signal(&self, stream: &Stream) -> CudaResult<()> {
+        self.signal_value(0, stream)
+    }
+
+    /// Queue a wait on this semaphore on `stream`, work queued on `stream` afterward does not
+    /// start until the semaphore has been signaled.
+    ///
+    /// Timeline semaphores and D3D12 fences are waited on for a value of 0, use
+    /// [`wait_value`](ExternalSemaphore::wait_value) for those instead.
+    ///
+    /// # Safety
+    ///
+    /// The other API must have submitted a signal of the semaphore, or the stream waits forever.
+    pub unsafe fn wait(&self, stream: &Stream) -> CudaResult<()> {
+        self.wait_value(0, stream)
+    }
+
+    /// Queue setting this timeline semaphore or D3D12 fence to `value` on `stream`, which
+    /// happens once all previously queued work on `stream` has completed. `value` is ignored
+    /// for binary semaphores.
+    ///
+    /// # Safety
+    ///
+    /// `value` must be greater than the current value of the semaphore.
+    pub unsafe fn signal_value(&self, value: u64, stream: &Stream) -> CudaResult<()> {
+        let mut params = mem::zeroed::<driver_sys::CUDA_EXTERNAL_SEMAPHORE_SIGNAL_PARAMS>();
+        params.params.fence.value = value;
+        driver_sys::cuSignalExternalSemaphoresAsync(&self.0, &params, 1, stream.as_inner())
+            .to_result()
+    }
+
+    /// Queue a wait on `stream` until this timeline semaphore or D3D12 fence reaches `value`.
+    /// `value` is ignored for binary semaphores.
+    ///
+    /// # Safety
+    ///
+    /// The other API must eventually set the semaphore to at least `value`, or the stream waits
+    /// forever.
+    pub unsafe fn wait_value(&self, value: u64, stream: &Stream) -> CudaResult<()> {
+        let mut params = mem::zeroed::<driver_sys::CUDA_EXTERNAL_SEMAPHORE_WAIT_PARAMS>();
+        params.params.fence.value = value;
+        driver_sys::cuWaitExternalSemaphoresAsync(&self.0, &params, 1, stream.as_inner())
+            .to_result()
+    }
+}
+
+impl Drop for ExternalSemaphore {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = driver_sys::cuDestroyExternalSemaphore(self.0);
+        }
+    }
+}
+
+/// A buffer shared with a graphics API, such as a Vulkan buffer that CUDA kernels write into and
+/// Vulkan then displays.
+///
+/// The buffer's memory is imported with `cuImportExternalMemory`. While the buffer is mapped,
+/// CUDA work may access it through the pointer returned by [`map`](GraphicsResource::map).
+/// If the resource was given semaphores with [`with_semaphores`](GraphicsResource::with_semaphores),
+/// mapping waits for the graphics API to signal the acquire semaphore and unmapping signals the
+/// release semaphore once the CUDA work has completed. Without semaphores, unmapping waits for
+/// the CUDA work to complete, and the graphics API must be done with the buffer before it is
+/// mapped.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use cust::*;
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// # let _ctx = quick_init()?;
+/// # let (memory_fd, acquire_fd, release_fd, size) = (0, 0, 0, 0);
+/// use cust::external::{
+///     ExternalHandle, ExternalSemaphore, ExternalSemaphoreHandleType, GraphicsRegisterFlags,
+///     GraphicsResource,
+/// };
+/// use cust::stream::{Stream, StreamFlags};
+///
+/// let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+///
+/// // the file descriptors are exported by Vulkan with `vkGetMemoryFdKHR` and
+/// // `vkGetSemaphoreFdKHR`.
+/// let mut resource = unsafe {
+///     GraphicsResource::register_buffer(memory_fd, size, GraphicsRegisterFlags::DEDICATED)?
+///         .with_semaphores(
+///             ExternalSemaphore::import(
+///                 ExternalHandle::Fd(acquire_fd),
+///                 ExternalSemaphoreHandleType::OpaqueFd,
+///             )?,
+///             ExternalSemaphore::import(
+///                 ExternalHandle::Fd(release_fd),
+///                 ExternalSemaphoreHandleType::OpaqueFd,
+///             )?,
+///         )
+/// };
+///
+/// unsafe {
+///     let pixels = resource.map::<u32>(&stream)?;
+///     // ... launch a kernel on `stream` writing to `pixels`
+///     resource.unmap(&stream)?;
+/// }
+/// // ... Vulkan waits on the release semaphore and displays the buffer
+/// # Ok(())
+/// # }
+/// ```
+pub struct GraphicsResource {
+    memory: ExternalMemory,
+    size: usize,
+    ptr: Option<DevicePointer<u8>>,
+    semaphores: Option<(ExternalSemaphore, ExternalSemaphore)>,
+    mapped: bool,
+}
+
+impl GraphicsResource {
+    /// Register a buffer of `size` bytes whose memory is referenced by the opaque file
+    /// descriptor `fd`, such as one exported by Vulkan with `vkGetMemoryFdKHR`.
+    ///
+    /// # Safety
+    ///
+    /// `fd` must be a valid opaque file descriptor of memory at least `size` bytes large, and
+    /// `flags` must match how the memory was allocated. Ownership of the file descriptor is
+    /// transferred to the driver on success and it must not be used afterward.
+    pub unsafe fn register_buffer(
+        fd: i32,
+        size: usize,
+        flags: GraphicsRegisterFlags,
+    ) -> CudaResult<GraphicsResource> {
+        let memory = ExternalMemory::import_with_flags(fd, size, flags.bits())?;
+        Ok(GraphicsResource {
+            memory,
+            size,
+            ptr: None,
+            semaphores: None,
+            mapped: false,
+        })
+    }
+
+    /// Synchronize mapping and unmapping with the graphics API. `acquire` is signaled by the
+    /// graphics API when it is done with the buffer, `release` is signaled by CUDA when it is.
+    pub fn with_semaphores(
+        mut self,
+        acquire: ExternalSemaphore,
+        release: ExternalSemaphore,
+    ) -> GraphicsResource {
+        self.semaphores = Some((acquire, release));
+        self
+    }
+
+    /// Returns the size of the buffer in bytes.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns `true` if the buffer is currently mapped.
+    pub fn is_mapped(&self) -> bool {
+        self.mapped
+    }
+
+    /// Map the buffer for access by CUDA work queued on `stream` afterward, returning a pointer
+    /// to its start.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CudaError::AlreadyMapped`] if the buffer is already mapped.
+    ///
+    /// # Safety
+    ///
+    /// The graphics API must be done with the buffer, or signal the acquire semaphore once it is,
+    /// and must not access it until it has been unmapped. The returned pointer must not be used
+    /// after unmapping. The buffer must hold a whole number of `T`s and be suitably aligned for
+    /// them.
+    pub unsafe fn map<T: DeviceCopy>(&mut self, stream: &Stream) -> CudaResult<DevicePointer<T>> {
+        if self.mapped {
+            return Err(CudaError::AlreadyMapped);
+        }
+        // the mapping stays valid until the memory is destroyed, so only create it once.
+        let ptr = match self.ptr {
+            Some(ptr) => ptr,
+            None => *self.ptr.insert(self.memory.mapped_buffer(self.size, 0)?),
+        };
+        if let Some((acquire, _)) = &self.semaphores {
+            acquire.wait(stream)?;
+        }
+        self.mapped = true;
+        Ok(ptr.cast())
+    }
+
+    /// Unmap the buffer, handing it back to the graphics API once the CUDA work queued on
+    /// `stream` so far has completed. Without semaphores, this waits for the work to complete.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CudaError::NotMapped`] if the buffer is not mapped.
+    ///
+    /// # Safety
+    ///
+    /// CUDA work accessing the buffer must have been queued on `stream`, or have completed.
+    pub unsafe fn unmap(&mut self, stream: &Stream) -> CudaResult<()> {
+        if !self.mapped {
+            return Err(CudaError::NotMapped);
+        }
+        match &self.semaphores {
+            Some((_, release)) => release.signal(stream)?,
+            None => stream.synchronize()?,
+        }
+        self.mapped = false;
+        Ok(())
+    }
+}
+
+impl Drop for GraphicsResource {
+    fn drop(&mut self) {
+        // mapped buffers must be freed before their external memory is destroyed.
+        if let Some(ptr) = self.ptr.take() {
+            unsafe {
+                let _ = driver_sys::cuMemFree(ptr.as_raw());
+            }
+        }
+    }
+}
@@ -1,14 +1,22 @@
 //! Functions and types for working with CUDA kernels.
 
-use std::marker::PhantomData;
-use std::mem::{transmute, MaybeUninit};
+use std::cell::Cell;
+use std::ffi::c_void;
+use std::fmt;
+use std::mem::{self, transmute, MaybeUninit};
+use std::os::raw::c_int;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
 
 use cust_raw::driver_sys;
 use cust_raw::driver_sys::CUfunction;
 
 use crate::context::{CacheConfig, SharedMemoryConfig};
-use crate::error::{CudaResult, ToResult};
+use crate::error::{strict_launch_sync, CudaError, CudaResult, ToResult};
+use crate::memory::{Allocator, DeviceBox, DeviceBuffer, DeviceCopy, DeviceSlice};
 use crate::module::Module;
+use crate::stream::{AccessPolicyWindow, Stream};
+use crate::trace::trace_span;
 
 /// Dimensions of a grid, or the number of thread blocks in a kernel launch.
 ///
@@ -168,6 +176,259 @@ impl From<vek::Vec3<usize>> for BlockSize {
     }
 }
 
+/// An attribute of a single kernel launch, see [`StreamLaunchConfig::attribute`] and
+/// [`Function::launch_with_attributes`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum LaunchAttribute {
+    /// Launch the grid as clusters of `x * y * z` blocks, which are co-scheduled on the same GPU
+    /// processing cluster and can access each other's shared memory. Every dimension of the grid
+    /// must be a multiple of the cluster dimension. Requires compute capability 9.0 (Hopper).
+    ClusterDimension {
+        /// Width of the cluster in blocks.
+        x: u32,
+        /// Height of the cluster in blocks.
+        y: u32,
+        /// Depth of the cluster in blocks.
+        z: u32,
+    },
+    /// Whether the kernel is launched cooperatively, which guarantees that all blocks of the grid
+    /// are resident at once so they can synchronize with each other.
+    Cooperative(bool),
+    /// The priority of the launch, overriding the priority of the stream it is launched into.
+    Priority(i32),
+    /// The L2 cache access policy window of the launch, overriding the one of the stream.
+    AccessPolicyWindow(AccessPolicyWindow),
+}
+
+impl From<LaunchAttribute> for driver_sys::CUlaunchAttribute {
+    fn from(attribute: LaunchAttribute) -> Self {
+        use driver_sys::CUlaunchAttributeID::*;
+
+        // SAFETY: `CUlaunchAttribute` is a plain C struct, for which zero is a valid value.
+        let mut raw = unsafe { mem::zeroed::<driver_sys::CUlaunchAttribute>() };
+        match attribute {
+            LaunchAttribute::ClusterDimension { x, y, z } => {
+                raw.id = CU_LAUNCH_ATTRIBUTE_CLUSTER_DIMENSION;
+                raw.value.clusterDim.x = x;
+                raw.value.clusterDim.y = y;
+                raw.value.clusterDim.z = z;
+            }
+            LaunchAttribute::Cooperative(cooperative) => {
+                raw.id = CU_LAUNCH_ATTRIBUTE_COOPERATIVE;
+                raw.value.cooperative = cooperative as i32;
+            }
+            LaunchAttribute::Priority(priority) => {
+                raw.id = CU_LAUNCH_ATTRIBUTE_PRIORITY;
+                raw.value.priority = priority;
+            }
+            LaunchAttribute::AccessPolicyWindow(window) => {
+                raw.id = CU_LAUNCH_ATTRIBUTE_ACCESS_POLICY_WINDOW;
+                raw.value.accessPolicyWindow = window.into();
+            }
+        }
+        raw
+    }
+}
+
+/// A complete set of launch parameters for a kernel: grid size, block size and the amount of
+/// dynamic shared memory.
+///
+/// `LaunchConfig` can be passed to [`launch!`](../macro.launch.html) in place of the separate grid,
+/// block and shared memory arguments, which avoids having to compute block counts by hand for
+/// the common one-dimensional case. It can also be launched directly with [`Function::launch`].
+///
+/// # Examples
+///
+/// ```
+/// # use cust::*;
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// # let _ctx = quick_init()?;
+/// # use cust::module::Module;
+/// # use std::ffi::CString;
+/// # let ptx = CString::new(include_str!("../resources/add.ptx"))?;
+/// # let module = Module::load_from_string(&ptx)?;
+/// use cust::function::{GridSize, LaunchConfig};
+///
+/// // enough blocks of the size suggested for `sum` to cover 1000 elements.
+/// let sum = module.get_function("sum")?;
+/// let config = LaunchConfig::for_num_elements(&sum, 1000)?;
+/// assert!(config.grid.x * config.block.x >= 1000);
+///
+/// let config = LaunchConfig::default()
+///     .grid_2d(32, 32)
+///     .block_2d(16, 16)
+///     .shared_mem(1024);
+/// assert_eq!(config.grid, GridSize::xy(32, 32));
+/// assert_eq!(config.shared_mem_bytes, 1024);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LaunchConfig {
+    /// The number of blocks in the grid.
+    pub grid: GridSize,
+    /// The number of threads in each block.
+    pub block: BlockSize,
+    /// The number of bytes of dynamic shared memory available to each block.
+    pub shared_mem_bytes: u32,
+}
+
+impl Default for LaunchConfig {
+    /// A launch of a single block of a single thread with no dynamic shared memory.
+    fn default() -> Self {
+        Self::new(1, 1)
+    }
+}
+
+impl LaunchConfig {
+    /// Create a launch config with the given grid and block size and no dynamic shared memory.
+    pub fn new<G, B>(grid: G, block: B) -> Self
+    where
+        G: Into<GridSize>,
+        B: Into<BlockSize>,
+    {
+        Self {
+            grid: grid.into(),
+            block: block.into(),
+            shared_mem_bytes: 0,
+        }
+    }
+
+    /// Create a one-dimensional launch config with enough threads to cover `n` elements, using
+    /// the block size suggested by the occupancy calculator for `function` without dynamic
+    /// shared memory.
+    ///
+    /// See [`Function::suggested_launch_configuration`] for how the block size is chosen. The
+    /// grid is rounded up, so the last block may contain threads past the end of the data;
+    /// kernels must bounds-check their index. At least one block is always launched.
+    pub fn for_num_elements(function: &Function, n: u32) -> CudaResult<Self> {
+        let (_, block_size) = function.suggested_launch_configuration(0, BlockSize::x(0))?;
+        let block_size = block_size.max(1);
+        let blocks = n.div_ceil(block_size).max(1);
+        Ok(Self::new(blocks, block_size))
+    }
+
+    /// Set the number of blocks in the grid.
+    pub fn grid_dim(mut self, grid: impl Into<GridSize>) -> Self {
+        self.grid = grid.into();
+        self
+    }
+
+    /// Set the number of threads in each block.
+    pub fn block_dim(mut self, block: impl Into<BlockSize>) -> Self {
+        self.block = block.into();
+        self
+    }
+
+    /// Set the grid to a two-dimensional grid of `x * y` blocks.
+    pub fn grid_2d(mut self, x: u32, y: u32) -> Self {
+        self.grid = GridSize::xy(x, y);
+        self
+    }
+
+    /// Set the block to a two-dimensional block of `x * y` threads.
+    pub fn block_2d(mut self, x: u32, y: u32) -> Self {
+        self.block = BlockSize::xy(x, y);
+        self
+    }
+
+    /// Set the number of bytes of dynamic shared memory available to each block.
+    pub fn shared_mem(mut self, bytes: u32) -> Self {
+        self.shared_mem_bytes = bytes;
+        self
+    }
+
+    /// Set the stream the kernel is launched into, returning a [`StreamLaunchConfig`] to which
+    /// [`LaunchAttribute`]s can be added.
+    pub fn stream(self, stream: &Stream) -> StreamLaunchConfig<'_> {
+        StreamLaunchConfig {
+            config: self,
+            stream,
+            attributes: Vec::new(),
+        }
+    }
+}
+
+/// A [`LaunchConfig`] together with the stream to launch into and the [`LaunchAttribute`]s of the
+/// launch, created with [`LaunchConfig::stream`].
+///
+/// The stream and attributes are kept out of `LaunchConfig` itself so that it stays a plain `Copy`
+/// value.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use cust::*;
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// # let _ctx = quick_init()?;
+/// # use cust::module::Module;
+/// # use std::ffi::CString;
+/// # let ptx = CString::new(include_str!("../resources/add.ptx"))?;
+/// # let module = Module::load_from_string(&ptx)?;
+/// use cust::function::{KernelArgs, LaunchAttribute, LaunchConfig};
+/// use cust::memory::*;
+/// use cust::stream::{Stream, StreamFlags};
+///
+/// let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+/// let sum = module.get_function("sum")?;
+/// let x = DeviceBuffer::from_slice(&[1.0f32; 1024])?;
+/// let out = DeviceBuffer::from_slice(&[0.0f32; 1024])?;
+/// let args = KernelArgs::new()
+///     .arg(x.as_device_ptr())
+///     .arg(x.as_device_ptr())
+///     .arg(out.as_device_ptr())
+///     .arg(1024u32);
+///
+/// // clusters of two blocks, which needs a Hopper GPU.
+/// let launch = LaunchConfig::default()
+///     .grid_dim(8)
+///     .block_dim(128)
+///     .stream(&stream)
+///     .attribute(LaunchAttribute::ClusterDimension { x: 2, y: 1, z: 1 });
+/// unsafe {
+///     launch.launch(&sum, args.as_params())?;
+/// }
+/// stream.synchronize()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct StreamLaunchConfig<'a> {
+    /// The grid, block and dynamic shared memory of the launch.
+    pub config: LaunchConfig,
+    /// The stream the kernel is launched into.
+    pub stream: &'a Stream,
+    attributes: Vec<LaunchAttribute>,
+}
+
+impl StreamLaunchConfig<'_> {
+    /// Add an attribute to the launch. An attribute which was already added is replaced.
+    pub fn attribute(mut self, attribute: LaunchAttribute) -> Self {
+        let same_kind = |a: &LaunchAttribute| mem::discriminant(a) == mem::discriminant(&attribute);
+        self.attributes.retain(|a| !same_kind(a));
+        self.attributes.push(attribute);
+        self
+    }
+
+    /// Returns the attributes added with [`attribute`](Self::attribute).
+    pub fn attributes(&self) -> &[LaunchAttribute] {
+        &self.attributes
+    }
+
+    /// Launch `function` with this configuration, see [`Function::launch_with_attributes`].
+    ///
+    /// # Safety
+    ///
+    /// `args` must point to values matching the kernel's parameters, see
+    /// [`Stream::launch`](crate::stream::Stream::launch).
+    pub unsafe fn launch(&self, function: &Function, args: &[*mut c_void]) -> CudaResult<()> {
+        function.launch_with_attributes(&self.config, self.stream, &self.attributes, args)
+    }
+}
+
 /// All supported function attributes for [Function::get_attribute](struct.Function.html#method.get_attribute)
 #[repr(u32)]
 #[non_exhaustive]
@@ -202,24 +463,99 @@ pub enum FunctionAttribute {
     CacheModeCa = 7,
 }
 
+thread_local! {
+    // the callback of the `Function::max_potential_block_size` call in progress on this thread.
+    // the driver calls it synchronously without any user data to find it through.
+    static BLOCK_SIZE_TO_DYNAMIC_SMEM: Cell<Option<*const (dyn Fn(u32) -> usize + 'static)>> =
+        const { Cell::new(None) };
+}
+
+unsafe extern "C" fn block_size_to_dynamic_smem(block_size: c_int) -> usize {
+    let Some(callback) = BLOCK_SIZE_TO_DYNAMIC_SMEM.with(Cell::get) else {
+        return 0;
+    };
+    // Stop panics from unwinding across the FFI
+    panic::catch_unwind(AssertUnwindSafe(|| (*callback)(block_size as u32))).unwrap_or(0)
+}
+
 /// Handle to a global kernel function.
+///
+/// A function borrows the [`Module`] it was loaded from, so the module cannot be dropped or
+/// unloaded while the function is still alive.
 #[derive(Debug)]
 pub struct Function<'a> {
     inner: CUfunction,
-    module: PhantomData<&'a Module>,
+    module: &'a Module,
+    name: String,
 }
 
 unsafe impl Send for Function<'_> {}
 unsafe impl Sync for Function<'_> {}
 
-impl Function<'_> {
-    pub(crate) fn new(inner: CUfunction, _module: &Module) -> Function {
+impl<'a> Function<'a> {
+    pub(crate) fn new(inner: CUfunction, module: &'a Module, name: &str) -> Function<'a> {
         Function {
             inner,
-            module: PhantomData,
+            module,
+            name: name.to_owned(),
         }
     }
 
+    /// Returns the name this function was looked up with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cust::*;
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let _ctx = quick_init()?;
+    /// # use cust::module::Module;
+    /// # use std::ffi::CString;
+    /// # let ptx = CString::new(include_str!("../resources/add.ptx"))?;
+    /// # let module = Module::load_from_string(&ptx)?;
+    /// let function = module.get_function("sum")?;
+    /// assert_eq!(function.name(), "sum");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the module this function was loaded from.
+    ///
+    /// The returned reference has the lifetime of the module borrow the function holds, so it
+    /// can outlive the function itself, for example to load a sibling kernel of a function that
+    /// was passed around on its own.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cust::*;
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let _ctx = quick_init()?;
+    /// # use cust::module::Module;
+    /// # use std::ffi::CString;
+    /// # let ptx = CString::new(include_str!("../resources/add.ptx"))?;
+    /// # let module = Module::load_from_string(&ptx)?;
+    /// use cust::function::Function;
+    ///
+    /// fn sibling<'a>(function: &Function<'a>, name: &str) -> cust::error::CudaResult<Function<'a>> {
+    ///     function.module().get_function(name)
+    /// }
+    ///
+    /// let function = module.get_function("sum")?;
+    /// let again = sibling(&function, "sum")?;
+    /// assert_eq!(again.to_raw(), function.to_raw());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn module(&self) -> &'a Module {
+        self.module
+    }
+
     /// Returns information about a function.
     ///
     /// # Examples
@@ -256,6 +592,132 @@ impl Function<'_> {
         }
     }
 
+    /// Launch this kernel into `stream` with the grid, block and dynamic shared memory of
+    /// `config`, through `cuLaunchKernelEx`.
+    ///
+    /// This is the same as [`launch_with_attributes`](Self::launch_with_attributes) without any
+    /// attributes.
+    ///
+    /// # Safety
+    ///
+    /// `args` must point to values matching the kernel's parameters, see
+    /// [`Stream::launch`](crate::stream::Stream::launch).
+    pub unsafe fn launch(
+        &self,
+        config: &LaunchConfig,
+        stream: &Stream,
+        args: &[*mut c_void],
+    ) -> CudaResult<()> {
+        self.launch_with_attributes(config, stream, &[], args)
+    }
+
+    /// Launch this kernel into `stream` with the grid, block and dynamic shared memory of
+    /// `config` and the given [`LaunchAttribute`]s, through `cuLaunchKernelEx`.
+    ///
+    /// Launches are asynchronous, errors of the kernel itself are reported by later calls such as
+    /// [`Stream::synchronize`] unless [`ErrorMode::Strict`](crate::error::ErrorMode::Strict) is
+    /// set.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CudaError::InvalidValue`] without launching if the block has more threads than
+    /// [`FunctionAttribute::MaxThreadsPerBlock`] allows for this kernel on this device.
+    ///
+    /// # Safety
+    ///
+    /// `args` must point to values matching the kernel's parameters, see
+    /// [`Stream::launch`](crate::stream::Stream::launch).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use cust::*;
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let _ctx = quick_init()?;
+    /// # use cust::module::Module;
+    /// # use std::ffi::CString;
+    /// # let ptx = CString::new(include_str!("../resources/add.ptx"))?;
+    /// # let module = Module::load_from_string(&ptx)?;
+    /// use cust::function::{KernelArgs, LaunchAttribute, LaunchConfig};
+    /// use cust::memory::*;
+    /// use cust::stream::{Stream, StreamFlags};
+    ///
+    /// let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+    /// let sum = module.get_function("sum")?;
+    /// let x = DeviceBuffer::from_slice(&[1.0f32; 1024])?;
+    /// let out = DeviceBuffer::from_slice(&[0.0f32; 1024])?;
+    /// let args = KernelArgs::new()
+    ///     .arg(x.as_device_ptr())
+    ///     .arg(x.as_device_ptr())
+    ///     .arg(out.as_device_ptr())
+    ///     .arg(1024u32);
+    ///
+    /// // clusters of two blocks, which needs a Hopper GPU.
+    /// let config = LaunchConfig::default().grid_dim(8).block_dim(128);
+    /// let cluster = LaunchAttribute::ClusterDimension { x: 2, y: 1, z: 1 };
+    /// unsafe {
+    ///     sum.launch_with_attributes(&config, &stream, &[cluster], args.as_params())?;
+    /// }
+    /// stream.synchronize()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub unsafe fn launch_with_attributes(
+        &self,
+        config: &LaunchConfig,
+        stream: &Stream,
+        attributes: &[LaunchAttribute],
+        args: &[*mut c_void],
+    ) -> CudaResult<()> {
+        let LaunchConfig {
+            grid,
+            block,
+            shared_mem_bytes,
+        } = *config;
+        let max_threads = self.get_attribute(FunctionAttribute::MaxThreadsPerBlock)?;
+        let threads = block.x as u64 * block.y as u64 * block.z as u64;
+        if threads > max_threads as u64 {
+            return Err(CudaError::InvalidValue);
+        }
+
+        trace_span!(
+            "cuda.launch",
+            kernel = self.name(),
+            grid = ?(grid.x, grid.y, grid.z),
+            block = ?(block.x, block.y, block.z),
+            shared_mem_bytes,
+        );
+
+        let mut attributes = attributes
+            .iter()
+            .map(|attribute| (*attribute).into())
+            .collect::<Vec<driver_sys::CUlaunchAttribute>>();
+        let raw_config = driver_sys::CUlaunchConfig {
+            gridDimX: grid.x,
+            gridDimY: grid.y,
+            gridDimZ: grid.z,
+            blockDimX: block.x,
+            blockDimY: block.y,
+            blockDimZ: block.z,
+            sharedMemBytes: shared_mem_bytes,
+            hStream: stream.as_inner(),
+            attrs: attributes.as_mut_ptr(),
+            numAttrs: attributes.len() as u32,
+        };
+
+        driver_sys::cuLaunchKernelEx(
+            &raw_config,
+            self.inner,
+            args.as_ptr() as *mut _,
+            ptr::null_mut(),
+        )
+        .to_async_result()?;
+
+        // strict mode waits for the kernel so that faults are reported by the launch itself.
+        strict_launch_sync(stream.as_inner())
+    }
+
     /// Sets the preferred cache configuration for this function.
     ///
     /// On devices where L1 cache and shared memory use the same hardware resources, this sets the
@@ -379,8 +841,73 @@ impl Function<'_> {
         }
     }
 
-    // TODO(RDambrosio016): Figure out a way to safely wrap a rust closure to pass it to cuda for blockSizeToDynamicSMemSize.
-    // It is an issue because we need to prevent unwinding but the no-unwinding wrapper cannot capture the function from its scope.
+    /// Returns the minimum grid size and the block size which achieve the maximum occupancy for
+    /// this function, for a function whose dynamic shared memory depends on its block size.
+    ///
+    /// `dynamic_smem` is called with candidate block sizes and returns the number of bytes of
+    /// dynamic shared memory a block of that size needs. Use
+    /// [`suggested_launch_configuration`](Function::suggested_launch_configuration) if it does not
+    /// depend on the block size.
+    ///
+    /// Note: all panics by `dynamic_smem` will be ignored and the function will instead use `0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cust::*;
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let _ctx = quick_init()?;
+    /// # use cust::module::Module;
+    /// # use std::ffi::CString;
+    /// # let ptx = CString::new(include_str!("../resources/add.ptx"))?;
+    /// # let module = Module::load_from_string(&ptx)?;
+    /// let function = module.get_function("sum")?;
+    /// // every thread stages one f32 in shared memory.
+    /// let (min_grid_size, block_size) =
+    ///     function.max_potential_block_size(|block_size| block_size as usize * 4)?;
+    /// println!("launch at least {min_grid_size} blocks of {block_size} threads");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn max_potential_block_size(
+        &self,
+        dynamic_smem: impl Fn(u32) -> usize,
+    ) -> CudaResult<(u32, u32)> {
+        let mut min_grid_size = MaybeUninit::uninit();
+        let mut block_size = MaybeUninit::uninit();
+
+        let callback: *const (dyn Fn(u32) -> usize + '_) = &dynamic_smem;
+        // SAFETY: the pointer is only used by `block_size_to_dynamic_smem` during the call below,
+        // while `dynamic_smem` is still alive, and is removed again before returning.
+        let callback = unsafe {
+            transmute::<*const (dyn Fn(u32) -> usize + '_), *const (dyn Fn(u32) -> usize + 'static)>(
+                callback,
+            )
+        };
+        let previous = BLOCK_SIZE_TO_DYNAMIC_SMEM.with(|cell| cell.replace(Some(callback)));
+
+        let result = unsafe {
+            driver_sys::cuOccupancyMaxPotentialBlockSize(
+                min_grid_size.as_mut_ptr(),
+                block_size.as_mut_ptr(),
+                self.to_raw(),
+                Some(block_size_to_dynamic_smem),
+                0,
+                0,
+            )
+            .to_result()
+        };
+        BLOCK_SIZE_TO_DYNAMIC_SMEM.with(|cell| cell.set(previous));
+        result?;
+
+        unsafe {
+            Ok((
+                min_grid_size.assume_init() as u32,
+                block_size.assume_init() as u32,
+            ))
+        }
+    }
 
     /// Returns a reasonable block and grid size to achieve the maximum capacity for the launch (the max number
     /// of active warps with the fewest blocks per multiprocessor).
@@ -455,6 +982,15 @@ impl Function<'_> {
 /// In this variant, the `function` parameter must be a variable. Use this form to avoid looking up
 /// the kernel function for each call.
 ///
+/// Both forms also accept a [`LaunchConfig`](function/struct.LaunchConfig.html) in place of the
+/// grid, block and shared memory arguments, in which case the kernel is launched with
+/// [`Function::launch`](function/struct.Function.html#method.launch):
+///
+/// ```ignore
+/// let config = LaunchConfig::for_num_elements(&function, len)?;
+/// let result = launch!(function<<<config, stream>>>(parameter1, parameter2...));
+/// ```
+///
 /// # Safety
 ///
 /// Launching kernels must be done in an `unsafe` block. Calling a kernel is similar to calling a
@@ -532,8 +1068,58 @@ impl Function<'_> {
 /// # }
 /// ```
 ///
+#[doc(hidden)]
+pub fn __kernel_param<T>(arg: &T) -> Option<*mut c_void> {
+    if mem::size_of::<T>() == 0 {
+        None
+    } else {
+        Some(arg as *const T as *mut c_void)
+    }
+}
+
+#[doc(hidden)]
+pub fn __kernel_params<const N: usize>(
+    args: [Option<*mut c_void>; N],
+) -> ([*mut c_void; N], usize) {
+    let mut params = [ptr::null_mut(); N];
+    let mut len = 0;
+    for param in args.into_iter().flatten() {
+        params[len] = param;
+        len += 1;
+    }
+    (params, len)
+}
+
 #[macro_export]
 macro_rules! launch {
+    ($module:ident . $function:ident <<<$config:expr, $stream:ident>>>( $( $arg:expr),* $(,)?)) => {
+        {
+            let function = $module.get_function(stringify!($function));
+            match function {
+                Ok(f) => launch!(f<<<$config, $stream>>>( $($arg),* ) ),
+                Err(e) => Err(e),
+            }
+        }
+    };
+    ($function:ident <<<$config:expr, $stream:ident>>>( $( $arg:expr),* $(,)?)) => {
+        {
+            fn assert_impl_devicecopy<T: $crate::memory::DeviceCopy>(_val: T) {}
+            if false {
+                $(
+                    assert_impl_devicecopy($arg);
+                )*
+            };
+
+            let config: $crate::function::LaunchConfig = $config;
+            match $crate::function::__kernel_params([
+                $(
+                    $crate::function::__kernel_param(&$arg),
+                )*
+            ]) {
+                (params, len) => $function.launch(&config, &$stream, &params[..len]),
+            }
+        }
+    };
     ($module:ident . $function:ident <<<$grid:expr, $block:expr, $shared:expr, $stream:ident>>>( $( $arg:expr),* $(,)?)) => {
         {
             let function = $module.get_function(stringify!($function));
@@ -552,13 +1138,474 @@ macro_rules! launch {
                 )*
             };
 
-            $stream.launch(&$function, $grid, $block, $shared,
-                &[
-                    $(
-                        &$arg as *const _ as *mut ::std::ffi::c_void,
-                    )*
-                ]
-            )
+            // zero-sized arguments are elided from the kernel signature by the codegen, so they
+            // must not take up a parameter slot here either.
+            match $crate::function::__kernel_params([
+                $(
+                    $crate::function::__kernel_param(&$arg),
+                )*
+            ]) {
+                (params, len) => $stream.launch(&$function, $grid, $block, $shared, &params[..len]),
+            }
         }
     };
 }
+
+/// A value that can be passed to a kernel through [`KernelArgs::arg`].
+///
+/// This is implemented for every [`DeviceCopy`] type, which is passed by value, and for
+/// references to device memory. Slices and buffers are passed as a pointer followed by their
+/// length, which is how the codegen lowers a `&[T]` or `&mut [T]` kernel parameter. Boxes are
+/// passed as a single device pointer.
+pub trait KernelArg<'a> {
+    #[doc(hidden)]
+    fn push_to(self, args: &mut KernelArgs<'a>);
+}
+
+impl<'a, T: DeviceCopy + 'a> KernelArg<'a> for T {
+    fn push_to(self, args: &mut KernelArgs<'a>) {
+        args.push_value(self);
+    }
+}
+
+impl<'a, T: DeviceCopy> KernelArg<'a> for &'a DeviceSlice<T> {
+    fn push_to(self, args: &mut KernelArgs<'a>) {
+        args.push_value(self.as_device_ptr());
+        args.push_value(self.len());
+    }
+}
+
+impl<'a, T: DeviceCopy> KernelArg<'a> for &'a mut DeviceSlice<T> {
+    fn push_to(self, args: &mut KernelArgs<'a>) {
+        args.push_value(self.as_device_ptr());
+        args.push_value(self.len());
+    }
+}
+
+impl<'a, T: DeviceCopy, A: Allocator> KernelArg<'a> for &'a DeviceBuffer<T, A> {
+    fn push_to(self, args: &mut KernelArgs<'a>) {
+        (&**self).push_to(args);
+    }
+}
+
+impl<'a, T: DeviceCopy, A: Allocator> KernelArg<'a> for &'a mut DeviceBuffer<T, A> {
+    fn push_to(self, args: &mut KernelArgs<'a>) {
+        (&mut **self).push_to(args);
+    }
+}
+
+impl<'a, T: DeviceCopy> KernelArg<'a> for &'a DeviceBox<T> {
+    fn push_to(self, args: &mut KernelArgs<'a>) {
+        args.push_value(self.as_device_ptr());
+    }
+}
+
+impl<'a, T: DeviceCopy> KernelArg<'a> for &'a mut DeviceBox<T> {
+    fn push_to(self, args: &mut KernelArgs<'a>) {
+        args.push_value(self.as_device_ptr());
+    }
+}
+
+/// The location of a kernel parameter in the kernel's parameter buffer, as returned by
+/// [`Module::get_kernel_params`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KernelParam {
+    /// The offset of the parameter in bytes.
+    pub offset: usize,
+    /// The size of the parameter in bytes.
+    pub size: usize,
+}
+
+trait ErasedArg {}
+
+impl<T> ErasedArg for T {}
+
+/// A builder for the parameter array of a kernel launch.
+///
+/// [`Stream::launch`](crate::stream::Stream::launch) takes a slice of pointers to the kernel's
+/// arguments, which is tedious and error prone to build by hand for kernels with many
+/// parameters. `KernelArgs` keeps its own copy of every argument so that the pointers it hands
+/// out stay valid for as long as the builder is alive, and it borrows any device memory passed
+/// to it for the lifetime `'a`, so a buffer cannot be freed while a launch still refers to it.
+///
+/// Zero-sized arguments are skipped, matching [`launch!`](../macro.launch.html).
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// use cust::function::KernelArgs;
+/// use cust::memory::*;
+/// use cust::module::Module;
+/// use cust::stream::*;
+/// use std::ffi::CString;
+///
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// let _ctx = cust::quick_init()?;
+/// let ptx = CString::new(include_str!("../resources/add.ptx"))?;
+/// let module = Module::load_from_string(&ptx)?;
+/// let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+/// let sum = module.get_function("sum")?;
+///
+/// let in_x = DeviceBuffer::from_slice(&[1.0f32; 10])?;
+/// let in_y = DeviceBuffer::from_slice(&[2.0f32; 10])?;
+/// let out = DeviceBuffer::from_slice(&[0.0f32; 10])?;
+///
+/// let args = KernelArgs::new()
+///     .arg(in_x.as_device_ptr())
+///     .arg(in_y.as_device_ptr())
+///     .arg(out.as_device_ptr())
+///     .arg(out.len());
+///
+/// unsafe {
+///     stream.launch(&sum, 1, 10, 0, args.as_params())?;
+/// }
+/// stream.synchronize()?;
+///
+/// let mut out_host = [0.0f32; 10];
+/// out.copy_to(&mut out_host)?;
+/// assert!(out_host.iter().all(|x| *x == 3.0));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// A kernel that takes `&[T]` or `&mut [T]` parameters can be given the buffers directly, each
+/// of which becomes a pointer and a length:
+///
+/// ```no_run
+/// # use cust::function::KernelArgs;
+/// # use cust::memory::*;
+/// # fn main() -> cust::error::CudaResult<()> {
+/// # let _ctx = cust::quick_init()?;
+/// let input = DeviceBuffer::from_slice(&[1.0f32; 10])?;
+/// let mut output = DeviceBuffer::from_slice(&[0.0f32; 10])?;
+///
+/// // For a kernel `fn scale(input: &[f32], output: &mut [f32], factor: f32)`.
+/// let args = KernelArgs::new().arg(&input).arg(&mut output).arg(2.0f32);
+/// assert_eq!(args.len(), 5);
+/// # Ok(())
+/// # }
+/// ```
+pub struct KernelArgs<'a> {
+    params: Vec<*mut c_void>,
+    sizes: Vec<usize>,
+    values: Vec<Box<dyn ErasedArg + 'a>>,
+}
+
+impl<'a> KernelArgs<'a> {
+    /// Creates an empty set of kernel arguments.
+    pub fn new() -> Self {
+        Self {
+            params: Vec::new(),
+            sizes: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+
+    /// Appends an argument, returning the builder.
+    pub fn arg(mut self, arg: impl KernelArg<'a>) -> Self {
+        arg.push_to(&mut self);
+        self
+    }
+
+    /// Appends an argument in place.
+    pub fn push(&mut self, arg: impl KernelArg<'a>) {
+        arg.push_to(self);
+    }
+
+    /// Returns the number of kernel parameters, counting slices and buffers as two.
+    pub fn len(&self) -> usize {
+        self.params.len()
+    }
+
+    /// Returns `true` if no parameters have been added.
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+
+    /// Returns the parameter array to pass to
+    /// [`Stream::launch`](crate::stream::Stream::launch).
+    ///
+    /// The pointers refer to values owned by `self`, so they are only valid while `self` is
+    /// alive and not modified.
+    pub fn as_params(&self) -> &[*mut c_void] {
+        &self.params
+    }
+
+    /// Checks that the arguments match the parameters of a kernel, as returned by
+    /// [`Module::get_kernel_params`].
+    ///
+    /// Only the number and sizes of the arguments can be checked, so passing a `u32` to an
+    /// `f32` parameter is not caught.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CudaError::InvalidValue`] if the number of arguments or the size of any
+    /// argument is different from the kernel's parameters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # use cust::function::KernelArgs;
+    /// # use cust::module::Module;
+    /// # use std::ffi::CString;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let _ctx = cust::quick_init()?;
+    /// # let ptx = CString::new(include_str!("../resources/add.ptx"))?;
+    /// # let module = Module::load_from_string(&ptx)?;
+    /// use cust::memory::*;
+    ///
+    /// let params = module.get_kernel_params("sum")?;
+    /// let x = DeviceBuffer::from_slice(&[1.0f32; 10])?;
+    ///
+    /// let ptrs = KernelArgs::new()
+    ///     .arg(x.as_device_ptr())
+    ///     .arg(x.as_device_ptr())
+    ///     .arg(x.as_device_ptr());
+    ///
+    /// // `sum` takes the length as a `u32`.
+    /// assert!(ptrs.arg(10usize).check(&params).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn check(&self, params: &[KernelParam]) -> CudaResult<()> {
+        let matches = self.sizes.len() == params.len()
+            && self
+                .sizes
+                .iter()
+                .zip(params)
+                .all(|(size, param)| *size == param.size);
+        if matches {
+            Ok(())
+        } else {
+            Err(CudaError::InvalidValue)
+        }
+    }
+
+    fn push_value<T: DeviceCopy + 'a>(&mut self, value: T) {
+        if mem::size_of::<T>() == 0 {
+            return;
+        }
+        // the value is boxed so that its address stays the same when `values` reallocates.
+        let value = Box::new(value);
+        self.params.push(&*value as *const T as *mut c_void);
+        self.sizes.push(mem::size_of::<T>());
+        self.values.push(value);
+    }
+}
+
+impl Default for KernelArgs<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for KernelArgs<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KernelArgs")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::memory::DevicePointer;
+    use crate::stream::StreamFlags;
+    use std::marker::PhantomData;
+
+    #[test]
+    fn test_kernel_params_skip_zsts() {
+        let a = 1u32;
+        let marker = PhantomData::<f32>;
+        let empty: [u64; 0] = [];
+        let b = 2.0f64;
+
+        let (params, len) = __kernel_params([
+            __kernel_param(&a),
+            __kernel_param(&marker),
+            __kernel_param(&empty),
+            __kernel_param(&b),
+        ]);
+
+        assert_eq!(len, 2);
+        assert_eq!(params[0], &a as *const u32 as *mut c_void);
+        assert_eq!(params[1], &b as *const f64 as *mut c_void);
+    }
+
+    #[test]
+    fn test_kernel_args_scalars() {
+        let args = KernelArgs::new()
+            .arg(1u32)
+            .arg(PhantomData::<f32>)
+            .arg(2.0f64)
+            .arg(3u8);
+
+        assert_eq!(args.len(), 3);
+        let params = args.as_params();
+        unsafe {
+            assert_eq!(*(params[0] as *const u32), 1);
+            assert_eq!(*(params[1] as *const f64), 2.0);
+            assert_eq!(*(params[2] as *const u8), 3);
+        }
+    }
+
+    #[test]
+    fn test_kernel_args_buffers() -> CudaResult<()> {
+        let _context = crate::quick_init()?;
+        let input = DeviceBuffer::from_slice(&[1.0f32; 10])?;
+        let mut output = DeviceBuffer::from_slice(&[0.0f32; 4])?;
+        let value = DeviceBox::new(&5u64)?;
+
+        let mut args = KernelArgs::new().arg(&input).arg(&mut output[1..]);
+        args.push(&value);
+
+        assert_eq!(args.len(), 5);
+        let params = args.as_params();
+        unsafe {
+            assert_eq!(
+                *(params[0] as *const DevicePointer<f32>),
+                input.as_device_ptr()
+            );
+            assert_eq!(*(params[1] as *const usize), 10);
+            assert_eq!(*(params[3] as *const usize), 3);
+            assert_eq!(
+                *(params[4] as *const DevicePointer<u64>),
+                value.as_device_ptr()
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_kernel_args_check() {
+        let ptr = DevicePointer::<f32>::null();
+        let params = [
+            KernelParam { offset: 0, size: 8 },
+            KernelParam { offset: 8, size: 4 },
+        ];
+
+        assert_eq!(KernelArgs::new().arg(ptr).arg(1u32).check(&params), Ok(()));
+        assert_eq!(
+            KernelArgs::new().arg(ptr).arg(1u64).check(&params),
+            Err(CudaError::InvalidValue)
+        );
+        assert_eq!(
+            KernelArgs::new().arg(ptr).check(&params),
+            Err(CudaError::InvalidValue)
+        );
+    }
+
+    #[test]
+    fn test_launch_config_for_num_elements() -> CudaResult<()> {
+        let _context = crate::quick_init()?;
+        let ptx = std::ffi::CString::new(include_str!("../resources/add.ptx")).unwrap();
+        let module = Module::load_from_string(&ptx)?;
+        let sum = module.get_function("sum")?;
+        let (_, block_size) = sum.suggested_launch_configuration(0, BlockSize::x(0))?;
+
+        let config = LaunchConfig::for_num_elements(&sum, 10 * block_size + 1)?;
+        assert_eq!(config, LaunchConfig::new(11, block_size));
+        // at least one block, even without elements.
+        let config = LaunchConfig::for_num_elements(&sum, 0)?;
+        assert_eq!(config, LaunchConfig::new(1, block_size));
+        Ok(())
+    }
+
+    #[test]
+    fn test_launch_config_builder() {
+        let config = LaunchConfig::default()
+            .grid_dim(GridSize::xy(4, 2))
+            .block_dim(64)
+            .shared_mem(256);
+
+        assert_eq!(config.grid, GridSize::xy(4, 2));
+        assert_eq!(config.block, BlockSize::x(64));
+        assert_eq!(config.shared_mem_bytes, 256);
+        assert_eq!(
+            config,
+            LaunchConfig::new(GridSize::xy(4, 2), 64).shared_mem(256)
+        );
+    }
+
+    #[test]
+    fn test_launch_config_attributes() -> CudaResult<()> {
+        let _context = crate::quick_init()?;
+        let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+        let launch = LaunchConfig::new(4, 64)
+            .stream(&stream)
+            .attribute(LaunchAttribute::Priority(1))
+            .attribute(LaunchAttribute::ClusterDimension { x: 2, y: 1, z: 1 })
+            .attribute(LaunchAttribute::Priority(-1));
+
+        assert_eq!(launch.config, LaunchConfig::new(4, 64));
+        assert_eq!(
+            launch.attributes(),
+            [
+                LaunchAttribute::ClusterDimension { x: 2, y: 1, z: 1 },
+                LaunchAttribute::Priority(-1),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_launch_too_many_threads() -> CudaResult<()> {
+        let _context = crate::quick_init()?;
+        let ptx = std::ffi::CString::new(include_str!("../resources/add.ptx")).unwrap();
+        let module = Module::load_from_string(&ptx)?;
+        let sum = module.get_function("sum")?;
+        let max_threads = sum.get_attribute(FunctionAttribute::MaxThreadsPerBlock)?;
+
+        let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+        let len = max_threads as u32 + 1;
+        let x = DeviceBuffer::from_slice(&vec![1.0f32; len as usize])?;
+        let out = DeviceBuffer::from_slice(&vec![0.0f32; len as usize])?;
+        let args = KernelArgs::new()
+            .arg(x.as_device_ptr())
+            .arg(x.as_device_ptr())
+            .arg(out.as_device_ptr())
+            .arg(len);
+
+        // the arguments are right, only the block is too large.
+        let config = LaunchConfig::new(1, len);
+        let result = unsafe { sum.launch(&config, &stream, args.as_params()) };
+        assert_eq!(result, Err(CudaError::InvalidValue));
+        let launch = config
+            .stream(&stream)
+            .attribute(LaunchAttribute::Priority(0));
+        let result = unsafe { launch.launch(&sum, args.as_params()) };
+        assert_eq!(result, Err(CudaError::InvalidValue));
+
+        // a block within the limit launches, so it was the block size which was rejected.
+        let config = LaunchConfig::new(1, max_threads as u32);
+        unsafe { sum.launch(&config, &stream, args.as_params())? };
+        stream.synchronize()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_potential_block_size() -> CudaResult<()> {
+        let _context = crate::quick_init()?;
+        let ptx = std::ffi::CString::new(include_str!("../resources/add.ptx")).unwrap();
+        let module = Module::load_from_string(&ptx)?;
+        let sum = module.get_function("sum")?;
+
+        let called = Cell::new(false);
+        let (min_grid_size, block_size) = sum.max_potential_block_size(|block_size| {
+            called.set(true);
+            block_size as usize * 4
+        })?;
+        assert!(called.get());
+        assert!(min_grid_size > 0);
+        assert!(block_size > 0);
+        assert_eq!(block_size % 32, 0);
+
+        let blocks =
+            sum.max_active_blocks_per_multiprocessor(block_size.into(), block_size as usize * 4)?;
+        assert!(blocks > 0);
+        Ok(())
+    }
+}
@@ -1,9 +1,8 @@
 //! CUDA Graph management.
 
 use std::{
-    ffi::c_void,
+    ffi::{c_void, CString},
     mem::{ManuallyDrop, MaybeUninit},
-    os::raw::{c_char, c_uint},
     path::Path,
     ptr,
 };
@@ -11,8 +10,9 @@ use std::{
 use cust_raw::driver_sys;
 
 use crate::{
-    error::{CudaResult, ToResult},
+    error::{CudaError, CudaResult, ToResult},
     function::{BlockSize, GridSize},
+    stream::Stream,
 };
 
 /// Creates a kernel invocation using the same syntax as [`launch`] to be used to insert kernel launches inside graphs.
@@ -82,6 +82,7 @@ impl KernelInvocation {
         }
     }
 
+    #[allow(clippy::wrong_self_convention)]
     pub fn to_raw(self) -> driver_sys::CUDA_KERNEL_NODE_PARAMS {
         driver_sys::CUDA_KERNEL_NODE_PARAMS {
             func: self.func,
@@ -240,7 +241,7 @@ impl GraphNodeType {
 /// Therefore, launching graphs is unsafe and always will be, the user must validate that:
 /// - All kernel launches are safe (same invariants as launching a normal kernel)
 /// - Memory structures used inside the graph must not be dropped before the graph is executed (this will likely
-/// throw an error if you try doing it).
+///   throw an error if you try doing it).
 ///
 /// These problems can easily be avoided by launching the graph as soon as or right after it is instantiated,
 /// instead of holding onto it long-term, which can cause problems if data is dropped before the graph is executed.
@@ -272,6 +273,39 @@ bitflags::bitflags! {
     }
 }
 
+bitflags::bitflags! {
+    /// Flags controlling what is written by [`Graph::debug_dump`].
+    #[derive(Default)]
+    pub struct GraphDebugDotFlags: u32 {
+        /// Output all debug data as if every debug flag is enabled.
+        const VERBOSE = 1 << 0;
+        /// Use CUDA runtime structures for output.
+        const RUNTIME_TYPES = 1 << 1;
+        /// Add the parameters of kernel nodes to the output.
+        const KERNEL_NODE_PARAMS = 1 << 2;
+        /// Add the parameters of memcpy nodes to the output.
+        const MEMCPY_NODE_PARAMS = 1 << 3;
+        /// Add the parameters of memset nodes to the output.
+        const MEMSET_NODE_PARAMS = 1 << 4;
+        /// Add the parameters of host nodes to the output.
+        const HOST_NODE_PARAMS = 1 << 5;
+        /// Add the event handles of event record and wait nodes to the output.
+        const EVENT_NODE_PARAMS = 1 << 6;
+        /// Add the parameters of external semaphore signal nodes to the output.
+        const EXT_SEMAS_SIGNAL_NODE_PARAMS = 1 << 7;
+        /// Add the parameters of external semaphore wait nodes to the output.
+        const EXT_SEMAS_WAIT_NODE_PARAMS = 1 << 8;
+        /// Add the attributes of kernel nodes to the output.
+        const KERNEL_NODE_ATTRIBUTES = 1 << 9;
+        /// Add the node handles and every kernel function handle to the output.
+        const HANDLES = 1 << 10;
+        /// Add the parameters of memory allocation nodes to the output.
+        const MEM_ALLOC_NODE_PARAMS = 1 << 11;
+        /// Add the parameters of memory free nodes to the output.
+        const MEM_FREE_NODE_PARAMS = 1 << 12;
+    }
+}
+
 impl Graph {
     fn check_deps_are_valid(&mut self, func_name: &str, nodes: &[GraphNode]) -> CudaResult<()> {
         // per the docs, nodes must be valid AND not duplicate.
@@ -343,39 +377,40 @@ impl Graph {
 
     /// Dumps a dotfile to a path which contains a visual representation of the graph for debugging.
     /// This dotfile can be turned into an image with graphviz.
-    #[cfg(any(windows, unix))]
+    ///
+    /// This is the same as [`Graph::debug_dump`] with [`GraphDebugDotFlags::VERBOSE`].
     pub fn dump_debug_dotfile<P: AsRef<Path>>(&mut self, path: P) -> CudaResult<()> {
+        self.debug_dump(path, GraphDebugDotFlags::VERBOSE)
+    }
+
+    /// Writes a Graphviz DOT file to `path` describing the nodes of this graph and the
+    /// dependencies between them. `flags` controls how much detail is included for each node.
+    ///
+    /// The file can be rendered with e.g. `dot -Tsvg graph.dot -o graph.svg`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CudaError::InvalidValue`] if the path contains a nul byte, or on platforms
+    /// other than unix, if it is not valid UTF-8.
+    pub fn debug_dump<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        flags: GraphDebugDotFlags,
+    ) -> CudaResult<()> {
         let path = path.as_ref();
-        let mut buf = Vec::new();
+
         #[cfg(unix)]
-        {
+        let bytes = {
             use std::os::unix::ffi::OsStrExt;
-            buf.extend(path.as_os_str().as_bytes());
-            buf.push(0);
-        }
+            path.as_os_str().as_bytes()
+        };
+        #[cfg(not(unix))]
+        let bytes = path.to_str().ok_or(CudaError::InvalidValue)?.as_bytes();
 
-        #[cfg(windows)]
-        {
-            use std::os::windows::ffi::OsStrExt;
-            buf.extend(
-                path.as_os_str()
-                    .encode_wide()
-                    .chain(Some(0))
-                    .map(|b| {
-                        let b = b.to_ne_bytes();
-                        b.get(0).copied().into_iter().chain(b.get(1).copied())
-                    })
-                    .flatten(),
-            );
-        }
+        let path = CString::new(bytes).map_err(|_| CudaError::InvalidValue)?;
 
         unsafe {
-            driver_sys::cuGraphDebugDotPrint(
-                self.raw,
-                c"./out.dot".as_ptr(),
-                driver_sys::CUgraphDebugDot_flags::CU_GRAPH_DEBUG_DOT_FLAGS_VERBOSE as u32,
-            )
-            .to_result()
+            driver_sys::cuGraphDebugDotPrint(self.raw, path.as_ptr(), flags.bits()).to_result()
         }
     }
 
@@ -431,7 +466,7 @@ impl Graph {
     /// `(A, B)` means that `B` has a dependency on `A`, that is, `A` will execute before `B`.
     pub fn edges(&mut self) -> CudaResult<Vec<(GraphNode, GraphNode)>> {
         unsafe {
-            let num_edges = self.num_edges()?;
+            let mut num_edges = self.num_edges()?;
             let mut from = vec![ptr::null_mut(); num_edges].into_boxed_slice();
             let mut to = vec![ptr::null_mut(); num_edges].into_boxed_slice();
 
@@ -439,7 +474,7 @@ impl Graph {
                 self.raw,
                 from.as_mut_ptr(),
                 to.as_mut_ptr(),
-                &num_edges as *const _ as *mut usize,
+                &mut num_edges,
             )
             .to_result()?;
 
@@ -476,7 +511,8 @@ impl Graph {
         );
         unsafe {
             let mut params = MaybeUninit::uninit();
-            driver_sys::cuGraphKernelNodeGetParams(node.to_raw(), params.as_mut_ptr());
+            driver_sys::cuGraphKernelNodeGetParams(node.to_raw(), params.as_mut_ptr())
+                .to_result()?;
             Ok(KernelInvocation::from_raw(params.assume_init()))
         }
     }
@@ -496,6 +532,34 @@ impl Graph {
         }
     }
 
+    /// Instantiates this graph into an executable graph which can be launched on a stream.
+    ///
+    /// The executable graph is a snapshot of this graph: it does not borrow it, and modifying or
+    /// dropping this graph afterwards does not affect it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let _context = cust::quick_init().unwrap();
+    /// use cust::graph::{Graph, GraphCreationFlags};
+    /// use cust::stream::{Stream, StreamFlags};
+    ///
+    /// let mut graph = Graph::new(GraphCreationFlags::NONE).unwrap();
+    /// let exec = graph.instantiate().unwrap();
+    /// drop(graph);
+    ///
+    /// let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+    /// unsafe { exec.launch(&stream).unwrap() };
+    /// stream.synchronize().unwrap();
+    /// ```
+    pub fn instantiate(&mut self) -> CudaResult<GraphExec> {
+        unsafe {
+            let mut raw = ptr::null_mut();
+            driver_sys::cuGraphInstantiateWithFlags(&mut raw, self.raw, 0).to_result()?;
+            Ok(GraphExec { raw })
+        }
+    }
+
     /// Consumes this [`Graph`], turning it into a raw handle. The handle will not be dropped,
     /// it is up to the caller to ensure the graph is destroyed.
     pub fn into_raw(self) -> driver_sys::CUgraph {
@@ -507,7 +571,49 @@ impl Graph {
 impl Drop for Graph {
     fn drop(&mut self) {
         unsafe {
-            driver_sys::cuGraphDestroy(self.raw);
+            let _ = driver_sys::cuGraphDestroy(self.raw);
+        }
+    }
+}
+
+/// An executable graph, created from a [`Graph`] with [`Graph::instantiate`].
+///
+/// An executable graph does not depend on the graph it was instantiated from, which can be
+/// dropped once the executable graph has been created. The memory and kernels it refers to must
+/// however stay alive for as long as it can be launched.
+#[derive(Debug)]
+pub struct GraphExec {
+    raw: driver_sys::CUgraphExec,
+}
+
+// SAFETY: like graphs, executable graphs may be used from any thread, but not concurrently.
+unsafe impl Send for GraphExec {}
+
+impl GraphExec {
+    /// Launches the executable graph on a stream.
+    ///
+    /// The launch is ordered with the rest of the work on `stream`. Each launch of a given
+    /// executable graph is ordered with the previous launches of it, even when they were made on
+    /// different streams.
+    ///
+    /// # Safety
+    ///
+    /// Launching a graph has the same invariants as launching every kernel in it, and every
+    /// allocation the graph uses must still be alive. See [`Graph`] for details.
+    pub unsafe fn launch(&self, stream: &Stream) -> CudaResult<()> {
+        driver_sys::cuGraphLaunch(self.raw, stream.as_inner()).to_async_result()
+    }
+
+    /// Returns the raw handle of the executable graph.
+    pub fn as_raw(&self) -> driver_sys::CUgraphExec {
+        self.raw
+    }
+}
+
+impl Drop for GraphExec {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = driver_sys::cuGraphExecDestroy(self.raw);
         }
     }
 }
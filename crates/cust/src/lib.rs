@@ -55,6 +55,7 @@
 
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+pub mod algorithms;
 pub mod device;
 pub mod error;
 pub mod event;
@@ -62,19 +63,27 @@ pub mod external;
 pub mod function;
 // WIP
 pub mod context;
-#[allow(warnings)]
-mod graph;
+pub mod graph;
 pub mod link;
 pub mod memory;
 pub mod module;
+#[cfg(feature = "nvrtc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "nvrtc")))]
+pub mod nvrtc;
+#[cfg(feature = "nvtx")]
+#[cfg_attr(docsrs, doc(cfg(feature = "nvtx")))]
+pub mod nvtx;
 pub mod prelude;
 pub mod stream;
 // WIP
 mod surface;
+pub mod testing;
 mod texture;
+mod trace;
 pub mod util;
 
 pub use cust_derive::DeviceCopy;
+pub use error::{set_error_mode, ErrorMode};
 
 use crate::context::{Context, ContextFlags};
 use crate::device::Device;
@@ -122,6 +131,40 @@ pub fn quick_init() -> CudaResult<Context> {
     Ok(ctx)
 }
 
+/// Returns the latest CUDA version supported by the installed driver as `(major, minor)`, eg.
+/// `(11, 2)` for CUDA 11.2.
+///
+/// This does not require the driver API to be initialized.
+pub fn driver_version() -> CudaResult<(u32, u32)> {
+    let version = CudaApiVersion::get()?;
+    Ok((version.major() as u32, version.minor() as u32))
+}
+
+/// Returns whether the installed driver supports at least CUDA `major.minor`.
+///
+/// This can be used to enable features from newer CUDA versions only when they are available at
+/// runtime. If the driver version cannot be queried this returns `false`.
+///
+/// # Examples
+///
+/// ```
+/// # let _context = cust::quick_init().unwrap();
+/// use cust::memory::DeviceBuffer;
+/// use cust::stream::{Stream, StreamFlags};
+///
+/// let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+/// let buffer = if cust::driver_at_least(11, 2) {
+///     unsafe { DeviceBuffer::<f32>::uninitialized_async(1024, &stream).unwrap() }
+/// } else {
+///     unsafe { DeviceBuffer::<f32>::uninitialized(1024).unwrap() }
+/// };
+/// ```
+pub fn driver_at_least(major: u32, minor: u32) -> bool {
+    CudaApiVersion::get()
+        .map(|version| version.is_at_least(major, minor))
+        .unwrap_or(false)
+}
+
 /// Struct representing the CUDA API version number.
 #[derive(Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
 pub struct CudaApiVersion {
@@ -148,6 +191,12 @@ impl CudaApiVersion {
     pub fn minor(self) -> i32 {
         (self.version % 1000) / 10
     }
+
+    /// Returns whether this version is at least `major.minor`.
+    #[inline]
+    pub fn is_at_least(self, major: u32, minor: u32) -> bool {
+        (self.major() as u32, self.minor() as u32) >= (major, minor)
+    }
 }
 
 #[cfg(test)]
@@ -161,6 +210,16 @@ mod test {
         assert_eq!(version.minor(), 2);
     }
 
+    #[test]
+    fn test_api_version_is_at_least() {
+        let version = CudaApiVersion { version: 11020 };
+        assert!(version.is_at_least(11, 2));
+        assert!(version.is_at_least(11, 0));
+        assert!(version.is_at_least(10, 8));
+        assert!(!version.is_at_least(11, 3));
+        assert!(!version.is_at_least(12, 0));
+    }
+
     #[test]
     fn test_init_twice() {
         init(CudaFlags::empty()).unwrap();
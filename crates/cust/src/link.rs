@@ -1,18 +1,53 @@
 //! Functions for linking together multiple PTX files into a module.
+//!
+//! The [`Linker`] combines separately compiled device code, PTX, cubins, fatbins and libraries of
+//! cubins, into a single cubin which can then be loaded with
+//! [`Module::from_cubin`](crate::module::Module::from_cubin). This is needed when device code is
+//! split into multiple compilation units which call each other's functions.
+//!
+//! # Examples
+//!
+//! ```
+//! # use std::error::Error;
+//! # fn main() -> Result<(), Box<dyn Error>> {
+//! # let _context = cust::quick_init()?;
+//! use cust::link::Linker;
+//! use cust::module::Module;
+//!
+//! let mut linker = Linker::new()?;
+//! linker.add_ptx("add.ptx", include_str!("../resources/add.ptx"))?;
+//! let cubin = linker.complete()?;
+//!
+//! let module = Module::from_cubin(cubin, &[])?;
+//! let sum = module.get_function("sum")?;
+//! # Ok(())
+//! # }
+//! ```
 
+use std::ffi::{c_void, CString};
 use std::mem::MaybeUninit;
+use std::os::raw::c_uint;
+use std::path::Path;
 use std::ptr::null_mut;
 
 use cust_raw::driver_sys;
 
-use crate::error::{CudaResult, ToResult};
-
-static UNNAMED: &str = "\0";
+use crate::error::{CudaError, ToResult};
+use crate::module::{jit_log_to_string, path_to_bytes, JitError, JIT_LOG_SIZE};
 
 /// A linker used to link together PTX files into a single module.
+///
+/// Every method which can fail returns a [`JitError`] holding the logs of the linker, which
+/// explain why an input was rejected or a symbol could not be resolved. It converts into a
+/// [`CudaError`], so `?` can be used in functions returning a
+/// [`CudaResult`](crate::error::CudaResult).
 #[derive(Debug)]
 pub struct Linker {
     raw: driver_sys::CUlinkState,
+    // the linker writes its logs to these until it is destroyed, so they are boxed to keep them
+    // in place when the linker is moved.
+    info_log: Box<[u8]>,
+    error_log: Box<[u8]>,
 }
 
 unsafe impl Send for Linker {}
@@ -20,109 +55,206 @@ unsafe impl Sync for Linker {}
 
 impl Linker {
     /// Creates a new linker.
-    pub fn new() -> CudaResult<Self> {
-        // per the docs, cuda expects the options pointers to last as long as CULinkState.
-        // Therefore we use box to alloc the memory for us, then into_raw it so we now have ownership
-        // of the memory (and dont have any aliasing requirements attached either).
+    pub fn new() -> Result<Self, JitError> {
+        let mut info_log = vec![0u8; JIT_LOG_SIZE].into_boxed_slice();
+        let mut error_log = vec![0u8; JIT_LOG_SIZE].into_boxed_slice();
+
+        // per the docs, cuda expects the option values to last as long as CULinkState, which is
+        // the case for the log buffers since they are owned by the linker. The sizes are passed
+        // by value.
+        let mut options = [
+            driver_sys::CUjit_option::CU_JIT_INFO_LOG_BUFFER,
+            driver_sys::CUjit_option::CU_JIT_INFO_LOG_BUFFER_SIZE_BYTES,
+            driver_sys::CUjit_option::CU_JIT_ERROR_LOG_BUFFER,
+            driver_sys::CUjit_option::CU_JIT_ERROR_LOG_BUFFER_SIZE_BYTES,
+        ];
+        let mut values = [
+            info_log.as_mut_ptr() as *mut c_void,
+            JIT_LOG_SIZE as *mut c_void,
+            error_log.as_mut_ptr() as *mut c_void,
+            JIT_LOG_SIZE as *mut c_void,
+        ];
 
         unsafe {
             let mut raw = MaybeUninit::uninit();
-            driver_sys::cuLinkCreate(0, null_mut(), null_mut(), raw.as_mut_ptr()).to_result()?;
-            Ok(Self {
-                raw: raw.assume_init(),
-            })
+            let result = driver_sys::cuLinkCreate(
+                options.len() as c_uint,
+                options.as_mut_ptr(),
+                values.as_mut_ptr(),
+                raw.as_mut_ptr(),
+            )
+            .to_result();
+
+            match result {
+                Ok(()) => Ok(Self {
+                    raw: raw.assume_init(),
+                    info_log,
+                    error_log,
+                }),
+                Err(error) => Err(JitError {
+                    error,
+                    info_log: jit_log_to_string(&info_log),
+                    error_log: jit_log_to_string(&error_log),
+                }),
+            }
         }
     }
 
-    // TODO(RDambrosio016): Support PTX compiler options and decide whether we should expose
-    // them as a separate crate or as part of cust.
+    /// Returns the informational messages of the linker so far, such as the register usage of
+    /// the linked kernels.
+    pub fn info_log(&self) -> String {
+        jit_log_to_string(&self.info_log)
+    }
 
-    /// Add some PTX assembly string to be linked in. The PTX code will be
-    /// compiled into cubin by CUDA then linked in.
-    ///
-    /// # Returns
-    ///
-    /// Returns an error if the PTX is invalid, cuda is out of memory, or the PTX
-    /// is of an unsupported version.
-    pub fn add_ptx(&mut self, ptx: impl AsRef<str>) -> CudaResult<()> {
-        let ptx = ptx.as_ref();
+    /// Returns the error messages of the linker so far.
+    pub fn error_log(&self) -> String {
+        jit_log_to_string(&self.error_log)
+    }
+
+    fn jit_error(&self, error: CudaError) -> JitError {
+        JitError {
+            error,
+            info_log: self.info_log(),
+            error_log: self.error_log(),
+        }
+    }
+
+    fn add_data(
+        &mut self,
+        kind: driver_sys::CUjitInputType,
+        name: &str,
+        data: &[u8],
+    ) -> Result<(), JitError> {
+        let name = CString::new(name).expect("name given to the Linker contained nul bytes");
 
         unsafe {
             driver_sys::cuLinkAddData(
                 self.raw,
-                driver_sys::CUjitInputType::CU_JIT_INPUT_PTX,
+                kind,
                 // cuda_sys wants *mut but from the API docs we know we retain ownership so
                 // this cast is sound.
-                ptx.as_ptr() as *mut _,
-                ptx.len(),
-                UNNAMED.as_ptr().cast(),
+                data.as_ptr() as *mut _,
+                data.len(),
+                name.as_ptr(),
                 0,
-                std::ptr::null_mut(),
-                std::ptr::null_mut(),
+                null_mut(),
+                null_mut(),
             )
             .to_result()
+            .map_err(|error| self.jit_error(error))
         }
     }
 
+    // TODO(RDambrosio016): Support PTX compiler options and decide whether we should expose
+    // them as a separate crate or as part of cust.
+
+    /// Add some PTX assembly string to be linked in. The PTX code will be
+    /// compiled into cubin by CUDA then linked in.
+    ///
+    /// `name` identifies the PTX in the logs of the linker, it is usually its file name.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if the PTX is invalid, cuda is out of memory, or the PTX
+    /// is of an unsupported version.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` or `ptx` contain nul bytes.
+    pub fn add_ptx(&mut self, name: &str, ptx: impl AsRef<str>) -> Result<(), JitError> {
+        let ptx = CString::new(ptx.as_ref()).expect("PTX given to the Linker contained nul bytes");
+        self.add_data(
+            driver_sys::CUjitInputType::CU_JIT_INPUT_PTX,
+            name,
+            ptx.as_bytes_with_nul(),
+        )
+    }
+
     /// Add some cubin (CUDA binary) to be linked in.
     ///
+    /// `name` identifies the cubin in the logs of the linker.
+    ///
     /// # Returns
     ///
     /// Returns an error if the cubin is invalid or CUDA is out of memory.
-    pub fn add_cubin(&mut self, cubin: impl AsRef<[u8]>) -> CudaResult<()> {
-        let cubin = cubin.as_ref();
-
-        unsafe {
-            driver_sys::cuLinkAddData(
-                self.raw,
-                driver_sys::CUjitInputType::CU_JIT_INPUT_CUBIN,
-                // cuda_sys wants *mut but from the API docs we know we retain ownership so
-                // this cast is sound.
-                cubin.as_ptr() as *mut _,
-                cubin.len(),
-                UNNAMED.as_ptr().cast(),
-                0,
-                std::ptr::null_mut(),
-                std::ptr::null_mut(),
-            )
-            .to_result()
-        }
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` contains nul bytes.
+    pub fn add_cubin(&mut self, name: &str, cubin: impl AsRef<[u8]>) -> Result<(), JitError> {
+        self.add_data(
+            driver_sys::CUjitInputType::CU_JIT_INPUT_CUBIN,
+            name,
+            cubin.as_ref(),
+        )
     }
 
     /// Add a fatbin (Fat Binary) to be linked in.
     ///
+    /// `name` identifies the fatbin in the logs of the linker.
+    ///
     /// # Returns
     ///
     /// Returns an error if the fatbin is invalid or CUDA is out of memory.
-    pub fn add_fatbin(&mut self, fatbin: impl AsRef<[u8]>) -> CudaResult<()> {
-        let fatbin = fatbin.as_ref();
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` contains nul bytes.
+    pub fn add_fatbin(&mut self, name: &str, fatbin: impl AsRef<[u8]>) -> Result<(), JitError> {
+        self.add_data(
+            driver_sys::CUjitInputType::CU_JIT_INPUT_FATBINARY,
+            name,
+            fatbin.as_ref(),
+        )
+    }
+
+    /// Add a library of cubins, such as `libcudadevrt.a`, to be linked in. Only the objects of
+    /// the library which define symbols used by the other inputs are linked.
+    ///
+    /// **Do note** that the driver linker cannot link NVVM IR, so `libdevice.*.bc` must be linked
+    /// when generating the PTX rather than here.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if the file cannot be read, is not a library of cubins, or CUDA is out of
+    /// memory.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the path contains nul bytes.
+    pub fn add_library(&mut self, path: impl AsRef<Path>) -> Result<(), JitError> {
+        let path = CString::new(path_to_bytes(path))
+            .expect("path given to Linker::add_library contained nul bytes");
 
         unsafe {
-            driver_sys::cuLinkAddData(
+            driver_sys::cuLinkAddFile(
                 self.raw,
-                driver_sys::CUjitInputType::CU_JIT_INPUT_FATBINARY,
-                // cuda_sys wants *mut but from the API docs we know we retain ownership so
-                // this cast is sound.
-                fatbin.as_ptr() as *mut _,
-                fatbin.len(),
-                UNNAMED.as_ptr().cast(),
+                driver_sys::CUjitInputType::CU_JIT_INPUT_LIBRARY,
+                path.as_ptr(),
                 0,
-                std::ptr::null_mut(),
-                std::ptr::null_mut(),
+                null_mut(),
+                null_mut(),
             )
             .to_result()
+            .map_err(|error| self.jit_error(error))
         }
     }
 
-    /// Runs the linker to generate the final cubin bytes. Also returns a duration
-    /// for how long it took to run the linker.
-    pub fn complete(self) -> CudaResult<Vec<u8>> {
+    /// Runs the linker to generate the final cubin bytes, which can be loaded with
+    /// [`Module::from_cubin`](crate::module::Module::from_cubin).
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if a symbol is undefined or defined multiple times, or if CUDA is out of
+    /// memory.
+    pub fn complete(self) -> Result<Vec<u8>, JitError> {
         let mut cubin = MaybeUninit::uninit();
         let mut size = MaybeUninit::uninit();
 
         unsafe {
             driver_sys::cuLinkComplete(self.raw, cubin.as_mut_ptr(), size.as_mut_ptr())
-                .to_result()?;
+                .to_result()
+                .map_err(|error| self.jit_error(error))?;
             // docs say that CULinkState owns the data, so clone it out before we destroy ourselves.
             let cubin = cubin.assume_init() as *const u8;
             let size = size.assume_init();
@@ -142,3 +274,84 @@ impl Drop for Linker {
         };
     }
 }
+
+#[cfg(test)]
+mod test_link {
+    use super::*;
+    use crate::launch;
+    use crate::memory::DeviceBox;
+    use crate::module::Module;
+    use crate::stream::{Stream, StreamFlags};
+
+    // calls `twice`, which is defined by `TWICE_PTX`.
+    const CALLER_PTX: &str = "
+.version 7.0
+.target sm_50
+.address_size 64
+
+.extern .func (.param .b32 ret) twice(.param .b32 x);
+
+.visible .entry call_twice(.param .u64 out)
+{
+    .reg .b32 %r<3>;
+    .reg .b64 %rd<3>;
+
+    ld.param.u64 %rd1, [out];
+    cvta.to.global.u64 %rd2, %rd1;
+    mov.b32 %r1, 21;
+    {
+        .param .b32 param0;
+        .param .b32 retval0;
+        st.param.b32 [param0], %r1;
+        call.uni (retval0), twice, (param0);
+        ld.param.b32 %r2, [retval0];
+    }
+    st.global.u32 [%rd2], %r2;
+    ret;
+}
+";
+
+    const TWICE_PTX: &str = "
+.version 7.0
+.target sm_50
+.address_size 64
+
+.visible .func (.param .b32 ret) twice(.param .b32 x)
+{
+    .reg .b32 %r<3>;
+
+    ld.param.b32 %r1, [x];
+    add.s32 %r2, %r1, %r1;
+    st.param.b32 [ret], %r2;
+    ret;
+}
+";
+
+    #[test]
+    fn test_link_ptx() {
+        let _context = crate::quick_init().unwrap();
+        let mut linker = Linker::new().unwrap();
+        linker.add_ptx("caller.ptx", CALLER_PTX).unwrap();
+        linker.add_ptx("twice.ptx", TWICE_PTX).unwrap();
+        let cubin = linker.complete().unwrap();
+
+        let module = Module::from_cubin(cubin, &[]).unwrap();
+        let call_twice = module.get_function("call_twice").unwrap();
+        let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+        let out = DeviceBox::new(&0u32).unwrap();
+        unsafe {
+            launch!(call_twice<<<1, 1, 0, stream>>>(out.as_device_ptr())).unwrap();
+        }
+        stream.synchronize().unwrap();
+        assert_eq!(out.as_host_value().unwrap(), 42);
+    }
+
+    #[test]
+    fn test_link_undefined_symbol() {
+        let _context = crate::quick_init().unwrap();
+        let mut linker = Linker::new().unwrap();
+        linker.add_ptx("caller.ptx", CALLER_PTX).unwrap();
+        let error = linker.complete().unwrap_err();
+        assert!(error.error_log.contains("twice"));
+    }
+}
@@ -0,0 +1,98 @@
+//! Pluggable allocation strategies for device buffers.
+
+use crate::error::{CudaError, CudaResult};
+use crate::memory::malloc::{cuda_free, cuda_malloc};
+use crate::memory::DevicePointer;
+
+/// The alignment of every allocation returned by `cuMemAlloc`.
+pub(crate) const DRIVER_ALIGNMENT: usize = 256;
+
+/// A source of device memory for [`DeviceBuffer`](crate::memory::DeviceBuffer).
+///
+/// This mirrors the allocator API of `std`: a buffer created with
+/// [`DeviceBuffer::uninitialized_in`](crate::memory::DeviceBuffer::uninitialized_in) or
+/// [`DeviceBuffer::from_slice_in`](crate::memory::DeviceBuffer::from_slice_in) keeps its
+/// allocator and returns its memory to it when dropped. Buffers that are not given an allocator
+/// use [`DriverAllocator`].
+///
+/// # Safety
+///
+/// [`alloc`](Allocator::alloc) must return a pointer to at least `size` bytes of device memory
+/// aligned to `align`, which stays valid until it is passed to [`dealloc`](Allocator::dealloc).
+///
+/// # Examples
+///
+/// ```
+/// # let _context = cust::quick_init().unwrap();
+/// use cust::error::CudaResult;
+/// use cust::memory::*;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// // Counts the bytes currently allocated through it.
+/// #[derive(Default)]
+/// struct CountingAllocator(AtomicUsize);
+///
+/// unsafe impl Allocator for CountingAllocator {
+///     fn alloc(&self, size: usize, align: usize) -> CudaResult<DevicePointer<u8>> {
+///         let ptr = DriverAllocator.alloc(size, align)?;
+///         self.0.fetch_add(size, Ordering::Relaxed);
+///         Ok(ptr)
+///     }
+///
+///     unsafe fn dealloc(&self, ptr: DevicePointer<u8>, size: usize, align: usize) -> CudaResult<()> {
+///         self.0.fetch_sub(size, Ordering::Relaxed);
+///         DriverAllocator.dealloc(ptr, size, align)
+///     }
+/// }
+///
+/// let allocator = CountingAllocator::default();
+/// let buffer = DeviceBuffer::from_slice_in(&[1u32, 2, 3], &allocator).unwrap();
+/// assert_eq!(allocator.0.load(Ordering::Relaxed), 12);
+/// drop(buffer);
+/// assert_eq!(allocator.0.load(Ordering::Relaxed), 0);
+/// ```
+pub unsafe trait Allocator {
+    /// Allocates `size` bytes of device memory aligned to `align`. `size` is never zero.
+    fn alloc(&self, size: usize, align: usize) -> CudaResult<DevicePointer<u8>>;
+
+    /// Returns an allocation made by [`alloc`](Allocator::alloc) to this allocator.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by `alloc` on this allocator with the same `size` and
+    /// `align`, and must not be used afterward.
+    unsafe fn dealloc(&self, ptr: DevicePointer<u8>, size: usize, align: usize) -> CudaResult<()>;
+}
+
+unsafe impl<A: Allocator + ?Sized> Allocator for &A {
+    fn alloc(&self, size: usize, align: usize) -> CudaResult<DevicePointer<u8>> {
+        (**self).alloc(size, align)
+    }
+
+    unsafe fn dealloc(&self, ptr: DevicePointer<u8>, size: usize, align: usize) -> CudaResult<()> {
+        (**self).dealloc(ptr, size, align)
+    }
+}
+
+/// The default [`Allocator`], which allocates directly from the driver with `cuMemAlloc` and
+/// frees with `cuMemFree`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct DriverAllocator;
+
+unsafe impl Allocator for DriverAllocator {
+    fn alloc(&self, size: usize, align: usize) -> CudaResult<DevicePointer<u8>> {
+        if align > DRIVER_ALIGNMENT {
+            return Err(CudaError::InvalidMemoryAllocation);
+        }
+        unsafe { cuda_malloc(size) }
+    }
+
+    unsafe fn dealloc(
+        &self,
+        ptr: DevicePointer<u8>,
+        _size: usize,
+        _align: usize,
+    ) -> CudaResult<()> {
+        cuda_free(ptr)
+    }
+}
@@ -0,0 +1,221 @@
+//! Asynchronous copies which pick the direction of the copy from the types of their arguments.
+
+use std::os::raw::c_void;
+
+use cust_raw::driver_sys;
+
+use crate::error::{CudaError, CudaResult, ToResult};
+use crate::memory::{Allocator, DeviceBuffer, DeviceCopy, DeviceSlice, LockedBuffer};
+use crate::stream::Stream;
+use crate::trace::trace_span;
+
+/// Memory which can be the source or destination of [`copy_async`].
+///
+/// This is implemented for host memory (slices, `Vec`s and [`LockedBuffer`]s) and for device
+/// memory ([`DeviceSlice`]s and [`DeviceBuffer`]s).
+pub trait CopyEndpoint<T: DeviceCopy>: private::Endpoint<T> {}
+
+impl<T: DeviceCopy, E: private::Endpoint<T> + ?Sized> CopyEndpoint<T> for E {}
+
+mod private {
+    use cust_raw::driver_sys;
+
+    pub enum Location<P> {
+        Host(P),
+        Device(driver_sys::CUdeviceptr),
+    }
+
+    pub trait Endpoint<T> {
+        fn source(&self) -> Location<*const T>;
+
+        fn destination(&mut self) -> Location<*mut T>;
+
+        fn len(&self) -> usize;
+    }
+}
+
+use private::{Endpoint, Location};
+
+impl<T: DeviceCopy> Endpoint<T> for [T] {
+    fn source(&self) -> Location<*const T> {
+        Location::Host(self.as_ptr())
+    }
+
+    fn destination(&mut self) -> Location<*mut T> {
+        Location::Host(self.as_mut_ptr())
+    }
+
+    fn len(&self) -> usize {
+        <[T]>::len(self)
+    }
+}
+
+impl<T: DeviceCopy> Endpoint<T> for Vec<T> {
+    fn source(&self) -> Location<*const T> {
+        Location::Host(self.as_ptr())
+    }
+
+    fn destination(&mut self) -> Location<*mut T> {
+        Location::Host(self.as_mut_ptr())
+    }
+
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+}
+
+impl<T: DeviceCopy> Endpoint<T> for LockedBuffer<T> {
+    fn source(&self) -> Location<*const T> {
+        Location::Host(self.as_slice().as_ptr())
+    }
+
+    fn destination(&mut self) -> Location<*mut T> {
+        Location::Host(self.as_mut_slice().as_mut_ptr())
+    }
+
+    fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+}
+
+impl<T: DeviceCopy> Endpoint<T> for DeviceSlice<T> {
+    fn source(&self) -> Location<*const T> {
+        Location::Device(self.as_device_ptr().as_raw())
+    }
+
+    fn destination(&mut self) -> Location<*mut T> {
+        Location::Device(self.as_device_ptr().as_raw())
+    }
+
+    fn len(&self) -> usize {
+        DeviceSlice::len(self)
+    }
+}
+
+impl<T: DeviceCopy, A: Allocator> Endpoint<T> for DeviceBuffer<T, A> {
+    fn source(&self) -> Location<*const T> {
+        Location::Device(self.as_device_ptr().as_raw())
+    }
+
+    fn destination(&mut self) -> Location<*mut T> {
+        Location::Device(self.as_device_ptr().as_raw())
+    }
+
+    fn len(&self) -> usize {
+        DeviceSlice::len(self)
+    }
+}
+
+/// Asynchronously copies `src` into `dst` on `stream`, picking `cuMemcpyHtoDAsync`,
+/// `cuMemcpyDtoHAsync` or `cuMemcpyDtoDAsync` depending on where `dst` and `src` live.
+///
+/// Host memory should be page-locked (a [`LockedBuffer`]) for the copy to be truly asynchronous,
+/// copies involving pageable host memory may block until they have completed.
+///
+/// # Panics
+///
+/// Panics if `dst` and `src` have different lengths.
+///
+/// # Errors
+///
+/// Returns [`CudaError::InvalidValue`] if both `dst` and `src` are host memory, use
+/// [`slice::copy_from_slice`] for those instead.
+///
+/// # Safety
+///
+/// For why this function is unsafe, see
+/// [AsyncCopyDestination](crate::memory::AsyncCopyDestination).
+///
+/// # Examples
+///
+/// ```
+/// # let _context = cust::quick_init().unwrap();
+/// use cust::memory::*;
+/// use cust::stream::{Stream, StreamFlags};
+///
+/// let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+/// let src = LockedBuffer::from_slice(&[1u32, 2, 3]).unwrap();
+/// let mut a = DeviceBuffer::from_slice(&[0u32; 3]).unwrap();
+/// let mut b = DeviceBuffer::from_slice(&[0u32; 3]).unwrap();
+/// let mut dst = LockedBuffer::new(&0u32, 3).unwrap();
+/// unsafe {
+///     copy_async(&mut a, &src, &stream).unwrap();
+///     copy_async(&mut b, &a, &stream).unwrap();
+///     copy_async(&mut dst, &b, &stream).unwrap();
+/// }
+/// stream.synchronize().unwrap();
+/// assert_eq!(dst.as_slice(), [1, 2, 3]);
+/// ```
+pub unsafe fn copy_async<T, D, S>(dst: &mut D, src: &S, stream: &Stream) -> CudaResult<()>
+where
+    T: DeviceCopy,
+    D: CopyEndpoint<T> + ?Sized,
+    S: CopyEndpoint<T> + ?Sized,
+{
+    assert!(
+        dst.len() == src.len(),
+        "destination and source slices have different lengths"
+    );
+    let size = src.len() * std::mem::size_of::<T>();
+
+    match (dst.destination(), src.source()) {
+        (Location::Host(_), Location::Host(_)) => Err(CudaError::InvalidValue),
+        _ if size == 0 => Ok(()),
+        (Location::Device(dst), Location::Host(src)) => {
+            trace_span!("cuda.memcpy", kind = "htod", bytes = size, is_async = true);
+            driver_sys::cuMemcpyHtoDAsync(dst, src as *const c_void, size, stream.as_inner())
+                .to_async_result()
+        }
+        (Location::Host(dst), Location::Device(src)) => {
+            trace_span!("cuda.memcpy", kind = "dtoh", bytes = size, is_async = true);
+            driver_sys::cuMemcpyDtoHAsync(dst as *mut c_void, src, size, stream.as_inner())
+                .to_async_result()
+        }
+        (Location::Device(dst), Location::Device(src)) => {
+            trace_span!("cuda.memcpy", kind = "dtod", bytes = size, is_async = true);
+            driver_sys::cuMemcpyDtoDAsync(dst, src, size, stream.as_inner()).to_async_result()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_copy {
+    use super::*;
+    use crate::stream::StreamFlags;
+
+    #[test]
+    fn test_copy_async_round_trip() {
+        let _context = crate::quick_init().unwrap();
+        let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+        let src = vec![1u64, 2, 3, 4];
+        let mut buf = DeviceBuffer::from_slice(&[0u64; 4]).unwrap();
+        let mut dst = [0u64; 4];
+        unsafe {
+            copy_async(&mut buf, &src, &stream).unwrap();
+            copy_async(&mut dst[..], &buf, &stream).unwrap();
+        }
+        stream.synchronize().unwrap();
+        assert_eq!(dst, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_copy_async_host_to_host() {
+        let _context = crate::quick_init().unwrap();
+        let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+        let mut dst = [0u8; 2];
+        let result = unsafe { copy_async(&mut dst[..], &[1u8, 2][..], &stream) };
+        assert_eq!(result, Err(CudaError::InvalidValue));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_copy_async_wrong_size() {
+        let _context = crate::quick_init().unwrap();
+        let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+        let buf = DeviceBuffer::from_slice(&[0u64; 4]).unwrap();
+        let mut dst = [0u64; 3];
+        unsafe {
+            let _ = copy_async(&mut dst[..], &buf, &stream);
+        }
+    }
+}
@@ -479,7 +479,7 @@ impl<T: DeviceCopy> AsyncCopyDestination<T> for DeviceBox<T> {
                 size,
                 stream.as_inner(),
             )
-            .to_result()?
+            .to_async_result()?
         }
         Ok(())
     }
@@ -493,7 +493,7 @@ impl<T: DeviceCopy> AsyncCopyDestination<T> for DeviceBox<T> {
                 size,
                 stream.as_inner(),
             )
-            .to_result()?
+            .to_async_result()?
         }
         Ok(())
     }
@@ -508,7 +508,7 @@ impl<T: DeviceCopy> AsyncCopyDestination<DeviceBox<T>> for DeviceBox<T> {
                 size,
                 stream.as_inner(),
             )
-            .to_result()?
+            .to_async_result()?
         }
         Ok(())
     }
@@ -522,7 +522,7 @@ impl<T: DeviceCopy> AsyncCopyDestination<DeviceBox<T>> for DeviceBox<T> {
                 size,
                 stream.as_inner(),
             )
-            .to_result()?
+            .to_async_result()?
         }
         Ok(())
     }
@@ -1,5 +1,7 @@
+use std::fmt::{self, Debug, Formatter};
 use std::mem::{self, align_of, size_of, transmute, ManuallyDrop};
 use std::ops::{Deref, DerefMut};
+use std::ptr;
 
 #[cfg(feature = "bytemuck")]
 pub use bytemuck;
@@ -7,23 +9,30 @@ pub use bytemuck;
 use bytemuck::{Pod, PodCastError, Zeroable};
 use cust_raw::driver_sys;
 
-use crate::error::{CudaResult, DropResult, ToResult};
+use crate::error::{CudaError, CudaResult, DropResult, ToResult};
 use crate::memory::device::{AsyncCopyDestination, CopyDestination, DeviceSlice};
 use crate::memory::malloc::{cuda_free, cuda_malloc};
-use crate::memory::{cuda_free_async, DevicePointer};
-use crate::memory::{cuda_malloc_async, DeviceCopy};
+use crate::memory::{cuda_free_async, cuda_malloc_default_pool_async, DevicePointer};
+use crate::memory::{cuda_malloc_async, Allocator, DeviceCopy, DriverAllocator};
+use crate::memory::{IpcAllocator, IpcMemHandle};
 use crate::stream::Stream;
 
 /// Fixed-size device-side buffer. Provides basic access to device memory.
-#[derive(Debug)]
+///
+/// The [`Debug`] implementation only prints the metadata of the buffer, its contents are never
+/// copied back to the host. Use [`DeviceSlice::dump_head`] to inspect the contents.
+///
+/// Buffers allocate from the driver by default. A buffer can instead be backed by any
+/// [`Allocator`] with [`DeviceBuffer::uninitialized_in`] or [`DeviceBuffer::from_slice_in`].
 #[repr(C)]
-pub struct DeviceBuffer<T: DeviceCopy> {
+pub struct DeviceBuffer<T: DeviceCopy, A: Allocator = DriverAllocator> {
     buf: DevicePointer<T>,
     len: usize,
+    alloc: A,
 }
 
-unsafe impl<T: Send + DeviceCopy> Send for DeviceBuffer<T> {}
-unsafe impl<T: Sync + DeviceCopy> Sync for DeviceBuffer<T> {}
+unsafe impl<T: Send + DeviceCopy, A: Allocator + Send> Send for DeviceBuffer<T, A> {}
+unsafe impl<T: Sync + DeviceCopy, A: Allocator + Sync> Sync for DeviceBuffer<T, A> {}
 
 impl<T: DeviceCopy> DeviceBuffer<T> {
     /// Allocate a new device buffer large enough to hold `size` `T`'s, but without
@@ -57,6 +66,7 @@ impl<T: DeviceCopy> DeviceBuffer<T> {
         Ok(DeviceBuffer {
             buf: ptr,
             len: size,
+            alloc: DriverAllocator,
         })
     }
 
@@ -81,6 +91,59 @@ impl<T: DeviceCopy> DeviceBuffer<T> {
         Ok(DeviceBuffer {
             buf: ptr,
             len: size,
+            alloc: DriverAllocator,
+        })
+    }
+
+    /// Allocates device memory asynchronously on a stream from the current device's default
+    /// memory pool, without initializing it.
+    ///
+    /// This is the simplest way to use stream-ordered allocation: repeatedly allocating and
+    /// freeing buffers of similar sizes with `alloc_async` and [`DeviceBuffer::drop_async`]
+    /// reuses memory from the pool instead of going through the driver every time.
+    ///
+    /// [`DeviceBuffer::uninitialized_async`] allocates from whichever pool is currently set for
+    /// the device, which is the default pool unless it has been changed. `alloc_async` always
+    /// uses the default pool returned by `cuDeviceGetDefaultMemPool`.
+    ///
+    /// This doesn't actually allocate if `T` is zero sized.
+    ///
+    /// # Safety
+    ///
+    /// The same as [`DeviceBuffer::uninitialized_async`]: the memory must be initialized before it
+    /// is read, and it must not be used until the allocation has taken place on the stream.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let _context = cust::quick_init().unwrap();
+    /// use cust::{memory::*, stream::*};
+    /// let stream = Stream::new(StreamFlags::DEFAULT, None)?;
+    /// // copies are only asynchronous from and to page-locked memory.
+    /// let values = LockedBuffer::from_slice(&[1u32, 2, 3, 4])?;
+    /// let mut host_vals = LockedBuffer::new(&0u32, 4)?;
+    /// unsafe {
+    ///     let mut buffer = DeviceBuffer::<u32>::alloc_async(4, &stream)?;
+    ///     buffer.async_copy_from(&values, &stream)?;
+    ///     buffer.async_copy_to(&mut host_vals, &stream)?;
+    ///     buffer.drop_async(&stream)?;
+    /// }
+    /// stream.synchronize()?;
+    /// assert_eq!(host_vals.as_slice(), [1, 2, 3, 4]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub unsafe fn alloc_async(len: usize, stream: &Stream) -> CudaResult<Self> {
+        let ptr = if len > 0 && size_of::<T>() > 0 {
+            cuda_malloc_default_pool_async(stream, len)?
+        } else {
+            DevicePointer::null()
+        };
+        Ok(DeviceBuffer {
+            buf: ptr,
+            len,
+            alloc: DriverAllocator,
         })
     }
 
@@ -166,9 +229,38 @@ impl<T: DeviceCopy> DeviceBuffer<T> {
         DeviceBuffer {
             buf: ptr,
             len: capacity,
+            alloc: DriverAllocator,
         }
     }
 
+    /// Decomposes a `DeviceBuffer<T>` into its raw components, the device pointer and the
+    /// number of elements it holds.
+    ///
+    /// After calling this function, the caller is responsible for the memory previously
+    /// managed by the `DeviceBuffer`. The only way to do this is to convert the pointer and
+    /// length back into a `DeviceBuffer` with [`from_raw_parts`](DeviceBuffer::from_raw_parts),
+    /// or to free the pointer with [`cuda_free`](crate::memory::cuda_free), for example after
+    /// handing it to a C library which takes ownership of it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let _context = cust::quick_init().unwrap();
+    /// use cust::memory::*;
+    ///
+    /// let buffer = DeviceBuffer::from_slice(&[1u64, 2, 3]).unwrap();
+    /// let (ptr, len) = buffer.into_raw_parts();
+    ///
+    /// // ... pass `ptr` and `len` through an FFI boundary
+    ///
+    /// let buffer = unsafe { DeviceBuffer::from_raw_parts(ptr, len) };
+    /// assert_eq!(buffer.as_host_vec().unwrap(), [1, 2, 3]);
+    /// ```
+    pub fn into_raw_parts(self) -> (DevicePointer<T>, usize) {
+        let me = ManuallyDrop::new(self);
+        (me.buf, me.len)
+    }
+
     /// Destroy a `DeviceBuffer`, returning an error.
     ///
     /// Deallocating device memory can return errors from previous asynchronous work. This function
@@ -333,6 +425,7 @@ impl<A: DeviceCopy + Pod> DeviceBuffer<A> {
             let ret = Ok(DeviceBuffer {
                 buf: self.buf.cast(),
                 len: new_len,
+                alloc: DriverAllocator,
             });
             std::mem::forget(self);
             ret
@@ -397,14 +490,292 @@ impl<T: DeviceCopy> DeviceBuffer<T> {
         uninit.async_copy_from(slice, stream)?;
         Ok(uninit)
     }
+}
+
+impl<T: DeviceCopy, A: Allocator> DeviceBuffer<T, A> {
+    /// Allocate a new device buffer large enough to hold `len` `T`'s from `alloc`, but without
+    /// initializing the contents. The memory is returned to `alloc` when the buffer is dropped.
+    ///
+    /// This doesn't actually allocate if `len` is zero or `T` is zero sized.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error from the allocator if the allocation fails, or InvalidMemoryAllocation
+    /// if the size in bytes overflows usize.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the contents of the buffer are initialized before reading from
+    /// the buffer.
+    pub unsafe fn uninitialized_in(len: usize, alloc: A) -> CudaResult<Self> {
+        let size = len
+            .checked_mul(size_of::<T>())
+            .ok_or(CudaError::InvalidMemoryAllocation)?;
+        let ptr = if size > 0 {
+            alloc.alloc(size, align_of::<T>())?.cast()
+        } else {
+            DevicePointer::null()
+        };
+        Ok(DeviceBuffer {
+            buf: ptr,
+            len,
+            alloc,
+        })
+    }
+
+    /// Allocate a new device buffer from `alloc` of the same size as `slice`, initialized with a
+    /// clone of the data in `slice`.
+    ///
+    /// # Errors
+    ///
+    /// If the allocation or the copy fails, returns the error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let _context = cust::quick_init().unwrap();
+    /// use cust::memory::*;
+    /// let buffer = DeviceBuffer::from_slice_in(&[0u64; 5], DriverAllocator).unwrap();
+    /// ```
+    pub fn from_slice_in(slice: &[T], alloc: A) -> CudaResult<Self> {
+        unsafe {
+            let mut uninit = DeviceBuffer::uninitialized_in(slice.len(), alloc)?;
+            uninit.copy_from(slice)?;
+            Ok(uninit)
+        }
+    }
+
+    /// Returns the allocator backing this buffer.
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    /// Exports a handle to this buffer which another process can open with
+    /// [`DeviceBuffer::from_ipc_handle`].
+    ///
+    /// The buffer stays owned by this process, it must outlive every use of the handle by other
+    /// processes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CudaError::InvalidValue`] if the buffer is empty, and the error from CUDA if the
+    /// memory cannot be shared, for instance because it was allocated from a memory pool or by a
+    /// custom allocator which suballocates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let _context = cust::quick_init().unwrap();
+    /// use cust::memory::*;
+    /// let buffer = DeviceBuffer::from_slice(&[1u32, 2, 3]).unwrap();
+    /// let handle = buffer.export_ipc_handle().unwrap();
+    /// // send `handle.as_bytes()` to another process.
+    /// ```
+    pub fn export_ipc_handle(&self) -> CudaResult<IpcMemHandle> {
+        if self.buf.is_null() {
+            return Err(CudaError::InvalidValue);
+        }
+
+        let mut raw = mem::MaybeUninit::uninit();
+        unsafe {
+            driver_sys::cuIpcGetMemHandle(raw.as_mut_ptr(), self.buf.as_raw()).to_result()?;
+            Ok(IpcMemHandle::from_raw(raw.assume_init()))
+        }
+    }
+
+    /// Allocates a new buffer and queues a device-to-device copy of this buffer into it on
+    /// `stream`.
+    ///
+    /// This is deliberately not [`Clone`], so the cost of the allocation and copy stays visible.
+    /// The new buffer is allocated from the driver whichever allocator backs this one.
+    ///
+    /// # Safety
+    ///
+    /// The copy is asynchronous: this buffer must not be modified or freed, and the new buffer must
+    /// not be used, until `stream` is synchronized or an event recorded after this call has
+    /// completed. See [AsyncCopyDestination](trait.AsyncCopyDestination.html) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let _context = cust::quick_init().unwrap();
+    /// use cust::memory::*;
+    /// use cust::stream::{Stream, StreamFlags};
+    ///
+    /// let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+    /// let buffer = DeviceBuffer::from_slice(&[1u32, 2, 3]).unwrap();
+    /// // snapshot the buffer before modifying it in place.
+    /// let snapshot = unsafe { buffer.clone_on(&stream).unwrap() };
+    /// stream.synchronize().unwrap();
+    /// assert_eq!(snapshot.as_host_vec().unwrap(), [1, 2, 3]);
+    /// ```
+    pub unsafe fn clone_on(&self, stream: &Stream) -> CudaResult<DeviceBuffer<T>> {
+        let mut clone = DeviceBuffer::uninitialized(self.len)?;
+        clone.async_copy_from(self.as_slice(), stream)?;
+        Ok(clone)
+    }
+
+    /// Decomposes the buffer into its pointer, length and allocator without freeing it.
+    pub(crate) fn into_raw_parts_in(self) -> (DevicePointer<T>, usize, A) {
+        let me = ManuallyDrop::new(self);
+        // SAFETY: `me` is never dropped, so the allocator is moved out exactly once.
+        (me.buf, me.len, unsafe { ptr::read(&me.alloc) })
+    }
 
     /// Explicitly creates a [`DeviceSlice`] from this buffer.
     pub fn as_slice(&self) -> &DeviceSlice<T> {
         self
     }
+
+    /// Returns the ordinal of the device the buffer was allocated on, or `None` if the buffer
+    /// is empty or the driver could not be queried.
+    fn device_ordinal(&self) -> Option<i32> {
+        if self.buf.is_null() {
+            return None;
+        }
+        let mut ordinal = 0i32;
+        unsafe {
+            driver_sys::cuPointerGetAttribute(
+                &mut ordinal as *mut i32 as *mut std::ffi::c_void,
+                driver_sys::CUpointer_attribute::CU_POINTER_ATTRIBUTE_DEVICE_ORDINAL,
+                self.buf.as_raw(),
+            )
+            .to_result()
+            .ok()?;
+        }
+        Some(ordinal)
+    }
 }
 
-impl<T: DeviceCopy> Deref for DeviceBuffer<T> {
+impl<T: DeviceCopy> DeviceBuffer<T, IpcAllocator> {
+    /// Opens a buffer of `len` `T`'s exported by another process with
+    /// [`DeviceBuffer::export_ipc_handle`].
+    ///
+    /// Dropping the returned buffer closes the handle, the memory itself is freed by the
+    /// exporting process. Handles cannot be opened by the process which exported them.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error from CUDA if the handle cannot be opened.
+    ///
+    /// # Safety
+    ///
+    /// The exported buffer must hold at least `len` valid `T`'s, and must not be freed by the
+    /// exporting process until the returned buffer has been dropped. Both processes access the
+    /// same memory, so the caller must synchronize their accesses to it.
+    pub unsafe fn from_ipc_handle(handle: IpcMemHandle, len: usize) -> CudaResult<Self> {
+        let mut ptr = 0;
+        driver_sys::cuIpcOpenMemHandle(
+            &mut ptr,
+            handle.to_raw(),
+            driver_sys::CUipcMem_flags_enum::CU_IPC_MEM_LAZY_ENABLE_PEER_ACCESS as u32,
+        )
+        .to_result()?;
+
+        Ok(DeviceBuffer {
+            buf: DevicePointer::from_raw(ptr),
+            len,
+            alloc: IpcAllocator,
+        })
+    }
+}
+
+/// The size of the chunks [`DeviceBuffer::from_file`] copies at a time.
+#[cfg(feature = "memmap2")]
+const FILE_CHUNK_SIZE: usize = 64 * 1024 * 1024;
+
+#[cfg(feature = "memmap2")]
+impl DeviceBuffer<u8> {
+    /// Allocates a new device buffer holding the contents of the file at `path`.
+    ///
+    /// The file is memory-mapped and copied to the device in chunks of 64 MiB, so it is never read
+    /// into a host allocation as a whole. This makes it suitable for loading large files such as
+    /// model weights. Each chunk is page-locked with `cuMemHostRegister` while it is being copied
+    /// so that the transfer can use DMA. If a chunk cannot be registered, for example because the
+    /// driver does not support registering read-only memory, it is copied from pageable memory
+    /// instead.
+    ///
+    /// The copies are enqueued on `stream`, but this function waits for them to complete because
+    /// the file is unmapped before returning, so the returned buffer can be used right away. Use
+    /// [`DeviceBuffer::try_cast`] to reinterpret the bytes as another type.
+    ///
+    /// This requires the `memmap2` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened or mapped. CUDA errors from the allocation or
+    /// the copies are returned as an [`io::Error`](std::io::Error) wrapping the [`CudaError`](crate::error::CudaError).
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated, by this or any other process, while it is being
+    /// loaded. Otherwise the contents of the buffer are unspecified and the process may be killed
+    /// when accessing a truncated page.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let _context = cust::quick_init().unwrap();
+    /// use cust::{memory::*, stream::*};
+    /// let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+    /// let weights = unsafe { DeviceBuffer::from_file("weights.bin", &stream)? };
+    /// let weights = weights.cast::<f32>();
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "memmap2")))]
+    pub unsafe fn from_file(
+        path: impl AsRef<std::path::Path>,
+        stream: &Stream,
+    ) -> std::io::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        let map = memmap2::Mmap::map(&file)?;
+
+        let buffer = DeviceBuffer::uninitialized(map.len()).map_err(std::io::Error::other)?;
+
+        let mut offset = 0;
+        while offset < map.len() {
+            let chunk_len = FILE_CHUNK_SIZE.min(map.len() - offset);
+            let src = map.as_ptr().add(offset) as *mut std::ffi::c_void;
+            let dest = buffer.as_device_ptr().as_raw() + offset as driver_sys::CUdeviceptr;
+
+            let registered = driver_sys::cuMemHostRegister(
+                src,
+                chunk_len,
+                driver_sys::CU_MEMHOSTREGISTER_READ_ONLY,
+            )
+            .to_result()
+            .is_ok();
+
+            let result = driver_sys::cuMemcpyHtoDAsync(dest, src, chunk_len, stream.as_inner())
+                .to_result()
+                .and_then(|()| stream.synchronize());
+
+            if registered {
+                driver_sys::cuMemHostUnregister(src);
+            }
+            result.map_err(std::io::Error::other)?;
+
+            offset += chunk_len;
+        }
+
+        Ok(buffer)
+    }
+}
+
+impl<T: DeviceCopy, A: Allocator> Debug for DeviceBuffer<T, A> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("DeviceBuffer")
+            .field("len", &self.len)
+            .field("ptr", &self.buf.as_ptr())
+            .field("device", &self.device_ordinal())
+            .finish()
+    }
+}
+
+impl<T: DeviceCopy, A: Allocator> Deref for DeviceBuffer<T, A> {
     type Target = DeviceSlice<T>;
 
     fn deref(&self) -> &DeviceSlice<T> {
@@ -412,13 +783,13 @@ impl<T: DeviceCopy> Deref for DeviceBuffer<T> {
     }
 }
 
-impl<T: DeviceCopy> DerefMut for DeviceBuffer<T> {
+impl<T: DeviceCopy, A: Allocator> DerefMut for DeviceBuffer<T, A> {
     fn deref_mut(&mut self) -> &mut DeviceSlice<T> {
         unsafe { DeviceSlice::from_raw_parts_mut(self.buf, self.len) }
     }
 }
 
-impl<T: DeviceCopy> Drop for DeviceBuffer<T> {
+impl<T: DeviceCopy, A: Allocator> Drop for DeviceBuffer<T, A> {
     fn drop(&mut self) {
         if self.buf.is_null() {
             return;
@@ -427,7 +798,9 @@ impl<T: DeviceCopy> Drop for DeviceBuffer<T> {
         if self.len > 0 && size_of::<T>() > 0 {
             let ptr = mem::replace(&mut self.buf, DevicePointer::null());
             unsafe {
-                let _ = cuda_free(ptr);
+                let _ = self
+                    .alloc
+                    .dealloc(ptr.cast(), self.len * size_of::<T>(), align_of::<T>());
             }
         }
         self.len = 0;
@@ -451,6 +824,33 @@ mod test_device_buffer {
         drop(buf);
     }
 
+    #[test]
+    fn test_copy_peer_async_same_context() {
+        let context = crate::quick_init().unwrap();
+        let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+        let src = DeviceBuffer::from_slice(&[1u64, 2, 3, 4]).unwrap();
+        let mut dst = DeviceBuffer::from_slice(&[0u64; 4]).unwrap();
+        unsafe {
+            src.copy_peer_async(&context, &mut dst, &context, &stream)
+                .unwrap();
+        }
+        stream.synchronize().unwrap();
+        assert_eq!(dst.as_host_vec().unwrap(), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_raw_parts_round_trip() {
+        let _context = crate::quick_init().unwrap();
+        let buf = DeviceBuffer::from_slice(&[0u64, 1, 2, 3, 4, 5]).unwrap();
+        let ptr = buf.as_device_ptr();
+        let (raw_ptr, len) = buf.into_raw_parts();
+        assert_eq!(raw_ptr, ptr);
+        assert_eq!(len, 6);
+
+        let buf = unsafe { DeviceBuffer::from_raw_parts(raw_ptr, len) };
+        assert_eq!(buf.as_host_vec().unwrap(), [0, 1, 2, 3, 4, 5]);
+    }
+
     #[test]
     fn test_copy_to_from_device() {
         let _context = crate::quick_init().unwrap();
@@ -461,6 +861,95 @@ mod test_device_buffer {
         assert_eq!(start, end);
     }
 
+    #[test]
+    #[cfg(feature = "impl_half")]
+    fn test_half_round_trip() {
+        use half::{bf16, f16};
+
+        let _context = crate::quick_init().unwrap();
+        let values: Vec<f16> = [0.5f32, 1.0, -2.0, 65504.0]
+            .iter()
+            .map(|x| f16::from_f32(*x))
+            .collect();
+        let buf = DeviceBuffer::from_slice(&values).unwrap();
+        assert_eq!(buf.as_host_vec().unwrap(), values);
+
+        let values = [bf16::from_f32(3.0), bf16::from_f32(-0.25)];
+        let buf = DeviceBuffer::from_slice(&values).unwrap();
+        assert_eq!(buf.as_host_vec().unwrap(), values);
+    }
+
+    #[test]
+    fn test_sub_slice() {
+        let _context = crate::quick_init().unwrap();
+        let mut buf = DeviceBuffer::from_slice(&[0u64, 1, 2, 3, 4, 5]).unwrap();
+        let window = &buf[2..5];
+        assert_eq!(window.len(), 3);
+        assert_eq!(
+            window.as_device_ptr().as_raw(),
+            buf.as_device_ptr().as_raw() + 2 * mem::size_of::<u64>() as u64
+        );
+        assert_eq!(window.as_host_vec().unwrap(), [2, 3, 4]);
+
+        assert!(buf.get(4..7).is_none());
+        assert!(buf.get(6).is_none());
+        assert!(buf.get(..=6).is_none());
+        assert_eq!(buf.get(6..).unwrap().len(), 0);
+
+        buf.get_mut(1..=2).unwrap().copy_from(&[10, 20]).unwrap();
+        assert_eq!(buf.as_host_vec().unwrap(), [0, 10, 20, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_alloc_async_default_pool() {
+        let _context = crate::quick_init().unwrap();
+        let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+        let mut end = [0u64; 6];
+        unsafe {
+            for _ in 0..4 {
+                let mut buf = DeviceBuffer::<u64>::alloc_async(6, &stream).unwrap();
+                buf.async_copy_from(&[0, 1, 2, 3, 4, 5], &stream).unwrap();
+                buf.async_copy_to(&mut end, &stream).unwrap();
+                buf.drop_async(&stream).unwrap();
+            }
+        }
+        stream.synchronize().unwrap();
+        assert_eq!(end, [0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_copy_within_async() {
+        let _context = crate::quick_init().unwrap();
+        let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+        let mut buf = DeviceBuffer::from_slice(&[0u32, 1, 2, 3, 4, 5, 6, 7, 8, 9]).unwrap();
+        unsafe {
+            // overlapping, shifting right
+            buf.copy_within_async(0..6, 2, &stream).unwrap();
+        }
+        stream.synchronize().unwrap();
+        assert_eq!(buf.as_host_vec().unwrap(), [0, 1, 0, 1, 2, 3, 4, 5, 8, 9]);
+
+        unsafe {
+            // overlapping, shifting left
+            buf.copy_within_async(2.., 0, &stream).unwrap();
+            // disjoint
+            buf.copy_within_async(..2, 8, &stream).unwrap();
+        }
+        stream.synchronize().unwrap();
+        assert_eq!(buf.as_host_vec().unwrap(), [0, 1, 2, 3, 4, 5, 8, 9, 0, 1]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_copy_within_async_out_of_bounds() {
+        let _context = crate::quick_init().unwrap();
+        let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+        let mut buf = DeviceBuffer::from_slice(&[0u32, 1, 2, 3]).unwrap();
+        unsafe {
+            let _ = buf.copy_within_async(0..3, 2, &stream);
+        }
+    }
+
     #[test]
     fn test_async_copy_to_from_device() {
         let _context = crate::quick_init().unwrap();
@@ -475,6 +964,28 @@ mod test_device_buffer {
         assert_eq!(start, end);
     }
 
+    #[test]
+    fn test_copy_to_uninit_and_array() {
+        let _context = crate::quick_init().unwrap();
+        let start = [0u64, 1, 2, 3, 4, 5];
+        let buf = DeviceBuffer::from_slice(&start).unwrap();
+        let mut uninit = [std::mem::MaybeUninit::<u64>::uninit(); 6];
+        assert_eq!(buf.copy_to_uninit(&mut uninit).unwrap(), &start);
+        let arr: [u64; 6] = buf.as_host_array().unwrap();
+        assert_eq!(arr, start);
+    }
+
+    #[test]
+    fn test_debug_does_not_copy() {
+        let _context = crate::quick_init().unwrap();
+        let buf = DeviceBuffer::from_slice(&[0u64, 1, 2, 3, 4, 5]).unwrap();
+        let debug = format!("{:?}", buf);
+        assert!(debug.contains("len: 6"));
+        assert!(debug.contains("device: Some(0)"));
+        buf.dump_head(3).unwrap();
+        buf.dump_head(100).unwrap();
+    }
+
     #[test]
     #[should_panic]
     fn test_copy_to_d2h_wrong_size() {
@@ -484,6 +995,30 @@ mod test_device_buffer {
         let _ = buf.copy_to(&mut end);
     }
 
+    #[test]
+    fn test_clone_on() {
+        let _context = crate::quick_init().unwrap();
+        let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+        let mut buf = DeviceBuffer::from_slice(&[0u64, 1, 2, 3]).unwrap();
+        let snapshot = unsafe { buf.clone_on(&stream).unwrap() };
+        stream.synchronize().unwrap();
+        buf.copy_from(&[4u64, 5, 6, 7]).unwrap();
+        assert_eq!(snapshot.as_host_vec().unwrap(), [0, 1, 2, 3]);
+        assert_eq!(buf.as_host_vec().unwrap(), [4, 5, 6, 7]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_copy_to_slice_async_wrong_size() {
+        let _context = crate::quick_init().unwrap();
+        let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+        let buf = DeviceBuffer::from_slice(&[0u64, 1, 2, 3, 4, 5]).unwrap();
+        let mut end = crate::memory::LockedBuffer::new(&0u64, 5).unwrap();
+        unsafe {
+            let _ = buf.copy_to_slice_async(&mut end, &stream);
+        }
+    }
+
     #[test]
     #[should_panic]
     fn test_async_copy_to_d2h_wrong_size() {
@@ -558,4 +1093,93 @@ mod test_device_buffer {
             let _ = buf.async_copy_from(&start, &stream);
         }
     }
+
+    #[test]
+    fn test_pointer_attributes() {
+        let _context = crate::quick_init().unwrap();
+        let buf = DeviceBuffer::from_slice(&[0u64, 1, 2, 3, 4, 5]).unwrap();
+        let attributes = crate::memory::pointer_attributes(buf.as_device_ptr().as_ptr()).unwrap();
+        assert_eq!(
+            attributes.memory_type,
+            Some(crate::memory::MemoryType::Device)
+        );
+        assert!(attributes.is_device());
+        assert!(!attributes.is_managed);
+        assert_eq!(
+            attributes.device_pointer,
+            Some(buf.as_device_ptr().as_raw())
+        );
+
+        let unified = crate::memory::UnifiedBuffer::new(&0u64, 6).unwrap();
+        let attributes = crate::memory::pointer_attributes(unified.as_ptr()).unwrap();
+        assert_eq!(
+            attributes.memory_type,
+            Some(crate::memory::MemoryType::Unified)
+        );
+        assert!(attributes.is_device());
+        assert!(attributes.is_managed);
+
+        let host = vec![0u64, 1, 2, 3, 4, 5];
+        let attributes = crate::memory::pointer_attributes(host.as_ptr()).unwrap();
+        assert_eq!(attributes.memory_type, None);
+        assert!(!attributes.is_device());
+    }
+
+    #[test]
+    fn test_array_and_tuple_elements() {
+        let _context = crate::quick_init().unwrap();
+
+        let arrays = [[0.0f32, 1.0, 2.0, 3.0], [4.0, 5.0, 6.0, 7.0]];
+        let buf = DeviceBuffer::from_slice(&arrays).unwrap();
+        let mut end = [[0.0f32; 4]; 2];
+        buf.copy_to(&mut end).unwrap();
+        assert_eq!(arrays, end);
+
+        let tuples = [(0.5f32, 1u32), (1.5, 2), (2.5, 3)];
+        let buf = DeviceBuffer::from_slice(&tuples).unwrap();
+        assert_eq!(buf.as_host_vec().unwrap(), tuples);
+    }
+
+    #[test]
+    fn test_custom_allocator() {
+        use std::cell::Cell;
+
+        let _context = crate::quick_init().unwrap();
+
+        #[derive(Default)]
+        struct CountingAllocator {
+            allocs: Cell<usize>,
+            deallocs: Cell<usize>,
+        }
+
+        unsafe impl Allocator for CountingAllocator {
+            fn alloc(&self, size: usize, align: usize) -> CudaResult<DevicePointer<u8>> {
+                self.allocs.set(self.allocs.get() + 1);
+                DriverAllocator.alloc(size, align)
+            }
+
+            unsafe fn dealloc(
+                &self,
+                ptr: DevicePointer<u8>,
+                size: usize,
+                align: usize,
+            ) -> CudaResult<()> {
+                self.deallocs.set(self.deallocs.get() + 1);
+                DriverAllocator.dealloc(ptr, size, align)
+            }
+        }
+
+        let alloc = CountingAllocator::default();
+        let buf = DeviceBuffer::from_slice_in(&[1u64, 2, 3], &alloc).unwrap();
+        assert_eq!(buf.as_host_vec().unwrap(), [1, 2, 3]);
+        assert_eq!(alloc.allocs.get(), 1);
+        drop(buf);
+        assert_eq!(alloc.deallocs.get(), 1);
+
+        // empty buffers never reach the allocator.
+        let empty = DeviceBuffer::<u64, _>::from_slice_in(&[], &alloc).unwrap();
+        drop(empty);
+        assert_eq!(alloc.allocs.get(), 1);
+        assert_eq!(alloc.deallocs.get(), 1);
+    }
 }
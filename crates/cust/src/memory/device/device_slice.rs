@@ -1,7 +1,9 @@
 use std::fmt::{self, Debug, Formatter};
 use std::marker::PhantomData;
+use std::mem::MaybeUninit;
 use std::ops::{
-    Index, IndexMut, Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive,
+    Bound, Index, IndexMut, Range, RangeBounds, RangeFrom, RangeFull, RangeInclusive, RangeTo,
+    RangeToInclusive,
 };
 use std::os::raw::c_void;
 use std::ptr::{slice_from_raw_parts, slice_from_raw_parts_mut};
@@ -11,12 +13,15 @@ use std::slice;
 use bytemuck::{Pod, Zeroable};
 use cust_raw::driver_sys;
 
+use crate::context::Context;
 use crate::error::{CudaResult, ToResult};
 use crate::memory::device::AsyncCopyDestination;
 use crate::memory::device::{CopyDestination, DeviceBuffer};
 use crate::memory::DevicePointer;
-use crate::memory::{DeviceCopy, DeviceMemory};
+use crate::memory::{cuda_free_async, cuda_malloc_async};
+use crate::memory::{Allocator, AsPageLocked, DeviceCopy, DeviceMemory};
 use crate::stream::Stream;
+use crate::trace::trace_span;
 
 /// Fixed-size device-side slice.
 #[repr(transparent)]
@@ -71,6 +76,33 @@ impl<T: DeviceCopy> DeviceSlice<T> {
         self.len() == 0
     }
 
+    /// Returns a sub-slice for the given index or range, or `None` if it is out of bounds.
+    ///
+    /// The returned slice borrows `self` and starts `start * size_of::<T>()` bytes after the
+    /// start of `self`. Use indexing, such as `&slice[100..200]`, for a sub-slice that panics
+    /// when out of bounds instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let _context = cust::quick_init().unwrap();
+    /// use cust::memory::*;
+    /// let a = DeviceBuffer::from_slice(&[1, 2, 3, 4]).unwrap();
+    /// assert_eq!(a.get(1..3).unwrap().as_host_vec().unwrap(), [2, 3]);
+    /// assert!(a.get(3..5).is_none());
+    /// ```
+    pub fn get<I: DeviceSliceIndex<T>>(&self, index: I) -> Option<&DeviceSlice<T>> {
+        index.get(self)
+    }
+
+    /// Returns a mutable sub-slice for the given index or range, or `None` if it is out of
+    /// bounds.
+    ///
+    /// See [`get`](DeviceSlice::get) for details.
+    pub fn get_mut<I: DeviceSliceIndex<T>>(&mut self, index: I) -> Option<&mut DeviceSlice<T>> {
+        index.get_mut(self)
+    }
+
     /// Return a raw device-pointer to the slice's buffer.
     ///
     /// The caller must ensure that the slice outlives the pointer this function returns, or else
@@ -91,15 +123,362 @@ impl<T: DeviceCopy> DeviceSlice<T> {
 
     pub fn as_host_vec(&self) -> CudaResult<Vec<T>> {
         let mut vec = Vec::with_capacity(self.len());
-        // SAFETY: The slice points to uninitialized memory, but we only write to it. Once it is
-        // written, all values are valid, so we can (and must) change the length of the vector.
-        unsafe {
-            self.copy_to(slice::from_raw_parts_mut(vec.as_mut_ptr(), self.len()))?;
-            vec.set_len(self.len())
-        }
+        self.copy_to_uninit(&mut vec.spare_capacity_mut()[..self.len()])?;
+        // SAFETY: `copy_to_uninit` initialized the first `self.len()` elements.
+        unsafe { vec.set_len(self.len()) }
         Ok(vec)
     }
 
+    /// Copies at most the first `n` elements of this slice back to the host and prints them to
+    /// stderr, similar to [`dbg!`]. Useful for inspecting large buffers without transferring their
+    /// entire contents.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let _context = cust::quick_init().unwrap();
+    /// use cust::memory::*;
+    /// let buf = DeviceBuffer::from_slice(&[1u32, 2, 3, 4]).unwrap();
+    /// // prints `DeviceSlice[..2] (len 4) = [1, 2]`
+    /// buf.dump_head(2).unwrap();
+    /// ```
+    pub fn dump_head(&self, n: usize) -> CudaResult<()>
+    where
+        T: Debug,
+    {
+        let n = n.min(self.len());
+        let head = self[..n].as_host_vec()?;
+        eprintln!("DeviceSlice[..{}] (len {}) = {:?}", n, self.len(), head);
+        Ok(())
+    }
+
+    /// Copy the contents of this slice into a fixed-size array, without an intermediate heap
+    /// allocation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is not equal to the length of this slice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let _context = cust::quick_init().unwrap();
+    /// use cust::memory::*;
+    /// let buf = DeviceBuffer::from_slice(&[1u32, 2, 3, 4]).unwrap();
+    /// let host: [u32; 4] = buf.as_host_array().unwrap();
+    /// assert_eq!(host, [1, 2, 3, 4]);
+    /// ```
+    pub fn as_host_array<const N: usize>(&self) -> CudaResult<[T; N]> {
+        let mut arr = MaybeUninit::<[T; N]>::uninit();
+        // SAFETY: `MaybeUninit<[T; N]>` has the same layout as `[MaybeUninit<T>; N]`.
+        let dest = unsafe { slice::from_raw_parts_mut(arr.as_mut_ptr() as *mut MaybeUninit<T>, N) };
+        self.copy_to_uninit(dest)?;
+        // SAFETY: every element was initialized by the copy above.
+        Ok(unsafe { arr.assume_init() })
+    }
+
+    /// Copy the contents of this slice into possibly-uninitialized host memory, returning the
+    /// now-initialized destination.
+    ///
+    /// Fixed-size arrays (`[T; N]`) are already accepted by [`CopyDestination`], this exists for
+    /// `[MaybeUninit<T>]`, which cannot implement the trait without overlapping its blanket
+    /// `AsRef<[T]>` impl.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dest` is not the same length as this slice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let _context = cust::quick_init().unwrap();
+    /// use cust::memory::*;
+    /// use std::mem::MaybeUninit;
+    /// let buf = DeviceBuffer::from_slice(&[1u8, 2, 3]).unwrap();
+    /// let mut host = [MaybeUninit::<u8>::uninit(); 3];
+    /// let init = buf.copy_to_uninit(&mut host).unwrap();
+    /// assert_eq!(init, &[1, 2, 3]);
+    /// ```
+    pub fn copy_to_uninit<'a>(&self, dest: &'a mut [MaybeUninit<T>]) -> CudaResult<&'a mut [T]> {
+        assert!(
+            self.len() == dest.len(),
+            "destination and source slices have different lengths"
+        );
+        let size = self.size_in_bytes();
+        if size != 0 {
+            unsafe {
+                driver_sys::cuMemcpyDtoH(dest.as_mut_ptr() as *mut c_void, self.as_raw_ptr(), size)
+                    .to_result()?
+            }
+        }
+        // SAFETY: every element was initialized by the copy above.
+        Ok(unsafe { &mut *(dest as *mut [MaybeUninit<T>] as *mut [T]) })
+    }
+
+    /// Asynchronously copy the contents of this slice into possibly-uninitialized host memory.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dest` is not the same length as this slice.
+    ///
+    /// # Safety
+    ///
+    /// For why this function is unsafe, see [AsyncCopyDestination](trait.AsyncCopyDestination.html).
+    /// Additionally, `dest` must not be assumed initialized until the copy has completed.
+    pub unsafe fn async_copy_to_uninit(
+        &self,
+        dest: &mut [MaybeUninit<T>],
+        stream: &Stream,
+    ) -> CudaResult<()> {
+        assert!(
+            self.len() == dest.len(),
+            "destination and source slices have different lengths"
+        );
+        let size = self.size_in_bytes();
+        if size != 0 {
+            driver_sys::cuMemcpyDtoHAsync(
+                dest.as_mut_ptr() as *mut c_void,
+                self.as_raw_ptr(),
+                size,
+                stream.as_inner(),
+            )
+            .to_async_result()?
+        }
+        Ok(())
+    }
+
+    /// Asynchronously copies the contents of this slice into page-locked host memory, without
+    /// synchronizing the stream.
+    ///
+    /// Unlike [`AsyncCopyDestination::async_copy_to`], which accepts any host slice, the
+    /// destination has to be page-locked, so the copy never blocks the calling thread and can
+    /// overlap with work on other streams.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dst` is not the same length as this slice.
+    ///
+    /// # Safety
+    ///
+    /// `dst` must outlive the copy and must not be read or written until it has completed, that is
+    /// until `stream` is synchronized or an event recorded after the copy has completed. This slice
+    /// must not be modified or freed before then either. See
+    /// [AsyncCopyDestination](trait.AsyncCopyDestination.html) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let _context = cust::quick_init().unwrap();
+    /// use cust::memory::*;
+    /// use cust::stream::{Stream, StreamFlags};
+    ///
+    /// let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+    /// let mut buf = DeviceBuffer::from_slice(&[0u32; 4]).unwrap();
+    /// let src = LockedBuffer::from_slice(&[1u32, 2, 3, 4]).unwrap();
+    /// let mut dst = LockedBuffer::new(&0u32, 4).unwrap();
+    /// unsafe {
+    ///     buf.copy_from_slice_async(&src, &stream).unwrap();
+    ///     buf.copy_to_slice_async(&mut dst, &stream).unwrap();
+    /// }
+    /// stream.synchronize().unwrap();
+    /// assert_eq!(dst.as_slice(), [1, 2, 3, 4]);
+    /// ```
+    pub unsafe fn copy_to_slice_async<H: AsPageLocked<T> + ?Sized>(
+        &self,
+        dst: &mut H,
+        stream: &Stream,
+    ) -> CudaResult<()> {
+        self.async_copy_to(dst.as_page_locked_mut(), stream)
+    }
+
+    /// Asynchronously copies the contents of page-locked host memory into this slice, without
+    /// synchronizing the stream.
+    ///
+    /// See [`DeviceSlice::copy_to_slice_async`] for an example.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `src` is not the same length as this slice.
+    ///
+    /// # Safety
+    ///
+    /// `src` must outlive the copy and must not be modified until it has completed, that is until
+    /// `stream` is synchronized or an event recorded after the copy has completed. This slice must
+    /// not be read, written or freed before then either. See
+    /// [AsyncCopyDestination](trait.AsyncCopyDestination.html) for details.
+    pub unsafe fn copy_from_slice_async<H: AsPageLocked<T> + ?Sized>(
+        &mut self,
+        src: &H,
+        stream: &Stream,
+    ) -> CudaResult<()> {
+        self.async_copy_from(src.as_page_locked(), stream)
+    }
+
+    /// Asynchronously copies the contents of this slice, which lives in `src_context`, into `dst`,
+    /// which lives in `dst_context`, on `stream`.
+    ///
+    /// This is how memory is copied between devices with `cuMemcpyPeerAsync`. The copy goes
+    /// directly over the link between the devices if the destination context has enabled peer
+    /// access to the source with
+    /// [`CurrentContext::enable_peer_access`](crate::context::CurrentContext::enable_peer_access),
+    /// and is staged through host memory otherwise. The two contexts may also be the same.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dst` is not the same length as this slice.
+    ///
+    /// # Safety
+    ///
+    /// This slice must be allocated in `src_context` and `dst` in `dst_context`. The copy is
+    /// asynchronous: this slice must not be modified or freed, and `dst` must not be read, written
+    /// or freed, until it has completed. See [AsyncCopyDestination](trait.AsyncCopyDestination.html)
+    /// for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let context = cust::quick_init().unwrap();
+    /// use cust::memory::*;
+    /// use cust::stream::{Stream, StreamFlags};
+    ///
+    /// let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+    /// let src = DeviceBuffer::from_slice(&[1u32, 2, 3]).unwrap();
+    /// let mut dst = DeviceBuffer::from_slice(&[0u32; 3]).unwrap();
+    /// // with multiple devices, `dst` would be allocated with another context current.
+    /// unsafe { src.copy_peer_async(&context, &mut dst, &context, &stream).unwrap() };
+    /// stream.synchronize().unwrap();
+    /// assert_eq!(dst.as_host_vec().unwrap(), [1, 2, 3]);
+    /// ```
+    pub unsafe fn copy_peer_async(
+        &self,
+        src_context: &Context,
+        dst: &mut DeviceSlice<T>,
+        dst_context: &Context,
+        stream: &Stream,
+    ) -> CudaResult<()> {
+        assert!(
+            self.len() == dst.len(),
+            "destination and source slices have different lengths"
+        );
+        let size = self.size_in_bytes();
+        trace_span!("cuda.memcpy", kind = "peer", bytes = size, is_async = true);
+        if size != 0 {
+            driver_sys::cuMemcpyPeerAsync(
+                dst.as_raw_ptr(),
+                dst_context.as_raw(),
+                self.as_raw_ptr(),
+                src_context.as_raw(),
+                size,
+                stream.as_inner(),
+            )
+            .to_async_result()?
+        }
+        Ok(())
+    }
+
+    /// Asynchronously copies the elements in `src` to the elements starting at `dest` within this
+    /// slice, like [`slice::copy_within`].
+    ///
+    /// The source and destination ranges may overlap. Copies between device memory are not
+    /// guaranteed to handle overlap correctly, so if the ranges overlap the source is first
+    /// copied into a temporary buffer allocated on `stream`, which is then copied into the
+    /// destination and freed, all in stream order. Non-overlapping ranges are copied directly.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `src` is out of bounds, if its start is after its end, or if `dest` is such that
+    /// the destination range would be out of bounds.
+    ///
+    /// # Safety
+    ///
+    /// For why this function is unsafe, see [AsyncCopyDestination](trait.AsyncCopyDestination.html).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let _context = cust::quick_init().unwrap();
+    /// use cust::memory::*;
+    /// use cust::stream::{Stream, StreamFlags};
+    ///
+    /// let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+    /// let mut buf = DeviceBuffer::from_slice(&[0u32, 1, 2, 3, 4, 5]).unwrap();
+    /// unsafe {
+    ///     // shift everything one element to the left
+    ///     buf.copy_within_async(1.., 0, &stream).unwrap();
+    /// }
+    /// stream.synchronize().unwrap();
+    /// assert_eq!(buf.as_host_vec().unwrap(), [1, 2, 3, 4, 5, 5]);
+    /// ```
+    #[track_caller]
+    pub unsafe fn copy_within_async<R: RangeBounds<usize>>(
+        &mut self,
+        src: R,
+        dest: usize,
+        stream: &Stream,
+    ) -> CudaResult<()> {
+        let len = self.len();
+        let start = match src.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start
+                .checked_add(1)
+                .unwrap_or_else(|| slice_end_index_overflow_fail()),
+            Bound::Unbounded => 0,
+        };
+        let end = match src.end_bound() {
+            Bound::Included(&end) => end
+                .checked_add(1)
+                .unwrap_or_else(|| slice_end_index_overflow_fail()),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => len,
+        };
+        if start > end {
+            slice_index_order_fail(start, end);
+        } else if end > len {
+            slice_end_index_len_fail(end, len);
+        }
+        let count = end - start;
+        assert!(dest <= len - count, "dest is out of bounds");
+
+        let size = count * std::mem::size_of::<T>();
+        if size == 0 || start == dest {
+            return Ok(());
+        }
+
+        let src_ptr = self.as_device_ptr().add(start);
+        let dest_ptr = self.as_device_ptr().add(dest);
+
+        if start.abs_diff(dest) >= count {
+            driver_sys::cuMemcpyDtoDAsync(
+                dest_ptr.as_raw(),
+                src_ptr.as_raw(),
+                size,
+                stream.as_inner(),
+            )
+            .to_async_result()
+        } else {
+            let tmp = cuda_malloc_async::<T>(stream, count)?;
+            let result = driver_sys::cuMemcpyDtoDAsync(
+                tmp.as_raw(),
+                src_ptr.as_raw(),
+                size,
+                stream.as_inner(),
+            )
+            .to_async_result()
+            .and_then(|_| {
+                driver_sys::cuMemcpyDtoDAsync(
+                    dest_ptr.as_raw(),
+                    tmp.as_raw(),
+                    size,
+                    stream.as_inner(),
+                )
+                .to_async_result()
+            });
+            // always free the temporary, even if one of the copies failed to be enqueued
+            let freed = cuda_free_async(stream, tmp);
+            result.and(freed)
+        }
+    }
+
     /* TODO (AL): keep these?
     /// Divides one DeviceSlice into two at a given index.
     ///
@@ -431,6 +810,8 @@ pub trait DeviceSliceIndex<T: DeviceCopy> {
     /// The range must be in-bounds of the slice.
     unsafe fn get_unchecked(self, slice: &DeviceSlice<T>) -> &DeviceSlice<T>;
     fn index(self, slice: &DeviceSlice<T>) -> &DeviceSlice<T>;
+    /// Indexes into this slice, returning `None` if it is out of bounds.
+    fn get(self, slice: &DeviceSlice<T>) -> Option<&DeviceSlice<T>>;
 
     /// Indexes into this slice without checking if it is in-bounds.
     ///
@@ -439,6 +820,8 @@ pub trait DeviceSliceIndex<T: DeviceCopy> {
     /// The range must be in-bounds of the slice.
     unsafe fn get_unchecked_mut(self, slice: &mut DeviceSlice<T>) -> &mut DeviceSlice<T>;
     fn index_mut(self, slice: &mut DeviceSlice<T>) -> &mut DeviceSlice<T>;
+    /// Mutably indexes into this slice, returning `None` if it is out of bounds.
+    fn get_mut(self, slice: &mut DeviceSlice<T>) -> Option<&mut DeviceSlice<T>>;
 }
 
 #[inline(never)]
@@ -482,6 +865,14 @@ impl<T: DeviceCopy> DeviceSliceIndex<T> for usize {
     fn index(self, slice: &DeviceSlice<T>) -> &DeviceSlice<T> {
         slice.index(self..self + 1)
     }
+    fn get(self, slice: &DeviceSlice<T>) -> Option<&DeviceSlice<T>> {
+        if self < slice.len() {
+            // SAFETY: `self` is checked to be in bounds above.
+            unsafe { Some(self.get_unchecked(slice)) }
+        } else {
+            None
+        }
+    }
 
     unsafe fn get_unchecked_mut(self, slice: &mut DeviceSlice<T>) -> &mut DeviceSlice<T> {
         (self..self + 1).get_unchecked_mut(slice)
@@ -489,6 +880,14 @@ impl<T: DeviceCopy> DeviceSliceIndex<T> for usize {
     fn index_mut(self, slice: &mut DeviceSlice<T>) -> &mut DeviceSlice<T> {
         slice.index_mut(self..self + 1)
     }
+    fn get_mut(self, slice: &mut DeviceSlice<T>) -> Option<&mut DeviceSlice<T>> {
+        if self < slice.len() {
+            // SAFETY: `self` is checked to be in bounds above.
+            unsafe { Some(self.get_unchecked_mut(slice)) }
+        } else {
+            None
+        }
+    }
 }
 
 impl<T: DeviceCopy> DeviceSliceIndex<T> for Range<usize> {
@@ -504,6 +903,14 @@ impl<T: DeviceCopy> DeviceSliceIndex<T> for Range<usize> {
         // SAFETY: `self` is checked to be valid and in bounds above.
         unsafe { self.get_unchecked(slice) }
     }
+    fn get(self, slice: &DeviceSlice<T>) -> Option<&DeviceSlice<T>> {
+        if self.start > self.end || self.end > slice.len() {
+            None
+        } else {
+            // SAFETY: `self` is checked to be valid and in bounds above.
+            unsafe { Some(self.get_unchecked(slice)) }
+        }
+    }
 
     unsafe fn get_unchecked_mut(self, slice: &mut DeviceSlice<T>) -> &mut DeviceSlice<T> {
         DeviceSlice::from_raw_parts_mut(
@@ -520,6 +927,14 @@ impl<T: DeviceCopy> DeviceSliceIndex<T> for Range<usize> {
         // SAFETY: `self` is checked to be valid and in bounds above.
         unsafe { self.get_unchecked_mut(slice) }
     }
+    fn get_mut(self, slice: &mut DeviceSlice<T>) -> Option<&mut DeviceSlice<T>> {
+        if self.start > self.end || self.end > slice.len() {
+            None
+        } else {
+            // SAFETY: `self` is checked to be valid and in bounds above.
+            unsafe { Some(self.get_unchecked_mut(slice)) }
+        }
+    }
 }
 
 impl<T: DeviceCopy> DeviceSliceIndex<T> for RangeTo<usize> {
@@ -529,6 +944,9 @@ impl<T: DeviceCopy> DeviceSliceIndex<T> for RangeTo<usize> {
     fn index(self, slice: &DeviceSlice<T>) -> &DeviceSlice<T> {
         (0..self.end).index(slice)
     }
+    fn get(self, slice: &DeviceSlice<T>) -> Option<&DeviceSlice<T>> {
+        (0..self.end).get(slice)
+    }
 
     unsafe fn get_unchecked_mut(self, slice: &mut DeviceSlice<T>) -> &mut DeviceSlice<T> {
         (0..self.end).get_unchecked_mut(slice)
@@ -536,6 +954,9 @@ impl<T: DeviceCopy> DeviceSliceIndex<T> for RangeTo<usize> {
     fn index_mut(self, slice: &mut DeviceSlice<T>) -> &mut DeviceSlice<T> {
         (0..self.end).index_mut(slice)
     }
+    fn get_mut(self, slice: &mut DeviceSlice<T>) -> Option<&mut DeviceSlice<T>> {
+        (0..self.end).get_mut(slice)
+    }
 }
 
 impl<T: DeviceCopy> DeviceSliceIndex<T> for RangeFrom<usize> {
@@ -549,6 +970,14 @@ impl<T: DeviceCopy> DeviceSliceIndex<T> for RangeFrom<usize> {
         // SAFETY: `self` is checked to be valid and in bounds above.
         unsafe { self.get_unchecked(slice) }
     }
+    fn get(self, slice: &DeviceSlice<T>) -> Option<&DeviceSlice<T>> {
+        if self.start > slice.len() {
+            None
+        } else {
+            // SAFETY: `self` is checked to be valid and in bounds above.
+            unsafe { Some(self.get_unchecked(slice)) }
+        }
+    }
 
     unsafe fn get_unchecked_mut(self, slice: &mut DeviceSlice<T>) -> &mut DeviceSlice<T> {
         (self.start..slice.len()).get_unchecked_mut(slice)
@@ -560,6 +989,14 @@ impl<T: DeviceCopy> DeviceSliceIndex<T> for RangeFrom<usize> {
         // SAFETY: `self` is checked to be valid and in bounds above.
         unsafe { self.get_unchecked_mut(slice) }
     }
+    fn get_mut(self, slice: &mut DeviceSlice<T>) -> Option<&mut DeviceSlice<T>> {
+        if self.start > slice.len() {
+            None
+        } else {
+            // SAFETY: `self` is checked to be valid and in bounds above.
+            unsafe { Some(self.get_unchecked_mut(slice)) }
+        }
+    }
 }
 
 impl<T: DeviceCopy> DeviceSliceIndex<T> for RangeFull {
@@ -569,6 +1006,9 @@ impl<T: DeviceCopy> DeviceSliceIndex<T> for RangeFull {
     fn index(self, slice: &DeviceSlice<T>) -> &DeviceSlice<T> {
         slice
     }
+    fn get(self, slice: &DeviceSlice<T>) -> Option<&DeviceSlice<T>> {
+        Some(slice)
+    }
 
     unsafe fn get_unchecked_mut(self, slice: &mut DeviceSlice<T>) -> &mut DeviceSlice<T> {
         slice
@@ -576,6 +1016,9 @@ impl<T: DeviceCopy> DeviceSliceIndex<T> for RangeFull {
     fn index_mut(self, slice: &mut DeviceSlice<T>) -> &mut DeviceSlice<T> {
         slice
     }
+    fn get_mut(self, slice: &mut DeviceSlice<T>) -> Option<&mut DeviceSlice<T>> {
+        Some(slice)
+    }
 }
 
 fn into_slice_range(range: RangeInclusive<usize>) -> Range<usize> {
@@ -598,6 +1041,13 @@ impl<T: DeviceCopy> DeviceSliceIndex<T> for RangeInclusive<usize> {
         }
         into_slice_range(self).index(slice)
     }
+    fn get(self, slice: &DeviceSlice<T>) -> Option<&DeviceSlice<T>> {
+        if *self.end() == usize::MAX {
+            None
+        } else {
+            into_slice_range(self).get(slice)
+        }
+    }
 
     unsafe fn get_unchecked_mut(self, slice: &mut DeviceSlice<T>) -> &mut DeviceSlice<T> {
         into_slice_range(self).get_unchecked_mut(slice)
@@ -608,6 +1058,13 @@ impl<T: DeviceCopy> DeviceSliceIndex<T> for RangeInclusive<usize> {
         }
         into_slice_range(self).index_mut(slice)
     }
+    fn get_mut(self, slice: &mut DeviceSlice<T>) -> Option<&mut DeviceSlice<T>> {
+        if *self.end() == usize::MAX {
+            None
+        } else {
+            into_slice_range(self).get_mut(slice)
+        }
+    }
 }
 
 impl<T: DeviceCopy> DeviceSliceIndex<T> for RangeToInclusive<usize> {
@@ -617,6 +1074,9 @@ impl<T: DeviceCopy> DeviceSliceIndex<T> for RangeToInclusive<usize> {
     fn index(self, slice: &DeviceSlice<T>) -> &DeviceSlice<T> {
         (0..=self.end).index(slice)
     }
+    fn get(self, slice: &DeviceSlice<T>) -> Option<&DeviceSlice<T>> {
+        (0..=self.end).get(slice)
+    }
 
     unsafe fn get_unchecked_mut(self, slice: &mut DeviceSlice<T>) -> &mut DeviceSlice<T> {
         (0..=self.end).get_unchecked_mut(slice)
@@ -624,6 +1084,9 @@ impl<T: DeviceCopy> DeviceSliceIndex<T> for RangeToInclusive<usize> {
     fn index_mut(self, slice: &mut DeviceSlice<T>) -> &mut DeviceSlice<T> {
         (0..=self.end).index_mut(slice)
     }
+    fn get_mut(self, slice: &mut DeviceSlice<T>) -> Option<&mut DeviceSlice<T>> {
+        (0..=self.end).get_mut(slice)
+    }
 }
 
 impl<T: DeviceCopy, Idx: DeviceSliceIndex<T>> Index<Idx> for DeviceSlice<T> {
@@ -649,6 +1112,7 @@ impl<T: DeviceCopy, I: AsRef<[T]> + AsMut<[T]> + ?Sized> CopyDestination<I> for
             "destination and source slices have different lengths"
         );
         let size = self.size_in_bytes();
+        trace_span!("cuda.memcpy", kind = "htod", bytes = size, is_async = false);
         if size != 0 {
             unsafe {
                 driver_sys::cuMemcpyHtoD(self.as_raw_ptr(), val.as_ptr() as *const c_void, size)
@@ -665,6 +1129,7 @@ impl<T: DeviceCopy, I: AsRef<[T]> + AsMut<[T]> + ?Sized> CopyDestination<I> for
             "destination and source slices have different lengths"
         );
         let size = self.size_in_bytes();
+        trace_span!("cuda.memcpy", kind = "dtoh", bytes = size, is_async = false);
         if size != 0 {
             unsafe {
                 driver_sys::cuMemcpyDtoH(val.as_mut_ptr() as *mut c_void, self.as_raw_ptr(), size)
@@ -681,6 +1146,7 @@ impl<T: DeviceCopy> CopyDestination<DeviceSlice<T>> for DeviceSlice<T> {
             "destination and source slices have different lengths"
         );
         let size = self.size_in_bytes();
+        trace_span!("cuda.memcpy", kind = "dtod", bytes = size, is_async = false);
         if size != 0 {
             unsafe {
                 driver_sys::cuMemcpyDtoD(self.as_raw_ptr(), val.as_raw_ptr(), size).to_result()?
@@ -695,6 +1161,7 @@ impl<T: DeviceCopy> CopyDestination<DeviceSlice<T>> for DeviceSlice<T> {
             "destination and source slices have different lengths"
         );
         let size = self.size_in_bytes();
+        trace_span!("cuda.memcpy", kind = "dtod", bytes = size, is_async = false);
         if size != 0 {
             unsafe {
                 driver_sys::cuMemcpyDtoD(val.as_raw_ptr(), self.as_raw_ptr(), size).to_result()?
@@ -703,12 +1170,12 @@ impl<T: DeviceCopy> CopyDestination<DeviceSlice<T>> for DeviceSlice<T> {
         Ok(())
     }
 }
-impl<T: DeviceCopy> CopyDestination<DeviceBuffer<T>> for DeviceSlice<T> {
-    fn copy_from(&mut self, val: &DeviceBuffer<T>) -> CudaResult<()> {
+impl<T: DeviceCopy, A: Allocator> CopyDestination<DeviceBuffer<T, A>> for DeviceSlice<T> {
+    fn copy_from(&mut self, val: &DeviceBuffer<T, A>) -> CudaResult<()> {
         self.copy_from(val as &DeviceSlice<T>)
     }
 
-    fn copy_to(&self, val: &mut DeviceBuffer<T>) -> CudaResult<()> {
+    fn copy_to(&self, val: &mut DeviceBuffer<T, A>) -> CudaResult<()> {
         self.copy_to(val as &mut DeviceSlice<T>)
     }
 }
@@ -722,6 +1189,7 @@ impl<T: DeviceCopy, I: AsRef<[T]> + AsMut<[T]> + ?Sized> AsyncCopyDestination<I>
             "destination and source slices have different lengths"
         );
         let size = self.size_in_bytes();
+        trace_span!("cuda.memcpy", kind = "htod", bytes = size, is_async = true);
         if size != 0 {
             driver_sys::cuMemcpyHtoDAsync(
                 self.as_raw_ptr(),
@@ -729,7 +1197,7 @@ impl<T: DeviceCopy, I: AsRef<[T]> + AsMut<[T]> + ?Sized> AsyncCopyDestination<I>
                 size,
                 stream.as_inner(),
             )
-            .to_result()?
+            .to_async_result()?
         }
         Ok(())
     }
@@ -741,6 +1209,7 @@ impl<T: DeviceCopy, I: AsRef<[T]> + AsMut<[T]> + ?Sized> AsyncCopyDestination<I>
             "destination and source slices have different lengths"
         );
         let size = self.size_in_bytes();
+        trace_span!("cuda.memcpy", kind = "dtoh", bytes = size, is_async = true);
         if size != 0 {
             driver_sys::cuMemcpyDtoHAsync(
                 val.as_mut_ptr() as *mut c_void,
@@ -748,7 +1217,7 @@ impl<T: DeviceCopy, I: AsRef<[T]> + AsMut<[T]> + ?Sized> AsyncCopyDestination<I>
                 size,
                 stream.as_inner(),
             )
-            .to_result()?
+            .to_async_result()?
         }
         Ok(())
     }
@@ -760,6 +1229,7 @@ impl<T: DeviceCopy> AsyncCopyDestination<DeviceSlice<T>> for DeviceSlice<T> {
             "destination and source slices have different lengths"
         );
         let size = self.size_in_bytes();
+        trace_span!("cuda.memcpy", kind = "dtod", bytes = size, is_async = true);
         if size != 0 {
             driver_sys::cuMemcpyDtoDAsync(
                 self.as_raw_ptr(),
@@ -767,7 +1237,7 @@ impl<T: DeviceCopy> AsyncCopyDestination<DeviceSlice<T>> for DeviceSlice<T> {
                 size,
                 stream.as_inner(),
             )
-            .to_result()?
+            .to_async_result()?
         }
         Ok(())
     }
@@ -778,6 +1248,7 @@ impl<T: DeviceCopy> AsyncCopyDestination<DeviceSlice<T>> for DeviceSlice<T> {
             "destination and source slices have different lengths"
         );
         let size = self.size_in_bytes();
+        trace_span!("cuda.memcpy", kind = "dtod", bytes = size, is_async = true);
         if size != 0 {
             driver_sys::cuMemcpyDtoDAsync(
                 val.as_raw_ptr(),
@@ -785,17 +1256,25 @@ impl<T: DeviceCopy> AsyncCopyDestination<DeviceSlice<T>> for DeviceSlice<T> {
                 size,
                 stream.as_inner(),
             )
-            .to_result()?
+            .to_async_result()?
         }
         Ok(())
     }
 }
-impl<T: DeviceCopy> AsyncCopyDestination<DeviceBuffer<T>> for DeviceSlice<T> {
-    unsafe fn async_copy_from(&mut self, val: &DeviceBuffer<T>, stream: &Stream) -> CudaResult<()> {
+impl<T: DeviceCopy, A: Allocator> AsyncCopyDestination<DeviceBuffer<T, A>> for DeviceSlice<T> {
+    unsafe fn async_copy_from(
+        &mut self,
+        val: &DeviceBuffer<T, A>,
+        stream: &Stream,
+    ) -> CudaResult<()> {
         self.async_copy_from(val as &DeviceSlice<T>, stream)
     }
 
-    unsafe fn async_copy_to(&self, val: &mut DeviceBuffer<T>, stream: &Stream) -> CudaResult<()> {
+    unsafe fn async_copy_to(
+        &self,
+        val: &mut DeviceBuffer<T, A>,
+        stream: &Stream,
+    ) -> CudaResult<()> {
         self.async_copy_to(val as &mut DeviceSlice<T>, stream)
     }
 }
@@ -6,10 +6,34 @@ use std::ops::{Deref, DerefMut};
 
 /// Wrapper around a variable on the host and a [`DeviceBox`] holding the
 /// variable on the device, allowing for easy synchronization and storage.
+///
+/// The host copy is accessible through [`Deref`] and [`DerefMut`]. Mutably dereferencing the
+/// variable marks the host copy as modified, so that [`copy_to_device`](Self::copy_to_device)
+/// only copies it when it may have changed.
+///
+/// # Examples
+///
+/// ```
+/// # let _context = cust::quick_init().unwrap();
+/// use cust::memory::DeviceVariable;
+///
+/// let mut learning_rate = DeviceVariable::new(0.1f32).unwrap();
+///
+/// // Nothing has changed since the variable was created, so this does not copy.
+/// learning_rate.copy_to_device().unwrap();
+///
+/// *learning_rate *= 0.5;
+/// learning_rate.copy_to_device().unwrap();
+/// // Launch kernels reading `learning_rate.as_device_ptr()` ...
+///
+/// learning_rate.copy_from_device().unwrap();
+/// assert_eq!(*learning_rate, 0.05);
+/// ```
 #[derive(Debug)]
 pub struct DeviceVariable<T: DeviceCopy> {
     mem: DeviceBox<T>,
     var: T,
+    modified: bool,
 }
 
 impl<T: DeviceCopy> DeviceVariable<T> {
@@ -18,19 +42,52 @@ impl<T: DeviceCopy> DeviceVariable<T> {
     /// Allocates storage on the device and copies `var` to the device.
     pub fn new(var: T) -> CudaResult<Self> {
         let mem = DeviceBox::new(&var)?;
-        Ok(Self { mem, var })
+        Ok(Self {
+            mem,
+            var,
+            modified: false,
+        })
     }
 
     /// Copy the host copy of the variable to the device
     pub fn copy_htod(&mut self) -> CudaResult<()> {
-        self.mem.copy_from(&self.var)
+        self.mem.copy_from(&self.var)?;
+        self.modified = false;
+        Ok(())
     }
 
     /// Copy the device copy of the variable to the host
     pub fn copy_dtoh(&mut self) -> CudaResult<()> {
-        self.mem.copy_to(&mut self.var)
+        self.mem.copy_to(&mut self.var)?;
+        self.modified = false;
+        Ok(())
     }
 
+    /// Copies the host copy of the variable to the device if it may have been modified since the
+    /// last copy in either direction.
+    ///
+    /// Use [`copy_htod`](Self::copy_htod) to copy unconditionally.
+    pub fn copy_to_device(&mut self) -> CudaResult<()> {
+        if self.modified {
+            self.copy_htod()?;
+        }
+        Ok(())
+    }
+
+    /// Refreshes the host copy of the variable from the device, discarding any host modifications.
+    ///
+    /// This is the same as [`copy_dtoh`](Self::copy_dtoh).
+    pub fn copy_from_device(&mut self) -> CudaResult<()> {
+        self.copy_dtoh()
+    }
+
+    /// Returns whether the host copy has been mutably accessed since the last copy in either
+    /// direction.
+    pub fn is_modified(&self) -> bool {
+        self.modified
+    }
+
+    /// Returns the device pointer to the device copy of the variable.
     pub fn as_device_ptr(&self) -> DevicePointer<T> {
         self.mem.as_device_ptr()
     }
@@ -46,6 +103,33 @@ impl<T: DeviceCopy> Deref for DeviceVariable<T> {
 
 impl<T: DeviceCopy> DerefMut for DeviceVariable<T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
+        self.modified = true;
         &mut self.var
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_copy_to_device_only_when_modified() {
+        let _context = crate::quick_init().unwrap();
+        let mut var = DeviceVariable::new(1u32).unwrap();
+        assert!(!var.is_modified());
+
+        *var = 2;
+        assert!(var.is_modified());
+        var.copy_to_device().unwrap();
+        assert!(!var.is_modified());
+
+        let mut host = 0u32;
+        var.mem.copy_to(&mut host).unwrap();
+        assert_eq!(host, 2);
+
+        var.mem.copy_from(&3).unwrap();
+        var.copy_to_device().unwrap();
+        var.copy_from_device().unwrap();
+        assert_eq!(*var, 3);
+    }
+}
@@ -0,0 +1,114 @@
+//! Sharing device allocations between processes.
+
+use cust_raw::driver_sys;
+
+use crate::error::{CudaError, CudaResult, ToResult};
+use crate::memory::{Allocator, DevicePointer};
+
+/// The size in bytes of an [`IpcMemHandle`].
+pub const IPC_HANDLE_SIZE: usize = 64;
+
+/// An opaque handle to a device allocation which can be opened by another process.
+///
+/// Handles are created with [`DeviceBuffer::export_ipc_handle`](crate::memory::DeviceBuffer::export_ipc_handle)
+/// and opened with [`DeviceBuffer::from_ipc_handle`](crate::memory::DeviceBuffer::from_ipc_handle).
+/// A handle is plain data, it can be sent to the other process by any means, for instance over a
+/// Unix socket, by transferring its [`bytes`](IpcMemHandle::as_bytes).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IpcMemHandle {
+    bytes: [u8; IPC_HANDLE_SIZE],
+}
+
+impl IpcMemHandle {
+    /// Reconstructs a handle from the bytes of a handle exported by another process.
+    pub fn from_bytes(bytes: [u8; IPC_HANDLE_SIZE]) -> Self {
+        Self { bytes }
+    }
+
+    /// Returns the bytes of the handle, to be sent to another process.
+    pub fn as_bytes(&self) -> &[u8; IPC_HANDLE_SIZE] {
+        &self.bytes
+    }
+
+    pub(crate) fn from_raw(raw: driver_sys::CUipcMemHandle) -> Self {
+        Self {
+            bytes: raw.reserved.map(|byte| byte as u8),
+        }
+    }
+
+    pub(crate) fn to_raw(self) -> driver_sys::CUipcMemHandle {
+        driver_sys::CUipcMemHandle {
+            reserved: self.bytes.map(|byte| byte as _),
+        }
+    }
+}
+
+/// The [`Allocator`] of buffers opened from an [`IpcMemHandle`].
+///
+/// It cannot allocate memory, [`alloc`](Allocator::alloc) always returns
+/// [`CudaError::NotSupported`]. Dropping a buffer using it closes the handle with
+/// `cuIpcCloseMemHandle` instead of freeing the memory, which stays owned by the exporting
+/// process.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct IpcAllocator;
+
+unsafe impl Allocator for IpcAllocator {
+    fn alloc(&self, _size: usize, _align: usize) -> CudaResult<DevicePointer<u8>> {
+        Err(CudaError::NotSupported)
+    }
+
+    unsafe fn dealloc(
+        &self,
+        ptr: DevicePointer<u8>,
+        _size: usize,
+        _align: usize,
+    ) -> CudaResult<()> {
+        driver_sys::cuIpcCloseMemHandle(ptr.as_raw()).to_result()
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod test_ipc {
+    use super::*;
+    use crate::memory::DeviceBuffer;
+    use std::process::Command;
+
+    const HANDLE_ENV: &str = "CUST_TEST_IPC_HANDLE";
+
+    // the parent exports a buffer and runs this same test in a child process, which opens it.
+    #[test]
+    fn test_ipc_share_buffer() {
+        if let Ok(hex) = std::env::var(HANDLE_ENV) {
+            let _context = crate::quick_init().unwrap();
+            let mut bytes = [0u8; IPC_HANDLE_SIZE];
+            for (i, byte) in bytes.iter_mut().enumerate() {
+                *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).unwrap();
+            }
+            let buffer = unsafe {
+                DeviceBuffer::<u32, _>::from_ipc_handle(IpcMemHandle::from_bytes(bytes), 4).unwrap()
+            };
+            assert_eq!(buffer.as_host_vec().unwrap(), [1, 2, 3, 4]);
+            return;
+        }
+
+        let _context = crate::quick_init().unwrap();
+        let buffer = DeviceBuffer::from_slice(&[1u32, 2, 3, 4]).unwrap();
+        let handle = buffer.export_ipc_handle().unwrap();
+        let hex: String = handle
+            .as_bytes()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+
+        let (_, module) = module_path!().split_once("::").unwrap();
+        let output = Command::new(std::env::current_exe().unwrap())
+            .args(["--exact", &format!("{module}::test_ipc_share_buffer")])
+            .env(HANDLE_ENV, hex)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        // make sure the child did not filter out every test.
+        assert!(String::from_utf8_lossy(&output.stdout).contains("1 passed"));
+    }
+}
@@ -1,5 +1,56 @@
 mod locked_box;
 mod locked_buffer;
+mod pinned_pool;
 
 pub use locked_box::*;
 pub use locked_buffer::*;
+pub use pinned_pool::*;
+
+use crate::memory::DeviceCopy;
+
+/// Host memory which is known to be page-locked, and can therefore be the host side of a truly
+/// asynchronous copy, see
+/// [`DeviceSlice::copy_to_slice_async`](crate::memory::DeviceSlice::copy_to_slice_async).
+///
+/// Copies to or from pageable host memory are staged through a driver owned pinned buffer and
+/// can block the calling thread, which defeats overlapping transfers with computation.
+pub trait AsPageLocked<T: DeviceCopy>: crate::private::Sealed {
+    /// Returns the page-locked memory as a slice.
+    fn as_page_locked(&self) -> &[T];
+
+    /// Returns the page-locked memory as a mutable slice.
+    fn as_page_locked_mut(&mut self) -> &mut [T];
+}
+
+impl<T: DeviceCopy> crate::private::Sealed for LockedBuffer<T> {}
+impl<T: DeviceCopy> AsPageLocked<T> for LockedBuffer<T> {
+    fn as_page_locked(&self) -> &[T] {
+        self.as_slice()
+    }
+
+    fn as_page_locked_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T: DeviceCopy> crate::private::Sealed for PinnedPoolBuffer<'_, T> {}
+impl<T: DeviceCopy> AsPageLocked<T> for PinnedPoolBuffer<'_, T> {
+    fn as_page_locked(&self) -> &[T] {
+        self.as_slice()
+    }
+
+    fn as_page_locked_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T: DeviceCopy> crate::private::Sealed for LockedBox<T> {}
+impl<T: DeviceCopy> AsPageLocked<T> for LockedBox<T> {
+    fn as_page_locked(&self) -> &[T] {
+        std::slice::from_ref(&**self)
+    }
+
+    fn as_page_locked_mut(&mut self) -> &mut [T] {
+        std::slice::from_mut(&mut **self)
+    }
+}
@@ -1,8 +1,10 @@
 use crate::error::*;
-use crate::memory::malloc::{cuda_free_locked, cuda_malloc_locked};
-use crate::memory::DeviceCopy;
+use crate::memory::malloc::{cuda_free_locked, cuda_malloc_locked_with_flags};
+use crate::memory::{DeviceCopy, DevicePointer, HostAllocFlags};
+use cust_raw::driver_sys;
 use std::mem;
 use std::ops;
+use std::os::raw::c_void;
 use std::ptr;
 use std::slice;
 
@@ -37,13 +39,7 @@ impl<T: DeviceCopy + Clone> LockedBuffer<T> {
     /// buffer[0] = 1;
     /// ```
     pub fn new(value: &T, size: usize) -> CudaResult<Self> {
-        unsafe {
-            let mut uninit = LockedBuffer::uninitialized(size)?;
-            for x in 0..size {
-                *uninit.get_unchecked_mut(x) = *value;
-            }
-            Ok(uninit)
-        }
+        Self::with_flags(value, size, HostAllocFlags::empty())
     }
 
     /// Allocate a new page-locked buffer of the same size as `slice`, initialized with a clone of
@@ -71,6 +67,37 @@ impl<T: DeviceCopy + Clone> LockedBuffer<T> {
             Ok(uninit)
         }
     }
+
+    /// Allocate a new page-locked buffer large enough to hold `size` `T`'s with the given
+    /// allocation `flags`, and initialized with clones of `value`.
+    ///
+    /// Note that memory allocated with [`HostAllocFlags::WRITE_COMBINED`] should not be read by
+    /// the host, as reading it is prohibitively slow.
+    ///
+    /// # Errors
+    ///
+    /// If the allocation fails, returns the error from CUDA. If `size` is large enough that
+    /// `size * mem::sizeof::<T>()` overflows usize, then returns InvalidMemoryAllocation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let _context = cust::quick_init().unwrap();
+    /// use cust::memory::*;
+    /// // a staging buffer for host to device copies.
+    /// let flags = HostAllocFlags::PORTABLE | HostAllocFlags::WRITE_COMBINED;
+    /// let mut buffer = LockedBuffer::with_flags(&0u64, 5, flags).unwrap();
+    /// buffer[0] = 1;
+    /// ```
+    pub fn with_flags(value: &T, size: usize, flags: HostAllocFlags) -> CudaResult<Self> {
+        unsafe {
+            let mut uninit = LockedBuffer::uninitialized_with_flags(size, flags)?;
+            for x in 0..size {
+                *uninit.get_unchecked_mut(x) = *value;
+            }
+            Ok(uninit)
+        }
+    }
 }
 impl<T: DeviceCopy> LockedBuffer<T> {
     /// Allocate a new page-locked buffer large enough to hold `size` `T`'s, but without
@@ -97,8 +124,36 @@ impl<T: DeviceCopy> LockedBuffer<T> {
     /// }
     /// ```
     pub unsafe fn uninitialized(size: usize) -> CudaResult<Self> {
+        Self::uninitialized_with_flags(size, HostAllocFlags::empty())
+    }
+
+    /// Allocate a new page-locked buffer large enough to hold `size` `T`'s with the given
+    /// allocation `flags`, but without initializing the contents.
+    ///
+    /// # Errors
+    ///
+    /// If the allocation fails, returns the error from CUDA. If `size` is large enough that
+    /// `size * mem::sizeof::<T>()` overflows usize, then returns InvalidMemoryAllocation.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the contents of the buffer are initialized before reading from
+    /// the buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let _context = cust::quick_init().unwrap();
+    /// use cust::memory::*;
+    /// let mut buffer =
+    ///     unsafe { LockedBuffer::uninitialized_with_flags(5, HostAllocFlags::PORTABLE).unwrap() };
+    /// for i in buffer.iter_mut() {
+    ///     *i = 0u64;
+    /// }
+    /// ```
+    pub unsafe fn uninitialized_with_flags(size: usize, flags: HostAllocFlags) -> CudaResult<Self> {
         let ptr: *mut T = if size > 0 && mem::size_of::<T>() > 0 {
-            cuda_malloc_locked(size)?
+            cuda_malloc_locked_with_flags(size, flags)?
         } else {
             ptr::NonNull::dangling().as_ptr()
         };
@@ -108,6 +163,40 @@ impl<T: DeviceCopy> LockedBuffer<T> {
         })
     }
 
+    /// Returns the device pointer of a buffer allocated with [`HostAllocFlags::DEVICE_MAP`],
+    /// through which kernels can access the buffer directly.
+    ///
+    /// Accesses from the device go over the bus, so this is mostly useful for data which is
+    /// read or written once.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CudaError::InvalidValue`] if the buffer is empty, and the error from CUDA if the
+    /// buffer is not mapped into the address space of the device. On systems with unified
+    /// addressing all page-locked memory is mapped, regardless of `HostAllocFlags::DEVICE_MAP`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let _context = cust::quick_init().unwrap();
+    /// use cust::memory::*;
+    /// let buffer = LockedBuffer::with_flags(&0u64, 5, HostAllocFlags::DEVICE_MAP).unwrap();
+    /// let ptr = buffer.as_device_ptr().unwrap();
+    /// // pass `ptr` to a kernel.
+    /// ```
+    pub fn as_device_ptr(&self) -> CudaResult<DevicePointer<T>> {
+        if self.capacity == 0 || mem::size_of::<T>() == 0 {
+            return Err(CudaError::InvalidValue);
+        }
+
+        let mut ptr = 0;
+        unsafe {
+            driver_sys::cuMemHostGetDevicePointer(&mut ptr, self.buf as *mut c_void, 0)
+                .to_result()?;
+        }
+        Ok(DevicePointer::from_raw(ptr))
+    }
+
     /// Extracts a slice containing the entire buffer.
     ///
     /// Equivalent to `&s[..]`.
@@ -149,8 +238,9 @@ impl<T: DeviceCopy> LockedBuffer<T> {
     ///
     /// This is highly unsafe, due to the number of invariants that aren't checked:
     ///
-    ///   * `ptr` needs to have been previously allocated via `LockedBuffer` or
-    ///     [`cuda_malloc_locked`](fn.cuda_malloc_locked.html).
+    ///   * `ptr` needs to have been previously allocated via `LockedBuffer`,
+    ///     [`cuda_malloc_locked`](fn.cuda_malloc_locked.html) or
+    ///     [`cuda_malloc_locked_with_flags`](fn.cuda_malloc_locked_with_flags.html).
     ///   * `ptr`'s `T` needs to have the same size and alignment as it was allocated
     ///     with.
     ///   * `capacity` needs to be the capacity that the pointer was allocated with.
@@ -297,6 +387,18 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_with_flags() {
+        let _context = crate::quick_init().unwrap();
+        let flags = HostAllocFlags::PORTABLE | HostAllocFlags::DEVICE_MAP;
+        let buffer = LockedBuffer::with_flags(&7u32, 4, flags).unwrap();
+        assert_eq!(buffer.as_slice(), [7, 7, 7, 7]);
+        assert!(!buffer.as_device_ptr().unwrap().is_null());
+
+        let empty = LockedBuffer::with_flags(&0u32, 0, flags).unwrap();
+        assert_eq!(empty.as_device_ptr(), Err(CudaError::InvalidValue));
+    }
+
     #[test]
     fn from_raw_parts() {
         let _context = crate::quick_init().unwrap();
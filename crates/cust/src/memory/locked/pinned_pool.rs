@@ -0,0 +1,195 @@
+use crate::error::CudaResult;
+use crate::memory::{DeviceCopy, LockedBuffer};
+use std::fmt;
+use std::ops;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// A pool of page-locked buffers of a fixed length, for recycling staging buffers.
+///
+/// Allocating page-locked memory is much more expensive than allocating pageable memory, so code
+/// that repeatedly stages uploads or downloads through short-lived [`LockedBuffer`]s can spend a
+/// large amount of time in the allocator. A `PinnedPool` keeps buffers that are no longer used
+/// around and hands them out again instead of allocating new ones.
+///
+/// [`get`](PinnedPool::get) returns a [`PinnedPoolBuffer`] guard, which derefs to the
+/// [`LockedBuffer`] and returns it to the pool when dropped. Recycled buffers keep the contents
+/// they had when they were returned.
+///
+/// The pool can be shared between threads. Idle buffers are only freed when the pool is dropped
+/// or [`clear`](PinnedPool::clear)ed.
+///
+/// # Examples
+///
+/// ```
+/// # let _context = cust::quick_init().unwrap();
+/// use cust::memory::*;
+///
+/// let pool = PinnedPool::<f32>::new(1024);
+/// let mut device = DeviceBuffer::<f32>::from_slice(&[0.0; 1024]).unwrap();
+///
+/// for i in 0..10 {
+///     let mut staging = pool.get().unwrap();
+///     staging.fill(i as f32);
+///     device.copy_from(&staging[..]).unwrap();
+///     // `staging` goes back to the pool here.
+/// }
+///
+/// // Only a single buffer was ever allocated.
+/// assert_eq!(pool.idle_buffers(), 1);
+/// ```
+pub struct PinnedPool<T: DeviceCopy> {
+    buffer_len: usize,
+    idle: Mutex<Vec<LockedBuffer<T>>>,
+}
+
+impl<T: DeviceCopy + Default> PinnedPool<T> {
+    /// Creates an empty pool handing out buffers of `buffer_len` elements.
+    ///
+    /// No memory is allocated until buffers are requested.
+    pub fn new(buffer_len: usize) -> Self {
+        Self {
+            buffer_len,
+            idle: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Creates a pool handing out buffers of `buffer_len` elements, with `count` buffers
+    /// allocated up front.
+    ///
+    /// # Errors
+    ///
+    /// If any of the allocations fails, returns the error from CUDA.
+    pub fn with_buffers(buffer_len: usize, count: usize) -> CudaResult<Self> {
+        let buffers = (0..count)
+            .map(|_| LockedBuffer::new(&T::default(), buffer_len))
+            .collect::<CudaResult<Vec<_>>>()?;
+        Ok(Self {
+            buffer_len,
+            idle: Mutex::new(buffers),
+        })
+    }
+
+    /// Takes a buffer out of the pool, allocating a new one if no buffer is idle.
+    ///
+    /// Newly allocated buffers are filled with `T::default()`, recycled buffers keep their
+    /// previous contents.
+    ///
+    /// # Errors
+    ///
+    /// If a new buffer has to be allocated and the allocation fails, returns the error from CUDA.
+    pub fn get(&self) -> CudaResult<PinnedPoolBuffer<'_, T>> {
+        let recycled = self.lock().pop();
+        let buffer = match recycled {
+            Some(buffer) => buffer,
+            None => LockedBuffer::new(&T::default(), self.buffer_len)?,
+        };
+        Ok(PinnedPoolBuffer {
+            buffer: Some(buffer),
+            pool: self,
+        })
+    }
+}
+
+impl<T: DeviceCopy> PinnedPool<T> {
+    /// Returns the number of elements of the buffers handed out by this pool.
+    pub fn buffer_len(&self) -> usize {
+        self.buffer_len
+    }
+
+    /// Returns the number of buffers currently waiting in the pool to be reused.
+    pub fn idle_buffers(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Frees all idle buffers. Buffers that are currently handed out are not affected and will
+    /// still return to the pool when dropped.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<LockedBuffer<T>>> {
+        // a panic while holding the lock cannot leave the list of buffers in an invalid state.
+        self.idle.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T: DeviceCopy> fmt::Debug for PinnedPool<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PinnedPool")
+            .field("buffer_len", &self.buffer_len)
+            .field("idle_buffers", &self.idle_buffers())
+            .finish()
+    }
+}
+
+/// A page-locked buffer borrowed from a [`PinnedPool`], which is returned to the pool on drop.
+#[derive(Debug)]
+pub struct PinnedPoolBuffer<'a, T: DeviceCopy> {
+    // only `None` while being dropped.
+    buffer: Option<LockedBuffer<T>>,
+    pool: &'a PinnedPool<T>,
+}
+
+impl<T: DeviceCopy> PinnedPoolBuffer<'_, T> {
+    /// Takes the buffer out of the pool for good, so it is not returned on drop.
+    pub fn detach(mut self) -> LockedBuffer<T> {
+        self.buffer.take().unwrap()
+    }
+}
+
+impl<T: DeviceCopy> ops::Deref for PinnedPoolBuffer<'_, T> {
+    type Target = LockedBuffer<T>;
+
+    fn deref(&self) -> &LockedBuffer<T> {
+        self.buffer.as_ref().unwrap()
+    }
+}
+
+impl<T: DeviceCopy> ops::DerefMut for PinnedPoolBuffer<'_, T> {
+    fn deref_mut(&mut self) -> &mut LockedBuffer<T> {
+        self.buffer.as_mut().unwrap()
+    }
+}
+
+impl<T: DeviceCopy> Drop for PinnedPoolBuffer<'_, T> {
+    fn drop(&mut self) {
+        if let Some(buffer) = self.buffer.take() {
+            self.pool.lock().push(buffer);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_buffers_are_recycled() {
+        let _context = crate::quick_init().unwrap();
+        let pool = PinnedPool::<u64>::new(16);
+
+        let mut first = pool.get().unwrap();
+        assert_eq!(first.len(), 16);
+        first[0] = 7;
+        let ptr = first.as_ptr();
+        drop(first);
+        assert_eq!(pool.idle_buffers(), 1);
+
+        let second = pool.get().unwrap();
+        assert_eq!(second.as_ptr(), ptr);
+        assert_eq!(second[0], 7);
+        assert_eq!(pool.idle_buffers(), 0);
+
+        let third = pool.get().unwrap();
+        assert_ne!(third.as_ptr(), ptr);
+        drop((second, third));
+        assert_eq!(pool.idle_buffers(), 2);
+
+        let detached = pool.get().unwrap().detach();
+        assert_eq!(pool.idle_buffers(), 1);
+        drop(detached);
+
+        pool.clear();
+        assert_eq!(pool.idle_buffers(), 0);
+    }
+}
@@ -81,6 +81,36 @@ pub unsafe fn cuda_malloc_async<T: DeviceCopy>(
     Ok(DevicePointer::from_raw(ptr as driver_sys::CUdeviceptr))
 }
 
+/// Unsafe wrapper around `cuMemAllocFromPoolAsync` which queues a memory allocation operation on a
+/// stream, allocating from the default memory pool of the current context's device.
+///
+/// Unlike [`cuda_malloc_async`], which allocates from whichever pool is currently set for the
+/// device, this always uses the pool returned by `cuDeviceGetDefaultMemPool`. The memory can be
+/// freed with [`cuda_free_async`].
+///
+/// # Safety
+///
+/// The memory behind the returned pointer must not be used in any way until the
+/// allocation actually takes place in the stream.
+pub unsafe fn cuda_malloc_default_pool_async<T: DeviceCopy>(
+    stream: &Stream,
+    count: usize,
+) -> CudaResult<DevicePointer<T>> {
+    let size = count.checked_mul(mem::size_of::<T>()).unwrap_or(0);
+    if size == 0 {
+        return Err(CudaError::InvalidMemoryAllocation);
+    }
+
+    let mut device = 0;
+    driver_sys::cuCtxGetDevice(&mut device).to_result()?;
+    let mut pool = ptr::null_mut();
+    driver_sys::cuDeviceGetDefaultMemPool(&mut pool, device).to_result()?;
+
+    let mut ptr = 0;
+    driver_sys::cuMemAllocFromPoolAsync(&mut ptr, size, pool, stream.as_inner()).to_result()?;
+    Ok(DevicePointer::from_raw(ptr))
+}
+
 /// Unsafe wrapper around `cuMemFreeAsync` which queues a memory allocation free operation on a stream.
 /// Retains all of the unsafe semantics of [`cuda_free`] with the extra requirement that the memory
 /// must not be used after it is dropped. Therefore, proper stream ordering semantics must be
@@ -273,8 +303,8 @@ pub unsafe fn cuda_free_unified<T: DeviceCopy>(mut p: UnifiedPointer<T>) -> Cuda
     Ok(())
 }
 
-/// Unsafe wrapper around the `cuMemAllocHost` function, which allocates some page-locked host memory
-/// and returns a raw pointer pointing to it. The memory is not cleared.
+/// Unsafe wrapper around the `cuMemHostAlloc` function without flags, which allocates some
+/// page-locked host memory and returns a raw pointer pointing to it. The memory is not cleared.
 ///
 /// Note that `count` is in units of T; thus a `count` of 3 will allocate `3 * size_of::<T>()` bytes
 /// of memory.
@@ -285,7 +315,8 @@ pub unsafe fn cuda_free_unified<T: DeviceCopy>(mut p: UnifiedPointer<T>) -> Cuda
 ///
 /// If allocating memory fails, returns the CUDA error value.
 /// If the number of bytes to allocate is zero (either because count is zero or because T is a
-/// zero-sized type), or if the size of the allocation would overflow a usize, returns InvalidValue.
+/// zero-sized type), or if the size of the allocation would overflow a usize, returns
+/// InvalidMemoryAllocation.
 ///
 /// # Safety
 ///
@@ -305,13 +336,72 @@ pub unsafe fn cuda_free_unified<T: DeviceCopy>(mut p: UnifiedPointer<T>) -> Cuda
 /// }
 /// ```
 pub unsafe fn cuda_malloc_locked<T>(count: usize) -> CudaResult<*mut T> {
+    cuda_malloc_locked_with_flags(count, HostAllocFlags::empty())
+}
+
+bitflags::bitflags! {
+    /// Flags for allocating page-locked host memory with [`cuda_malloc_locked_with_flags`].
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct HostAllocFlags: u32 {
+        /// The memory is considered page-locked by every context, not just the one which was
+        /// current when it was allocated.
+        const PORTABLE = 0x01;
+
+        /// The memory is mapped into the address space of the device, the device pointer can be
+        /// obtained with [`LockedBuffer::as_device_ptr`](crate::memory::LockedBuffer::as_device_ptr).
+        const DEVICE_MAP = 0x02;
+
+        /// The memory is allocated as write-combined, which frees up the host's caches and makes
+        /// transfers to the device more efficient. Reading write-combined memory from the host
+        /// is prohibitively slow, so it should only be used for memory the host only writes to,
+        /// such as staging buffers for host to device copies.
+        const WRITE_COMBINED = 0x04;
+    }
+}
+
+/// Unsafe wrapper around the `cuMemHostAlloc` function, which allocates some page-locked host
+/// memory with the given `flags` and returns a raw pointer pointing to it. The memory is not
+/// cleared.
+///
+/// This is the same as [`cuda_malloc_locked`] with the exception of the flags. Memory buffers
+/// allocated using `cuda_malloc_locked_with_flags` must be freed using
+/// [`cuda_free_locked`](fn.cuda_free_locked.html).
+///
+/// # Errors
+///
+/// If allocating memory fails, returns the CUDA error value.
+/// If the number of bytes to allocate is zero (either because count is zero or because T is a
+/// zero-sized type), or if the size of the allocation would overflow a usize, returns
+/// InvalidMemoryAllocation.
+///
+/// # Safety
+///
+/// Since the allocated memory is not initialized, the caller must ensure that it is initialized
+/// before reading from it in any way. Additionally, the caller must ensure that the memory
+/// allocated is freed using `cuda_free_locked`, or the memory will be leaked.
+///
+/// # Examples
+///
+/// ```
+/// # let _context = cust::quick_init().unwrap();
+/// use cust::memory::*;
+/// unsafe {
+///     // Allocate space for 5 u64s which can be used from any context.
+///     let locked_buffer = cuda_malloc_locked_with_flags::<u64>(5, HostAllocFlags::PORTABLE).unwrap();
+///     cuda_free_locked(locked_buffer).unwrap();
+/// }
+/// ```
+pub unsafe fn cuda_malloc_locked_with_flags<T>(
+    count: usize,
+    flags: HostAllocFlags,
+) -> CudaResult<*mut T> {
     let size = count.checked_mul(mem::size_of::<T>()).unwrap_or(0);
     if size == 0 {
         return Err(CudaError::InvalidMemoryAllocation);
     }
 
     let mut ptr: *mut c_void = ptr::null_mut();
-    driver_sys::cuMemAllocHost(&mut ptr as *mut *mut c_void, size).to_result()?;
+    driver_sys::cuMemHostAlloc(&mut ptr as *mut *mut c_void, size, flags.bits()).to_result()?;
     let ptr = ptr as *mut T;
     Ok(ptr)
 }
@@ -325,7 +415,8 @@ pub unsafe fn cuda_malloc_locked<T>(count: usize) -> CudaResult<*mut T> {
 ///
 /// # Safety
 ///
-/// The given pointer must have been allocated with `cuda_malloc_locked`, or null.
+/// The given pointer must have been allocated with `cuda_malloc_locked` or
+/// `cuda_malloc_locked_with_flags`, or null.
 /// The caller is responsible for ensuring that no other pointers to the deallocated buffer exist.
 ///
 /// # Examples
@@ -74,14 +74,21 @@
 //! ensure that the memory allocation is safely cleaned up.
 
 pub mod array;
+pub mod pool;
 
+mod allocator;
+mod copy;
 mod device;
+mod ipc;
 mod locked;
 mod malloc;
 mod pointer;
 mod unified;
 
+pub use self::allocator::*;
+pub use self::copy::*;
 pub use self::device::*;
+pub use self::ipc::*;
 pub use self::locked::*;
 pub use self::malloc::*;
 pub use self::pointer::*;
@@ -107,7 +114,7 @@ pub trait GpuBuffer<T: DeviceCopy>: private::Sealed {
     fn len(&self) -> usize;
 }
 
-impl<T: DeviceCopy> GpuBuffer<T> for DeviceBuffer<T> {
+impl<T: DeviceCopy, A: Allocator> GpuBuffer<T> for DeviceBuffer<T, A> {
     fn as_device_ptr(&self) -> DevicePointer<T> {
         self.as_slice().as_device_ptr()
     }
@@ -175,7 +182,7 @@ impl<T: DeviceCopy> DeviceMemory for DeviceVariable<T> {
     }
 }
 
-impl<T: DeviceCopy> DeviceMemory for DeviceBuffer<T> {
+impl<T: DeviceCopy, A: Allocator> DeviceMemory for DeviceBuffer<T, A> {
     fn as_raw_ptr(&self) -> driver_sys::CUdeviceptr {
         self.as_device_ptr().as_raw()
     }
@@ -196,11 +203,11 @@ impl<T: DeviceCopy> DeviceMemory for DeviceSlice<T> {
 }
 
 mod private {
-    use super::{DeviceBox, DeviceBuffer, DeviceCopy, UnifiedBox, UnifiedBuffer};
+    use super::{Allocator, DeviceBox, DeviceBuffer, DeviceCopy, UnifiedBox, UnifiedBuffer};
 
     pub trait Sealed {}
     impl<T: DeviceCopy> Sealed for UnifiedBuffer<T> {}
-    impl<T: DeviceCopy> Sealed for DeviceBuffer<T> {}
+    impl<T: DeviceCopy, A: Allocator> Sealed for DeviceBuffer<T, A> {}
     impl<T: DeviceCopy> Sealed for UnifiedBox<T> {}
     impl<T: DeviceCopy> Sealed for DeviceBox<T> {}
 }
@@ -413,3 +420,264 @@ pub fn mem_get_info() -> CudaResult<(usize, usize)> {
     }
     Ok((mem_free, mem_total))
 }
+
+/// The precision of [`FragmentationReport::largest_free_block`].
+const FRAGMENTATION_PROBE_GRANULARITY: usize = 2 * 1024 * 1024;
+
+/// An estimate of how fragmented the memory of the current device is, see
+/// [`fragmentation_report`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FragmentationReport {
+    /// The total amount of memory of the device in bytes.
+    pub total: usize,
+    /// The amount of free memory in bytes, as reported by [`mem_get_info`].
+    pub free: usize,
+    /// The size in bytes of the largest block that could be allocated at once. This is rounded
+    /// down to a multiple of 2 MiB.
+    pub largest_free_block: usize,
+    /// The memory reserved by the default memory pool of the device in bytes, or `None` if the
+    /// device does not support memory pools. Memory reserved by the pool is not reported as free
+    /// even when no pool allocation uses it.
+    pub pool_reserved: Option<usize>,
+    /// The memory of the default memory pool of the device in use by allocations in bytes, or
+    /// `None` if the device does not support memory pools.
+    pub pool_used: Option<usize>,
+}
+
+impl FragmentationReport {
+    /// The fraction of free memory which cannot be allocated as a single block, from `0.0` when
+    /// all free memory can be allocated at once to `1.0` when nothing can be allocated.
+    pub fn fragmentation(&self) -> f64 {
+        if self.free == 0 {
+            return 0.0;
+        }
+        1.0 - (self.largest_free_block as f64 / self.free as f64).min(1.0)
+    }
+}
+
+impl std::fmt::Display for FragmentationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const MIB: usize = 1024 * 1024;
+
+        write!(
+            f,
+            "{} MiB free of {} MiB, largest free block {} MiB ({:.1}% fragmented)",
+            self.free / MIB,
+            self.total / MIB,
+            self.largest_free_block / MIB,
+            self.fragmentation() * 100.0,
+        )?;
+        if let (Some(reserved), Some(used)) = (self.pool_reserved, self.pool_used) {
+            write!(
+                f,
+                ", default pool {} MiB reserved, {} MiB used",
+                reserved / MIB,
+                used / MIB
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Estimates how fragmented the memory of the current device is, to diagnose allocations failing
+/// with [`CudaError::OutOfMemory`] while plenty of memory is free.
+///
+/// The largest allocatable block is found by repeatedly allocating and immediately freeing blocks
+/// of decreasing size, so this takes a few milliseconds and is only meant for diagnostics, such as
+/// logging after an allocation failed unexpectedly. Allocations made concurrently by other threads
+/// or processes can make the estimate inaccurate.
+///
+/// # Examples
+///
+/// ```
+/// # let _context = cust::quick_init().unwrap();
+/// use cust::memory::fragmentation_report;
+///
+/// let report = fragmentation_report().unwrap();
+/// assert!(report.largest_free_block <= report.free);
+/// println!("{}", report);
+/// ```
+pub fn fragmentation_report() -> CudaResult<FragmentationReport> {
+    let (free, total) = mem_get_info()?;
+
+    let can_allocate = |size: usize| -> CudaResult<bool> {
+        match unsafe { cuda_malloc::<u8>(size) } {
+            Ok(ptr) => unsafe { cuda_free(ptr) }.map(|_| true),
+            Err(CudaError::OutOfMemory) => Ok(false),
+            Err(e) => Err(e),
+        }
+    };
+
+    // binary search between a size known to be allocatable and one known not to be.
+    let granularity = FRAGMENTATION_PROBE_GRANULARITY;
+    let mut low = 0;
+    let mut high = free - free % granularity;
+    if !can_allocate(high.max(granularity))? {
+        while high - low > granularity {
+            let mid = low + (high - low) / 2;
+            let mid = mid - mid % granularity;
+            if can_allocate(mid.max(granularity))? {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        high = low;
+    }
+
+    let (pool_reserved, pool_used) = unsafe {
+        let pool_attribute = |pool, attribute| {
+            let mut value = 0u64;
+            driver_sys::cuMemPoolGetAttribute(
+                pool,
+                attribute,
+                &mut value as *mut u64 as *mut c_void,
+            )
+            .to_result()
+            .map(|_| value as usize)
+        };
+
+        let mut device = 0;
+        driver_sys::cuCtxGetDevice(&mut device).to_result()?;
+        let mut pool = std::ptr::null_mut();
+        match driver_sys::cuDeviceGetDefaultMemPool(&mut pool, device).to_result() {
+            Ok(()) => (
+                Some(pool_attribute(
+                    pool,
+                    driver_sys::CUmemPool_attribute::CU_MEMPOOL_ATTR_RESERVED_MEM_CURRENT,
+                )?),
+                Some(pool_attribute(
+                    pool,
+                    driver_sys::CUmemPool_attribute::CU_MEMPOOL_ATTR_USED_MEM_CURRENT,
+                )?),
+            ),
+            Err(_) => (None, None),
+        }
+    };
+
+    Ok(FragmentationReport {
+        total,
+        free,
+        largest_free_block: high,
+        pool_reserved,
+        pool_used,
+    })
+}
+
+/// The kind of memory a pointer refers to, as reported by [`pointer_attributes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MemoryType {
+    /// Host memory that was allocated or registered with CUDA, eg. page-locked memory.
+    Host,
+    /// Device memory.
+    Device,
+    /// A CUDA array.
+    Array,
+    /// Unified (managed) memory. The driver reports it as device memory, it is told apart by
+    /// `CU_POINTER_ATTRIBUTE_IS_MANAGED`.
+    Unified,
+}
+
+/// Information about the memory a pointer refers to, see [`pointer_attributes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PointerAttributes {
+    /// The kind of memory the pointer refers to, or `None` if the pointer is not known to CUDA,
+    /// such as ordinary pageable host memory.
+    pub memory_type: Option<MemoryType>,
+    /// The ordinal of the device the memory was allocated on or registered with, if any.
+    pub device_ordinal: Option<i32>,
+    /// Whether the pointer refers to managed memory.
+    pub is_managed: bool,
+    /// The address through which the memory can be accessed from the device, if any.
+    pub device_pointer: Option<driver_sys::CUdeviceptr>,
+    /// The address through which the memory can be accessed from the host, if any.
+    pub host_pointer: Option<*mut c_void>,
+}
+
+impl PointerAttributes {
+    /// Returns whether the pointer refers to device memory, which includes managed memory.
+    pub fn is_device(&self) -> bool {
+        matches!(
+            self.memory_type,
+            Some(MemoryType::Device | MemoryType::Unified)
+        )
+    }
+
+    /// Returns whether the pointer refers to host memory that is page-locked or registered with
+    /// CUDA.
+    pub fn is_pinned_host(&self) -> bool {
+        self.memory_type == Some(MemoryType::Host)
+    }
+}
+
+/// Queries what kind of memory `ptr` refers to.
+///
+/// Unlike most CUDA functions this does not fail for pointers that CUDA does not know about,
+/// those are reported with a `memory_type` of `None`. Device pointers can be passed in through
+/// [`DevicePointer::as_ptr`].
+///
+/// # Examples
+///
+/// ```
+/// # let _context = cust::quick_init().unwrap();
+/// use cust::memory::*;
+///
+/// let buffer = DeviceBuffer::from_slice(&[1u32, 2, 3]).unwrap();
+/// let attributes = pointer_attributes(buffer.as_device_ptr().as_ptr()).unwrap();
+/// assert!(attributes.is_device());
+///
+/// let host = vec![1u32, 2, 3];
+/// let attributes = pointer_attributes(host.as_ptr()).unwrap();
+/// assert_eq!(attributes.memory_type, None);
+/// ```
+pub fn pointer_attributes<T>(ptr: *const T) -> CudaResult<PointerAttributes> {
+    use driver_sys::CUpointer_attribute::*;
+
+    let mut memory_type = 0u32;
+    let mut device_ordinal = -1i32;
+    let mut is_managed = 0u32;
+    let mut device_pointer: driver_sys::CUdeviceptr = 0;
+    let mut host_pointer: *mut c_void = std::ptr::null_mut();
+
+    let mut attributes = [
+        CU_POINTER_ATTRIBUTE_MEMORY_TYPE,
+        CU_POINTER_ATTRIBUTE_DEVICE_ORDINAL,
+        CU_POINTER_ATTRIBUTE_IS_MANAGED,
+        CU_POINTER_ATTRIBUTE_DEVICE_POINTER,
+        CU_POINTER_ATTRIBUTE_HOST_POINTER,
+    ];
+    let mut data = [
+        &mut memory_type as *mut u32 as *mut c_void,
+        &mut device_ordinal as *mut i32 as *mut c_void,
+        &mut is_managed as *mut u32 as *mut c_void,
+        &mut device_pointer as *mut driver_sys::CUdeviceptr as *mut c_void,
+        &mut host_pointer as *mut *mut c_void as *mut c_void,
+    ];
+
+    unsafe {
+        driver_sys::cuPointerGetAttributes(
+            attributes.len() as u32,
+            attributes.as_mut_ptr(),
+            data.as_mut_ptr(),
+            ptr as driver_sys::CUdeviceptr,
+        )
+        .to_result()?;
+    }
+
+    let is_managed = is_managed != 0;
+    let memory_type = match memory_type {
+        1 => Some(MemoryType::Host),
+        2 if is_managed => Some(MemoryType::Unified),
+        2 => Some(MemoryType::Device),
+        3 => Some(MemoryType::Array),
+        _ => None,
+    };
+
+    Ok(PointerAttributes {
+        memory_type,
+        device_ordinal: (memory_type.is_some() && device_ordinal >= 0).then_some(device_ordinal),
+        is_managed,
+        device_pointer: (device_pointer != 0).then_some(device_pointer),
+        host_pointer: (!host_pointer.is_null()).then_some(host_pointer),
+    })
+}
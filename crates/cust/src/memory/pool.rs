@@ -0,0 +1,375 @@
+//! Stream-ordered memory pools.
+//!
+//! A [`MemoryPool`] keeps the memory of freed allocations around and hands it out again to later
+//! allocations made with [`MemoryPool::alloc_async`], so allocating in a hot loop does not go
+//! through the driver every time. Allocations and frees are queued on a stream, like kernel
+//! launches and asynchronous copies.
+
+use std::ffi::c_void;
+use std::mem::{self, size_of};
+use std::ptr;
+
+use cust_raw::driver_sys;
+
+use crate::device::Device;
+use crate::error::{CudaError, CudaResult, DropResult, ToResult};
+use crate::memory::allocator::DRIVER_ALIGNMENT;
+use crate::memory::{Allocator, DeviceBuffer, DeviceCopy, DevicePointer};
+use crate::stream::Stream;
+
+/// The properties of a [`MemoryPool`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MemPoolProps {
+    /// The device whose memory the pool allocates.
+    pub device: Device,
+}
+
+impl MemPoolProps {
+    /// Creates the properties of a pool allocating memory of `device`.
+    pub fn new(device: Device) -> Self {
+        Self { device }
+    }
+}
+
+/// An attribute of a [`MemoryPool`], see [`MemoryPool::set_attribute`] and
+/// [`MemoryPool::get_attribute`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MemPoolAttr {
+    /// The amount of reserved memory in bytes the pool holds on to before trying to release memory
+    /// back to the OS when a stream, event or context is synchronized. Defaults to zero, so a pool
+    /// releases all unused memory on every synchronization unless this is raised.
+    ReleaseThreshold,
+    /// Whether memory freed on one stream may be reused by an allocation on another stream which
+    /// waits for an event recorded after the free. Non-zero means enabled.
+    ReuseFollowEventDependencies,
+    /// Whether memory may be reused as soon as the free is known to have completed, without
+    /// any dependency between the streams. Non-zero means enabled.
+    ReuseAllowOpportunistic,
+    /// Whether the driver may insert dependencies between streams to reuse memory freed on
+    /// another stream. Non-zero means enabled.
+    ReuseAllowInternalDependencies,
+    /// The amount of memory in bytes currently reserved by the pool. Read only.
+    ReservedMemCurrent,
+    /// The highest amount of memory in bytes reserved by the pool since the high watermark was
+    /// last reset, which is done by setting it to zero.
+    ReservedMemHigh,
+    /// The amount of memory in bytes currently used by allocations from the pool. Read only.
+    UsedMemCurrent,
+    /// The highest amount of memory in bytes used by allocations from the pool since the high
+    /// watermark was last reset, which is done by setting it to zero.
+    UsedMemHigh,
+}
+
+impl MemPoolAttr {
+    fn into_raw(self) -> driver_sys::CUmemPool_attribute {
+        use driver_sys::CUmemPool_attribute::*;
+
+        match self {
+            Self::ReleaseThreshold => CU_MEMPOOL_ATTR_RELEASE_THRESHOLD,
+            Self::ReuseFollowEventDependencies => CU_MEMPOOL_ATTR_REUSE_FOLLOW_EVENT_DEPENDENCIES,
+            Self::ReuseAllowOpportunistic => CU_MEMPOOL_ATTR_REUSE_ALLOW_OPPORTUNISTIC,
+            Self::ReuseAllowInternalDependencies => {
+                CU_MEMPOOL_ATTR_REUSE_ALLOW_INTERNAL_DEPENDENCIES
+            }
+            Self::ReservedMemCurrent => CU_MEMPOOL_ATTR_RESERVED_MEM_CURRENT,
+            Self::ReservedMemHigh => CU_MEMPOOL_ATTR_RESERVED_MEM_HIGH,
+            Self::UsedMemCurrent => CU_MEMPOOL_ATTR_USED_MEM_CURRENT,
+            Self::UsedMemHigh => CU_MEMPOOL_ATTR_USED_MEM_HIGH,
+        }
+    }
+
+    /// Whether the driver stores the attribute as an `int` rather than a `cuuint64_t`.
+    fn is_flag(self) -> bool {
+        matches!(
+            self,
+            Self::ReuseFollowEventDependencies
+                | Self::ReuseAllowOpportunistic
+                | Self::ReuseAllowInternalDependencies
+        )
+    }
+}
+
+/// A stream-ordered memory pool, allocating device memory with `cuMemAllocFromPoolAsync`.
+///
+/// # Examples
+///
+/// ```
+/// # use cust::memory::pool::{MemoryPool, MemPoolAttr, MemPoolProps};
+/// # use cust::memory::*;
+/// # use cust::stream::{Stream, StreamFlags};
+/// # use std::error::Error;
+/// #
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// # let _context = cust::quick_init()?;
+/// let device = cust::device::Device::get_device(0)?;
+/// let pool = MemoryPool::new(MemPoolProps::new(device))?;
+/// // keep up to 64 MiB around between iterations instead of releasing it.
+/// pool.set_attribute(MemPoolAttr::ReleaseThreshold, 64 << 20)?;
+///
+/// let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+/// let mut host = [0u32; 4];
+/// for _ in 0..10 {
+///     unsafe {
+///         let mut buffer = pool.alloc_async::<u32>(4, &stream)?;
+///         buffer.async_copy_from(&[1, 2, 3, 4], &stream)?;
+///         buffer.async_copy_to(&mut host, &stream)?;
+///         // dropping the buffer queues the free on `stream`.
+///     }
+/// }
+/// stream.synchronize()?;
+/// assert_eq!(host, [1, 2, 3, 4]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct MemoryPool {
+    inner: driver_sys::CUmemoryPool,
+}
+
+unsafe impl Send for MemoryPool {}
+unsafe impl Sync for MemoryPool {}
+
+impl MemoryPool {
+    /// Creates a new memory pool with the given properties.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CudaError::NotSupported`] if the device does not support memory pools.
+    pub fn new(props: MemPoolProps) -> CudaResult<Self> {
+        unsafe {
+            let mut raw: driver_sys::CUmemPoolProps = mem::zeroed();
+            raw.allocType = driver_sys::CUmemAllocationType::CU_MEM_ALLOCATION_TYPE_PINNED;
+            raw.handleTypes = driver_sys::CUmemAllocationHandleType::CU_MEM_HANDLE_TYPE_NONE;
+            raw.location = driver_sys::CUmemLocation {
+                type_: driver_sys::CUmemLocationType::CU_MEM_LOCATION_TYPE_DEVICE,
+                id: props.device.as_raw(),
+            };
+
+            let mut inner = ptr::null_mut();
+            driver_sys::cuMemPoolCreate(&mut inner, &raw).to_result()?;
+            Ok(Self { inner })
+        }
+    }
+
+    /// Sets an attribute of the pool. Flag attributes are enabled by any non-zero value.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CudaError::InvalidValue`] if the attribute cannot be set, such as
+    /// [`MemPoolAttr::UsedMemCurrent`], or if a high watermark is set to something other than zero.
+    pub fn set_attribute(&self, attr: MemPoolAttr, value: u64) -> CudaResult<()> {
+        unsafe {
+            if attr.is_flag() {
+                let mut value = (value != 0) as i32;
+                driver_sys::cuMemPoolSetAttribute(
+                    self.inner,
+                    attr.into_raw(),
+                    &mut value as *mut i32 as *mut c_void,
+                )
+                .to_result()
+            } else {
+                let mut value = value;
+                driver_sys::cuMemPoolSetAttribute(
+                    self.inner,
+                    attr.into_raw(),
+                    &mut value as *mut u64 as *mut c_void,
+                )
+                .to_result()
+            }
+        }
+    }
+
+    /// Returns the value of an attribute of the pool. Flag attributes are `1` if enabled and `0`
+    /// otherwise.
+    pub fn get_attribute(&self, attr: MemPoolAttr) -> CudaResult<u64> {
+        unsafe {
+            if attr.is_flag() {
+                let mut value = 0i32;
+                driver_sys::cuMemPoolGetAttribute(
+                    self.inner,
+                    attr.into_raw(),
+                    &mut value as *mut i32 as *mut c_void,
+                )
+                .to_result()?;
+                Ok((value != 0) as u64)
+            } else {
+                let mut value = 0u64;
+                driver_sys::cuMemPoolGetAttribute(
+                    self.inner,
+                    attr.into_raw(),
+                    &mut value as *mut u64 as *mut c_void,
+                )
+                .to_result()?;
+                Ok(value)
+            }
+        }
+    }
+
+    /// Releases unused memory held by the pool back to the OS, until it holds at most
+    /// `min_bytes_to_keep` bytes of reserved memory.
+    pub fn trim_to(&self, min_bytes_to_keep: usize) -> CudaResult<()> {
+        unsafe { driver_sys::cuMemPoolTrimTo(self.inner, min_bytes_to_keep).to_result() }
+    }
+
+    /// Allocates a buffer of `len` elements from the pool, queued on `stream`.
+    ///
+    /// The buffer remembers the pool and the stream, and queues the free on `stream` when it is
+    /// dropped or passed to [`MemoryPool::free_async`], so the memory can be reused by later
+    /// allocations on that stream without synchronizing.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CudaError::InvalidMemoryAllocation`] if `len * size_of::<T>()` overflows.
+    ///
+    /// # Safety
+    ///
+    /// The memory only exists in the stream order of `stream`. It must not be used by work queued
+    /// on another stream without that stream first waiting on an event recorded on `stream` after
+    /// this call, and must not be used after the buffer has been dropped, which only queues the
+    /// free. The contents are uninitialized and must be written before being read.
+    pub unsafe fn alloc_async<'a, T: DeviceCopy>(
+        &'a self,
+        len: usize,
+        stream: &'a Stream,
+    ) -> CudaResult<DeviceBuffer<T, StreamPoolAllocator<'a>>> {
+        DeviceBuffer::uninitialized_in(len, StreamPoolAllocator { pool: self, stream })
+    }
+
+    /// Queues freeing a buffer allocated with [`MemoryPool::alloc_async`] on the stream it was
+    /// allocated on, returning any error, which dropping the buffer would ignore.
+    pub fn free_async<T: DeviceCopy>(
+        &self,
+        buffer: DeviceBuffer<T, StreamPoolAllocator<'_>>,
+    ) -> CudaResult<()> {
+        let (ptr, len, alloc) = buffer.into_raw_parts_in();
+        if ptr.is_null() {
+            return Ok(());
+        }
+        unsafe { alloc.dealloc(ptr.cast(), len * size_of::<T>(), mem::align_of::<T>()) }
+    }
+
+    /// Returns the raw handle of the pool.
+    pub fn as_inner(&self) -> driver_sys::CUmemoryPool {
+        self.inner
+    }
+
+    /// Destroys a `MemoryPool`, returning an error.
+    ///
+    /// Destroying a pool with allocations which have not been freed yet defers destroying it until
+    /// they have been.
+    pub fn drop(mut pool: MemoryPool) -> DropResult<MemoryPool> {
+        if pool.inner.is_null() {
+            return Ok(());
+        }
+
+        unsafe {
+            let inner = mem::replace(&mut pool.inner, ptr::null_mut());
+            match driver_sys::cuMemPoolDestroy(inner).to_result() {
+                Ok(()) => {
+                    mem::forget(pool);
+                    Ok(())
+                }
+                Err(e) => Err((e, MemoryPool { inner })),
+            }
+        }
+    }
+}
+
+impl Drop for MemoryPool {
+    fn drop(&mut self) {
+        if self.inner.is_null() {
+            return;
+        }
+
+        unsafe {
+            let inner = mem::replace(&mut self.inner, ptr::null_mut());
+            let _ = driver_sys::cuMemPoolDestroy(inner);
+        }
+    }
+}
+
+/// The [`Allocator`] of buffers returned by [`MemoryPool::alloc_async`], which allocates from a
+/// pool and frees back to it in the order of a stream.
+#[derive(Debug)]
+pub struct StreamPoolAllocator<'a> {
+    pool: &'a MemoryPool,
+    stream: &'a Stream,
+}
+
+impl StreamPoolAllocator<'_> {
+    /// Returns the pool the allocations are made from.
+    pub fn pool(&self) -> &MemoryPool {
+        self.pool
+    }
+
+    /// Returns the stream the allocations and frees are queued on.
+    pub fn stream(&self) -> &Stream {
+        self.stream
+    }
+}
+
+unsafe impl Allocator for StreamPoolAllocator<'_> {
+    fn alloc(&self, size: usize, align: usize) -> CudaResult<DevicePointer<u8>> {
+        if align > DRIVER_ALIGNMENT {
+            return Err(CudaError::InvalidMemoryAllocation);
+        }
+        let mut ptr = 0;
+        unsafe {
+            driver_sys::cuMemAllocFromPoolAsync(
+                &mut ptr,
+                size,
+                self.pool.inner,
+                self.stream.as_inner(),
+            )
+            .to_result()?;
+        }
+        Ok(DevicePointer::from_raw(ptr))
+    }
+
+    unsafe fn dealloc(
+        &self,
+        ptr: DevicePointer<u8>,
+        _size: usize,
+        _align: usize,
+    ) -> CudaResult<()> {
+        driver_sys::cuMemFreeAsync(ptr.as_raw(), self.stream.as_inner()).to_result()
+    }
+}
+
+#[cfg(test)]
+mod test_pool {
+    use super::*;
+    use crate::memory::AsyncCopyDestination;
+    use crate::stream::StreamFlags;
+
+    #[test]
+    fn test_pool_alloc_round_trip() {
+        let _context = crate::quick_init().unwrap();
+        let device = Device::get_device(0).unwrap();
+        let pool = MemoryPool::new(MemPoolProps::new(device)).unwrap();
+        let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+        let mut host = [0u64; 3];
+        unsafe {
+            let mut buffer = pool.alloc_async::<u64>(3, &stream).unwrap();
+            buffer.async_copy_from(&[1, 2, 3], &stream).unwrap();
+            buffer.async_copy_to(&mut host, &stream).unwrap();
+            pool.free_async(buffer).unwrap();
+        }
+        stream.synchronize().unwrap();
+        assert_eq!(host, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_pool_release_threshold() {
+        let _context = crate::quick_init().unwrap();
+        let device = Device::get_device(0).unwrap();
+        let pool = MemoryPool::new(MemPoolProps::new(device)).unwrap();
+        pool.set_attribute(MemPoolAttr::ReleaseThreshold, 1 << 20)
+            .unwrap();
+        assert_eq!(
+            pool.get_attribute(MemPoolAttr::ReleaseThreshold).unwrap(),
+            1 << 20
+        );
+    }
+}
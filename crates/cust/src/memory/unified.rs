@@ -4,7 +4,7 @@ use std::convert::{AsMut, AsRef};
 use std::fmt::{self, Display, Pointer};
 use std::hash::{Hash, Hasher};
 use std::mem;
-use std::ops::{Deref, DerefMut};
+use std::ops::{Deref, DerefMut, RangeBounds};
 use std::ptr;
 use std::slice;
 
@@ -476,6 +476,89 @@ impl<T: DeviceCopy> UnifiedBuffer<T> {
         self.buf
     }
 
+    /// Queries where the pages of a range of elements of this buffer reside and which advice
+    /// applies to them.
+    ///
+    /// This is mostly useful to check that calls to the [`MemoryAdvise`] functions had the
+    /// intended effect.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds of the buffer.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let _context = cust::quick_init().unwrap();
+    /// # use cust::prelude::*;
+    /// use cust::memory::*;
+    /// let device = Device::get_device(0)?;
+    /// let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+    /// let x = UnifiedBuffer::from_slice(&[0u32; 1024])?;
+    ///
+    /// x.preferred_location(Some(device))?;
+    /// x.prefetch_to_device(&stream, &device)?;
+    /// stream.synchronize()?;
+    ///
+    /// let attributes = x.range_attributes(..)?;
+    /// assert_eq!(attributes.preferred_location, Some(MemoryLocation::Device(device)));
+    /// assert_eq!(attributes.last_prefetch_location, Some(MemoryLocation::Device(device)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn range_attributes(&self, range: impl RangeBounds<usize>) -> CudaResult<RangeAttributes> {
+        let slice = &self.as_slice()[(range.start_bound().cloned(), range.end_bound().cloned())];
+        let ptr = slice.as_ptr() as driver_sys::CUdeviceptr;
+        let size = mem::size_of_val(slice);
+
+        let get = |attribute, data: &mut [i32]| unsafe {
+            driver_sys::cuMemRangeGetAttribute(
+                data.as_mut_ptr().cast(),
+                mem::size_of_val(data),
+                attribute,
+                ptr,
+                size,
+            )
+            .to_result()
+        };
+
+        let mut read_mostly = [0];
+        get(
+            driver_sys::CUmem_range_attribute::CU_MEM_RANGE_ATTRIBUTE_READ_MOSTLY,
+            &mut read_mostly,
+        )?;
+
+        let mut preferred_location = [CU_DEVICE_INVALID];
+        get(
+            driver_sys::CUmem_range_attribute::CU_MEM_RANGE_ATTRIBUTE_PREFERRED_LOCATION,
+            &mut preferred_location,
+        )?;
+
+        let mut last_prefetch_location = [CU_DEVICE_INVALID];
+        get(
+            driver_sys::CUmem_range_attribute::CU_MEM_RANGE_ATTRIBUTE_LAST_PREFETCH_LOCATION,
+            &mut last_prefetch_location,
+        )?;
+
+        // one slot per device plus one for the CPU, unused slots are set to CU_DEVICE_INVALID.
+        let mut accessed_by = vec![CU_DEVICE_INVALID; Device::num_devices()? as usize + 1];
+        get(
+            driver_sys::CUmem_range_attribute::CU_MEM_RANGE_ATTRIBUTE_ACCESSED_BY,
+            &mut accessed_by,
+        )?;
+
+        Ok(RangeAttributes {
+            read_mostly: read_mostly[0] != 0,
+            preferred_location: MemoryLocation::from_raw(preferred_location[0]),
+            last_prefetch_location: MemoryLocation::from_raw(last_prefetch_location[0]),
+            accessed_by: accessed_by
+                .into_iter()
+                .filter_map(MemoryLocation::from_raw)
+                .collect(),
+        })
+    }
+
     /// Creates a `UnifiedBuffer<T>` directly from the raw components of another unified
     /// buffer.
     ///
@@ -602,6 +685,100 @@ impl<T: DeviceCopy> Drop for UnifiedBuffer<T> {
     }
 }
 
+/// `CU_DEVICE_CPU`, the device ordinal used by the driver for the host.
+const CU_DEVICE_CPU: i32 = -1;
+/// `CU_DEVICE_INVALID`, returned by the driver when there is no single location.
+const CU_DEVICE_INVALID: i32 = -2;
+
+/// A processor whose memory unified memory pages can reside in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MemoryLocation {
+    /// The host.
+    Host,
+    /// A device.
+    Device(Device),
+}
+
+impl MemoryLocation {
+    fn as_raw(&self) -> i32 {
+        match self {
+            MemoryLocation::Host => CU_DEVICE_CPU,
+            MemoryLocation::Device(device) => device.as_raw(),
+        }
+    }
+
+    fn from_raw(raw: i32) -> Option<Self> {
+        match raw {
+            CU_DEVICE_CPU => Some(MemoryLocation::Host),
+            device if device >= 0 => Some(MemoryLocation::Device(Device { device })),
+            _ => None,
+        }
+    }
+}
+
+/// Advice about the use of a range of unified memory, given to the driver with
+/// [`MemoryAdvise::advise`].
+///
+/// Every advice applies to a [`MemoryLocation`], the processor which will use the memory. Devices
+/// must have [`DeviceAttribute::ConcurrentManagedAccess`] for the advice to apply to them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum MemAdvise {
+    /// The memory is mostly read and only occasionally written, so processors reading it get a
+    /// read-only copy of the pages they access, see
+    /// [`advise_read_mostly`](MemoryAdvise::advise_read_mostly). The location is ignored.
+    SetReadMostly,
+    /// Undoes [`SetReadMostly`](Self::SetReadMostly). The location is ignored.
+    UnsetReadMostly,
+    /// The pages should preferably reside in the memory of the location, see
+    /// [`preferred_location`](MemoryAdvise::preferred_location).
+    SetPreferredLocation,
+    /// Undoes [`SetPreferredLocation`](Self::SetPreferredLocation). The location is ignored.
+    UnsetPreferredLocation,
+    /// The memory will be accessed by the location, so the pages stay mapped in its page tables
+    /// wherever they reside, which avoids page faults when it first touches them.
+    SetAccessedBy,
+    /// Undoes [`SetAccessedBy`](Self::SetAccessedBy) for the location.
+    UnsetAccessedBy,
+}
+
+impl From<MemAdvise> for driver_sys::CUmem_advise {
+    fn from(advice: MemAdvise) -> Self {
+        use driver_sys::CUmem_advise::*;
+        match advice {
+            MemAdvise::SetReadMostly => CU_MEM_ADVISE_SET_READ_MOSTLY,
+            MemAdvise::UnsetReadMostly => CU_MEM_ADVISE_UNSET_READ_MOSTLY,
+            MemAdvise::SetPreferredLocation => CU_MEM_ADVISE_SET_PREFERRED_LOCATION,
+            MemAdvise::UnsetPreferredLocation => CU_MEM_ADVISE_UNSET_PREFERRED_LOCATION,
+            MemAdvise::SetAccessedBy => CU_MEM_ADVISE_SET_ACCESSED_BY,
+            MemAdvise::UnsetAccessedBy => CU_MEM_ADVISE_UNSET_ACCESSED_BY,
+        }
+    }
+}
+
+/// The state of a range of unified memory, as returned by
+/// [`UnifiedBuffer::range_attributes`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RangeAttributes {
+    /// Whether all pages of the range have been advised to be read mostly, see
+    /// [`MemoryAdvise::advise_read_mostly`].
+    pub read_mostly: bool,
+    /// The preferred location of all pages of the range, see
+    /// [`MemoryAdvise::preferred_location`]. `None` if the pages do not all have the same
+    /// preferred location or have none.
+    pub preferred_location: Option<MemoryLocation>,
+    /// The location the whole range was last prefetched to, see
+    /// [`MemoryAdvise::prefetch_to_device`]. `None` if the pages were not all last prefetched to
+    /// the same location.
+    ///
+    /// This only reports where the last prefetch was requested to, not where the pages reside
+    /// now, nor whether the prefetch has completed.
+    pub last_prefetch_location: Option<MemoryLocation>,
+    /// The processors which have been advised to access the range, see
+    /// [`MemAdvise::SetAccessedBy`].
+    pub accessed_by: Vec<MemoryLocation>,
+}
+
 /// Functions for advising the driver about certain uses of unified memory. Such as advising the driver
 /// to prefetch memory or to treat memory as read-mostly.
 ///
@@ -643,7 +820,7 @@ pub trait MemoryAdvise<T: DeviceCopy>: private::Sealed {
             driver_sys::cuMemPrefetchAsync(
                 slice.as_ptr() as driver_sys::CUdeviceptr,
                 mem_size,
-                -1, // CU_DEVICE_CPU #define
+                CU_DEVICE_CPU,
                 stream.as_inner(),
             )
             .to_result()?;
@@ -771,6 +948,43 @@ pub trait MemoryAdvise<T: DeviceCopy>: private::Sealed {
         }
         Ok(())
     }
+
+    /// Gives `advice` about the use of this memory by `location` to the driver, see [`MemAdvise`]
+    /// for the possible advice.
+    ///
+    /// This covers every advice of the driver, including [`MemAdvise::SetAccessedBy`], which has
+    /// no dedicated function.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let _context = cust::quick_init().unwrap();
+    /// # use cust::prelude::*;
+    /// use cust::memory::*;
+    /// let device = Device::get_device(0)?;
+    /// let x = UnifiedBuffer::from_slice(&[10u32, 20, 30])?;
+    /// // the host reads the results, so keep the pages mapped for it wherever they reside.
+    /// x.advise(MemAdvise::SetAccessedBy, MemoryLocation::Host)?;
+    /// x.advise(MemAdvise::SetPreferredLocation, MemoryLocation::Device(device))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn advise(&self, advice: MemAdvise, location: MemoryLocation) -> CudaResult<()> {
+        let slice = self.as_slice();
+        let mem_size = std::mem::size_of_val(slice);
+
+        unsafe {
+            driver_sys::cuMemAdvise(
+                slice.as_ptr() as driver_sys::CUdeviceptr,
+                mem_size,
+                advice.into(),
+                location.as_raw(),
+            )
+            .to_result()?;
+        }
+        Ok(())
+    }
 }
 
 impl<T: DeviceCopy> MemoryAdvise<T> for UnifiedBox<T> {
@@ -863,6 +1077,51 @@ mod test_unified_buffer {
     struct ZeroSizedType;
     unsafe impl DeviceCopy for ZeroSizedType {}
 
+    #[test]
+    fn test_range_attributes_read_mostly() {
+        let _context = crate::quick_init().unwrap();
+        let buffer = UnifiedBuffer::new(&0u64, 1024).unwrap();
+        assert!(!buffer.range_attributes(..).unwrap().read_mostly);
+
+        buffer.advise_read_mostly(true).unwrap();
+        assert!(buffer.range_attributes(..).unwrap().read_mostly);
+        assert!(buffer.range_attributes(..512).unwrap().read_mostly);
+    }
+
+    #[test]
+    fn test_prefetch_and_advise() {
+        let _context = crate::quick_init().unwrap();
+        let device = Device::get_device(0).unwrap();
+        if !device
+            .get_attribute(DeviceAttribute::ConcurrentManagedAccess)
+            .is_ok_and(|value| value != 0)
+        {
+            return;
+        }
+        let stream = Stream::new(crate::stream::StreamFlags::NON_BLOCKING, None).unwrap();
+        let buffer = UnifiedBuffer::new(&1u32, 1 << 20).unwrap();
+
+        buffer.prefetch_to_device(&stream, &device).unwrap();
+        buffer.prefetch_to_host(&stream).unwrap();
+        stream.synchronize().unwrap();
+        assert_eq!(
+            buffer.range_attributes(..).unwrap().last_prefetch_location,
+            Some(MemoryLocation::Host)
+        );
+
+        let location = MemoryLocation::Device(device);
+        buffer.advise(MemAdvise::SetAccessedBy, location).unwrap();
+        buffer
+            .advise(MemAdvise::SetPreferredLocation, location)
+            .unwrap();
+        let attributes = buffer.range_attributes(..).unwrap();
+        assert_eq!(attributes.accessed_by, [location]);
+        assert_eq!(attributes.preferred_location, Some(location));
+
+        buffer.advise(MemAdvise::UnsetAccessedBy, location).unwrap();
+        assert!(buffer.range_attributes(..).unwrap().accessed_by.is_empty());
+    }
+
     #[test]
     fn test_new() {
         let _context = crate::quick_init().unwrap();
@@ -10,8 +10,8 @@ use std::ptr;
 
 use cust_raw::driver_sys;
 
-use crate::error::{CudaResult, DropResult, ToResult};
-use crate::function::Function;
+use crate::error::{CudaError, CudaResult, DropResult, ToResult};
+use crate::function::{Function, KernelParam};
 use crate::memory::{CopyDestination, DeviceCopy, DevicePointer};
 
 /// A compiled CUDA module, loaded into a context.
@@ -117,7 +117,9 @@ impl ModuleJitOption {
                     raw_vals.push(*level as usize as *mut c_void);
                 }
                 Self::DetermineTargetFromContext => {
+                    // takes no value, but the values must stay in step with the options.
                     raw_opts.push(driver_sys::CUjit_option::CU_JIT_TARGET_FROM_CUCONTEXT);
+                    raw_vals.push(ptr::null_mut());
                 }
                 Self::Target(target) => {
                     raw_opts.push(driver_sys::CUjit_option::CU_JIT_TARGET);
@@ -141,14 +143,57 @@ impl ModuleJitOption {
     }
 }
 
+/// The size of the buffers the JIT compiler writes its logs to in
+/// [`Module::from_ptx_with_options`] and the [`Linker`](crate::link::Linker), longer logs are
+/// truncated.
+pub(crate) const JIT_LOG_SIZE: usize = 8192;
+
+/// An error returned when JIT compiling a module, along with the logs of the compiler.
+///
+/// The [`Display`](fmt::Display) implementation includes the error log, which usually points to
+/// the offending line of PTX.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JitError {
+    /// The error returned by the driver.
+    pub error: CudaError,
+    /// The informational messages of the JIT compiler, such as register usage.
+    pub info_log: String,
+    /// The error messages of the JIT compiler.
+    pub error_log: String,
+}
+
+impl fmt::Display for JitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.error)?;
+        if !self.error_log.is_empty() {
+            write!(f, ": {}", self.error_log)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for JitError {}
+
+impl From<JitError> for CudaError {
+    fn from(error: JitError) -> Self {
+        error.error
+    }
+}
+
+// reads a log written by the JIT compiler, which is nul terminated.
+pub(crate) fn jit_log_to_string(log: &[u8]) -> String {
+    let len = log.iter().position(|&byte| byte == 0).unwrap_or(log.len());
+    String::from_utf8_lossy(&log[..len]).trim_end().to_string()
+}
+
 #[cfg(unix)]
-fn path_to_bytes<P: AsRef<Path>>(path: P) -> Vec<u8> {
+pub(crate) fn path_to_bytes<P: AsRef<Path>>(path: P) -> Vec<u8> {
     use std::os::unix::ffi::OsStrExt;
     path.as_ref().as_os_str().as_bytes().to_vec()
 }
 
 #[cfg(not(unix))]
-fn path_to_bytes<P: AsRef<Path>>(path: P) -> Vec<u8> {
+pub(crate) fn path_to_bytes<P: AsRef<Path>>(path: P) -> Vec<u8> {
     path.as_ref().to_string_lossy().to_string().into_bytes()
 }
 
@@ -301,6 +346,90 @@ impl Module {
         Self::from_ptx_cstr(cstr.as_c_str(), options)
     }
 
+    /// Creates a new module from a PTX string like [`from_ptx`](Self::from_ptx), capturing the
+    /// logs of the JIT compiler.
+    ///
+    /// On failure the returned [`JitError`] holds the error and info logs of the compiler, which
+    /// explain why the PTX was rejected. Use [`from_ptx`](Self::from_ptx) when the logs are not
+    /// needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `string` contains a nul.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cust::*;
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let _ctx = quick_init()?;
+    /// use cust::module::{Module, ModuleJitOption, OptLevel};
+    ///
+    /// let ptx = std::fs::read_to_string("./resources/add.ptx")?;
+    /// let module = Module::from_ptx_with_options(
+    ///     &ptx,
+    ///     &[
+    ///         ModuleJitOption::MaxRegisters(32),
+    ///         ModuleJitOption::OptLevel(OptLevel::O3),
+    ///         ModuleJitOption::DetermineTargetFromContext,
+    ///     ],
+    /// )?;
+    ///
+    /// let error = Module::from_ptx_with_options("not ptx", &[]).unwrap_err();
+    /// println!("{}", error.error_log);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_ptx_with_options<T: AsRef<str>>(
+        string: T,
+        options: &[ModuleJitOption],
+    ) -> Result<Module, JitError> {
+        let cstr = CString::new(string.as_ref())
+            .expect("string given to Module::from_ptx_with_options contained nul bytes");
+
+        let mut info_log = vec![0u8; JIT_LOG_SIZE];
+        let mut error_log = vec![0u8; JIT_LOG_SIZE];
+
+        let (mut raw_opts, mut raw_vals) = ModuleJitOption::into_raw(options);
+        raw_opts.extend([
+            driver_sys::CUjit_option::CU_JIT_INFO_LOG_BUFFER,
+            driver_sys::CUjit_option::CU_JIT_INFO_LOG_BUFFER_SIZE_BYTES,
+            driver_sys::CUjit_option::CU_JIT_ERROR_LOG_BUFFER,
+            driver_sys::CUjit_option::CU_JIT_ERROR_LOG_BUFFER_SIZE_BYTES,
+        ]);
+        // the sizes are passed by value, see `ModuleJitOption::into_raw`.
+        raw_vals.extend([
+            info_log.as_mut_ptr() as *mut c_void,
+            JIT_LOG_SIZE as *mut c_void,
+            error_log.as_mut_ptr() as *mut c_void,
+            JIT_LOG_SIZE as *mut c_void,
+        ]);
+
+        let mut module = Module {
+            inner: ptr::null_mut(),
+        };
+        let result = unsafe {
+            driver_sys::cuModuleLoadDataEx(
+                &mut module.inner as *mut driver_sys::CUmodule,
+                cstr.as_ptr() as *const c_void,
+                raw_opts.len() as c_uint,
+                raw_opts.as_mut_ptr(),
+                raw_vals.as_mut_ptr(),
+            )
+            .to_result()
+        };
+
+        match result {
+            Ok(()) => Ok(module),
+            Err(error) => Err(JitError {
+                error,
+                info_log: jit_log_to_string(&info_log),
+                error_log: jit_log_to_string(&error_log),
+            }),
+        }
+    }
+
     /// Load a module from a normal (rust) string, implicitly making it into
     /// a cstring.
     #[deprecated(
@@ -360,9 +489,10 @@ impl Module {
 
     /// Get a reference to a global symbol, which can then be copied to/from.
     ///
-    /// # Panics:
+    /// # Errors
     ///
-    /// This function panics if the size of the symbol is not the same as the `mem::sizeof<T>()`.
+    /// Returns [`CudaError::NotFound`] if the module has no global called `name`, and
+    /// [`CudaError::InvalidValue`] if the size of the global is not `mem::size_of::<T>()`.
     ///
     /// # Examples
     ///
@@ -382,6 +512,9 @@ impl Module {
     /// let mut host_const = 0;
     /// symbol.copy_to(&mut host_const)?;
     /// assert_eq!(314, host_const);
+    ///
+    /// // `my_constant` is a `u32`, so it cannot be read as a `u64`.
+    /// assert!(module.get_global::<u64>(&name).is_err());
     /// # Ok(())
     /// # }
     /// ```
@@ -397,7 +530,9 @@ impl Module {
                 name.as_ptr(),
             )
             .to_result()?;
-            assert_eq!(size, mem::size_of::<T>());
+            if size != mem::size_of::<T>() {
+                return Err(CudaError::InvalidValue);
+            }
             Ok(Symbol {
                 ptr,
                 module: PhantomData,
@@ -405,6 +540,156 @@ impl Module {
         }
     }
 
+    /// Get the parameters of the kernel `name`, as laid out in its parameter buffer.
+    ///
+    /// This reads the table rustc_codegen_nvvm emits for every kernel when
+    /// `CudaBuilder::emit_kernel_params` is enabled, which can be used to check the arguments of a
+    /// launch with [`KernelArgs::check`](crate::function::KernelArgs::check). Slices take up two
+    /// parameters, a pointer and a length, and zero-sized parameters are left out.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CudaError::NotFound`] if the module has no parameter table for `name`, for
+    /// example because it was not built with `emit_kernel_params`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cust::*;
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let _ctx = quick_init()?;
+    /// use cust::module::Module;
+    /// use std::ffi::CString;
+    ///
+    /// let ptx = CString::new(include_str!("../resources/add.ptx"))?;
+    /// let module = Module::load_from_string(&ptx)?;
+    /// let params = module.get_kernel_params("sum")?;
+    /// assert_eq!(params.len(), 4);
+    /// assert_eq!((params[3].offset, params[3].size), (24, 4));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_kernel_params(&self, name: &str) -> CudaResult<Vec<KernelParam>> {
+        let symbol = CString::new(format!("__rust_cuda_kernel_params_{name}"))
+            .expect("Argument to get_kernel_params had a nul");
+        unsafe {
+            let mut ptr: driver_sys::CUdeviceptr = 0;
+            let mut size: usize = 0;
+            driver_sys::cuModuleGetGlobal(&mut ptr, &mut size, self.inner, symbol.as_ptr())
+                .to_result()?;
+
+            let mut table = vec![0u32; size / mem::size_of::<u32>()];
+            driver_sys::cuMemcpyDtoH(table.as_mut_ptr() as *mut c_void, ptr, size).to_result()?;
+
+            match table.split_first() {
+                Some((&len, params)) if params.len() == 2 * len as usize => Ok(params
+                    .chunks_exact(2)
+                    .map(|param| KernelParam {
+                        offset: param[0] as usize,
+                        size: param[1] as usize,
+                    })
+                    .collect()),
+                _ => Err(CudaError::InvalidImage),
+            }
+        }
+    }
+
+    /// Get how many threads executed each basic block of the kernel `name`, in the layout order of
+    /// its blocks.
+    ///
+    /// This reads the counters rustc_codegen_nvvm emits for every kernel when
+    /// `CudaBuilder::instrument_kernels` is enabled, along with the source line of every block.
+    /// The first block is the entry of the kernel, so its count is the number of threads that ran
+    /// the kernel. Lines are only known when the kernels are built with debuginfo, such as
+    /// `DebugInfo::LineTables`, and are 0 otherwise; several blocks can share a line, like the
+    /// branches of an `if` written on one line. The counts add up over launches until they are
+    /// reset with [`Module::reset_kernel_block_counts`], and must only be read once the launches
+    /// are done.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CudaError::NotFound`] if the module has no counters for `name`, for example
+    /// because it was not built with `instrument_kernels`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cust::*;
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let _ctx = quick_init()?;
+    /// use cust::module::Module;
+    /// use std::ffi::CString;
+    ///
+    /// let ptx = CString::new(include_str!("../resources/add.ptx"))?;
+    /// let module = Module::load_from_string(&ptx)?;
+    /// let blocks = module.get_kernel_block_counts("sum")?;
+    /// assert_eq!(blocks.len(), 3);
+    /// assert_eq!((blocks[1].line, blocks[1].count), (9, 0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_kernel_block_counts(&self, name: &str) -> CudaResult<Vec<BlockCount>> {
+        let counts = self.read_kernel_table::<u64>("calls", name)?;
+        let lines = self.read_kernel_table::<u32>("lines", name)?;
+        if counts.len() != lines.len() {
+            return Err(CudaError::InvalidImage);
+        }
+        Ok(lines
+            .into_iter()
+            .zip(counts)
+            .map(|(line, count)| BlockCount { line, count })
+            .collect())
+    }
+
+    /// Sets the block counters of the kernel `name` back to zero, see
+    /// [`Module::get_kernel_block_counts`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CudaError::NotFound`] if the module has no counters for `name`.
+    pub fn reset_kernel_block_counts(&self, name: &str) -> CudaResult<()> {
+        let (ptr, size) = self.kernel_table("calls", name)?;
+        unsafe { driver_sys::cuMemsetD8(ptr, 0, size).to_result() }
+    }
+
+    /// Looks up the `__rust_cuda_kernel_{table}_{name}` global rustc_codegen_nvvm emits for a
+    /// kernel, returning its address and size.
+    fn kernel_table(
+        &self,
+        table: &str,
+        name: &str,
+    ) -> CudaResult<(driver_sys::CUdeviceptr, usize)> {
+        let symbol = CString::new(format!("__rust_cuda_kernel_{table}_{name}"))
+            .expect("Kernel name had a nul");
+        let mut ptr: driver_sys::CUdeviceptr = 0;
+        let mut size: usize = 0;
+        unsafe {
+            driver_sys::cuModuleGetGlobal(&mut ptr, &mut size, self.inner, symbol.as_ptr())
+                .to_result()?;
+        }
+        Ok((ptr, size))
+    }
+
+    fn read_kernel_table<T: DeviceCopy + Default + Clone>(
+        &self,
+        table: &str,
+        name: &str,
+    ) -> CudaResult<Vec<T>> {
+        let (ptr, size) = self.kernel_table(table, name)?;
+        let mut values = vec![T::default(); size / mem::size_of::<T>()];
+        unsafe {
+            driver_sys::cuMemcpyDtoH(
+                values.as_mut_ptr() as *mut c_void,
+                ptr,
+                values.len() * mem::size_of::<T>(),
+            )
+            .to_result()?;
+        }
+        Ok(values)
+    }
+
     /// Get a reference to a kernel function which can then be launched.
     ///
     /// # Examples
@@ -435,7 +720,7 @@ impl Module {
                 cstr.as_ptr(),
             )
             .to_result()?;
-            Ok(Function::new(func, self))
+            Ok(Function::new(func, self, name))
         }
     }
 
@@ -496,6 +781,16 @@ impl Drop for Module {
     }
 }
 
+/// How many threads executed a basic block of an instrumented kernel, as returned by
+/// [`Module::get_kernel_block_counts`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BlockCount {
+    /// The source line of the block in the kernel, or 0 if it is not known.
+    pub line: u32,
+    /// The number of threads that executed the block.
+    pub count: u64,
+}
+
 /// Handle to a symbol defined within a CUDA module.
 #[derive(Debug)]
 pub struct Symbol<'a, T: DeviceCopy> {
@@ -531,3 +826,54 @@ impl<T: DeviceCopy> CopyDestination<T> for Symbol<'_, T> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test_module {
+    use super::*;
+
+    #[test]
+    fn test_from_ptx_with_max_registers() {
+        let _context = crate::quick_init().unwrap();
+        let module = Module::from_ptx_with_options(
+            include_str!("../resources/add.ptx"),
+            &[
+                ModuleJitOption::MaxRegisters(32),
+                ModuleJitOption::DetermineTargetFromContext,
+            ],
+        )
+        .unwrap();
+        module.get_function("sum").unwrap();
+    }
+
+    #[test]
+    fn test_kernel_block_counts() {
+        let _context = crate::quick_init().unwrap();
+        let module = Module::from_ptx(include_str!("../resources/add.ptx"), &[]).unwrap();
+        let mut counters = module
+            .get_global::<[u64; 3]>(c"__rust_cuda_kernel_calls_sum")
+            .unwrap();
+        counters.copy_from(&[256, 200, 256]).unwrap();
+        let blocks = module.get_kernel_block_counts("sum").unwrap();
+        assert_eq!(
+            blocks,
+            [(7, 256), (9, 200), (12, 256)].map(|(line, count)| BlockCount { line, count })
+        );
+
+        module.reset_kernel_block_counts("sum").unwrap();
+        let blocks = module.get_kernel_block_counts("sum").unwrap();
+        assert!(blocks.iter().all(|block| block.count == 0));
+        assert_eq!(
+            module.get_kernel_block_counts("missing").unwrap_err(),
+            CudaError::NotFound
+        );
+    }
+
+    #[test]
+    fn test_from_ptx_with_options_error_log() {
+        let _context = crate::quick_init().unwrap();
+        let error =
+            Module::from_ptx_with_options(".version 7.0\n.target sm_50\nnot ptx", &[]).unwrap_err();
+        assert!(!error.error_log.is_empty());
+        assert!(error.to_string().contains(&error.error_log));
+    }
+}
@@ -0,0 +1,373 @@
+//! Compiling CUDA C++ to PTX at runtime with [NVRTC](https://docs.nvidia.com/cuda/nvrtc/), and
+//! loading the result as a [`Module`].
+//!
+//! This requires the `nvrtc` feature, which links against the `nvrtc` library shipped with the
+//! CUDA toolkit. It lets kernels be generated at runtime, such as fused elementwise expressions,
+//! and compiled for the device they run on. See [`Jit`].
+
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int, c_void};
+use std::ptr;
+use std::sync::{Arc, Mutex};
+
+use cust_raw::driver_sys;
+
+use crate::context::Context;
+use crate::error::{CudaError, ToResult};
+use crate::module::{JitError, Module};
+
+#[allow(non_camel_case_types)]
+type nvrtcProgram = *mut c_void;
+
+// nvrtcResult, 0 is NVRTC_SUCCESS.
+const NVRTC_ERROR_OUT_OF_MEMORY: c_int = 1;
+const NVRTC_ERROR_INVALID_INPUT: c_int = 3;
+const NVRTC_ERROR_INVALID_OPTION: c_int = 5;
+const NVRTC_ERROR_COMPILATION: c_int = 6;
+
+#[link(name = "nvrtc")]
+extern "C" {
+    fn nvrtcGetErrorString(result: c_int) -> *const c_char;
+    fn nvrtcCreateProgram(
+        prog: *mut nvrtcProgram,
+        src: *const c_char,
+        name: *const c_char,
+        num_headers: c_int,
+        headers: *const *const c_char,
+        include_names: *const *const c_char,
+    ) -> c_int;
+    fn nvrtcDestroyProgram(prog: *mut nvrtcProgram) -> c_int;
+    fn nvrtcCompileProgram(
+        prog: nvrtcProgram,
+        num_options: c_int,
+        options: *const *const c_char,
+    ) -> c_int;
+    fn nvrtcGetPTXSize(prog: nvrtcProgram, ptx_size: *mut usize) -> c_int;
+    fn nvrtcGetPTX(prog: nvrtcProgram, ptx: *mut c_char) -> c_int;
+    fn nvrtcGetProgramLogSize(prog: nvrtcProgram, log_size: *mut usize) -> c_int;
+    fn nvrtcGetProgramLog(prog: nvrtcProgram, log: *mut c_char) -> c_int;
+}
+
+// NVRTC errors are reported as the closest driver error, with the messages of NVRTC in the log.
+fn nvrtc_error(result: c_int, log: String) -> JitError {
+    let error = match result {
+        NVRTC_ERROR_OUT_OF_MEMORY => CudaError::OutOfMemory,
+        NVRTC_ERROR_INVALID_INPUT | NVRTC_ERROR_INVALID_OPTION => CudaError::InvalidValue,
+        NVRTC_ERROR_COMPILATION => CudaError::InvalidSource,
+        _ => CudaError::UnknownError,
+    };
+    let error_log = if log.is_empty() {
+        unsafe { CStr::from_ptr(nvrtcGetErrorString(result)) }
+            .to_string_lossy()
+            .to_string()
+    } else {
+        log
+    };
+    JitError {
+        error,
+        info_log: String::new(),
+        error_log,
+    }
+}
+
+fn check(result: c_int) -> Result<(), JitError> {
+    match result {
+        0 => Ok(()),
+        _ => Err(nvrtc_error(result, String::new())),
+    }
+}
+
+// destroys the program when dropped, so that it is not leaked on errors.
+struct Program(nvrtcProgram);
+
+impl Drop for Program {
+    fn drop(&mut self) {
+        unsafe {
+            nvrtcDestroyProgram(&mut self.0);
+        }
+    }
+}
+
+impl Program {
+    // the log is nul terminated, its size includes the nul.
+    fn log(&self) -> String {
+        let mut size = 0;
+        if unsafe { nvrtcGetProgramLogSize(self.0, &mut size) } != 0 || size <= 1 {
+            return String::new();
+        }
+        let mut log = vec![0u8; size];
+        if unsafe { nvrtcGetProgramLog(self.0, log.as_mut_ptr().cast()) } != 0 {
+            return String::new();
+        }
+        log.truncate(size - 1);
+        String::from_utf8_lossy(&log).trim_end().to_string()
+    }
+}
+
+/// The modules compiled by a [`Jit`], by source and options.
+type ModuleCache = HashMap<(String, Vec<String>), Arc<Module>>;
+
+/// Compiles CUDA C++ source code at runtime with NVRTC and caches the resulting modules.
+///
+/// A `Jit` loads its modules in the context it was created for, which it retains so that the
+/// context outlives the cached modules. Compiling the same source with the same options again
+/// returns the module compiled the first time, so kernels generated at runtime can be requested
+/// every time they are needed without being compiled more than once. Modules stay cached as long
+/// as the `Jit`, so generating unboundedly many different kernels also keeps them all loaded.
+///
+/// Kernels should be declared `extern "C"`, so that they can be retrieved from the module by
+/// their name rather than their mangled C++ name.
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// # let context = cust::quick_init()?;
+/// use cust::nvrtc::Jit;
+///
+/// let jit = Jit::new(&context);
+/// let source = r#"
+///     extern "C" __global__ void scale(float *values, float factor, unsigned int len) {
+///         unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+///         if (i < len) {
+///             values[i] *= factor;
+///         }
+///     }
+/// "#;
+///
+/// let module = jit.compile(source, &["--use_fast_math"])?;
+/// let scale = module.get_function("scale")?;
+///
+/// // the second time, the cached module is returned.
+/// let again = jit.compile(source, &["--use_fast_math"])?;
+/// assert!(std::sync::Arc::ptr_eq(&module, &again));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Jit {
+    modules: Mutex<ModuleCache>,
+    context: Context,
+}
+
+impl Jit {
+    /// Creates a new `Jit` with an empty cache, which loads its modules in `context`.
+    ///
+    /// The context is retained by cloning it, which like [`Context::new`] also makes it current on
+    /// the calling thread.
+    pub fn new(context: &Context) -> Self {
+        Self {
+            modules: Mutex::new(HashMap::new()),
+            context: context.clone(),
+        }
+    }
+
+    /// Returns the context the modules are loaded in.
+    pub fn context(&self) -> &Context {
+        &self.context
+    }
+
+    /// Returns the module of `source` compiled with `options`, compiling it with NVRTC and loading
+    /// it if it was not compiled before.
+    ///
+    /// The context of the `Jit` must be current, otherwise a [`JitError`] with
+    /// [`CudaError::InvalidContext`] is returned.
+    ///
+    /// `options` are NVRTC options, such as `--gpu-architecture=compute_80` to compile for a
+    /// specific architecture or `-DNAME=VALUE` to define macros. See [`compile_to_ptx`] for the
+    /// errors.
+    ///
+    /// [`compile_to_ptx`]: Self::compile_to_ptx
+    ///
+    /// # Panics
+    ///
+    /// Panics if `source` or any of `options` contains a nul.
+    pub fn compile(&self, source: &str, options: &[&str]) -> Result<Arc<Module>, JitError> {
+        let mut current = ptr::null_mut();
+        unsafe { driver_sys::cuCtxGetCurrent(&mut current).to_result() }
+            .and_then(|()| {
+                if current == self.context.as_raw() {
+                    Ok(())
+                } else {
+                    Err(CudaError::InvalidContext)
+                }
+            })
+            .map_err(|error| JitError {
+                error,
+                info_log: String::new(),
+                error_log: String::new(),
+            })?;
+        let key = (
+            source.to_string(),
+            options.iter().map(|option| option.to_string()).collect(),
+        );
+
+        if let Some(module) = self.lock().get(&key) {
+            return Ok(module.clone());
+        }
+
+        // compiling takes a while, so the cache is not locked meanwhile. If another thread
+        // compiled the same module in the meantime, its module is kept.
+        let ptx = Self::compile_to_ptx(source, options)?;
+        let module = Arc::new(Module::from_ptx_with_options(ptx, &[])?);
+        Ok(self.lock().entry(key).or_insert(module).clone())
+    }
+
+    /// Compiles `source` with `options` to PTX with NVRTC, without caching or loading it.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`JitError`] whose error log is the compile log of NVRTC if compiling fails, with
+    /// [`CudaError::InvalidSource`] if the source has errors and [`CudaError::InvalidValue`] if an
+    /// option is invalid.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `source` or any of `options` contains a nul.
+    pub fn compile_to_ptx(source: &str, options: &[&str]) -> Result<String, JitError> {
+        let source = CString::new(source).expect("source given to Jit contained nul bytes");
+        let options = options
+            .iter()
+            .map(|option| CString::new(*option).expect("option given to Jit contained nul bytes"))
+            .collect::<Vec<_>>();
+        let option_ptrs = options
+            .iter()
+            .map(|option| option.as_ptr())
+            .collect::<Vec<_>>();
+
+        unsafe {
+            let mut raw = ptr::null_mut();
+            check(nvrtcCreateProgram(
+                &mut raw,
+                source.as_ptr(),
+                ptr::null(),
+                0,
+                ptr::null(),
+                ptr::null(),
+            ))?;
+            let program = Program(raw);
+
+            let result =
+                nvrtcCompileProgram(program.0, option_ptrs.len() as c_int, option_ptrs.as_ptr());
+            if result != 0 {
+                return Err(nvrtc_error(result, program.log()));
+            }
+
+            // the ptx is nul terminated, its size includes the nul.
+            let mut size = 0;
+            check(nvrtcGetPTXSize(program.0, &mut size))?;
+            let mut ptx = vec![0u8; size];
+            check(nvrtcGetPTX(program.0, ptx.as_mut_ptr().cast()))?;
+            ptx.truncate(size.saturating_sub(1));
+            Ok(String::from_utf8_lossy(&ptx).into_owned())
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ModuleCache> {
+        self.modules.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for Jit {
+    fn drop(&mut self) {
+        let modules = std::mem::take(self.modules.get_mut().unwrap_or_else(|e| e.into_inner()));
+        if modules.is_empty() {
+            return;
+        }
+        unsafe {
+            // modules are unloaded from the current context, the context itself is released after
+            // them.
+            if driver_sys::cuCtxPushCurrent(self.context.as_raw())
+                .to_result()
+                .is_err()
+            {
+                // leaking the modules is better than unloading them from another context.
+                std::mem::forget(modules);
+                return;
+            }
+            drop(modules);
+            let _ = driver_sys::cuCtxPopCurrent(&mut ptr::null_mut());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::memory::{CopyDestination, DeviceBuffer};
+    use crate::stream::{Stream, StreamFlags};
+
+    const SCALE: &str = r#"
+        extern "C" __global__ void scale(float *values, float factor, unsigned int len) {
+            unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+            if (i < len) {
+                values[i] *= FACTOR * factor;
+            }
+        }
+    "#;
+
+    #[test]
+    fn test_compile_and_launch() -> Result<(), Box<dyn std::error::Error>> {
+        let context = crate::quick_init()?;
+        let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+        let jit = Jit::new(&context);
+
+        let module = jit.compile(SCALE, &["-DFACTOR=2.0f"])?;
+        let scale = module.get_function("scale")?;
+        let values = DeviceBuffer::from_slice(&[1.0f32, 2.0, 3.0])?;
+        unsafe {
+            crate::launch!(scale<<<1, 32, 0, stream>>>(
+                values.as_device_ptr(),
+                1.5f32,
+                values.len() as u32
+            ))?;
+        }
+        stream.synchronize()?;
+        let mut host = [0.0f32; 3];
+        values.copy_to(&mut host)?;
+        assert_eq!(host, [3.0, 6.0, 9.0]);
+
+        // the same source and options are only compiled once, other options are compiled again.
+        assert!(Arc::ptr_eq(
+            &module,
+            &jit.compile(SCALE, &["-DFACTOR=2.0f"])?
+        ));
+        assert!(!Arc::ptr_eq(
+            &module,
+            &jit.compile(SCALE, &["-DFACTOR=3.0f"])?
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_compile_error_log() {
+        let context = crate::quick_init().unwrap();
+        let error = Jit::new(&context)
+            .compile(
+                "extern \"C\" __global__ void broken() { undeclared(); }",
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(error.error, CudaError::InvalidSource);
+        assert!(
+            error.error_log.contains("undeclared"),
+            "{}",
+            error.error_log
+        );
+    }
+
+    #[test]
+    fn test_compile_in_other_context() {
+        let context = crate::quick_init().unwrap();
+        let jit = Jit::new(&context);
+        unsafe {
+            driver_sys::cuCtxSetCurrent(ptr::null_mut())
+                .to_result()
+                .unwrap()
+        };
+        let error = jit.compile(SCALE, &["-DFACTOR=2.0f"]).unwrap_err();
+        assert_eq!(error.error, CudaError::InvalidContext);
+    }
+}
@@ -1 +1,81 @@
+//! Annotating host code with [NVTX](https://nvidia.github.io/NVTX/) ranges, which show up on the
+//! timelines of Nsight Systems and other profilers.
+//!
+//! This requires the `nvtx` feature, which links against the `nvToolsExt` library shipped with
+//! the CUDA toolkit. Ranges are per thread and nest, a range must be popped on the thread which
+//! pushed it and after every range pushed inside of it, which [`Range`] takes care of.
+//!
+//! Streams and contexts can also be given names to label their rows on the timeline, see
+//! [`Stream::set_name`](crate::stream::Stream::set_name) and
+//! [`Context::set_name`](crate::context::Context::set_name).
+//!
+//! See also [`profile!`](crate::profile), which additionally times the range on the GPU.
 
+use std::ffi::CString;
+use std::marker::PhantomData;
+use std::os::raw::{c_char, c_int};
+
+use cust_raw::driver_sys::{CUcontext, CUstream};
+
+#[link(name = "nvToolsExt")]
+extern "C" {
+    fn nvtxRangePushA(message: *const c_char) -> c_int;
+    fn nvtxRangePop() -> c_int;
+    fn nvtxNameCuStreamA(stream: CUstream, name: *const c_char);
+    fn nvtxNameCuContextA(context: CUcontext, name: *const c_char);
+}
+
+// nvtx strings are nul terminated, nul bytes in names are removed rather than truncating them.
+fn to_cstring(name: &str) -> CString {
+    CString::new(name.replace('\0', "")).expect("nul bytes were removed")
+}
+
+pub(crate) fn name_stream(stream: CUstream, name: &str) {
+    let name = to_cstring(name);
+    unsafe { nvtxNameCuStreamA(stream, name.as_ptr()) }
+}
+
+pub(crate) fn name_context(context: CUcontext, name: &str) {
+    let name = to_cstring(name);
+    unsafe { nvtxNameCuContextA(context, name.as_ptr()) }
+}
+
+/// An NVTX range which is pushed when created and popped when dropped.
+///
+/// # Examples
+///
+/// ```
+/// use cust::nvtx::Range;
+///
+/// let _range = Range::push("upload");
+/// // everything until the end of the scope is shown as "upload" by the profiler.
+/// ```
+#[derive(Debug)]
+#[must_use = "the range is popped as soon as it is dropped"]
+pub struct Range {
+    // ranges are per thread, so the guard must not be sent to another thread.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Range {
+    /// Pushes a new range called `name`, nested inside of the current range of this thread.
+    ///
+    /// Nul bytes in `name` are removed.
+    pub fn push(name: &str) -> Self {
+        let name = to_cstring(name);
+        unsafe {
+            nvtxRangePushA(name.as_ptr());
+        }
+        Self {
+            _not_send: PhantomData,
+        }
+    }
+}
+
+impl Drop for Range {
+    fn drop(&mut self) {
+        unsafe {
+            nvtxRangePop();
+        }
+    }
+}
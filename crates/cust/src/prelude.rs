@@ -7,7 +7,7 @@ pub use crate::context::{Context, ContextFlags};
 pub use crate::device::Device;
 pub use crate::event::{Event, EventFlags, EventStatus};
 pub use crate::external::*;
-pub use crate::function::Function;
+pub use crate::function::{Function, LaunchConfig};
 pub use crate::launch;
 pub use crate::memory::{
     CopyDestination, DeviceBuffer, DevicePointer, DeviceSlice, DeviceVariable, UnifiedBuffer,
@@ -14,13 +14,18 @@ use std::ffi::c_void;
 use std::mem;
 use std::panic;
 use std::ptr;
+use std::time::Duration;
 
 use cust_raw::driver_sys;
-use cust_raw::driver_sys::{cudaError_enum, CUstream};
+use cust_raw::driver_sys::CUstream;
 
-use crate::error::{CudaResult, DropResult, ToResult};
-use crate::event::Event;
+use crate::context::CurrentContext;
+use crate::error::{strict_launch_sync, CudaError, CudaResult, DropResult, ToResult};
+use crate::event::{Event, EventFlags};
 use crate::function::{BlockSize, Function, GridSize};
+use crate::graph::Graph;
+use crate::memory::DevicePointer;
+use crate::trace::trace_span;
 
 bitflags::bitflags! {
     /// Bit flags for configuring a CUDA Stream.
@@ -55,6 +60,124 @@ bitflags::bitflags! {
     }
 }
 
+/// Which potentially unsafe API calls are prohibited while a stream is being captured, see
+/// [`Stream::begin_capture`].
+///
+/// Calls such as `cuMemAlloc` may synchronize implicitly, which is not allowed while work is being
+/// captured instead of executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StreamCaptureMode {
+    /// Potentially unsafe calls are prohibited on every thread while any thread has a global
+    /// capture ongoing.
+    Global,
+    /// Potentially unsafe calls are prohibited on the thread which began the capture.
+    ThreadLocal,
+    /// Potentially unsafe calls are not prohibited, the caller has to make sure they do not
+    /// interfere with the capture.
+    Relaxed,
+}
+
+impl StreamCaptureMode {
+    fn into_raw(self) -> driver_sys::CUstreamCaptureMode {
+        use driver_sys::CUstreamCaptureMode::*;
+
+        match self {
+            Self::Global => CU_STREAM_CAPTURE_MODE_GLOBAL,
+            Self::ThreadLocal => CU_STREAM_CAPTURE_MODE_THREAD_LOCAL,
+            Self::Relaxed => CU_STREAM_CAPTURE_MODE_RELAXED,
+        }
+    }
+}
+
+/// How the L2 cache treats memory accesses covered by an [`AccessPolicyWindow`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccessProperty {
+    /// Normal cache persistence.
+    Normal,
+    /// Accesses are less likely to persist in the L2 cache, because they are preferentially
+    /// evicted.
+    Streaming,
+    /// Accesses are more likely to persist in the L2 cache, in the portion of it set aside for
+    /// persisting accesses.
+    Persisting,
+}
+
+impl From<AccessProperty> for driver_sys::CUaccessProperty {
+    fn from(prop: AccessProperty) -> Self {
+        match prop {
+            AccessProperty::Normal => driver_sys::CUaccessProperty::CU_ACCESS_PROPERTY_NORMAL,
+            AccessProperty::Streaming => driver_sys::CUaccessProperty::CU_ACCESS_PROPERTY_STREAMING,
+            AccessProperty::Persisting => {
+                driver_sys::CUaccessProperty::CU_ACCESS_PROPERTY_PERSISTING
+            }
+        }
+    }
+}
+
+impl From<driver_sys::CUaccessProperty> for AccessProperty {
+    fn from(prop: driver_sys::CUaccessProperty) -> Self {
+        match prop {
+            driver_sys::CUaccessProperty::CU_ACCESS_PROPERTY_NORMAL => AccessProperty::Normal,
+            driver_sys::CUaccessProperty::CU_ACCESS_PROPERTY_STREAMING => AccessProperty::Streaming,
+            driver_sys::CUaccessProperty::CU_ACCESS_PROPERTY_PERSISTING => {
+                AccessProperty::Persisting
+            }
+        }
+    }
+}
+
+/// A range of device memory together with a hint of how the L2 cache should treat accesses to it.
+///
+/// Accesses to `[base_ptr, base_ptr + num_bytes)` are randomly split into hits, which get
+/// `hit_prop`, and misses, which get `miss_prop`, with `hit_ratio` of them being hits. This is
+/// typically used to mark data that is read by many kernels in a row as persisting.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AccessPolicyWindow {
+    /// The start of the window.
+    pub base_ptr: DevicePointer<u8>,
+    /// The size of the window in bytes. This is limited by the maximum access policy window
+    /// size of the device.
+    pub num_bytes: usize,
+    /// The fraction of accesses in the window which get `hit_prop`, between `0.0` and `1.0`.
+    pub hit_ratio: f32,
+    /// The access property of hits.
+    pub hit_prop: AccessProperty,
+    /// The access property of misses. This must not be [`AccessProperty::Persisting`].
+    pub miss_prop: AccessProperty,
+}
+
+impl From<AccessPolicyWindow> for driver_sys::CUaccessPolicyWindow {
+    fn from(window: AccessPolicyWindow) -> Self {
+        driver_sys::CUaccessPolicyWindow {
+            base_ptr: window.base_ptr.as_raw() as *mut c_void,
+            num_bytes: window.num_bytes,
+            hitRatio: window.hit_ratio,
+            hitProp: window.hit_prop.into(),
+            missProp: window.miss_prop.into(),
+        }
+    }
+}
+
+impl From<driver_sys::CUaccessPolicyWindow> for AccessPolicyWindow {
+    fn from(window: driver_sys::CUaccessPolicyWindow) -> Self {
+        AccessPolicyWindow {
+            base_ptr: DevicePointer::from_raw(window.base_ptr as driver_sys::CUdeviceptr),
+            num_bytes: window.num_bytes,
+            hit_ratio: window.hitRatio,
+            hit_prop: window.hitProp.into(),
+            miss_prop: window.missProp.into(),
+        }
+    }
+}
+
+/// An attribute which applies to all work launched into a stream.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum StreamAttribute {
+    /// The L2 cache access policy window of kernels launched into the stream.
+    AccessPolicyWindow(AccessPolicyWindow),
+}
+
 /// A stream of work for the device to perform.
 ///
 /// See the module-level documentation for more information.
@@ -66,6 +189,16 @@ pub struct Stream {
 unsafe impl Send for Stream {}
 unsafe impl Sync for Stream {}
 
+/// The time taken and effective bandwidth of a memory transfer, as measured by
+/// [`Stream::time_copy`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CopyTiming {
+    /// The time the transfer took, in milliseconds.
+    pub millis: f32,
+    /// The effective bandwidth of the transfer, in gigabytes (10^9 bytes) per second.
+    pub gigabytes_per_second: f64,
+}
+
 impl Stream {
     /// Create a new stream with the given flags and optional priority.
     ///
@@ -109,6 +242,42 @@ impl Stream {
         }
     }
 
+    /// Create a new stream with the given flags and priority, checking that the priority is valid.
+    ///
+    /// Unlike [`Stream::new`], which clamps priorities outside of the valid range, this returns an
+    /// error for them. The valid range can be queried with
+    /// [`CurrentContext::get_stream_priority_range`](crate::context::CurrentContext::get_stream_priority_range),
+    /// lower numbers represent greater priorities.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CudaError::InvalidValue`] if `priority` is outside of the valid range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cust::*;
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let _ctx = quick_init()?;
+    /// use cust::context::CurrentContext;
+    /// use cust::stream::{Stream, StreamFlags};
+    ///
+    /// // latency sensitive work can pre-empt work queued on lower priority streams.
+    /// let range = CurrentContext::get_stream_priority_range()?;
+    /// let stream = Stream::new_with_priority(StreamFlags::NON_BLOCKING, range.greatest)?;
+    /// assert_eq!(stream.get_priority()?, range.greatest);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_with_priority(flags: StreamFlags, priority: i32) -> CudaResult<Self> {
+        let range = CurrentContext::get_stream_priority_range()?;
+        if priority < range.greatest || priority > range.least {
+            return Err(CudaError::InvalidValue);
+        }
+        Stream::new(flags, Some(priority))
+    }
+
     /// Return the flags which were used to create this stream.
     pub fn get_flags(&self) -> CudaResult<StreamFlags> {
         unsafe {
@@ -146,16 +315,99 @@ impl Stream {
         }
     }
 
+    /// Names this stream in profilers such as Nsight Systems, which label its row of the timeline
+    /// with `name` instead of a number.
+    ///
+    /// Nul bytes in `name` are removed. Naming a stream again replaces its name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cust::*;
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let _ctx = quick_init()?;
+    /// use cust::stream::{Stream, StreamFlags};
+    ///
+    /// let upload = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+    /// upload.set_name("upload");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "nvtx")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "nvtx")))]
+    pub fn set_name(&self, name: &str) {
+        crate::nvtx::name_stream(self.inner, name);
+    }
+
+    /// Set an attribute of this stream, which applies to all work launched into it afterward.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cust::*;
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let _ctx = quick_init()?;
+    /// use cust::memory::DeviceBuffer;
+    /// use cust::stream::{AccessPolicyWindow, AccessProperty, Stream, StreamAttribute, StreamFlags};
+    ///
+    /// let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+    /// let weights = DeviceBuffer::<f32>::zeroed(1024)?;
+    ///
+    /// // keep the weights in L2 across all the kernels launched into the stream.
+    /// let window = AccessPolicyWindow {
+    ///     base_ptr: weights.as_device_ptr().cast(),
+    ///     num_bytes: weights.len() * std::mem::size_of::<f32>(),
+    ///     hit_ratio: 0.6,
+    ///     hit_prop: AccessProperty::Persisting,
+    ///     miss_prop: AccessProperty::Streaming,
+    /// };
+    /// stream.set_attribute(StreamAttribute::AccessPolicyWindow(window))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_attribute(&self, attribute: StreamAttribute) -> CudaResult<()> {
+        unsafe {
+            let mut value = mem::zeroed::<driver_sys::CUstreamAttrValue>();
+            let id = match attribute {
+                StreamAttribute::AccessPolicyWindow(window) => {
+                    value.accessPolicyWindow = window.into();
+                    driver_sys::CUstreamAttrID::CU_LAUNCH_ATTRIBUTE_ACCESS_POLICY_WINDOW
+                }
+            };
+            driver_sys::cuStreamSetAttribute(self.inner, id, &value as *const _).to_result()
+        }
+    }
+
+    /// Return the L2 cache access policy window of this stream, as set by
+    /// [`set_attribute`](Stream::set_attribute).
+    pub fn get_access_policy_window(&self) -> CudaResult<AccessPolicyWindow> {
+        unsafe {
+            let mut value = mem::zeroed::<driver_sys::CUstreamAttrValue>();
+            driver_sys::cuStreamGetAttribute(
+                self.inner,
+                driver_sys::CUstreamAttrID::CU_LAUNCH_ATTRIBUTE_ACCESS_POLICY_WINDOW,
+                &mut value as *mut _,
+            )
+            .to_result()?;
+            Ok(value.accessPolicyWindow.into())
+        }
+    }
+
     /// Add a callback to a stream.
     ///
-    /// The callback will be executed after all previously queued
+    /// The callback will be executed on a host thread after all previously queued
     /// items in the stream have been completed. Subsequently queued
-    /// items will not execute until the callback is finished.
+    /// items will not execute until the callback is finished. The callback is
+    /// invoked exactly once.
     ///
-    /// Callbacks must not make any CUDA API calls.
+    /// Callbacks must not make any CUDA API calls, doing so may deadlock or return
+    /// [`CudaError::NotPermitted`]. The callback runs on a thread of the driver after this returns,
+    /// so it must be `'static` and may not borrow from the caller.
     ///
-    /// The callback will be passed a `CudaResult<()>` indicating the
-    /// current state of the device with `Ok(())` denoting normal operation.
+    /// The callback is enqueued with `cuLaunchHostFunc`, so it is always passed `Ok(())`. If the
+    /// context encounters an error before the callback is reached, the callback does not run.
     ///
     /// # Examples
     ///
@@ -165,36 +417,156 @@ impl Stream {
     /// # fn main() -> Result<(), Box<dyn Error>> {
     /// # let _ctx = quick_init()?;
     /// use cust::stream::{Stream, StreamFlags};
+    /// use std::sync::mpsc;
     ///
     /// let stream = Stream::new(StreamFlags::NON_BLOCKING, 1i32.into())?;
+    /// let (sender, receiver) = mpsc::channel();
     ///
     /// // ... queue up some work on the stream
     ///
-    /// stream.add_callback(Box::new(|status| {
-    ///     println!("Device status is {:?}", status);
-    /// }));
+    /// stream.add_callback(move |status| {
+    ///     sender.send(status).unwrap();
+    /// })?;
     ///
     /// // ... queue up some more work on the stream
+    ///
+    /// assert_eq!(receiver.recv()?, Ok(()));
     /// # Ok(())
     /// # }
-    pub fn add_callback<T>(&self, callback: Box<T>) -> CudaResult<()>
+    /// ```
+    pub fn add_callback<F>(&self, callback: F) -> CudaResult<()>
     where
-        T: FnOnce(CudaResult<()>) + Send,
+        F: FnOnce(CudaResult<()>) + Send + 'static,
     {
+        let callback = Box::into_raw(Box::new(callback));
         unsafe {
-            driver_sys::cuStreamAddCallback(
+            let result = driver_sys::cuLaunchHostFunc(
                 self.inner,
-                Some(callback_wrapper::<T>),
-                Box::into_raw(callback) as *mut c_void,
-                0,
+                Some(callback_wrapper::<F>),
+                callback as *mut c_void,
             )
-            .to_result()
+            .to_result();
+            if result.is_err() {
+                // the callback was never enqueued, so it will not reclaim its box itself.
+                drop(Box::from_raw(callback));
+            }
+            result
         }
     }
 
+    /// Times a memory transfer of `bytes` bytes enqueued on this stream by `copy`.
+    ///
+    /// Events are recorded before and after `copy` is called, then this waits for the stop event
+    /// and returns both the elapsed time and the effective bandwidth of the transfer. Other work
+    /// already queued on the stream is not included in the measurement, but work `copy` enqueues
+    /// besides the transfer is.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of `copy` if it fails, and any error recording or waiting for the
+    /// events.
+    ///
+    /// # Examples
+    ///
+    /// Comparing the host to device bandwidth of pinned and pageable memory.
+    ///
+    /// ```
+    /// # use cust::*;
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let _ctx = quick_init()?;
+    /// use cust::memory::*;
+    /// use cust::stream::{Stream, StreamFlags};
+    ///
+    /// const LEN: usize = 16 << 20;
+    ///
+    /// let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+    /// let pinned = LockedBuffer::new(&0u8, LEN)?;
+    /// let pageable = vec![0u8; LEN];
+    /// let mut device = unsafe { DeviceBuffer::uninitialized(LEN)? };
+    ///
+    /// let pinned_timing =
+    ///     stream.time_copy(LEN, |stream| unsafe { device.async_copy_from(&pinned, stream) })?;
+    /// let pageable_timing =
+    ///     stream.time_copy(LEN, |stream| unsafe { device.async_copy_from(&pageable, stream) })?;
+    ///
+    /// println!(
+    ///     "pinned: {:.2} GB/s, pageable: {:.2} GB/s",
+    ///     pinned_timing.gigabytes_per_second, pageable_timing.gigabytes_per_second
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn time_copy<F>(&self, bytes: usize, copy: F) -> CudaResult<CopyTiming>
+    where
+        F: FnOnce(&Stream) -> CudaResult<()>,
+    {
+        let (start, stop) = self.record_around(copy)?;
+        Ok(CopyTiming {
+            millis: stop.elapsed_time_f32(&start)?,
+            gigabytes_per_second: stop.elapsed_throughput(&start, bytes)?,
+        })
+    }
+
+    /// Measures the GPU time of the work `f` queues on this stream.
+    ///
+    /// This records an event on the stream before and after calling `f` with the stream, waits
+    /// for the second event and returns the time between them, see
+    /// [`Event::elapsed_time_since`]. Like [`time_copy`](Self::time_copy), work already queued on
+    /// the stream is not included in the measurement.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of `f` if it fails, and any error recording or waiting for the events.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cust::*;
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let _ctx = quick_init()?;
+    /// use cust::memory::*;
+    /// use cust::stream::{Stream, StreamFlags};
+    ///
+    /// let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+    /// let mut buffer = DeviceBuffer::from_slice(&[0u32; 1024])?;
+    ///
+    /// let time = stream.time(|stream| unsafe { buffer.set_8_async(0xff, stream) })?;
+    /// println!("memset took {:?}", time);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn time<F>(&self, f: F) -> CudaResult<Duration>
+    where
+        F: FnOnce(&Stream) -> CudaResult<()>,
+    {
+        let (start, stop) = self.record_around(f)?;
+        stop.elapsed_time_since(&start)
+    }
+
+    // Records events before and after the work queued by `f` and waits for the second one.
+    fn record_around<F>(&self, f: F) -> CudaResult<(Event, Event)>
+    where
+        F: FnOnce(&Stream) -> CudaResult<()>,
+    {
+        let start = Event::new(EventFlags::DEFAULT)?;
+        let stop = Event::new(EventFlags::DEFAULT)?;
+
+        start.record(self)?;
+        f(self)?;
+        stop.record(self)?;
+        stop.synchronize()?;
+
+        Ok((start, stop))
+    }
+
     /// Wait until a stream's tasks are completed.
     ///
-    /// Waits until the device has completed all operations scheduled for this stream.
+    /// Waits until the device has completed all operations scheduled for this stream. Work queued
+    /// on other streams keeps running and is not waited for, use
+    /// [`CurrentContext::synchronize`](crate::context::CurrentContext::synchronize) to wait for
+    /// every stream of the context.
     ///
     /// # Examples
     ///
@@ -215,7 +587,8 @@ impl Stream {
     /// # }
     /// ```
     pub fn synchronize(&self) -> CudaResult<()> {
-        unsafe { driver_sys::cuStreamSynchronize(self.inner).to_result() }
+        trace_span!("cuda.stream.synchronize");
+        unsafe { driver_sys::cuStreamSynchronize(self.inner).to_sync_result() }
     }
 
     /// Make the stream wait on an event.
@@ -254,6 +627,71 @@ impl Stream {
         }
     }
 
+    /// Begins capturing the work queued on this stream into a graph instead of executing it.
+    ///
+    /// Capture ends with [`Stream::end_capture`], which returns the captured work as a
+    /// [`Graph`]. `mode` controls which potentially unsafe API calls are prohibited while the
+    /// capture is ongoing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cust::quick_init;
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let _context = quick_init()?;
+    /// use cust::memory::*;
+    /// use cust::stream::{Stream, StreamCaptureMode, StreamFlags};
+    ///
+    /// let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+    /// let mut buffer = DeviceBuffer::from_slice(&[1u32; 4])?;
+    ///
+    /// stream.begin_capture(StreamCaptureMode::Global)?;
+    /// unsafe { buffer.set_zero_async(&stream)? };
+    /// let mut graph = stream.end_capture()?;
+    ///
+    /// let exec = graph.instantiate()?;
+    /// unsafe { exec.launch(&stream)? };
+    /// stream.synchronize()?;
+    /// assert_eq!(buffer.as_host_vec()?, [0; 4]);
+    /// # Ok(())
+    /// }
+    /// ```
+    pub fn begin_capture(&self, mode: StreamCaptureMode) -> CudaResult<()> {
+        unsafe { driver_sys::cuStreamBeginCapture(self.inner, mode.into_raw()).to_result() }
+    }
+
+    /// Ends capturing the work queued on this stream, returning it as a graph. See
+    /// [`Stream::begin_capture`] for an example.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CudaError::StreamCaptureUnmatched`] if the stream is not being captured, and
+    /// [`CudaError::StreamCaptureInvalidated`] if the capture was invalidated by an operation
+    /// which is not allowed during capture.
+    pub fn end_capture(&self) -> CudaResult<Graph> {
+        if !self.is_capturing()? {
+            return Err(CudaError::StreamCaptureUnmatched);
+        }
+
+        unsafe {
+            let mut graph = ptr::null_mut();
+            driver_sys::cuStreamEndCapture(self.inner, &mut graph).to_result()?;
+            Ok(Graph::from_raw(graph))
+        }
+    }
+
+    /// Returns whether the work queued on this stream is currently being captured into a graph.
+    ///
+    /// A capture which has been invalidated still counts as ongoing until it is ended.
+    pub fn is_capturing(&self) -> CudaResult<bool> {
+        unsafe {
+            let mut status = driver_sys::CUstreamCaptureStatus::CU_STREAM_CAPTURE_STATUS_NONE;
+            driver_sys::cuStreamIsCapturing(self.inner, &mut status).to_result()?;
+            Ok(status != driver_sys::CUstreamCaptureStatus::CU_STREAM_CAPTURE_STATUS_NONE)
+        }
+    }
+
     // Hidden implementation detail function. Highly unsafe. Use the `launch!` macro instead.
     #[doc(hidden)]
     pub unsafe fn launch<G, B>(
@@ -270,6 +708,13 @@ impl Stream {
     {
         let grid_size: GridSize = grid_size.into();
         let block_size: BlockSize = block_size.into();
+        trace_span!(
+            "cuda.launch",
+            kernel = func.name(),
+            grid = ?(grid_size.x, grid_size.y, grid_size.z),
+            block = ?(block_size.x, block_size.y, block_size.z),
+            shared_mem_bytes,
+        );
 
         driver_sys::cuLaunchKernel(
             func.to_raw(),
@@ -284,7 +729,10 @@ impl Stream {
             args.as_ptr() as *mut _,
             ptr::null_mut(),
         )
-        .to_result()
+        .to_async_result()?;
+
+        // strict mode waits for the kernel so that faults are reported by the launch itself.
+        strict_launch_sync(self.inner)
     }
 
     // Get the inner `CUstream` from the `Stream`. If you use this handle elsewhere,
@@ -348,16 +796,84 @@ impl Drop for Stream {
         }
     }
 }
-unsafe extern "C" fn callback_wrapper<T>(
-    _stream: CUstream,
-    status: cudaError_enum,
-    callback: *mut c_void,
-) where
-    T: FnOnce(CudaResult<()>) + Send,
+unsafe extern "C" fn callback_wrapper<F>(callback: *mut c_void)
+where
+    F: FnOnce(CudaResult<()>) + Send + 'static,
 {
     // Stop panics from unwinding across the FFI
     let _ = panic::catch_unwind(|| {
-        let callback: Box<T> = Box::from_raw(callback as *mut T);
-        callback(status.to_result());
+        let callback: Box<F> = Box::from_raw(callback as *mut F);
+        callback(Ok(()));
     });
 }
+
+#[cfg(test)]
+mod test_stream {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_new_with_priority() {
+        let _context = crate::quick_init().unwrap();
+        let range = CurrentContext::get_stream_priority_range().unwrap();
+        let stream = Stream::new_with_priority(StreamFlags::NON_BLOCKING, range.greatest).unwrap();
+        assert_eq!(stream.get_priority().unwrap(), range.greatest);
+        let stream = Stream::new_with_priority(StreamFlags::NON_BLOCKING, range.least).unwrap();
+        assert_eq!(stream.get_priority().unwrap(), range.least);
+        assert_eq!(
+            Stream::new_with_priority(StreamFlags::NON_BLOCKING, range.greatest - 1).unwrap_err(),
+            CudaError::InvalidValue
+        );
+    }
+
+    #[test]
+    fn test_time_copy() {
+        let _context = crate::quick_init().unwrap();
+        let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+        let host = crate::memory::LockedBuffer::new(&1u8, 1 << 20).unwrap();
+        let mut device = crate::memory::DeviceBuffer::from_slice(&[0u8; 1 << 20]).unwrap();
+
+        let timing = stream
+            .time_copy(host.len(), |stream| unsafe {
+                crate::memory::AsyncCopyDestination::async_copy_from(&mut *device, &host, stream)
+            })
+            .unwrap();
+        assert!(timing.millis > 0.0);
+        assert!(timing.gigabytes_per_second > 0.0);
+    }
+
+    #[test]
+    fn test_time() {
+        let _context = crate::quick_init().unwrap();
+        let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+        let mut device = crate::memory::DeviceBuffer::from_slice(&[0u8; 1 << 20]).unwrap();
+
+        let time = stream
+            .time(|stream| unsafe { device.set_8_async(1, stream) })
+            .unwrap();
+        assert!(time > Duration::ZERO);
+        assert_eq!(
+            stream.time(|_| Err(CudaError::InvalidValue)),
+            Err(CudaError::InvalidValue)
+        );
+    }
+
+    #[test]
+    fn test_add_callback() {
+        let _context = crate::quick_init().unwrap();
+        let stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+        let count = Arc::new(AtomicUsize::new(0));
+        let callback_count = Arc::clone(&count);
+        stream
+            .add_callback(move |status| {
+                assert_eq!(status, Ok(()));
+                callback_count.fetch_add(1, Ordering::SeqCst);
+            })
+            .unwrap();
+        stream.synchronize().unwrap();
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+        // the callback and its clone of the counter have been dropped.
+        assert_eq!(Arc::strong_count(&count), 1);
+    }
+}
@@ -0,0 +1,192 @@
+//! Assertions on the contents of device memory, for writing tests of GPU code.
+//!
+//! [`assert_device_eq!`](crate::assert_device_eq) and
+//! [`assert_device_close!`](crate::assert_device_close) copy a buffer back to the host and compare
+//! it with the expected values. On failure they panic with the number of mismatched elements and
+//! the index and values of the first [`MAX_REPORTED_MISMATCHES`] of them, rather than printing both
+//! buffers in full.
+
+use std::fmt::{self, Debug, Write};
+
+use crate::memory::{DeviceCopy, DeviceSlice};
+
+/// The maximum number of mismatched elements listed in the message of a failed assertion.
+pub const MAX_REPORTED_MISMATCHES: usize = 10;
+
+#[track_caller]
+fn copy_back<T: DeviceCopy>(buffer: &DeviceSlice<T>, expected: &[T]) -> Vec<T> {
+    let actual = buffer
+        .as_host_vec()
+        .unwrap_or_else(|e| panic!("failed to copy the device buffer to the host: {}", e));
+    if actual.len() != expected.len() {
+        panic!(
+            "device buffer has {} elements but {} were expected",
+            actual.len(),
+            expected.len()
+        );
+    }
+    actual
+}
+
+#[track_caller]
+fn report<T: Debug>(actual: &[T], expected: &[T], mismatches: &[usize], condition: fmt::Arguments) {
+    if mismatches.is_empty() {
+        return;
+    }
+
+    let mut message = format!(
+        "{} of {} elements of the device buffer are not {}:",
+        mismatches.len(),
+        actual.len(),
+        condition
+    );
+    for &i in mismatches.iter().take(MAX_REPORTED_MISMATCHES) {
+        let _ = write!(message, "\n  [{}]: {:?} != {:?}", i, actual[i], expected[i]);
+    }
+    if mismatches.len() > MAX_REPORTED_MISMATCHES {
+        let _ = write!(
+            message,
+            "\n  ... and {} more",
+            mismatches.len() - MAX_REPORTED_MISMATCHES
+        );
+    }
+    panic!("{}", message);
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn __assert_device_eq<T: DeviceCopy + PartialEq + Debug>(
+    buffer: &DeviceSlice<T>,
+    expected: &[T],
+) {
+    let actual = copy_back(buffer, expected);
+    let mismatches: Vec<usize> = (0..actual.len())
+        .filter(|&i| actual[i] != expected[i])
+        .collect();
+    report(&actual, expected, &mismatches, format_args!("equal"));
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn __assert_device_close<T: DeviceCopy + Into<f64> + Debug>(
+    buffer: &DeviceSlice<T>,
+    expected: &[T],
+    tolerance: f64,
+) {
+    let actual = copy_back(buffer, expected);
+    let mismatches: Vec<usize> = (0..actual.len())
+        .filter(|&i| {
+            let difference = (actual[i].into() - expected[i].into()).abs();
+            difference.is_nan() || difference > tolerance
+        })
+        .collect();
+    report(
+        &actual,
+        expected,
+        &mismatches,
+        format_args!("within {} of the expected values", tolerance),
+    );
+}
+
+/// Asserts that a device buffer holds the expected values, panicking with the mismatched
+/// elements otherwise.
+///
+/// `assert_device_eq!(buffer, expected)` copies `buffer`, anything which dereferences to a
+/// [`DeviceSlice`], back to the host and compares it element by element with `expected`, anything
+/// which can be indexed with `[..]` to get a slice, such as an array or a `Vec`. The lengths must
+/// match too.
+///
+/// # Panics
+///
+/// Panics if the buffer cannot be copied back, if the lengths differ, or if any element differs.
+///
+/// # Examples
+///
+/// ```
+/// # let _context = cust::quick_init().unwrap();
+/// use cust::assert_device_eq;
+/// use cust::memory::DeviceBuffer;
+///
+/// let buffer = DeviceBuffer::from_slice(&[1u32, 2, 3]).unwrap();
+/// assert_device_eq!(buffer, [1, 2, 3]);
+/// ```
+#[macro_export]
+macro_rules! assert_device_eq {
+    ($buffer:expr, $expected:expr $(,)?) => {
+        $crate::testing::__assert_device_eq(&$buffer, &$expected[..])
+    };
+}
+
+/// Asserts that a device buffer of floating point values is within `tolerance` of the expected
+/// values, panicking with the mismatched elements otherwise.
+///
+/// This is [`assert_device_eq!`](crate::assert_device_eq) with an absolute tolerance, for
+/// comparing the output of a kernel with a reference computed on the host. Elements are converted
+/// to `f64` to be compared, and `NaN` is never within the tolerance of anything.
+///
+/// # Panics
+///
+/// Panics if the buffer cannot be copied back, if the lengths differ, or if any element is further
+/// than `tolerance` from the expected value.
+///
+/// # Examples
+///
+/// ```
+/// # let _context = cust::quick_init().unwrap();
+/// use cust::assert_device_close;
+/// use cust::memory::DeviceBuffer;
+///
+/// let buffer = DeviceBuffer::from_slice(&[0.1f32 + 0.2, 1.0 / 3.0]).unwrap();
+/// assert_device_close!(buffer, [0.3, 0.333_333], 1e-5);
+/// ```
+#[macro_export]
+macro_rules! assert_device_close {
+    ($buffer:expr, $expected:expr, $tolerance:expr $(,)?) => {
+        $crate::testing::__assert_device_close(&$buffer, &$expected[..], $tolerance)
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use crate::memory::DeviceBuffer;
+    use crate::quick_init;
+
+    #[test]
+    fn test_assert_device_eq() {
+        let _context = quick_init().unwrap();
+        let buffer = DeviceBuffer::from_slice(&[1u32, 2, 3, 4]).unwrap();
+        assert_device_eq!(buffer, [1, 2, 3, 4]);
+        assert_device_eq!(buffer[1..3], vec![2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "2 of 4 elements of the device buffer are not equal")]
+    fn test_assert_device_eq_mismatch() {
+        let _context = quick_init().unwrap();
+        let buffer = DeviceBuffer::from_slice(&[1u32, 2, 3, 4]).unwrap();
+        assert_device_eq!(buffer, [1, 0, 3, 0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "device buffer has 4 elements but 3 were expected")]
+    fn test_assert_device_eq_length() {
+        let _context = quick_init().unwrap();
+        let buffer = DeviceBuffer::from_slice(&[1u32, 2, 3, 4]).unwrap();
+        assert_device_eq!(buffer, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_assert_device_close() {
+        let _context = quick_init().unwrap();
+        let buffer = DeviceBuffer::from_slice(&[1.0f32, 2.0, 3.0]).unwrap();
+        assert_device_close!(buffer, [1.000_001, 1.999_999, 3.0], 1e-5);
+    }
+
+    #[test]
+    #[should_panic(expected = "1 of 2 elements of the device buffer are not within")]
+    fn test_assert_device_close_nan() {
+        let _context = quick_init().unwrap();
+        let buffer = DeviceBuffer::from_slice(&[1.0f64, f64::NAN]).unwrap();
+        assert_device_close!(buffer, [1.0, f64::NAN], 1e-5);
+    }
+}
@@ -0,0 +1,19 @@
+//! Optional [`tracing`](https://docs.rs/tracing) instrumentation of GPU operations.
+//!
+//! When the `tracing` feature is enabled, kernel launches, memory copies and synchronization
+//! calls are wrapped in `tracing` spans carrying their parameters. Without the feature the spans
+//! compile to nothing.
+//!
+//! Spans for asynchronous operations only cover enqueuing the work on the stream, not its
+//! execution on the device.
+
+/// Enters a `tracing` span for the rest of the enclosing block if the `tracing` feature is
+/// enabled, the arguments are the same as for `tracing::info_span!`.
+macro_rules! trace_span {
+    ($($args:tt)*) => {
+        #[cfg(feature = "tracing")]
+        let _span = ::tracing::info_span!($($args)*).entered();
+    };
+}
+
+pub(crate) use trace_span;
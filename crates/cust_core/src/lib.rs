@@ -67,6 +67,15 @@ pub mod _hidden {
     /// type implementing `Drop` cannot be `DeviceCopy` since it is responsible for some resource that
     /// would not be available on the device.
     ///
+    /// ## Arrays and tuples
+    ///
+    /// `DeviceCopy` is implemented for arrays `[T; N]` and for tuples of up to eight elements whose
+    /// elements are `DeviceCopy`, so these can be stored in device buffers without a newtype.
+    /// Arrays have the same layout everywhere, but the layout of tuples is unspecified and is only
+    /// guaranteed to match when host and device code are built by the same compiler with the same
+    /// target data layout. When a kernel is built separately, eg. in C++, prefer a `#[repr(C)]`
+    /// struct over a tuple.
+    ///
     /// # Safety
     ///
     /// The type being implemented must hold no references to CPU data.
@@ -88,7 +88,7 @@ fn main() {
         println!("cargo::rustc-link-lib=dylib=cublas");
     }
     if cfg!(feature = "cublaslt") {
-        println!("cargo::rustc-link-lib=dylib=cublaslt");
+        println!("cargo::rustc-link-lib=dylib=cublasLt");
     }
     if cfg!(feature = "nvvm") {
         for libdir in sdk.nvvm_library_paths() {
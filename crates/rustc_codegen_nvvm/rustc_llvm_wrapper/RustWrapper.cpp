@@ -1520,6 +1520,23 @@ extern "C" void LLVMRustPositionBuilderAtStart(LLVMBuilderRef B,
   unwrap(B)->SetInsertPoint(unwrap(BB), Point);
 }
 
+// Returns the source line of the first instruction of the block that has a location, or 0 if
+// none has one. For code inlined into the function, this is the line of the outermost call, so
+// that the line is in the function itself.
+extern "C" unsigned LLVMRustGetBasicBlockLine(LLVMBasicBlockRef BB)
+{
+  for (Instruction &I : *unwrap(BB))
+  {
+    DILocation *Loc = I.getDebugLoc().get();
+    if (!Loc || Loc->getLine() == 0)
+      continue;
+    while (DILocation *InlinedAt = Loc->getInlinedAt())
+      Loc = InlinedAt;
+    return Loc->getLine();
+  }
+  return 0;
+}
+
 extern "C" void LLVMRustSetComdat(LLVMModuleRef M, LLVMValueRef V,
                                   const char *Name)
 {
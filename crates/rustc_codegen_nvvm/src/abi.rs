@@ -37,9 +37,12 @@ pub(crate) fn readjust_fn_abi<'tcx>(
             mode: arg.mode.clone(),
         };
 
-        // ignore zsts
+        // ignore zsts. this must take priority over the adjustments below, otherwise zst adts and
+        // empty arrays would be passed directly and take up a parameter slot that the host side
+        // (which skips zsts) does not fill, shifting every following argument.
         if arg.layout.is_zst() {
             arg.mode = PassMode::Ignore;
+            return arg;
         }
 
         if let TyKind::Ref(_, ty, _) = arg.layout.ty.kind() {
@@ -7,6 +7,7 @@ use crate::{
 };
 use rustc_ast::{InlineAsmOptions, InlineAsmTemplatePiece};
 use rustc_codegen_ssa::{
+    common::asm_const_to_str,
     mir::operand::OperandValue,
     traits::{
         AsmBuilderMethods, AsmCodegenMethods, BaseTypeCodegenMethods, BuilderMethods,
@@ -14,7 +15,10 @@ use rustc_codegen_ssa::{
     },
 };
 use rustc_hash::FxHashMap;
-use rustc_middle::{span_bug, ty::Instance};
+use rustc_middle::mir::{InlineAsmOperand, TerminatorKind};
+use rustc_middle::ty::layout::{HasTypingEnv, LayoutOf};
+use rustc_middle::ty::{self, Instance};
+use rustc_middle::{bug, span_bug};
 use rustc_span::{Pos, Span};
 use rustc_target::asm::{InlineAsmRegClass, InlineAsmRegOrRegClass, NvptxInlineAsmRegClass};
 
@@ -342,3 +346,94 @@ pub(crate) fn inline_asm_call<'ll>(
         }
     }
 }
+
+/// Defines a `#[naked]` function, whose body is nothing but the PTX of its `naked_asm!` block.
+///
+/// Naked functions would normally be emitted as module level assembly with an ELF style symbol
+/// header, which is not valid PTX. Instead the function is declared like any other (so kernels
+/// still get their `.entry` and parameter list) and its body consists of the assembly followed by
+/// `unreachable`. Nothing reads the parameters, so no loads of them are emitted and the assembly
+/// has to access them itself, for example with `ld.param.u64 %rd1, [name_param_0];`.
+pub(crate) fn codegen_naked_fn<'tcx>(cx: &CodegenCx<'_, 'tcx>, instance: Instance<'tcx>) {
+    let mir = cx.tcx.instance_mir(instance.def);
+    let TerminatorKind::InlineAsm {
+        template,
+        ref operands,
+        line_spans,
+        ..
+    } = mir.basic_blocks.iter().next().unwrap().terminator().kind
+    else {
+        bug!("#[naked] functions should always terminate with an asm! block")
+    };
+
+    let llfn = cx.get_fn(instance);
+    let start = Builder::append_block(cx, llfn, "start");
+    let mut bx = Builder::build(cx, start);
+
+    let mut inputs = vec![];
+    let mut constraints = vec![];
+    let mut template_str = String::new();
+    for piece in template {
+        match *piece {
+            InlineAsmTemplatePiece::String(ref s) => template_str.push_str(&s.replace('$', "$$")),
+            InlineAsmTemplatePiece::Placeholder { operand_idx, .. } => {
+                match operands[operand_idx] {
+                    InlineAsmOperand::Const { ref value } => {
+                        let const_value = instance
+                            .instantiate_mir_and_normalize_erasing_regions(
+                                cx.tcx,
+                                cx.typing_env(),
+                                ty::EarlyBinder::bind(value.const_),
+                            )
+                            .eval(cx.tcx, cx.typing_env(), value.span)
+                            .expect("erroneous constant missed by mono item collection");
+                        let mono_type = instance.instantiate_mir_and_normalize_erasing_regions(
+                            cx.tcx,
+                            cx.typing_env(),
+                            ty::EarlyBinder::bind(value.ty()),
+                        );
+                        template_str.push_str(&asm_const_to_str(
+                            cx.tcx,
+                            value.span,
+                            const_value,
+                            cx.layout_of(mono_type),
+                        ));
+                    }
+                    InlineAsmOperand::SymFn { ref value } => {
+                        let mono_type = instance.instantiate_mir_and_normalize_erasing_regions(
+                            cx.tcx,
+                            cx.typing_env(),
+                            ty::EarlyBinder::bind(value.ty()),
+                        );
+                        let &ty::FnDef(def_id, args) = mono_type.kind() else {
+                            bug!("asm sym is not a function")
+                        };
+                        template_str.push_str(&format!("${{{}:c}}", inputs.len()));
+                        inputs.push(cx.get_fn(Instance::new(def_id, args)));
+                        constraints.push("s");
+                    }
+                    InlineAsmOperand::SymStatic { def_id } => {
+                        template_str.push_str(&format!("${{{}:c}}", inputs.len()));
+                        inputs.push(cx.get_static(def_id));
+                        constraints.push("s");
+                    }
+                    _ => bug!("invalid operand type for naked_asm!"),
+                }
+            }
+        }
+    }
+
+    inline_asm_call(
+        &mut bx,
+        &template_str,
+        &constraints.join(","),
+        &inputs,
+        cx.type_void(),
+        true,
+        false,
+        llvm::AsmDialect::Att,
+        line_spans,
+    )
+    .unwrap_or_else(|| span_bug!(line_spans[0], "LLVM asm constraint validation failed"));
+    bx.unreachable();
+}
@@ -7,6 +7,7 @@ use rustc_session::{Session, config::OptLevel};
 use rustc_span::{Symbol, sym};
 
 use crate::context::CodegenCx;
+use crate::target;
 
 #[inline] // so meta
 fn inline(val: &'_ Value, inline: InlineAttr) {
@@ -80,6 +81,27 @@ pub(crate) fn from_fn_attrs<'ll, 'tcx>(
     };
     inline(llfn, inline_attr);
 
+    // a function gated on a newer compute capability than the one being compiled for would
+    // contain instructions nvvm cannot emit, so reject it before it gets that far.
+    if let Some(feature) = codegen_fn_attrs
+        .target_features
+        .iter()
+        .find(|feature| !target::is_feature_available(cx.tcx.sess, feature.name))
+    {
+        cx.tcx.dcx().span_err(
+            cx.tcx.def_span(instance.def_id()),
+            format!(
+                "`{}` requires target feature `{}`, but the crate is being compiled for `{}`",
+                cx.tcx.def_path_str(instance.def_id()),
+                feature.name,
+                target::target_arch(cx.tcx.sess),
+            ),
+        );
+    }
+
+    if codegen_fn_attrs.flags.contains(CodegenFnAttrFlags::NAKED) {
+        llvm::Attribute::Naked.apply_llfn(Function, llfn);
+    }
     if codegen_fn_attrs.flags.contains(CodegenFnAttrFlags::COLD) {
         llvm::Attribute::Cold.apply_llfn(Function, llfn);
     }
@@ -101,6 +123,8 @@ pub struct Symbols {
     pub nvvm_internal: Symbol,
     pub kernel: Symbol,
     pub addrspace: Symbol,
+    pub shared_array: Symbol,
+    pub launch_bounds: Symbol,
 }
 
 // inspired by rust-gpu's attribute handling
@@ -109,6 +133,10 @@ pub(crate) struct NvvmAttributes {
     pub kernel: bool,
     pub used: bool,
     pub addrspace: Option<u8>,
+    pub shared_array: bool,
+    /// The maximum number of threads per block of a kernel, and optionally the minimum number of
+    /// blocks which should fit on a multiprocessor, like `__launch_bounds__`.
+    pub launch_bounds: Option<(u32, Option<u32>)>,
 }
 
 impl NvvmAttributes {
@@ -120,6 +148,8 @@ impl NvvmAttributes {
                 nvvm_attrs.kernel = true;
             } else if attr.path_matches(&[cx.symbols.nvvm_internal, sym::used]) {
                 nvvm_attrs.used = true;
+            } else if attr.path_matches(&[cx.symbols.nvvm_internal, cx.symbols.shared_array]) {
+                nvvm_attrs.shared_array = true;
             } else if attr.path_matches(&[cx.symbols.nvvm_internal, cx.symbols.addrspace]) {
                 let args = attr.meta_item_list().unwrap_or_default();
                 if let Some(MetaItemInner::Lit(MetaItemLit {
@@ -131,6 +161,21 @@ impl NvvmAttributes {
                 } else {
                     panic!();
                 }
+            } else if attr.path_matches(&[cx.symbols.nvvm_internal, cx.symbols.launch_bounds]) {
+                // the attribute is generated by `#[kernel]`, which validates the bounds.
+                let bounds = attr
+                    .meta_item_list()
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|arg| match arg {
+                        MetaItemInner::Lit(MetaItemLit {
+                            kind: LitKind::Int(val, _),
+                            ..
+                        }) => val.get() as u32,
+                        _ => panic!(),
+                    })
+                    .collect::<Vec<_>>();
+                nvvm_attrs.launch_bounds = Some((bounds[0], bounds.get(1).copied()));
             }
         }
         nvvm_attrs
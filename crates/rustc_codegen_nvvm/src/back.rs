@@ -4,7 +4,9 @@ use std::sync::Arc;
 
 use libc::{c_char, size_t};
 use rustc_codegen_ssa::back::write::{TargetMachineFactoryConfig, TargetMachineFactoryFn};
-use rustc_codegen_ssa::traits::{DebugInfoCodegenMethods, MiscCodegenMethods};
+use rustc_codegen_ssa::traits::{
+    DebugInfoCodegenMethods, MiscCodegenMethods, PreDefineCodegenMethods,
+};
 use rustc_codegen_ssa::{
     CompiledModule, ModuleCodegen,
     back::write::{CodegenContext, ModuleConfig},
@@ -24,7 +26,7 @@ use rustc_target::spec::{CodeModel, RelocModel};
 
 use crate::common::AsCCharPtr;
 use crate::llvm::{self};
-use crate::override_fns::define_or_override_fn;
+use crate::override_fns::{define_or_override_fn, is_naked};
 use crate::{LlvmMod, NvvmCodegenBackend, builder::Builder, context::CodegenCx, lto::ThinBuffer};
 
 pub fn llvm_err(handle: DiagCtxtHandle, msg: &str) -> FatalError {
@@ -267,7 +269,17 @@ pub fn compile_codegen_unit(tcx: TyCtxt<'_>, cgu_name: Symbol) -> (ModuleCodegen
                 },
             ) in &mono_items
             {
-                mono_item.predefine::<Builder<'_, '_, '_>>(&cx, linkage, visibility);
+                // naked functions are skipped by the generic predefine because other backends emit
+                // them as module level assembly, we define them as regular functions instead.
+                match mono_item {
+                    MonoItem::Fn(instance) if is_naked(&cx, instance) => cx.predefine_fn(
+                        instance,
+                        linkage,
+                        visibility,
+                        mono_item.symbol_name(cx.tcx).name,
+                    ),
+                    _ => mono_item.predefine::<Builder<'_, '_, '_>>(&cx, linkage, visibility),
+                }
             }
 
             // ... and now that we have everything pre-defined, fill out those definitions.
@@ -284,6 +296,11 @@ pub fn compile_codegen_unit(tcx: TyCtxt<'_>, cgu_name: Symbol) -> (ModuleCodegen
             // sanitize attrs are not allowed in nvvm so do nothing further.
             maybe_create_entry_wrapper::<Builder<'_, '_, '_>>(&cx);
 
+            // the kernel bodies are complete now, so counters can be added to their blocks.
+            for &(llfn, ref name) in cx.kernels_to_instrument.borrow().iter() {
+                Builder::instrument_kernel(&cx, llfn, name);
+            }
+
             // Run replace-all-uses-with for statics that need it
             for &(old_g, new_g) in cx.statics_to_rauw.borrow().iter() {
                 unsafe {
@@ -356,7 +373,9 @@ pub(crate) unsafe fn optimize(
             let fpm = llvm::LLVMCreateFunctionPassManagerForModule(llmod);
             let mpm = llvm::LLVMCreatePassManager();
 
-            let addpass = |pass_name: &str| {
+            // the function pass manager runs before the module one, so function passes which
+            // must see the result of the module pipeline are added to the module pass manager.
+            let addpass = |pass_name: &str, after_module_passes: bool| {
                 let pass =
                     llvm::LLVMRustFindAndCreatePass(pass_name.as_c_char_ptr(), pass_name.len());
                 if pass.is_none() {
@@ -364,6 +383,7 @@ pub(crate) unsafe fn optimize(
                 }
                 let pass = pass.unwrap();
                 let pass_manager = match llvm::LLVMRustPassKind(pass) {
+                    llvm::PassKind::Function if after_module_passes => &mpm,
                     llvm::PassKind::Function => &fpm,
                     llvm::PassKind::Module => &mpm,
                     llvm::PassKind::Other => {
@@ -384,11 +404,22 @@ pub(crate) unsafe fn optimize(
                 with_llvm_pmb(llmod, config, opt_level, &mut |b| {
                     llvm::LLVMPassManagerBuilderPopulateFunctionPassManager(b, fpm);
                     llvm::LLVMPassManagerBuilderPopulateModulePassManager(b, mpm);
-                })
+                });
+
+                // The module pipeline fully unrolls small loops late, after the last SROA run, so
+                // per-thread arrays like `[f32; 8]` indexed by an unrolled loop counter would stay
+                // in local memory even though every index is now constant. Run SROA once more so
+                // they get promoted to registers.
+                if matches!(
+                    opt_level,
+                    llvm::CodeGenOptLevel::Default | llvm::CodeGenOptLevel::Aggressive
+                ) {
+                    addpass("sroa", true);
+                }
             }
 
             for pass in &config.passes {
-                if !addpass(pass) {
+                if !addpass(pass, false) {
                     diag_handler.warn(format!("unknown pass `{}`, ignoring", pass));
                 }
             }
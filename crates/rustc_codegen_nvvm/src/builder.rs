@@ -245,6 +245,44 @@ impl<'ll, 'tcx, 'a> BuilderMethods<'a, 'tcx> for Builder<'a, 'll, 'tcx> {
         }
     }
 
+    fn cond_br_with_expect(
+        &mut self,
+        cond: &'ll Value,
+        then_llbb: &'ll BasicBlock,
+        else_llbb: &'ll BasicBlock,
+        expect: Option<bool>,
+    ) {
+        trace!("Cond br `{:?}` with expect {:?}", cond, expect);
+        let br = unsafe { llvm::LLVMBuildCondBr(self.llbuilder, cond, then_llbb, else_llbb) };
+        if let Some(expect) = expect {
+            if self.sess().opts.optimize != OptLevel::No {
+                self.branch_weights_metadata(br, &[expect, !expect]);
+            }
+        }
+    }
+
+    fn switch_with_weights(
+        &mut self,
+        v: &'ll Value,
+        else_llbb: &'ll BasicBlock,
+        else_is_cold: bool,
+        cases: impl ExactSizeIterator<Item = (u128, &'ll BasicBlock, bool)>,
+    ) {
+        trace!("Switch with weights `{:?}`", v);
+        let switch =
+            unsafe { llvm::LLVMBuildSwitch(self.llbuilder, v, else_llbb, cases.len() as c_uint) };
+        let mut hot = Vec::with_capacity(cases.len() + 1);
+        hot.push(!else_is_cold);
+        for (on_val, dest, is_cold) in cases {
+            let on_val = self.const_uint_big(self.val_ty(v), on_val);
+            unsafe { llvm::LLVMAddCase(switch, on_val, dest) }
+            hot.push(!is_cold);
+        }
+        if self.sess().opts.optimize != OptLevel::No {
+            self.branch_weights_metadata(switch, &hot);
+        }
+    }
+
     fn invoke(
         &mut self,
         llty: &'ll Type,
@@ -1179,6 +1217,72 @@ impl<'a, 'll, 'tcx> Builder<'a, 'll, 'tcx> {
         unsafe { llvm::LLVMConstVector(vals.as_ptr(), 2) }
     }
 
+    /// Makes the kernel `llfn` count how many threads execute each of its basic blocks, for
+    /// `--instrument-kernels`.
+    ///
+    /// This defines a `[u64; N]` global named `__rust_cuda_kernel_calls_<name>` in global memory,
+    /// with one counter for each of the `N` basic blocks of the kernel in layout order, and
+    /// atomically increments the counter of a block at its start. The first counter is the entry
+    /// block, so it counts the threads entering the kernel. A constant `[u32; N]` global named
+    /// `__rust_cuda_kernel_lines_<name>` maps every counter to the source line of its block in
+    /// the kernel, or 0 when the block has no location, which is always the case without
+    /// debuginfo. Both globals are marked as used so that they survive optimizations, the host
+    /// reads them with `Module::get_kernel_block_counts`.
+    pub(crate) fn instrument_kernel(cx: &'a CodegenCx<'ll, 'tcx>, llfn: &'ll Value, name: &str) {
+        let first = unsafe { llvm::LLVMGetFirstBasicBlock(llfn) };
+        let blocks: Vec<_> = std::iter::successors(Some(first), |&llbb| unsafe {
+            llvm::LLVMGetNextBasicBlock(llbb)
+        })
+        .collect();
+
+        let i64_ty = cx.type_i64();
+        let counters_ty = cx.type_array(i64_ty, blocks.len() as u64);
+        let counters = cx.declare_global(
+            &format!("__rust_cuda_kernel_calls_{name}"),
+            counters_ty,
+            AddressSpace(1),
+        );
+        unsafe { llvm::LLVMSetInitializer(counters, cx.const_null(counters_ty)) };
+        cx.add_used_global(counters);
+
+        // the lines are taken before the counters are added, which have no location.
+        let lines: Vec<_> = blocks
+            .iter()
+            .map(|&llbb| cx.const_u32(unsafe { llvm::LLVMRustGetBasicBlockLine(llbb) }))
+            .collect();
+        let lines = cx.const_array(cx.type_i32(), &lines);
+        let lines_name = format!("__rust_cuda_kernel_lines_{name}");
+        let lines_global = cx
+            .define_global(&lines_name, cx.val_ty(lines), AddressSpace(1))
+            .unwrap_or_else(|| bug!("kernel line table `{lines_name}` is already defined"));
+        unsafe {
+            llvm::LLVMSetInitializer(lines_global, lines);
+            llvm::LLVMSetGlobalConstant(lines_global, llvm::True);
+        }
+        cx.add_used_global(lines_global);
+
+        let mut bx = Self::with_cx(cx);
+        for (idx, &llbb) in blocks.iter().enumerate() {
+            // after any phis, which must stay at the top of the block.
+            bx.position_at_start(llbb);
+            let base = bx.ptrtoint(counters, i64_ty);
+            let addr = bx.add(base, cx.const_u64(idx as u64 * 8));
+            // nvvm does not let us emit atomicrmw, so use a reduction like cuda_std's atomics do.
+            crate::asm::inline_asm_call(
+                &mut bx,
+                "red.global.add.u64 [$0], 1;",
+                "l,~{memory}",
+                &[addr],
+                cx.type_void(),
+                true,
+                false,
+                llvm::AsmDialect::Att,
+                &[],
+            )
+            .unwrap_or_else(|| bug!("failed to generate the block counters of kernel `{name}`"));
+        }
+    }
+
     fn with_cx(cx: &'a CodegenCx<'ll, 'tcx>) -> Self {
         // Create a fresh builder from the crate context.
         let llbuilder = unsafe { llvm::LLVMCreateBuilderInContext(cx.llcx) };
@@ -1195,6 +1299,35 @@ impl<'a, 'll, 'tcx> Builder<'a, 'll, 'tcx> {
         }
     }
 
+    /// Attaches `!prof` branch weights to a `br` or `switch`, one entry per successor in order,
+    /// `true` meaning the successor is expected to be taken. Unlike `llvm.expect`, this does not
+    /// rely on the expect lowering pass running inside libNVVM.
+    fn branch_weights_metadata(&mut self, inst: &'ll Value, hot: &[bool]) {
+        // the same weights llvm's LowerExpectIntrinsic pass uses for `llvm.expect`.
+        const HOT_WEIGHT: u32 = 2000;
+        const COLD_WEIGHT: u32 = 1;
+
+        let name = "branch_weights";
+        unsafe {
+            let mut node = Vec::with_capacity(hot.len() + 1);
+            node.push(llvm::LLVMMDStringInContext(
+                self.cx.llcx,
+                name.as_ptr().cast(),
+                name.len() as c_uint,
+            ));
+            node.extend(hot.iter().map(|&hot| {
+                self.cx
+                    .const_u32(if hot { HOT_WEIGHT } else { COLD_WEIGHT })
+            }));
+
+            llvm::LLVMSetMetadata(
+                inst,
+                llvm::MetadataType::MD_prof as c_uint,
+                llvm::LLVMMDNodeInContext(self.cx.llcx, node.as_ptr(), node.len() as c_uint),
+            );
+        }
+    }
+
     fn align_metadata(&mut self, _load: &'ll Value, _align: Align) {}
 
     fn noundef_metadata(&mut self, _load: &'ll Value) {}
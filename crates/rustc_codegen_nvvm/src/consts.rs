@@ -263,6 +263,15 @@ impl<'ll> CodegenCx<'ll, '_> {
         }
     }
 
+    /// Raises the alignment of a global to `align`, never lowering an alignment that was
+    /// already set.
+    pub(crate) fn set_static_alignment(&self, g: &'ll Value, align: Align) {
+        unsafe {
+            let align = (align.bytes() as c_uint).max(llvm::LLVMGetAlignment(g));
+            llvm::LLVMSetAlignment(g, align);
+        }
+    }
+
     pub(crate) fn get_static(&self, def_id: DefId) -> &'ll Value {
         let instance = Instance::mono(self.tcx, def_id);
         if let Some(&g) = self.instances.borrow().get(&instance) {
@@ -310,6 +319,10 @@ impl<'ll> CodegenCx<'ll, '_> {
             self.unsupported("thread locals");
         }
 
+        // declarations must carry the alignment of the static too, otherwise accesses through
+        // them (such as to shared memory arrays) only assume the alignment of the llvm type.
+        self.set_static_alignment(g, self.align_of(ty));
+
         self.instances.borrow_mut().insert(instance, g);
         g
     }
@@ -403,7 +416,7 @@ impl<'ll> StaticCodegenMethods for CodegenCx<'ll, '_> {
                 new_g
             };
             trace!("Codegen static `{:?}`", g);
-            llvm::LLVMSetAlignment(g, self.align_of(ty).bytes() as c_uint);
+            self.set_static_alignment(g, self.align_of(ty));
             llvm::LLVMSetInitializer(g, v);
 
             debug_info::build_global_var_di_node(self, def_id, g);
@@ -76,6 +76,10 @@ pub(crate) struct CodegenCx<'ll, 'tcx> {
     /// See <https://llvm.org/docs/LangRef.html#the-llvm-compiler-used-global-variable> for details
     pub compiler_used_statics: RefCell<Vec<&'ll Value>>,
 
+    /// Kernels and their symbol names that get basic block counters when `--instrument-kernels`
+    /// is passed, see [`Builder::instrument_kernel`](crate::builder::Builder::instrument_kernel).
+    pub kernels_to_instrument: RefCell<Vec<(&'ll Value, String)>>,
+
     pub lltypes: RefCell<FxHashMap<(Ty<'tcx>, Option<VariantIdx>), &'ll Type>>,
     pub scalar_lltypes: RefCell<FxHashMap<Ty<'tcx>, &'ll Type>>,
     pub pointee_infos: RefCell<FxHashMap<(Ty<'tcx>, Size), Option<PointeeInfo>>>,
@@ -144,6 +148,7 @@ impl<'ll, 'tcx> CodegenCx<'ll, 'tcx> {
             statics_to_rauw: RefCell::new(Vec::new()),
             used_statics: RefCell::new(Vec::new()),
             compiler_used_statics: RefCell::new(Vec::new()),
+            kernels_to_instrument: RefCell::new(Vec::new()),
             lltypes: Default::default(),
             scalar_lltypes: Default::default(),
             pointee_infos: Default::default(),
@@ -165,6 +170,8 @@ impl<'ll, 'tcx> CodegenCx<'ll, 'tcx> {
                 nvvm_internal: Symbol::intern("nvvm_internal"),
                 kernel: Symbol::intern("kernel"),
                 addrspace: Symbol::intern("addrspace"),
+                shared_array: Symbol::intern("shared_array"),
+                launch_bounds: Symbol::intern("launch_bounds"),
             },
             dbg_cx,
             codegen_args: CodegenArgs::from_session(tcx.sess()),
@@ -426,6 +433,17 @@ impl<'ll, 'tcx> CodegenCx<'ll, 'tcx> {
             sym
         );
 
+        // foreign functions linked to nvvm intrinsics (such as the special register reads of
+        // `core::arch::nvptx`) are declared like our own intrinsics, without the attributes of
+        // rust functions which nvvm rejects on intrinsic declarations.
+        if tcx.is_foreign_item(instance.def_id()) && sym.starts_with("llvm.") {
+            let intrinsic = self.intrinsics.borrow().get(sym).cloned();
+            if let Some((_, llfn)) = intrinsic.or_else(|| self.declare_intrinsic(sym)) {
+                self.instances.borrow_mut().insert(instance, llfn);
+                return llfn;
+            }
+        }
+
         let fn_abi = self.fn_abi_of_instance(instance, ty::List::empty());
 
         let llfn = if let Some(llfn) = self.get_declared_value(sym) {
@@ -520,6 +538,9 @@ pub struct CodegenArgs {
     pub nvvm_options: Vec<NvvmOption>,
     pub override_libm: bool,
     pub final_module_path: Option<PathBuf>,
+    pub instrument_kernels: bool,
+    pub emit_kernel_params: bool,
+    pub link_bitcode: Vec<PathBuf>,
 }
 
 impl CodegenArgs {
@@ -537,8 +558,16 @@ impl CodegenArgs {
                 cg_args.nvvm_options.push(flag);
             } else if arg == "--override-libm" {
                 cg_args.override_libm = true;
+            } else if arg == "--instrument-kernels" {
+                cg_args.instrument_kernels = true;
+            } else if arg == "--emit-kernel-params" {
+                cg_args.emit_kernel_params = true;
+            } else if arg == "--link-bitcode" {
+                cg_args.link_bitcode.push(decode_path_arg(
+                    args.get(idx + 1).expect("No path for --link-bitcode"),
+                ));
             } else if arg == "--final-module-path" {
-                cg_args.final_module_path = Some(PathBuf::from(
+                cg_args.final_module_path = Some(decode_path_arg(
                     args.get(idx + 1).expect("No path for --final-module-path"),
                 ));
             }
@@ -548,6 +577,29 @@ impl CodegenArgs {
     }
 }
 
+/// Decodes the `%XX` escapes of a path given in the llvm-args. rustc splits the llvm-args on
+/// whitespace, so cuda_builder percent-encodes the whitespace and `%` in paths.
+fn decode_path_arg(arg: &str) -> PathBuf {
+    let mut bytes = Vec::with_capacity(arg.len());
+    let mut idx = 0;
+    while idx < arg.len() {
+        let escaped = arg
+            .get(idx..idx + 3)
+            .filter(|escape| {
+                escape.starts_with('%') && escape[1..].bytes().all(|b| b.is_ascii_hexdigit())
+            })
+            .and_then(|escape| u8::from_str_radix(&escape[1..], 16).ok());
+        if let Some(byte) = escaped {
+            bytes.push(byte);
+            idx += 3;
+        } else {
+            bytes.push(arg.as_bytes()[idx]);
+            idx += 1;
+        }
+    }
+    PathBuf::from(String::from_utf8_lossy(&bytes).into_owned())
+}
+
 impl<'ll> BackendTypes for CodegenCx<'ll, '_> {
     type Value = &'ll Value;
     type Function = &'ll Value;
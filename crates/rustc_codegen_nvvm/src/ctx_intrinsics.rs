@@ -49,7 +49,6 @@ impl<'ll> CodegenCx<'ll, '_> {
 
         ifn!(map, "llvm.trap" | "llvm.sideeffect", fn() -> void);
         ifn!(map, "llvm.assume", fn(i1) -> void);
-        ifn!(map, "llvm.prefetch", fn(i8p, t_i32, t_i32, t_i32) -> void);
 
         ifn!(map, "llvm.sadd.with.overflow.i16", fn(t_i16, t_i16) -> t_i16_i1);
         ifn!(map, "llvm.sadd.with.overflow.i32", fn(t_i32, t_i32) -> t_i32_i1);
@@ -176,7 +175,27 @@ impl<'ll> CodegenCx<'ll, '_> {
         ifn!(map, "llvm.lifetime.end.p0i8", fn(t_i64, i8p) -> void);
 
         ifn!(map, "llvm.expect.i1", fn(i1, i1) -> i1);
-        ifn!(map, "llvm.prefetch", fn(i8p, t_i32, t_i32, t_i32) -> void);
+
+        // nvvm intrinsics that lower to a single PTX instruction (`fma.rn`, `min`, `max`), used
+        // instead of going through libdevice for the corresponding float intrinsics.
+        ifn!(map, "llvm.nvvm.fma.rn.f", fn(t_f32, t_f32, t_f32) -> t_f32);
+        ifn!(map, "llvm.nvvm.fma.rn.d", fn(t_f64, t_f64, t_f64) -> t_f64);
+        ifn!(map, "llvm.nvvm.fmin.f", fn(t_f32, t_f32) -> t_f32);
+        ifn!(map, "llvm.nvvm.fmin.d", fn(t_f64, t_f64) -> t_f64);
+        ifn!(map, "llvm.nvvm.fmax.f", fn(t_f32, t_f32) -> t_f32);
+        ifn!(map, "llvm.nvvm.fmax.d", fn(t_f64, t_f64) -> t_f64);
+
+        // special register reads and barriers declared by `core::arch::nvptx`.
+        ifn!(
+            map,
+            "llvm.nvvm.read.ptx.sreg.tid.x" | "llvm.nvvm.read.ptx.sreg.tid.y" | "llvm.nvvm.read.ptx.sreg.tid.z"
+            | "llvm.nvvm.read.ptx.sreg.ntid.x" | "llvm.nvvm.read.ptx.sreg.ntid.y" | "llvm.nvvm.read.ptx.sreg.ntid.z"
+            | "llvm.nvvm.read.ptx.sreg.ctaid.x" | "llvm.nvvm.read.ptx.sreg.ctaid.y" | "llvm.nvvm.read.ptx.sreg.ctaid.z"
+            | "llvm.nvvm.read.ptx.sreg.nctaid.x" | "llvm.nvvm.read.ptx.sreg.nctaid.y" | "llvm.nvvm.read.ptx.sreg.nctaid.z"
+            | "llvm.nvvm.read.ptx.sreg.warpsize",
+            fn() -> t_i32
+        );
+        ifn!(map, "llvm.nvvm.barrier0", fn() -> void);
 
         // This isn't an "LLVM intrinsic", but LLVM's optimization passes
         // recognize it like one and we assume it exists in `core::slice::cmp`
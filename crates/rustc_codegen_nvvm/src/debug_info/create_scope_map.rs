@@ -28,20 +28,25 @@ pub(crate) fn compute_mir_scopes<'ll, 'tcx>(
     debug_context: &mut FunctionDebugContext<&'ll DIScope, &'ll DILocation>,
 ) {
     // Find all the scopes with variables defined in them.
-    let variables = if cx.sess().opts.debuginfo == DebugInfo::Full {
-        // Only consider variables when they're going to be emitted.
-        let mut vars = DenseBitSet::new_empty(mir.source_scopes.len());
-        // FIXME(eddyb) take into account that arguments always have debuginfo,
-        // irrespective of their name (assuming full debuginfo is enabled).
-        // NOTE(eddyb) actually, on second thought, those are always in the
-        // function scope, which always exists.
-        for var_debug_info in &mir.var_debug_info {
-            vars.insert(var_debug_info.source_info.scope);
+    let variables = match cx.sess().opts.debuginfo {
+        DebugInfo::Full => {
+            // Only consider variables when they're going to be emitted.
+            let mut vars = DenseBitSet::new_empty(mir.source_scopes.len());
+            // FIXME(eddyb) take into account that arguments always have debuginfo,
+            // irrespective of their name (assuming full debuginfo is enabled).
+            // NOTE(eddyb) actually, on second thought, those are always in the
+            // function scope, which always exists.
+            for var_debug_info in &mir.var_debug_info {
+                vars.insert(var_debug_info.source_info.scope);
+            }
+            Some(vars)
         }
-        Some(vars)
-    } else {
+        // Line tables never contain variables (rustc_codegen_ssa only creates them with full
+        // debuginfo, which `create_dbg_var` asserts), so lexical blocks would only bloat the PTX.
+        // Inlined scopes are still created, they are needed for the inlinedAt of the locations.
+        DebugInfo::LineTablesOnly => Some(DenseBitSet::new_empty(mir.source_scopes.len())),
         // Nothing to emit, of course.
-        None
+        _ => None,
     };
 
     let mut instantiated = DenseBitSet::new_empty(mir.source_scopes.len());
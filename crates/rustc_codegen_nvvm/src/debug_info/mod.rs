@@ -329,7 +329,11 @@ impl<'ll, 'tcx> DebugInfoCodegenMethods<'tcx> for CodegenCx<'ll, 'tcx> {
             cx: &CodegenCx<'ll, 'tcx>,
             fn_abi: &FnAbi<'tcx, Ty<'tcx>>,
         ) -> &'ll DIArray {
-            if cx.sess().opts.debuginfo == DebugInfo::Limited {
+            // only full debuginfo has variables, which need the types of the arguments.
+            if matches!(
+                cx.sess().opts.debuginfo,
+                DebugInfo::Limited | DebugInfo::LineTablesOnly
+            ) {
                 return create_DIArray(DIB(cx), &[]);
             }
 
@@ -489,6 +493,14 @@ impl<'ll, 'tcx> DebugInfoCodegenMethods<'tcx> for CodegenCx<'ll, 'tcx> {
         variable_kind: VariableKind,
         span: Span,
     ) -> Self::DIVariable {
+        // compute_mir_scopes relies on this to skip the lexical blocks below full debuginfo, a
+        // variable in a scope it did not create would be attached to the wrong scope.
+        debug_assert_eq!(
+            self.sess().opts.debuginfo,
+            DebugInfo::Full,
+            "debuginfo variable `{variable_name}` created without full debuginfo"
+        );
+
         let loc = self.lookup_debug_loc(span.lo());
         let file_metadata = file_metadata(self, &loc.file);
 
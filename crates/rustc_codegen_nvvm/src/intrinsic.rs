@@ -17,6 +17,7 @@ use rustc_target::callconv::{FnAbi, PassMode};
 use tracing::trace;
 
 use crate::abi::LlvmType;
+use crate::asm::inline_asm_call;
 use crate::builder::Builder;
 use crate::context::CodegenCx;
 use crate::llvm::{self, Metadata, Type, Value};
@@ -29,17 +30,71 @@ fn handle_128_bit_intrinsic<'ll>(b: &mut Builder<'_, 'll, '_>) -> &'ll Value {
 }
 
 // llvm 7 does not have saturating intrinsics, so we reimplement them right here.
-// This is derived from what rustc used to do before the intrinsics. It should map to the same assembly.
+// Signed 32 bit operations map directly to `add.sat.s32`/`sub.sat.s32`, 8 and 16 bit operations
+// are done in 32 bits and clamped to the bounds of the type, which ptxas turns into `min`/`max`.
+// Everything else is derived from what rustc used to do before the intrinsics. None of these
+// lowerings branch.
 fn saturating_intrinsic_impl<'ll, 'tcx>(
     b: &mut Builder<'_, 'll, 'tcx>,
     width: u32,
     signed: bool,
     is_add: bool,
     args: &[OperandRef<'tcx, &'ll Value>],
+    span: Span,
 ) -> &'ll Value {
+    use rustc_codegen_ssa::common::IntPredicate;
     use rustc_middle::ty::IntTy::*;
     use rustc_middle::ty::UintTy::*;
 
+    if signed && width == 32 {
+        let asm = if is_add {
+            "add.sat.s32 $0, $1, $2;"
+        } else {
+            "sub.sat.s32 $0, $1, $2;"
+        };
+        let i32_ty = b.type_i32();
+        return inline_asm_call(
+            b,
+            asm,
+            "=r,r,r",
+            &[args[0].immediate(), args[1].immediate()],
+            i32_ty,
+            false,
+            false,
+            llvm::AsmDialect::Att,
+            &[span],
+        )
+        .unwrap_or_else(|| span_bug!(span, "LLVM asm constraint validation failed"));
+    }
+
+    if width < 32 {
+        // the exact result always fits in 32 bits, so compute it and clamp it.
+        let i32_ty = b.type_i32();
+        let lhs = b.intcast(args[0].immediate(), i32_ty, signed);
+        let rhs = b.intcast(args[1].immediate(), i32_ty, signed);
+        let val = if is_add {
+            b.add(lhs, rhs)
+        } else {
+            b.sub(lhs, rhs)
+        };
+
+        let (min_value, max_value) = if signed {
+            (-(1 << (width - 1)), (1 << (width - 1)) - 1)
+        } else {
+            (0, (1 << width) - 1)
+        };
+        let min_value = b.const_i32(min_value);
+        let max_value = b.const_i32(max_value);
+
+        let too_small = b.icmp(IntPredicate::IntSLT, val, min_value);
+        let val = b.select(too_small, min_value, val);
+        let too_large = b.icmp(IntPredicate::IntSGT, val, max_value);
+        let val = b.select(too_large, max_value, val);
+
+        let llty = b.type_ix(width as u64);
+        return b.trunc(val, llty);
+    }
+
     let tcx = b.tcx;
     let ty = match (signed, width) {
         (true, 8) => Ty::new_int(tcx, I8),
@@ -129,14 +184,14 @@ fn get_simple_intrinsic<'ll>(
         sym::log10f64     => "__nv_log10",
         sym::log2f32      => "__nv_log2f",
         sym::log2f64      => "__nv_log2",
-        sym::fmaf32       => "__nv_fmaf",
-        sym::fmaf64       => "__nv_fma",
+        sym::fmaf32       => "llvm.nvvm.fma.rn.f",
+        sym::fmaf64       => "llvm.nvvm.fma.rn.d",
         sym::fabsf32      => "__nv_fabsf",
         sym::fabsf64      => "__nv_fabs",
-        sym::minnumf32    => "__nv_fminf",
-        sym::minnumf64    => "__nv_fmin",
-        sym::maxnumf32    => "__nv_fmaxf",
-        sym::maxnumf64    => "__nv_fmax",
+        sym::minnumf32    => "llvm.nvvm.fmin.f",
+        sym::minnumf64    => "llvm.nvvm.fmin.d",
+        sym::maxnumf32    => "llvm.nvvm.fmax.f",
+        sym::maxnumf64    => "llvm.nvvm.fmax.d",
         sym::copysignf32  => "__nv_copysignf",
         sym::copysignf64  => "__nv_copysign",
         sym::floorf32     => "__nv_floorf",
@@ -328,22 +383,38 @@ impl<'ll, 'tcx> IntrinsicCallBuilderMethods<'tcx> for Builder<'_, 'll, 'tcx> {
             | sym::prefetch_write_data
             | sym::prefetch_read_instruction
             | sym::prefetch_write_instruction => {
-                let (rw, cache_type) = match name {
-                    sym::prefetch_read_data => (0, 1),
-                    sym::prefetch_write_data => (1, 1),
-                    sym::prefetch_read_instruction => (0, 0),
-                    sym::prefetch_write_instruction => (1, 0),
-                    _ => bug!(),
+                // libnvvm does not understand `llvm.prefetch`, so data prefetches are lowered to
+                // the PTX `prefetch` instruction directly. PTX has no way to prefetch into the
+                // instruction cache, so instruction prefetches are dropped.
+                if matches!(
+                    name,
+                    sym::prefetch_read_instruction | sym::prefetch_write_instruction
+                ) {
+                    return Ok(());
+                }
+                // The locality hint goes from 0 (no temporal locality) to 3 (keep in all caches),
+                // data expected to be reused soon goes into L1, everything else only into L2.
+                let locality = self.const_to_opt_uint(args[1].immediate()).unwrap_or(3);
+                let asm = if locality >= 2 {
+                    "prefetch.L1 [$0];"
+                } else {
+                    "prefetch.L2 [$0];"
                 };
-                self.call_intrinsic(
-                    "llvm.prefetch",
-                    &[
-                        args[0].immediate(),
-                        self.const_i32(rw),
-                        args[1].immediate(),
-                        self.const_i32(cache_type),
-                    ],
+                let ptr = args[0].immediate();
+                let void = self.type_void();
+                inline_asm_call(
+                    self,
+                    asm,
+                    "l",
+                    &[ptr],
+                    void,
+                    true,
+                    false,
+                    llvm::AsmDialect::Att,
+                    &[span],
                 )
+                .unwrap_or_else(|| span_bug!(span, "LLVM asm constraint validation failed"));
+                return Ok(());
             }
             sym::carrying_mul_add => {
                 let (size, signed) = fn_args.type_at(0).int_size_and_signed(self.tcx);
@@ -401,6 +472,7 @@ impl<'ll, 'tcx> IntrinsicCallBuilderMethods<'tcx> for Builder<'_, 'll, 'tcx> {
                         signed,
                         name == sym::saturating_add,
                         args,
+                        span,
                     )
                 } else if width == 128 {
                     handle_128_bit_intrinsic(self)
@@ -112,6 +112,14 @@ impl CodegenBackend for NvvmCodegenBackend {
         ""
     }
 
+    fn target_features_cfg(
+        &self,
+        sess: &Session,
+        _allow_unstable: bool,
+    ) -> Vec<rustc_span::Symbol> {
+        target::target_features_cfg(sess)
+    }
+
     fn init(&self, sess: &Session) {
         let filter = tracing_subscriber::EnvFilter::from_env("NVVM_LOG");
         let subscriber = tracing_subscriber::fmt()
@@ -136,6 +144,9 @@ impl CodegenBackend for NvvmCodegenBackend {
         // into `target_machine_factory` (which is a noop), but it might make
         // sense to move some of the target feature parsing into here.
         providers.global_backend_features = |_tcx, ()| vec![];
+        providers.rust_target_features = |_tcx, _cnum| target::rust_target_features();
+        providers.implied_target_features =
+            |_tcx, feature| target::implied_target_features(feature);
 
         providers.fn_abi_of_fn_ptr = |tcx, key| {
             let result = (rustc_interface::DEFAULT_QUERY_PROVIDERS.fn_abi_of_fn_ptr)(tcx, key);
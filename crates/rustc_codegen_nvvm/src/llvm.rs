@@ -234,6 +234,7 @@ pub(crate) enum Attribute {
     Cold = 2,
     InlineHint = 3,
     MinSize = 4,
+    Naked = 5,
     NoAlias = 6,
     NoCapture = 7,
     NoInline = 8,
@@ -331,6 +332,7 @@ impl TypeKind {
 #[derive(Copy, Clone)]
 #[repr(C)]
 pub(crate) enum MetadataType {
+    MD_prof = 2,
     MD_range = 4,
     MD_invariant_load = 6,
     MD_nontemporal = 9,
@@ -716,6 +718,7 @@ unsafe extern "C" {
         AddressSpace: c_uint,
     ) -> &'a Value;
     pub(crate) fn LLVMGetOperand(Val: &Value, Index: c_uint) -> &Value;
+    pub(crate) fn LLVMGetNumOperands(Val: &Value) -> c_int;
     pub(crate) fn LLVMIsABitCastInst(Val: &Value) -> Option<&Value>;
     pub(crate) fn LLVMIsASelectInst(Val: &Value) -> Option<&Value>;
     pub(crate) fn LLVMRustGetFunctionType(V: &Value) -> &Type;
@@ -1382,6 +1385,7 @@ unsafe extern "C" {
     // Operations on instructions
     pub(crate) fn LLVMIsAInstruction(Val: &Value) -> Option<&Value>;
     pub(crate) fn LLVMGetFirstBasicBlock(Fn: &Value) -> &BasicBlock;
+    pub(crate) fn LLVMGetNextBasicBlock(BB: &BasicBlock) -> Option<&BasicBlock>;
 
     // Operations on call sites
     pub(crate) fn LLVMRustAddCallSiteAttribute(Instr: &Value, index: c_uint, attr: Attribute);
@@ -1947,4 +1951,5 @@ unsafe extern "C" {
     pub(crate) fn LLVMRustAddDereferenceableOrNullAttr(Fn: &Value, index: c_uint, bytes: u64);
 
     pub(crate) fn LLVMRustPositionBuilderAtStart<'a>(B: &Builder<'a>, BB: &'a BasicBlock);
+    pub(crate) fn LLVMRustGetBasicBlockLine(BB: &BasicBlock) -> c_uint;
 }
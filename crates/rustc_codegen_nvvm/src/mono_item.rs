@@ -4,13 +4,17 @@ use crate::attributes::NvvmAttributes;
 use crate::consts::linkage_to_llvm;
 use crate::context::CodegenCx;
 use crate::llvm;
+use crate::override_fns::is_naked;
 use crate::ty::LayoutLlvmExt;
+use rustc_abi::{AddressSpace, BackendRepr, HasDataLayout, Size};
 use rustc_codegen_ssa::traits::*;
 use rustc_hir::def_id::{DefId, LOCAL_CRATE};
+use rustc_middle::bug;
 use rustc_middle::mir::mono::{Linkage, Visibility};
 use rustc_middle::ty::TypeVisitableExt;
 use rustc_middle::ty::layout::{FnAbiOf, HasTypingEnv, LayoutOf};
-use rustc_middle::ty::{self, Instance};
+use rustc_middle::ty::{self, Instance, Ty};
+use rustc_target::callconv::{FnAbi, PassMode};
 use tracing::trace;
 
 pub(crate) fn visibility_to_llvm(linkage: Visibility) -> llvm::Visibility {
@@ -107,6 +111,21 @@ impl<'tcx> PreDefineCodegenMethods<'tcx> for CodegenCx<'_, 'tcx> {
                     c"nvvm.annotations".as_ptr().cast(),
                     node,
                 );
+                if let Some((max_threads, min_blocks)) = nvvm_attrs.launch_bounds {
+                    self.add_kernel_annotation(lldecl, "maxntidx", max_threads);
+                    if let Some(min_blocks) = min_blocks {
+                        self.add_kernel_annotation(lldecl, "minctasm", min_blocks);
+                    }
+                }
+                // naked kernels have no prologue for the counters to go in.
+                if self.codegen_args.instrument_kernels && !is_naked(self, instance) {
+                    self.kernels_to_instrument
+                        .borrow_mut()
+                        .push((lldecl, symbol_name.to_string()));
+                }
+                if self.codegen_args.emit_kernel_params {
+                    self.emit_kernel_params(fn_abi, symbol_name);
+                }
             }
             if nvvm_attrs.used {
                 trace!("Marking function `{:?}` as used", symbol_name);
@@ -124,3 +143,75 @@ impl<'tcx> PreDefineCodegenMethods<'tcx> for CodegenCx<'_, 'tcx> {
         self.instances.borrow_mut().insert(instance, lldecl);
     }
 }
+
+impl<'ll, 'tcx> CodegenCx<'ll, 'tcx> {
+    /// Adds `!{kernel, !"{key}", i32 value}` to `nvvm.annotations`, such as the `maxntidx` and
+    /// `minctasm` launch bounds.
+    fn add_kernel_annotation(&self, kernel: &'ll llvm::Value, key: &str, value: u32) {
+        unsafe {
+            let key = llvm::LLVMMDStringInContext(self.llcx, key.as_ptr().cast(), key.len() as u32);
+            let mdvals = &[kernel, key, self.const_u32(value)];
+            let node = llvm::LLVMMDNodeInContext(self.llcx, mdvals.as_ptr(), mdvals.len() as u32);
+            llvm::LLVMAddNamedMetadataOperand(
+                self.llmod,
+                c"nvvm.annotations".as_ptr().cast(),
+                node,
+            );
+        }
+    }
+
+    /// Emits `__rust_cuda_kernel_params_{name}`, a table of the kernel's parameters which the host
+    /// can read with `Module::get_kernel_params` to check launch arguments against. It holds the
+    /// number of parameters followed by the offset and size in bytes of each one in the kernel's
+    /// parameter buffer, with slices taking up two parameters like in the ptx signature.
+    fn emit_kernel_params(&self, fn_abi: &FnAbi<'tcx, Ty<'tcx>>, name: &str) {
+        let ptr = (
+            self.data_layout().pointer_size,
+            self.data_layout().pointer_align.abi,
+        );
+        let mut params = Vec::with_capacity(fn_abi.args.len());
+        for arg in fn_abi.args.iter() {
+            match arg.mode {
+                PassMode::Ignore => {}
+                PassMode::Pair(..) => {
+                    let BackendRepr::ScalarPair(a, b) = arg.layout.backend_repr else {
+                        bug!("kernel parameter `{}` is passed as a pair", arg.layout.ty)
+                    };
+                    params.push((a.size(self), a.align(self).abi));
+                    params.push((b.size(self), b.align(self).abi));
+                }
+                PassMode::Indirect {
+                    meta_attrs: Some(_),
+                    ..
+                } => params.extend([ptr, ptr]),
+                PassMode::Indirect {
+                    meta_attrs: None, ..
+                } => params.push(ptr),
+                PassMode::Direct(_) | PassMode::Cast { .. } => {
+                    params.push((arg.layout.size, arg.layout.align.abi))
+                }
+            }
+        }
+
+        let mut table = vec![params.len() as u32];
+        let mut offset = Size::ZERO;
+        for (size, align) in params {
+            offset = offset.align_to(align);
+            table.extend([offset.bytes() as u32, size.bytes() as u32]);
+            offset += size;
+        }
+
+        let values = table.iter().map(|x| self.const_u32(*x)).collect::<Vec<_>>();
+        let init = self.const_array(self.type_i32(), &values);
+        let global_name = format!("__rust_cuda_kernel_params_{name}");
+        let g = self
+            .define_global(&global_name, self.val_ty(init), AddressSpace(1))
+            .unwrap_or_else(|| bug!("kernel parameter table `{global_name}` is already defined"));
+        unsafe {
+            llvm::LLVMSetInitializer(g, init);
+            llvm::LLVMSetGlobalConstant(g, llvm::True);
+        }
+        // the table is only ever read by the host, keep it from being internalized and removed.
+        self.add_used_global(g);
+    }
+}
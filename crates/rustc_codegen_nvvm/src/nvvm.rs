@@ -9,6 +9,7 @@ use crate::lto::ThinBuffer;
 use nvvm::*;
 use rustc_codegen_ssa::traits::ThinBufferMethods;
 use rustc_session::{Session, config::DebugInfo};
+use std::ffi::c_uint;
 use std::fmt::Display;
 use std::marker::PhantomData;
 use std::ptr;
@@ -48,7 +49,8 @@ impl Display for CodegenErr {
 /// it returns a vector of bytes.
 ///
 /// Note that this will implicitly try to find libdevice and add it, so don't do that
-/// step before this. It will fatal error if it cannot find it.
+/// step before this. It will fatal error if it cannot find it. The bitcode libraries
+/// passed with `--link-bitcode` are added the same way.
 pub fn codegen_bitcode_modules(
     args: &CodegenArgs,
     sess: &Session,
@@ -107,6 +109,24 @@ pub fn codegen_bitcode_modules(
     prog.add_lazy_module(LIBDEVICE_BITCODE, "libdevice".to_string())?;
     prog.add_lazy_module(LIBINTRINSICS, "libintrinsics".to_string())?;
 
+    // the libraries must stay alive until the program is compiled.
+    let libraries = args
+        .link_bitcode
+        .iter()
+        .map(|path| {
+            std::fs::read(path).unwrap_or_else(|err| {
+                sess.dcx().fatal(format!(
+                    "failed to read bitcode library `{}`: {}",
+                    path.display(),
+                    err
+                ))
+            })
+        })
+        .collect::<Vec<_>>();
+    for (path, library) in args.link_bitcode.iter().zip(&libraries) {
+        prog.add_lazy_module(library, path.display().to_string())?;
+    }
+
     // for now, while the codegen is young, we always run verification on the program.
     // This is to make debugging much easier, libnvvm tends to infinitely loop or segfault on invalid programs
     // which makes debugging extremely hard. This way, if a malformed program is created, it is caught before
@@ -123,7 +143,18 @@ pub fn codegen_bitcode_modules(
         );
     }
 
-    let res = match prog.compile(&args.nvvm_options) {
+    // libnvvm drops the debug info unless asked for it, ask for the line tables if only those
+    // were requested on the rustc side.
+    let mut nvvm_options = args.nvvm_options.clone();
+    if sess.opts.debuginfo == DebugInfo::LineTablesOnly
+        && !nvvm_options
+            .iter()
+            .any(|opt| matches!(opt, NvvmOption::GenDebugInfo | NvvmOption::GenLineInfo))
+    {
+        nvvm_options.push(NvvmOption::GenLineInfo);
+    }
+
+    let res = match prog.compile(&nvvm_options) {
         Ok(b) => b,
         Err(error) => {
             // this should never happen, if it does, something went really bad or its a bug on libnvvm's end
@@ -298,18 +329,52 @@ unsafe fn internalize_pass(module: &Module, cx: &Context) {
             }
         }
 
+        // see what globals are marked as `#[used]` by the user, those may only ever be accessed
+        // by the host (e.g. through cuModuleGetGlobal), so they must not be internalized.
+        let used_globals = llvm_used_globals(module);
+
         let iter = GlobalIter::new(&module);
-        for func in iter {
-            let is_decl = LLVMIsDeclaration(func) == True;
+        for global in iter {
+            // globals such as `llvm.used` and `llvm.compiler.used` are special to LLVM, they only
+            // keep their meaning with appending linkage.
+            if get_value_name(global).starts_with(b"llvm.") {
+                continue;
+            }
 
-            if !is_decl {
-                LLVMRustSetLinkage(func, Linkage::InternalLinkage);
-                LLVMRustSetVisibility(func, Visibility::Default);
+            let is_decl = LLVMIsDeclaration(global) == True;
+            let is_used = used_globals.contains(&global);
+
+            if is_used {
+                LLVMRustSetLinkage(global, Linkage::ExternalLinkage);
+                LLVMRustSetVisibility(global, Visibility::Default);
+            } else if !is_decl {
+                LLVMRustSetLinkage(global, Linkage::InternalLinkage);
+                LLVMRustSetVisibility(global, Visibility::Default);
             }
         }
     }
 }
 
+/// Collects the globals referenced by the `llvm.used` variable. Each entry is either the global
+/// itself or a pointer cast of it to `i8*`.
+unsafe fn llvm_used_globals(module: &Module) -> Vec<&Value> {
+    unsafe {
+        let Some(init) = LLVMGetNamedGlobal(module, c"llvm.used".as_ptr().cast())
+            .and_then(|used| LLVMGetInitializer(used))
+        else {
+            return Vec::new();
+        };
+
+        (0..LLVMGetNumOperands(init) as c_uint)
+            .map(|i| LLVMGetOperand(init, i))
+            .map(|entry| match LLVMIsAGlobalVariable(entry) {
+                Some(global) => global,
+                None => LLVMGetOperand(entry, 0),
+            })
+            .collect()
+    }
+}
+
 unsafe fn dce_pass(module: &Module) {
     unsafe {
         let pass_manager = LLVMCreatePassManager();
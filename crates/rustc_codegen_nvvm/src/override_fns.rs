@@ -1,22 +1,94 @@
 //! Functions for overriding certain functions in certain crates with special
-//! codegen-builtin methods. Currently the only use for this is overriding libm functions
-//! with libdevice intrinsics (which are much faster and smaller).
+//! codegen-builtin methods. This is used for overriding libm functions with libdevice
+//! intrinsics (which are much faster and smaller), and for defining the generic shared
+//! memory arrays of `cuda_std::shared_array!`.
 
-use crate::{builder::Builder, context::CodegenCx, llvm};
+use crate::attributes::NvvmAttributes;
+use crate::llvm::AttributePlace::Function;
+use crate::ty::LayoutLlvmExt;
+use crate::{asm, builder::Builder, context::CodegenCx, llvm};
+use rustc_abi::{AddressSpace, Align};
 use rustc_codegen_ssa::mono_item::MonoItemExt;
-use rustc_codegen_ssa::traits::BuilderMethods;
+use rustc_codegen_ssa::traits::{BuilderMethods, ConstCodegenMethods};
 use rustc_hir::def_id::LOCAL_CRATE;
-use rustc_middle::{mir::mono::MonoItem, ty::Instance};
+use rustc_middle::bug;
+use rustc_middle::middle::codegen_fn_attrs::CodegenFnAttrFlags;
+use rustc_middle::ty::layout::{FnAbiOf, LayoutOf};
+use rustc_middle::{mir::mono::MonoItem, ty, ty::Instance};
 
 /// Either override or define a function.
 pub(crate) fn define_or_override_fn<'tcx>(func: Instance<'tcx>, cx: &CodegenCx<'_, 'tcx>) {
-    if should_override(func, cx) {
+    if is_naked(cx, func) {
+        asm::codegen_naked_fn(cx, func);
+    } else if is_shared_array(cx, func) {
+        define_shared_array(func, cx);
+    } else if should_override(func, cx) {
         override_libm_function(func, cx);
     } else {
         MonoItem::define::<Builder<'_, '_, '_>>(&MonoItem::Fn(func), cx);
     }
 }
 
+pub(crate) fn is_naked<'tcx>(cx: &CodegenCx<'_, 'tcx>, func: Instance<'tcx>) -> bool {
+    cx.tcx
+        .codegen_fn_attrs(func.def_id())
+        .flags
+        .contains(CodegenFnAttrFlags::NAKED)
+}
+
+fn is_shared_array<'tcx>(cx: &CodegenCx<'_, 'tcx>, func: Instance<'tcx>) -> bool {
+    let attrs = cx.tcx.get_attrs_unchecked(func.def_id());
+    NvvmAttributes::parse(cx, attrs).shared_array
+}
+
+/// Defines an instance of `cuda_std::shared::__shared_array::<T, N, ALIGN, Site>`, which returns a
+/// pointer to a `[T; N]` in shared memory aligned to at least `ALIGN`.
+///
+/// Statics cannot be generic, so `shared_array!` cannot declare one with a generic element type.
+/// Instead every instance of the function gets its own shared global, `Site` being a type unique
+/// to each invocation of the macro. Sizes are only computed here, after monomorphization, so
+/// element types sized with `size_of` of a generic parameter work.
+fn define_shared_array<'tcx>(func: Instance<'tcx>, cx: &CodegenCx<'_, 'tcx>) {
+    let fn_abi = cx.fn_abi_of_instance(func, ty::List::empty());
+    let ret_ty = fn_abi.ret.layout.ty;
+    let Some(array_ty) = ret_ty.builtin_deref(true) else {
+        bug!(
+            "shared array function returns `{}` instead of a pointer",
+            ret_ty
+        )
+    };
+    let layout = cx.layout_of(array_ty);
+    let requested_align = func
+        .args
+        .const_at(2)
+        .try_to_target_usize(cx.tcx)
+        .and_then(|align| Align::from_bytes(align).ok())
+        .unwrap_or(Align::ONE);
+
+    let llty = layout.llvm_type(cx);
+    let name = format!("{}_shared", cx.tcx.symbol_name(func).name);
+    let g = cx
+        .define_global(&name, llty, AddressSpace(3))
+        .unwrap_or_else(|| bug!("shared array `{}` is already defined", name));
+    unsafe {
+        llvm::LLVMRustSetLinkage(g, llvm::Linkage::InternalLinkage);
+        // the initializer is discarded for shared globals.
+        llvm::LLVMSetInitializer(g, cx.const_undef(llty));
+    }
+    cx.set_static_alignment(g, layout.align.abi.max(requested_align));
+
+    // the function is `#[inline(never)]` so the mir inliner keeps the call, but it
+    // should be inlined into its callers now that it has a body.
+    let llfn = cx.get_fn(func);
+    llvm::Attribute::NoInline.unapply_llfn(Function, llfn);
+    llvm::Attribute::AlwaysInline.apply_llfn(Function, llfn);
+
+    let start = Builder::append_block(cx, llfn, "start");
+    let mut bx = Builder::build(cx, start);
+    let ptr = bx.bitcast(g, cx.layout_of(ret_ty).llvm_type(cx));
+    bx.ret(ptr);
+}
+
 fn should_override<'tcx>(func: Instance<'tcx>, cx: &CodegenCx<'_, 'tcx>) -> bool {
     if !cx.codegen_args.override_libm {
         return false;
@@ -1,7 +1,13 @@
+use crate::context::CodegenArgs;
 use crate::llvm::{self, Type};
+use nvvm::{NvvmArch, NvvmOption};
+use rustc_data_structures::unord::UnordMap;
+use rustc_session::Session;
+use rustc_span::Symbol;
 use rustc_target::spec::{
     LinkerFlavor, MergeFunctions, PanicStrategy, Target, TargetMetadata, TargetOptions,
 };
+use rustc_target::target_features::Stability;
 
 pub const DATA_LAYOUT: &str = "e-p:64:64:64-i1:8:8-i8:8:8-i16:16:16-i32:32:32-i64:64:64-f32:32:32-f64:64:64-v16:16:16-v32:32:32-v64:64:64-v128:128:128-n16:32:64";
 pub const TARGET_TRIPLE: &str = "nvptx64-nvidia-cuda";
@@ -53,3 +59,78 @@ pub fn target() -> Target {
         },
     }
 }
+
+/// The target features accepted by `#[target_feature(enable = "...")]` and set in
+/// `cfg(target_feature)`, one per compute capability, in ascending order. Each feature implies
+/// all the ones before it.
+const SM_FEATURES: &[(&str, NvvmArch)] = &[
+    ("sm_35", NvvmArch::Compute35),
+    ("sm_37", NvvmArch::Compute37),
+    ("sm_50", NvvmArch::Compute50),
+    ("sm_52", NvvmArch::Compute52),
+    ("sm_53", NvvmArch::Compute53),
+    ("sm_60", NvvmArch::Compute60),
+    ("sm_61", NvvmArch::Compute61),
+    ("sm_62", NvvmArch::Compute62),
+    ("sm_70", NvvmArch::Compute70),
+    ("sm_72", NvvmArch::Compute72),
+    ("sm_75", NvvmArch::Compute75),
+    ("sm_80", NvvmArch::Compute80),
+];
+
+/// The compute capability the crate is being compiled for, as given by the `-arch` nvvm option.
+pub(crate) fn target_arch(sess: &Session) -> NvvmArch {
+    CodegenArgs::from_session(sess)
+        .nvvm_options
+        .iter()
+        .rev()
+        .find_map(|opt| match opt {
+            NvvmOption::Arch(arch) => Some(*arch),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+fn sm_level(feature: &str) -> Option<usize> {
+    SM_FEATURES.iter().position(|(name, _)| *name == feature)
+}
+
+fn arch_level(arch: NvvmArch) -> usize {
+    SM_FEATURES
+        .iter()
+        .position(|(_, a)| *a == arch)
+        .expect("every nvvm arch has a matching target feature")
+}
+
+/// Replacement for the `rust_target_features` query, which knows no features for nvptx.
+pub(crate) fn rust_target_features() -> UnordMap<String, Stability> {
+    SM_FEATURES
+        .iter()
+        .map(|(name, _)| (name.to_string(), Stability::Stable))
+        .collect()
+}
+
+/// Replacement for the `implied_target_features` query. `sm_XX` implies every lower `sm_YY`.
+pub(crate) fn implied_target_features(feature: Symbol) -> Vec<Symbol> {
+    match sm_level(feature.as_str()) {
+        Some(level) => SM_FEATURES[..=level]
+            .iter()
+            .map(|(name, _)| Symbol::intern(name))
+            .collect(),
+        None => vec![feature],
+    }
+}
+
+/// The features made available by the target arch, used for `cfg(target_feature)`.
+pub(crate) fn target_features_cfg(sess: &Session) -> Vec<Symbol> {
+    SM_FEATURES[..=arch_level(target_arch(sess))]
+        .iter()
+        .map(|(name, _)| Symbol::intern(name))
+        .collect()
+}
+
+/// Whether code that requires `feature` can be compiled for the target arch. Features that are
+/// not compute capabilities are always available.
+pub(crate) fn is_feature_available(sess: &Session, feature: Symbol) -> bool {
+    sm_level(feature.as_str()).is_none_or(|level| level <= arch_level(target_arch(sess)))
+}
@@ -0,0 +1,18 @@
+use std::env;
+use std::path;
+
+use cuda_builder::{CudaBuilder, DebugInfo};
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=kernels");
+
+    let out_path = path::PathBuf::from(env::var("OUT_DIR").unwrap());
+    CudaBuilder::new("kernels")
+        .instrument_kernels(true)
+        // the line tables give the source line of every counter.
+        .debug(DebugInfo::LineTables)
+        .copy_to(out_path.join("kernels.ptx"))
+        .build()
+        .unwrap();
+}
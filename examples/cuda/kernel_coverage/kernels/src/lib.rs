@@ -0,0 +1,19 @@
+use core::cmp::Ordering;
+
+use cuda_std::prelude::*;
+
+/// Transforms every value depending on its sign, with a branch for each sign.
+#[kernel]
+#[allow(improper_ctypes_definitions, clippy::missing_safety_doc)]
+pub unsafe fn transform(values: &[i32], out: *mut i32) {
+    let idx = thread::index_1d() as usize;
+    if idx < values.len() {
+        let value = values[idx];
+        let transformed = match value.cmp(&0) {
+            Ordering::Less => value.wrapping_mul(3),
+            Ordering::Equal => 7,
+            Ordering::Greater => value / 2,
+        };
+        unsafe { *out.add(idx) = transformed };
+    }
+}
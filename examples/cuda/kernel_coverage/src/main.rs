@@ -0,0 +1,72 @@
+//! Instruments a kernel with per basic block counters, runs it and reads back how many threads
+//! executed each block, and so which branches were taken.
+
+use cust::prelude::*;
+use std::cmp::Ordering;
+use std::error::Error;
+
+const VALUES_LEN: usize = 1000;
+const BLOCK_SIZE: u32 = 256;
+
+static PTX: &str = include_str!(concat!(env!("OUT_DIR"), "/kernels.ptx"));
+static KERNEL_SOURCE: &str = include_str!("../kernels/src/lib.rs");
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let _ctx = cust::quick_init()?;
+    let module = Module::from_ptx(PTX, &[])?;
+    let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+    let transform = module.get_function("transform")?;
+
+    // 300 negative values, 100 zeros and 600 positive values.
+    let values: Vec<i32> = (0..VALUES_LEN as i32).map(|i| i % 10 - 3).collect();
+    let values_gpu = values.as_slice().as_dbuf()?;
+    let out_gpu = DeviceBuffer::<i32>::zeroed(VALUES_LEN)?;
+    let grid_size = (VALUES_LEN as u32).div_ceil(BLOCK_SIZE);
+    unsafe {
+        launch!(transform<<<grid_size, BLOCK_SIZE, 0, stream>>>(
+            values_gpu.as_device_ptr(),
+            values_gpu.len(),
+            out_gpu.as_device_ptr(),
+        ))?;
+    }
+    stream.synchronize()?;
+
+    let expected: Vec<i32> = values
+        .iter()
+        .map(|&v| match v.cmp(&0) {
+            Ordering::Less => v * 3,
+            Ordering::Equal => 7,
+            Ordering::Greater => v / 2,
+        })
+        .collect();
+    assert_eq!(out_gpu.as_host_vec()?, expected);
+
+    let blocks = module.get_kernel_block_counts("transform")?;
+    println!("{:>6} {:>8}  source", "line", "threads");
+    for block in &blocks {
+        let source = match block.line {
+            0 => "",
+            line => KERNEL_SOURCE
+                .lines()
+                .nth(line as usize - 1)
+                .unwrap_or("")
+                .trim(),
+        };
+        println!("{:>6} {:>8}  {source}", block.line, block.count);
+    }
+
+    // every launched thread enters the kernel, then the blocks of the bounds check and of each
+    // branch are executed by as many threads as there are values taking them.
+    assert_eq!(blocks[0].count, (grid_size * BLOCK_SIZE) as u64);
+    for count in [VALUES_LEN as u64, 300, 100, 600] {
+        assert!(
+            blocks.iter().any(|block| block.count == count),
+            "no block was executed by {count} threads"
+        );
+    }
+
+    module.reset_kernel_block_counts("transform")?;
+    let blocks = module.get_kernel_block_counts("transform")?;
+    assert!(blocks.iter().all(|block| block.count == 0));
+    Ok(())
+}
@@ -0,0 +1,17 @@
+use std::env;
+use std::path;
+
+use cuda_builder::{CudaBuilder, DebugInfo};
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=kernels");
+
+    let out_path = path::PathBuf::from(env::var("OUT_DIR").unwrap());
+    CudaBuilder::new("kernels")
+        .debug(DebugInfo::LineTables)
+        .final_module_path(out_path.join("kernels.ll"))
+        .copy_to(out_path.join("kernels.ptx"))
+        .build()
+        .unwrap();
+}
@@ -0,0 +1,19 @@
+use cuda_std::prelude::*;
+
+// inlined into the kernel, so its locations are inlined at the call.
+#[inline(always)]
+fn scale(x: f32, factor: f32) -> f32 {
+    x * factor
+}
+
+/// Adds `a * xs` to `ys`.
+#[kernel]
+#[allow(improper_ctypes_definitions, clippy::missing_safety_doc)]
+pub unsafe fn saxpy(a: f32, xs: &[f32], ys: *mut f32) {
+    let idx = thread::index_1d() as usize;
+    if idx < xs.len() {
+        let scaled = scale(xs[idx], a);
+        let y = unsafe { &mut *ys.add(idx) };
+        *y += scaled;
+    }
+}
@@ -0,0 +1,54 @@
+//! Checks that building with `DebugInfo::LineTables` emits source locations but no variables,
+//! and that the kernels still run.
+
+use cust::prelude::*;
+use std::error::Error;
+
+const VALUES_LEN: usize = 10_000;
+const BLOCK_SIZE: u32 = 256;
+
+static PTX: &str = include_str!(concat!(env!("OUT_DIR"), "/kernels.ptx"));
+/// The NVVM IR the codegen gave to libnvvm.
+static NVVM_IR: &str = include_str!(concat!(env!("OUT_DIR"), "/kernels.ll"));
+
+fn main() -> Result<(), Box<dyn Error>> {
+    assert!(
+        NVVM_IR.contains("!DILocation("),
+        "the NVVM IR has no source locations"
+    );
+    assert!(
+        !NVVM_IR.contains("!DILocalVariable("),
+        "the NVVM IR describes local variables"
+    );
+    // libnvvm turns the locations into `.loc` directives.
+    assert!(
+        PTX.lines().any(|line| line.trim().starts_with(".loc")),
+        "the PTX has no line info"
+    );
+
+    let _ctx = cust::quick_init()?;
+    let module = Module::from_ptx(PTX, &[])?;
+    let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+    let saxpy = module.get_function("saxpy")?;
+
+    let xs: Vec<f32> = (0..VALUES_LEN).map(|i| i as f32).collect();
+    let xs_gpu = xs.as_slice().as_dbuf()?;
+    let ys_gpu = DeviceBuffer::from_slice(&vec![1.0f32; VALUES_LEN])?;
+    let grid_size = (VALUES_LEN as u32).div_ceil(BLOCK_SIZE);
+    unsafe {
+        launch!(saxpy<<<grid_size, BLOCK_SIZE, 0, stream>>>(
+            2.0f32,
+            xs_gpu.as_device_ptr(),
+            xs_gpu.len(),
+            ys_gpu.as_device_ptr(),
+        ))?;
+    }
+    stream.synchronize()?;
+    assert_eq!(
+        ys_gpu.as_host_vec()?,
+        xs.iter().map(|x| 2.0 * x + 1.0).collect::<Vec<_>>()
+    );
+
+    println!("the kernels have line info and no variables");
+    Ok(())
+}
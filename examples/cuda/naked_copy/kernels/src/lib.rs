@@ -0,0 +1,37 @@
+#![cfg_attr(
+    target_arch = "nvptx64",
+    feature(naked_functions, asm_experimental_arch)
+)]
+
+// the kernel is only PTX, so it is not compiled for the host.
+#[cfg(target_arch = "nvptx64")]
+use core::arch::naked_asm;
+#[cfg(target_arch = "nvptx64")]
+use cuda_std::kernel;
+
+/// Copies one `u32` per thread of a single block from `src` to `dst`, written entirely in PTX.
+#[cfg(target_arch = "nvptx64")]
+#[kernel]
+#[naked]
+#[allow(clippy::missing_safety_doc)]
+pub unsafe fn copy(src: *const u32, dst: *mut u32) {
+    unsafe {
+        naked_asm!(
+            "{{",
+            ".reg .u64 %src, %dst, %offset;",
+            ".reg .u32 %i, %value;",
+            "ld.param.u64 %src, [copy_param_0];",
+            "ld.param.u64 %dst, [copy_param_1];",
+            "cvta.to.global.u64 %src, %src;",
+            "cvta.to.global.u64 %dst, %dst;",
+            "mov.u32 %i, %tid.x;",
+            "mul.wide.u32 %offset, %i, 4;",
+            "add.u64 %src, %src, %offset;",
+            "add.u64 %dst, %dst, %offset;",
+            "ld.global.u32 %value, [%src];",
+            "st.global.u32 [%dst], %value;",
+            "ret;",
+            "}}",
+        )
+    }
+}
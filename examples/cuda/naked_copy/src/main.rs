@@ -0,0 +1,41 @@
+//! Checks that a `#[naked]` kernel is emitted as only its PTX and can be launched like any other
+//! kernel.
+
+use cust::prelude::*;
+use std::error::Error;
+
+const BLOCK_SIZE: u32 = 256;
+
+static PTX: &str = include_str!(concat!(env!("OUT_DIR"), "/kernels.ptx"));
+
+fn main() -> Result<(), Box<dyn Error>> {
+    // the kernel keeps its `.entry` and parameter list, and its body is the assembly.
+    assert!(
+        PTX.lines().any(|line| line.contains(".entry copy(")),
+        "the PTX has no `copy` kernel"
+    );
+    assert!(
+        PTX.contains("ld.param.u64 %src, [copy_param_0];"),
+        "the PTX of the kernel is missing"
+    );
+
+    let _ctx = cust::quick_init()?;
+    let module = Module::from_ptx(PTX, &[])?;
+    let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+    let copy = module.get_function("copy")?;
+
+    let values: Vec<u32> = (0..BLOCK_SIZE).map(|i| i * 3 + 1).collect();
+    let src_gpu = values.as_slice().as_dbuf()?;
+    let dst_gpu = DeviceBuffer::<u32>::zeroed(values.len())?;
+    unsafe {
+        launch!(copy<<<1, BLOCK_SIZE, 0, stream>>>(
+            src_gpu.as_device_ptr(),
+            dst_gpu.as_device_ptr(),
+        ))?;
+    }
+    stream.synchronize()?;
+    assert_eq!(dst_gpu.as_host_vec()?, values);
+
+    println!("the naked kernel copied {} values", values.len());
+    Ok(())
+}
@@ -0,0 +1,15 @@
+use cuda_std::prelude::*;
+
+/// Doubles every value, panicking with a formatted message on values too large to double.
+#[kernel]
+#[allow(improper_ctypes_definitions, clippy::missing_safety_doc)]
+pub unsafe fn checked_double(values: *mut u32, len: usize) {
+    let idx = thread::index_1d() as usize;
+    if idx < len {
+        let value = unsafe { &mut *values.add(idx) };
+        match value.checked_mul(2) {
+            Some(doubled) => *value = doubled,
+            None => panic!("value {} at index {} overflows when doubled", *value, idx),
+        }
+    }
+}
@@ -0,0 +1,56 @@
+//! Checks that a panicking kernel reports its formatted message and fails the launch with
+//! `CudaError::AssertError`, using the `panic_messages` feature of `cuda_std`.
+
+use cust::error::CudaError;
+use cust::prelude::*;
+use std::error::Error;
+
+const BLOCK_SIZE: u32 = 256;
+
+static PTX: &str = include_str!(concat!(env!("OUT_DIR"), "/kernels.ptx"));
+
+fn main() -> Result<(), Box<dyn Error>> {
+    // the panic handler reports the message through `__assertfail`.
+    assert!(
+        PTX.contains("__assertfail"),
+        "the kernels do not call __assertfail"
+    );
+
+    let _ctx = cust::quick_init()?;
+    let module = Module::from_ptx(PTX, &[])?;
+    let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+    let checked_double = module.get_function("checked_double")?;
+
+    let values: Vec<u32> = (0..1000).collect();
+    let values_gpu = values.as_slice().as_dbuf()?;
+    let grid_size = (values.len() as u32).div_ceil(BLOCK_SIZE);
+    unsafe {
+        launch!(checked_double<<<grid_size, BLOCK_SIZE, 0, stream>>>(
+            values_gpu.as_device_ptr(),
+            values_gpu.len(),
+        ))?;
+    }
+    stream.synchronize()?;
+    assert_eq!(
+        values_gpu.as_host_vec()?,
+        values.iter().map(|v| v * 2).collect::<Vec<_>>()
+    );
+
+    // the last value overflows, its thread panics and prints something like
+    // `kernels/src/lib.rs:12: block: [3,0,0], thread: [231,0,0] Assertion `value 4294967295 at
+    // index 999 overflows when doubled` failed.`
+    let mut values = values;
+    values[999] = u32::MAX;
+    let values_gpu = values.as_slice().as_dbuf()?;
+    unsafe {
+        launch!(checked_double<<<grid_size, BLOCK_SIZE, 0, stream>>>(
+            values_gpu.as_device_ptr(),
+            values_gpu.len(),
+        ))?;
+    }
+    // the context can not be used anymore after the assertion failed.
+    assert_eq!(stream.synchronize(), Err(CudaError::AssertError));
+
+    println!("the panic was reported as {:?}", CudaError::AssertError);
+    Ok(())
+}
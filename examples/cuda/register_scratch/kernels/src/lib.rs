@@ -0,0 +1,24 @@
+use cuda_std::prelude::*;
+
+pub const COEFFICIENTS: [f32; 8] = [1.0, -0.5, 0.25, -0.125, 2.0, -1.0, 0.5, -0.25];
+
+/// Evaluates the polynomial with `COEFFICIENTS` at every value. The powers of the value are kept
+/// in a per-thread array which is only indexed by loop counters, so once the loops are unrolled
+/// it fits in registers.
+#[kernel]
+#[allow(improper_ctypes_definitions, clippy::missing_safety_doc)]
+pub unsafe fn polynomial(xs: &[f32], ys: *mut f32) {
+    let idx = thread::index_1d() as usize;
+    if idx < xs.len() {
+        let x = xs[idx];
+        let mut powers = [1.0f32; 8];
+        for k in 1..8 {
+            powers[k] = powers[k - 1] * x;
+        }
+        let mut y = 0.0;
+        for k in 0..8 {
+            y += COEFFICIENTS[k] * powers[k];
+        }
+        unsafe { *ys.add(idx) = y };
+    }
+}
@@ -0,0 +1,61 @@
+//! Checks that a small per-thread array with constant indices is kept in registers instead of
+//! being spilled to local memory.
+
+use cust::prelude::*;
+use register_scratch_kernels::COEFFICIENTS;
+use std::error::Error;
+
+const VALUES_LEN: usize = 10_000;
+const BLOCK_SIZE: u32 = 256;
+
+static PTX: &str = include_str!(concat!(env!("OUT_DIR"), "/kernels.ptx"));
+
+fn main() -> Result<(), Box<dyn Error>> {
+    // local memory is declared as a `__local_depot` array and accessed with `ld.local` and
+    // `st.local`.
+    let spills: Vec<&str> = PTX
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.contains("__local_depot") || line.contains(".local"))
+        .collect();
+    assert!(
+        spills.is_empty(),
+        "the kernel uses local memory: {spills:#?}"
+    );
+
+    let _ctx = cust::quick_init()?;
+    let module = Module::from_ptx(PTX, &[])?;
+    let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+    let polynomial = module.get_function("polynomial")?;
+
+    let xs: Vec<f32> = (0..VALUES_LEN)
+        .map(|i| i as f32 / VALUES_LEN as f32)
+        .collect();
+    let xs_gpu = xs.as_slice().as_dbuf()?;
+    let ys_gpu = DeviceBuffer::<f32>::zeroed(VALUES_LEN)?;
+    let grid_size = (VALUES_LEN as u32).div_ceil(BLOCK_SIZE);
+    unsafe {
+        launch!(polynomial<<<grid_size, BLOCK_SIZE, 0, stream>>>(
+            xs_gpu.as_device_ptr(),
+            xs_gpu.len(),
+            ys_gpu.as_device_ptr(),
+        ))?;
+    }
+    stream.synchronize()?;
+
+    // the kernel may contract the operations into fused multiply-adds, which round differently.
+    for (x, y) in xs.iter().zip(ys_gpu.as_host_vec()?) {
+        let expected: f32 = COEFFICIENTS
+            .iter()
+            .enumerate()
+            .map(|(k, c)| c * x.powi(k as i32))
+            .sum();
+        assert!(
+            (y - expected).abs() < 1e-4,
+            "p({x}) = {y} instead of {expected}"
+        );
+    }
+
+    println!("the scratch array was kept in registers");
+    Ok(())
+}
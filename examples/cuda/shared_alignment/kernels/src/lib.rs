@@ -0,0 +1,46 @@
+use cuda_std::prelude::*;
+use cuda_std::shared_array;
+
+#[repr(C, align(16))]
+#[derive(Clone, Copy)]
+pub struct Vec4(pub [f32; 4]);
+
+// `#[used]` keeps the statics in the PTX even where their loads are folded, so that their
+// declarations can be checked.
+#[used]
+#[unsafe(no_mangle)]
+static SCALE: Vec4 = Vec4([1.0, 2.0, 3.0, 4.0]);
+
+#[used]
+#[unsafe(no_mangle)]
+static mut OFFSET: f64 = 0.0;
+
+/// Reverses the 32 `doubles` and adds `OFFSET` to them, and multiplies every group of four of the
+/// 64 `values` by `SCALE`. Launched with a single block of 64 threads.
+#[kernel]
+#[allow(improper_ctypes_definitions, clippy::missing_safety_doc)]
+pub unsafe fn reverse_and_scale(doubles: *mut f64, values: *mut f32) {
+    // 8 byte aligned because of the element type, and 16 byte aligned on request.
+    let a = shared_array![f64; 32];
+    let b = shared_array![f32; 64, align = 16];
+    let t = thread::thread_idx_x() as usize;
+
+    unsafe {
+        if t < 32 {
+            *a.add(t) = *doubles.add(t);
+        }
+        *b.add(t) = *values.add(t);
+        thread::sync_threads();
+
+        if t < 32 {
+            *doubles.add(t) = *a.add(31 - t) + OFFSET;
+        }
+        if t < 16 {
+            // a single 16 byte load, which faults if `b` is not 16 byte aligned.
+            let Vec4(group) = *b.cast::<Vec4>().add(t);
+            for (i, value) in group.into_iter().enumerate() {
+                *values.add(4 * t + i) = value * SCALE.0[i];
+            }
+        }
+    }
+}
@@ -0,0 +1,80 @@
+//! Checks that shared memory arrays and statics are declared with the alignment of their type, or
+//! the one requested from `shared_array!`, and that a kernel relying on it runs.
+
+use cust::prelude::*;
+use std::error::Error;
+
+static PTX: &str = include_str!(concat!(env!("OUT_DIR"), "/kernels.ptx"));
+
+/// Finds the declaration of the global `name` in the PTX.
+fn declaration(name: &str) -> &'static str {
+    PTX.lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with('.'))
+        .find(|line| {
+            line.split(|c: char| c.is_whitespace() || c == '[' || c == ';')
+                .any(|token| token == name)
+        })
+        .unwrap_or_else(|| panic!("{name} is not declared in the PTX"))
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    // the names of shared arrays are those of the `__shared_array` instance that defines them,
+    // followed by `_shared`.
+    let shared: Vec<&str> = PTX
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.contains(".shared ") && line.contains("_shared["))
+        .collect();
+    for align in [".align 8 ", ".align 16 "] {
+        assert!(
+            shared
+                .iter()
+                .any(|line| line.contains(align) && line.ends_with("[256];")),
+            "no 256 byte shared array is declared with `{align}`: {shared:#?}"
+        );
+    }
+
+    let scale = declaration("SCALE");
+    assert!(
+        scale.contains(".const ") && scale.contains(".align 16 "),
+        "SCALE is declared as `{scale}`"
+    );
+    let offset = declaration("OFFSET");
+    assert!(
+        offset.contains(".global ") && offset.contains(".align 8 "),
+        "OFFSET is declared as `{offset}`"
+    );
+
+    let _ctx = cust::quick_init()?;
+    let module = Module::from_ptx(PTX, &[])?;
+    let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+    let reverse_and_scale = module.get_function("reverse_and_scale")?;
+
+    module.get_global::<f64>(c"OFFSET")?.copy_from(&0.5)?;
+
+    let doubles: Vec<f64> = (0..32).map(f64::from).collect();
+    let values: Vec<f32> = (0..64u8).map(f32::from).collect();
+    let doubles_gpu = doubles.as_slice().as_dbuf()?;
+    let values_gpu = values.as_slice().as_dbuf()?;
+    unsafe {
+        launch!(reverse_and_scale<<<1, 64, 0, stream>>>(
+            doubles_gpu.as_device_ptr(),
+            values_gpu.as_device_ptr(),
+        ))?;
+    }
+    stream.synchronize()?;
+
+    let reversed: Vec<f64> = doubles.iter().rev().map(|d| d + 0.5).collect();
+    assert_eq!(doubles_gpu.as_host_vec()?, reversed);
+    let scale = [1.0, 2.0, 3.0, 4.0];
+    let scaled: Vec<f32> = values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| v * scale[i % 4])
+        .collect();
+    assert_eq!(values_gpu.as_host_vec()?, scaled);
+
+    println!("shared arrays and statics are aligned");
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use core::ops::Add;
+
+use cuda_std::prelude::*;
+use cuda_std::warp::{
+    WarpShuffleValue, lane_id, shfl_down_sync, shfl_sync, shfl_up_sync, shfl_xor_sync,
+};
+
+const FULL_MASK: u32 = u32::MAX;
+
+/// Sums `value` over the warp by halving the active lanes, lane 0 ends up with the sum.
+unsafe fn sum_down<T: WarpShuffleValue + Copy + Add<Output = T>>(mut value: T) -> T {
+    for delta in [16, 8, 4, 2, 1] {
+        value = value + unsafe { shfl_down_sync(FULL_MASK, value, delta) };
+    }
+    value
+}
+
+/// Sums `value` over the warp with a butterfly, every lane ends up with the sum.
+unsafe fn sum_xor<T: WarpShuffleValue + Copy + Add<Output = T>>(mut value: T) -> T {
+    for lane_mask in [16, 8, 4, 2, 1] {
+        value = value + unsafe { shfl_xor_sync(FULL_MASK, value, lane_mask) };
+    }
+    value
+}
+
+// every kernel expects a multiple of 32 values, so that every warp is full.
+
+#[kernel]
+#[allow(improper_ctypes_definitions, clippy::missing_safety_doc)]
+pub unsafe fn sum_i32(values: &[i32], sums: *mut i32) {
+    let idx = thread::index_1d() as usize;
+    let sum = unsafe { sum_down(values[idx]) };
+    if lane_id() == 0 {
+        unsafe { *sums.add(idx / 32) = sum };
+    }
+}
+
+#[kernel]
+#[allow(improper_ctypes_definitions, clippy::missing_safety_doc)]
+pub unsafe fn sum_u64(values: &[u64], sums: *mut u64) {
+    let idx = thread::index_1d() as usize;
+    let sum = unsafe { sum_down(values[idx]) };
+    if lane_id() == 0 {
+        unsafe { *sums.add(idx / 32) = sum };
+    }
+}
+
+/// Writes the sum of its warp for every value.
+#[kernel]
+#[allow(improper_ctypes_definitions, clippy::missing_safety_doc)]
+pub unsafe fn sum_f32_all_lanes(values: &[f32], sums: *mut f32) {
+    let idx = thread::index_1d() as usize;
+    let sum = unsafe { sum_xor(values[idx]) };
+    unsafe { *sums.add(idx) = sum };
+}
+
+/// Writes the inclusive prefix sum of every value within its warp, and the sum of every warp as
+/// broadcast from its last lane.
+#[kernel]
+#[allow(improper_ctypes_definitions, clippy::missing_safety_doc)]
+pub unsafe fn scan_u32(values: &[u32], scanned: *mut u32, sums: *mut u32) {
+    let idx = thread::index_1d() as usize;
+    let lane = lane_id();
+    let mut value = values[idx];
+    for delta in [1, 2, 4, 8, 16] {
+        let below = unsafe { shfl_up_sync(FULL_MASK, value, delta) };
+        if lane >= delta {
+            value += below;
+        }
+    }
+    let sum = unsafe { shfl_sync(FULL_MASK, value, 31) };
+    unsafe {
+        *scanned.add(idx) = value;
+        if lane == 0 {
+            *sums.add(idx / 32) = sum;
+        }
+    }
+}
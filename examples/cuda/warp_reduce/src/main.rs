@@ -0,0 +1,100 @@
+//! Checks the warp shuffles of `cuda_std` against sums computed on the CPU.
+
+use cust::prelude::*;
+use std::error::Error;
+
+/// How many values to sum, a multiple of the warp size.
+const VALUES_LEN: usize = 32 * 1024;
+const WARP_SIZE: usize = 32;
+const BLOCK_SIZE: u32 = 256;
+
+static PTX: &str = include_str!(concat!(env!("OUT_DIR"), "/kernels.ptx"));
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let _ctx = cust::quick_init()?;
+    let module = Module::from_ptx(PTX, &[])?;
+    let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+    let grid_size = (VALUES_LEN as u32).div_ceil(BLOCK_SIZE);
+    let warps = VALUES_LEN / WARP_SIZE;
+
+    // small values, so that the floats are summed exactly in any order.
+    let ints: Vec<i32> = (0..VALUES_LEN as i32).map(|i| i % 97 - 48).collect();
+    let longs: Vec<u64> = (0..VALUES_LEN as u64).map(|i| (i << 33) | i).collect();
+    let floats: Vec<f32> = ints.iter().map(|&i| i as f32 * 0.5).collect();
+    let uints: Vec<u32> = (0..VALUES_LEN as u32).map(|i| i % 13).collect();
+
+    let ints_gpu = ints.as_slice().as_dbuf()?;
+    let longs_gpu = longs.as_slice().as_dbuf()?;
+    let floats_gpu = floats.as_slice().as_dbuf()?;
+    let uints_gpu = uints.as_slice().as_dbuf()?;
+    let int_sums_gpu = DeviceBuffer::<i32>::zeroed(warps)?;
+    let long_sums_gpu = DeviceBuffer::<u64>::zeroed(warps)?;
+    let float_sums_gpu = DeviceBuffer::<f32>::zeroed(VALUES_LEN)?;
+    let scanned_gpu = DeviceBuffer::<u32>::zeroed(VALUES_LEN)?;
+    let uint_sums_gpu = DeviceBuffer::<u32>::zeroed(warps)?;
+
+    let sum_i32 = module.get_function("sum_i32")?;
+    let sum_u64 = module.get_function("sum_u64")?;
+    let sum_f32_all_lanes = module.get_function("sum_f32_all_lanes")?;
+    let scan_u32 = module.get_function("scan_u32")?;
+
+    unsafe {
+        launch!(sum_i32<<<grid_size, BLOCK_SIZE, 0, stream>>>(
+            ints_gpu.as_device_ptr(),
+            ints_gpu.len(),
+            int_sums_gpu.as_device_ptr(),
+        ))?;
+        launch!(sum_u64<<<grid_size, BLOCK_SIZE, 0, stream>>>(
+            longs_gpu.as_device_ptr(),
+            longs_gpu.len(),
+            long_sums_gpu.as_device_ptr(),
+        ))?;
+        launch!(sum_f32_all_lanes<<<grid_size, BLOCK_SIZE, 0, stream>>>(
+            floats_gpu.as_device_ptr(),
+            floats_gpu.len(),
+            float_sums_gpu.as_device_ptr(),
+        ))?;
+        launch!(scan_u32<<<grid_size, BLOCK_SIZE, 0, stream>>>(
+            uints_gpu.as_device_ptr(),
+            uints_gpu.len(),
+            scanned_gpu.as_device_ptr(),
+            uint_sums_gpu.as_device_ptr(),
+        ))?;
+    }
+    stream.synchronize()?;
+
+    let expected: Vec<i32> = ints.chunks(WARP_SIZE).map(|w| w.iter().sum()).collect();
+    assert_eq!(int_sums_gpu.as_host_vec()?, expected, "i32 warp sums");
+
+    // the halves of the values are shuffled separately, the sums must still carry over.
+    let expected: Vec<u64> = longs.chunks(WARP_SIZE).map(|w| w.iter().sum()).collect();
+    assert_eq!(long_sums_gpu.as_host_vec()?, expected, "u64 warp sums");
+
+    let expected: Vec<f32> = floats
+        .chunks(WARP_SIZE)
+        .flat_map(|w| [w.iter().sum::<f32>(); WARP_SIZE])
+        .collect();
+    assert_eq!(
+        float_sums_gpu.as_host_vec()?,
+        expected,
+        "f32 butterfly sums"
+    );
+
+    let expected: Vec<u32> = uints
+        .chunks(WARP_SIZE)
+        .flat_map(|w| {
+            w.iter()
+                .scan(0, |sum, &v| {
+                    *sum += v;
+                    Some(*sum)
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    assert_eq!(scanned_gpu.as_host_vec()?, expected, "u32 warp scans");
+    let expected: Vec<u32> = uints.chunks(WARP_SIZE).map(|w| w.iter().sum()).collect();
+    assert_eq!(uint_sums_gpu.as_host_vec()?, expected, "u32 broadcast sums");
+
+    println!("all {warps} warps summed correctly");
+    Ok(())
+}